@@ -21,6 +21,7 @@ use vec1::{vec1, Vec1};
 
 pub const BACKPASS_VARIABLE: &str = "_backpass";
 pub const CAPTURE_VARIABLE: &str = "_capture";
+pub const DICTIONARY_VARIABLE: &str = "_dict";
 pub const PIPE_VARIABLE: &str = "_pipe";
 
 pub const ENV_MODULE: &str = "env";
@@ -117,7 +118,12 @@ impl TypedModule {
             Definition::DataType(t) => t.public && t.name == name,
             Definition::Use(_) => false,
             Definition::Test(_) => false,
+            Definition::Benchmark(_) => false,
             Definition::Validator(_) => false,
+            Definition::Interface(i) => {
+                i.public && (i.name == name || i.methods.iter().any(|m| m.name == name))
+            }
+            Definition::Instance(_) => false,
         })
     }
 
@@ -133,7 +139,10 @@ impl TypedModule {
             Definition::ModuleConstant(_) => false,
             Definition::Use(_) => false,
             Definition::Test(_) => false,
+            Definition::Benchmark(_) => false,
             Definition::Validator(_) => false,
+            Definition::Interface(_) => false,
+            Definition::Instance(_) => false,
         })
     }
 
@@ -176,7 +185,7 @@ impl TypedModule {
                     );
                 }
 
-                Definition::Test(test) => {
+                Definition::Test(test) | Definition::Benchmark(test) => {
                     functions.insert(
                         FunctionAccessKey {
                             module_name: self.name.clone(),
@@ -214,7 +223,15 @@ impl TypedModule {
                     );
                 }
 
-                Definition::TypeAlias(_) | Definition::Use(_) => {}
+                Definition::Instance(instance) => {
+                    let module_name = self.name.as_str();
+
+                    for (k, v) in instance.into_function_definitions(module_name) {
+                        functions.insert(k, v);
+                    }
+                }
+
+                Definition::TypeAlias(_) | Definition::Use(_) | Definition::Interface(_) => {}
             }
         }
     }
@@ -239,6 +256,7 @@ fn str_to_keyword(word: &str) -> Option<Token> {
         "type" => Some(Token::Type),
         "trace" => Some(Token::Trace),
         "test" => Some(Token::Test),
+        "bench" => Some(Token::Benchmark),
         // TODO: remove this in a future release
         "error" => Some(Token::Fail),
         "fail" => Some(Token::Fail),
@@ -288,9 +306,21 @@ pub struct Function<T, Expr, Arg> {
     pub return_type: T,
     pub end_position: usize,
     pub on_test_failure: OnTestFailure,
+    /// Interface constraints on the function's type parameters, as given in a
+    /// 'where' clause (e.g. `where Show<a>`).
+    pub constraints: Vec<Annotation>,
+    /// Arguments carrying the methods of the function's constraints, added by the type-checker
+    /// after the declared arguments. Those are never written by users, nor part of the function's
+    /// type, as they are filled in at every call site.
+    pub dictionaries: Vec<Arg>,
 }
 
 impl<T, Expr, Arg> Function<T, Expr, Arg> {
+    /// The arguments of the function as compiled: the declared ones, followed by its dictionaries.
+    pub fn compiled_arguments(&self) -> impl Iterator<Item = &Arg> {
+        self.arguments.iter().chain(self.dictionaries.iter())
+    }
+
     pub fn is_spend(&self) -> bool {
         self.name == HANDLER_SPEND
     }
@@ -371,6 +401,8 @@ impl From<UntypedTest> for UntypedFunction {
             body: f.body,
             on_test_failure: f.on_test_failure,
             end_position: f.end_position,
+            constraints: f.constraints,
+            dictionaries: f.dictionaries.into_iter().map(|arg| arg.into()).collect(),
         }
     }
 }
@@ -388,6 +420,8 @@ impl From<TypedTest> for TypedFunction {
             body: f.body,
             on_test_failure: f.on_test_failure,
             end_position: f.end_position,
+            constraints: f.constraints,
+            dictionaries: f.dictionaries.into_iter().map(|arg| arg.into()).collect(),
         }
     }
 }
@@ -403,6 +437,90 @@ pub struct TypeAlias<T> {
     pub tipo: T,
 }
 
+pub type TypedInterface = Interface<Rc<Type>>;
+pub type UntypedInterface = Interface<()>;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Interface<T> {
+    pub doc: Option<String>,
+    pub location: Span,
+    pub methods: Vec<InterfaceMethod<T>>,
+    pub name: String,
+    pub parameter: String,
+    pub public: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct InterfaceMethod<T> {
+    pub annotation: Annotation,
+    pub doc: Option<String>,
+    pub location: Span,
+    pub name: String,
+    pub tipo: T,
+}
+
+pub type TypedInstance = Instance<Rc<Type>, TypedArg, TypedExpr>;
+pub type UntypedInstance = Instance<(), UntypedArg, UntypedExpr>;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Instance<T, Arg, Expr> {
+    pub doc: Option<String>,
+    pub end_position: usize,
+    /// The implemented interface, applied to the instance type (e.g. `Show<Int>`).
+    pub interface: Annotation,
+    pub location: Span,
+    pub methods: Vec<Function<T, Expr, Arg>>,
+    pub tipo: T,
+}
+
+impl<T, Arg, Expr> Instance<T, Arg, Expr> {
+    /// The name under which a method of an instance is compiled. Such a name cannot be written by
+    /// users and therefore never clashes with other definitions.
+    pub fn method_name(interface: &str, tipo: &str, method: &str) -> String {
+        format!("{interface}<{tipo}>.{method}")
+    }
+
+    pub fn interface_name(&self) -> &str {
+        match &self.interface {
+            Annotation::Constructor { name, .. } => name,
+            _ => "",
+        }
+    }
+}
+
+impl TypedInstance {
+    pub fn find_node(&self, byte_index: usize) -> Option<Located<'_>> {
+        self.methods
+            .iter()
+            .find_map(|method| method.find_node(byte_index))
+            .or_else(|| self.interface.find_node(byte_index))
+    }
+
+    pub fn into_function_definitions(
+        &self,
+        module_name: &str,
+    ) -> Vec<(FunctionAccessKey, TypedFunction)> {
+        let tipo = self.tipo.to_pretty(0);
+
+        self.methods
+            .iter()
+            .map(|method| {
+                (
+                    FunctionAccessKey {
+                        module_name: module_name.to_string(),
+                        function_name: TypedInstance::method_name(
+                            self.interface_name(),
+                            &tipo,
+                            &method.name,
+                        ),
+                    },
+                    method.clone(),
+                )
+            })
+            .collect()
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct DataTypeKey {
     pub module_name: String,
@@ -540,6 +658,8 @@ impl UntypedValidator {
             return_annotation: Some(Annotation::boolean(location)),
             return_type: (),
             on_test_failure: OnTestFailure::FailImmediately,
+            constraints: vec![],
+            dictionaries: vec![],
         }
     }
 }
@@ -810,7 +930,13 @@ pub enum Definition<T, Arg, Expr, PackageName> {
 
     Test(Function<T, Expr, ArgVia<Arg, Expr>>),
 
+    Benchmark(Function<T, Expr, ArgVia<Arg, Expr>>),
+
     Validator(Validator<T, Arg, Expr>),
+
+    Interface(Interface<T>),
+
+    Instance(Instance<T, Arg, Expr>),
 }
 
 impl<A, B, C, D> Definition<A, B, C, D> {
@@ -822,7 +948,10 @@ impl<A, B, C, D> Definition<A, B, C, D> {
             | Definition::DataType(DataType { location, .. })
             | Definition::ModuleConstant(ModuleConstant { location, .. })
             | Definition::Validator(Validator { location, .. })
-            | Definition::Test(Function { location, .. }) => *location,
+            | Definition::Test(Function { location, .. })
+            | Definition::Benchmark(Function { location, .. })
+            | Definition::Interface(Interface { location, .. })
+            | Definition::Instance(Instance { location, .. }) => *location,
         }
     }
 
@@ -834,7 +963,10 @@ impl<A, B, C, D> Definition<A, B, C, D> {
             | Definition::DataType(DataType { doc, .. })
             | Definition::ModuleConstant(ModuleConstant { doc, .. })
             | Definition::Validator(Validator { doc, .. })
-            | Definition::Test(Function { doc, .. }) => {
+            | Definition::Test(Function { doc, .. })
+            | Definition::Benchmark(Function { doc, .. })
+            | Definition::Interface(Interface { doc, .. })
+            | Definition::Instance(Instance { doc, .. }) => {
                 let _ = std::mem::replace(doc, Some(new_doc));
             }
         }
@@ -848,7 +980,10 @@ impl<A, B, C, D> Definition<A, B, C, D> {
             | Definition::DataType(DataType { doc, .. })
             | Definition::ModuleConstant(ModuleConstant { doc, .. })
             | Definition::Validator(Validator { doc, .. })
-            | Definition::Test(Function { doc, .. }) => doc.clone(),
+            | Definition::Test(Function { doc, .. })
+            | Definition::Benchmark(Function { doc, .. })
+            | Definition::Interface(Interface { doc, .. })
+            | Definition::Instance(Instance { doc, .. }) => doc.clone(),
         }
     }
 }
@@ -861,6 +996,8 @@ impl TypedDefinition {
             Definition::Validator(validator) => validator.find_node(byte_index),
             Definition::Fn(func) => func.find_node(byte_index),
             Definition::Test(func) => func.find_node(byte_index),
+            Definition::Benchmark(func) => func.find_node(byte_index),
+            Definition::Instance(instance) => instance.find_node(byte_index),
             _ => None,
         };
 
@@ -2078,7 +2215,7 @@ impl Default for Bls12_381Point {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct AssignmentPattern {
     pub pattern: UntypedPattern,
     pub annotation: Option<Annotation>,
@@ -2179,9 +2316,10 @@ pub type MultiPattern<PatternConstructor, Type> = Vec<Pattern<PatternConstructor
 pub type UntypedMultiPattern = MultiPattern<(), ()>;
 pub type TypedMultiPattern = MultiPattern<PatternConstructor, Rc<Type>>;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct UntypedClause {
     pub location: Span,
+    #[serde(with = "crate::expr::Vec1Ref")]
     pub patterns: Vec1<Pattern<(), ()>>,
     pub then: UntypedExpr,
 }
@@ -2235,20 +2373,20 @@ impl TypedRecordUpdateArg {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct UntypedRecordUpdateArg {
     pub label: String,
     pub location: Span,
     pub value: UntypedExpr,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct RecordUpdateSpread {
     pub base: Box<UntypedExpr>,
     pub location: Span,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum TraceKind {
     Trace,
     Todo,
@@ -2427,7 +2565,7 @@ impl chumsky::Span for Span {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum LogicalOpChainKind {
     And,
     Or,
@@ -25,6 +25,7 @@ pub const PAIRS: &str = "Pairs";
 pub const PRNG: &str = "PRNG";
 pub const PRNG_CONSTRUCTORS: &[&str] = &["Seeded", "Replayed"];
 pub const REDEEMER_WRAPPER: &str = "RedeemerWrapper";
+pub const SAMPLER: &str = "Sampler";
 pub const STRING: &str = "String";
 pub const VOID: &str = "Void";
 pub const VOID_CONSTRUCTORS: &[&str] = &["Void"];
@@ -220,6 +221,34 @@ impl Type {
         })
     }
 
+    pub fn sampler(a: Rc<Type>) -> Rc<Type> {
+        Rc::new(Type::Fn {
+            args: vec![Type::int()],
+            ret: Type::fuzzer(a),
+            alias: Some(
+                TypeAliasAnnotation {
+                    alias: SAMPLER.to_string(),
+                    parameters: vec!["a".to_string()],
+                    annotation: Annotation::Fn {
+                        location: Span::empty(),
+                        arguments: vec![Annotation::int(Span::empty())],
+                        ret: Annotation::Constructor {
+                            location: Span::empty(),
+                            module: None,
+                            name: FUZZER.to_string(),
+                            arguments: vec![Annotation::Var {
+                                location: Span::empty(),
+                                name: "a".to_string(),
+                            }],
+                        }
+                        .into(),
+                    },
+                }
+                .into(),
+            ),
+        })
+    }
+
     pub fn map(k: Rc<Type>, v: Rc<Type>) -> Rc<Type> {
         Rc::new(Type::App {
             public: true,
@@ -32,6 +32,9 @@ pub fn prelude(id_gen: &IdGenerator) -> TypeInfo {
         values: HashMap::new(),
         accessors: HashMap::new(),
         annotations: HashMap::new(),
+        interfaces: HashMap::new(),
+        instances: Vec::new(),
+        constraints: HashMap::new(),
     };
 
     // Data
@@ -490,6 +493,22 @@ pub fn prelude(id_gen: &IdGenerator) -> TypeInfo {
         },
     );
 
+    // Sampler
+    //
+    // pub type Sampler<a> =
+    //   fn(Int) -> Fuzzer<a>
+    let sampler_value = Type::generic_var(id_gen.next());
+    prelude.types.insert(
+        well_known::SAMPLER.to_string(),
+        TypeConstructor {
+            location: Span::empty(),
+            parameters: vec![sampler_value.clone()],
+            tipo: Type::sampler(sampler_value),
+            module: "".to_string(),
+            public: true,
+        },
+    );
+
     prelude
 }
 
@@ -503,6 +522,9 @@ pub fn plutus(id_gen: &IdGenerator) -> TypeInfo {
         values: HashMap::new(),
         accessors: HashMap::new(),
         annotations: HashMap::new(),
+        interfaces: HashMap::new(),
+        instances: Vec::new(),
+        constraints: HashMap::new(),
     };
 
     for builtin in DefaultFunction::iter() {
@@ -1031,6 +1053,8 @@ pub fn prelude_functions(
                 tipo: Type::bool(),
             }],
             on_test_failure: OnTestFailure::FailImmediately,
+            constraints: vec![],
+            dictionaries: vec![],
             doc: Some(
                 indoc::indoc! {
                     r#"
@@ -1088,6 +1112,8 @@ pub fn prelude_functions(
                 tipo: a_var.clone(),
             }],
             on_test_failure: OnTestFailure::FailImmediately,
+            constraints: vec![],
+            dictionaries: vec![],
             body: TypedExpr::Var {
                 location: Span::empty(),
                 constructor: ValueConstructor {
@@ -1130,6 +1156,8 @@ pub fn prelude_functions(
         },
         Function {
             on_test_failure: OnTestFailure::FailImmediately,
+            constraints: vec![],
+            dictionaries: vec![],
             arguments: vec![
                 TypedArg {
                     arg_name: ArgName::Named {
@@ -1209,6 +1237,8 @@ pub fn prelude_functions(
         },
         Function {
             on_test_failure: OnTestFailure::FailImmediately,
+            constraints: vec![],
+            dictionaries: vec![],
             arguments: vec![TypedArg {
                 arg_name: ArgName::Named {
                     name: "f".to_string(),
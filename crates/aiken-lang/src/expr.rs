@@ -194,7 +194,7 @@ pub enum TypedExpr {
 
 #[derive(serde::Serialize, serde::Deserialize)]
 #[serde(remote = "Vec1")]
-struct Vec1Ref<T>(#[serde(getter = "Vec1::as_vec")] Vec<T>);
+pub(crate) struct Vec1Ref<T>(#[serde(getter = "Vec1::as_vec")] Vec<T>);
 
 impl<T> From<Vec1Ref<T>> for Vec1<T> {
     fn from(v: Vec1Ref<T>) -> Self {
@@ -570,6 +570,55 @@ impl TypedExpr {
         }
     }
 
+    /// Direct sub-expressions of an expression, in evaluation order.
+    pub fn children_mut(&mut self) -> Vec<&mut Self> {
+        match self {
+            TypedExpr::UInt { .. }
+            | TypedExpr::String { .. }
+            | TypedExpr::ByteArray { .. }
+            | TypedExpr::CurvePoint { .. }
+            | TypedExpr::Var { .. }
+            | TypedExpr::ModuleSelect { .. }
+            | TypedExpr::ErrorTerm { .. } => vec![],
+            TypedExpr::Sequence { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+                expressions.iter_mut().collect()
+            }
+            TypedExpr::Fn { body, .. } => vec![body],
+            TypedExpr::List { elements, tail, .. } => elements
+                .iter_mut()
+                .chain(tail.iter_mut().map(|e| e.as_mut()))
+                .collect(),
+            TypedExpr::Call { fun, args, .. } => std::iter::once(fun.as_mut())
+                .chain(args.iter_mut().map(|arg| &mut arg.value))
+                .collect(),
+            TypedExpr::BinOp { left, right, .. } => vec![left, right],
+            TypedExpr::Assignment { value, .. } => vec![value],
+            TypedExpr::Trace { then, text, .. } => vec![text, then],
+            TypedExpr::When {
+                subject, clauses, ..
+            } => std::iter::once(subject.as_mut())
+                .chain(clauses.iter_mut().map(|clause| &mut clause.then))
+                .collect(),
+            TypedExpr::If {
+                branches,
+                final_else,
+                ..
+            } => branches
+                .iter_mut()
+                .flat_map(|branch| [&mut branch.condition, &mut branch.body])
+                .chain(std::iter::once(final_else.as_mut()))
+                .collect(),
+            TypedExpr::RecordAccess { record, .. } => vec![record],
+            TypedExpr::Tuple { elems, .. } => elems.iter_mut().collect(),
+            TypedExpr::Pair { fst, snd, .. } => vec![fst, snd],
+            TypedExpr::TupleIndex { tuple, .. } => vec![tuple],
+            TypedExpr::RecordUpdate { spread, args, .. } => std::iter::once(spread.as_mut())
+                .chain(args.iter_mut().map(|arg| &mut arg.value))
+                .collect(),
+            TypedExpr::UnOp { value, .. } => vec![value],
+        }
+    }
+
     pub fn void(location: Span) -> Self {
         TypedExpr::Var {
             name: "Void".to_string(),
@@ -591,14 +640,14 @@ impl TypedExpr {
 }
 
 // Represent how a function was written so that we can format it back.
-#[derive(Debug, Clone, PartialEq, Copy)]
+#[derive(Debug, Clone, PartialEq, Copy, serde::Serialize, serde::Deserialize)]
 pub enum FnStyle {
     Plain,
     Capture,
     BinOp(BinOp),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum UntypedExpr {
     UInt {
         location: Span,
@@ -661,6 +710,7 @@ pub enum UntypedExpr {
     },
 
     PipeLine {
+        #[serde(with = "Vec1Ref")]
         expressions: Vec1<Self>,
         one_liner: bool,
     },
@@ -668,6 +718,7 @@ pub enum UntypedExpr {
     Assignment {
         location: Span,
         value: Box<Self>,
+        #[serde(with = "Vec1Ref")]
         patterns: Vec1<AssignmentPattern>,
         kind: UntypedAssignmentKind,
     },
@@ -693,6 +744,7 @@ pub enum UntypedExpr {
 
     If {
         location: Span,
+        #[serde(with = "Vec1Ref")]
         branches: Vec1<UntypedIfBranch>,
         final_else: Box<Self>,
     },
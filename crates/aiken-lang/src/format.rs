@@ -1,12 +1,12 @@
 use crate::{
     ast::{
         Annotation, ArgBy, ArgName, ArgVia, AssignmentKind, AssignmentPattern, BinOp,
-        ByteArrayFormatPreference, CallArg, CurveType, DataType, Definition, Function,
-        LogicalOpChainKind, ModuleConstant, OnTestFailure, Pattern, RecordConstructor,
-        RecordConstructorArg, RecordUpdateSpread, Span, TraceKind, TypeAlias, TypedArg,
-        TypedValidator, UnOp, UnqualifiedImport, UntypedArg, UntypedArgVia, UntypedAssignmentKind,
-        UntypedClause, UntypedDefinition, UntypedFunction, UntypedIfBranch, UntypedModule,
-        UntypedPattern, UntypedRecordUpdateArg, Use, Validator, CAPTURE_VARIABLE,
+        ByteArrayFormatPreference, CallArg, CurveType, DataType, Definition, Function, Instance,
+        Interface, InterfaceMethod, LogicalOpChainKind, ModuleConstant, OnTestFailure, Pattern,
+        RecordConstructor, RecordConstructorArg, RecordUpdateSpread, Span, TraceKind, TypeAlias,
+        TypedArg, TypedValidator, UnOp, UnqualifiedImport, UntypedArg, UntypedArgVia,
+        UntypedAssignmentKind, UntypedClause, UntypedDefinition, UntypedFunction, UntypedIfBranch,
+        UntypedModule, UntypedPattern, UntypedRecordUpdateArg, Use, Validator, CAPTURE_VARIABLE,
     },
     docvec,
     expr::{FnStyle, TypedExpr, UntypedExpr, DEFAULT_ERROR_STR, DEFAULT_TODO_STR},
@@ -231,12 +231,14 @@ impl<'comments> Formatter<'comments> {
                 public,
                 return_annotation,
                 end_position,
+                constraints,
                 ..
             }) => self.definition_fn(
                 public,
                 name,
                 args,
                 return_annotation,
+                constraints,
                 body,
                 *end_position,
                 false,
@@ -258,7 +260,16 @@ impl<'comments> Formatter<'comments> {
                 end_position,
                 on_test_failure,
                 ..
-            }) => self.definition_test(name, args, body, *end_position, on_test_failure),
+            }) => self.definition_test("test", name, args, body, *end_position, on_test_failure),
+
+            Definition::Benchmark(Function {
+                name,
+                arguments: args,
+                body,
+                end_position,
+                on_test_failure,
+                ..
+            }) => self.definition_test("bench", name, args, body, *end_position, on_test_failure),
 
             Definition::TypeAlias(TypeAlias {
                 alias,
@@ -280,6 +291,22 @@ impl<'comments> Formatter<'comments> {
 
             Definition::Use(import) => self.import(import),
 
+            Definition::Interface(Interface {
+                public,
+                name,
+                parameter,
+                methods,
+                location,
+                ..
+            }) => self.definition_interface(*public, name, parameter, methods, location),
+
+            Definition::Instance(Instance {
+                interface,
+                methods,
+                end_position,
+                ..
+            }) => self.definition_instance(interface, methods, *end_position),
+
             Definition::ModuleConstant(ModuleConstant {
                 public,
                 name,
@@ -551,6 +578,7 @@ impl<'comments> Formatter<'comments> {
         name: &'a str,
         args: &'a [UntypedArg],
         return_annotation: &'a Option<Annotation>,
+        constraints: &'a [Annotation],
         body: &'a UntypedExpr,
         end_location: usize,
         is_validator: bool,
@@ -577,6 +605,18 @@ impl<'comments> Formatter<'comments> {
                 }
             }
             None => head,
+        };
+
+        // Add constraints
+        let head = if constraints.is_empty() {
+            head
+        } else {
+            head.append(" where ").append(join(
+                constraints
+                    .iter()
+                    .map(|constraint| self.annotation(constraint)),
+                ", ".to_doc(),
+            ))
         }
         .group();
 
@@ -599,6 +639,7 @@ impl<'comments> Formatter<'comments> {
     #[allow(clippy::too_many_arguments)]
     fn definition_test<'a>(
         &mut self,
+        keyword: &'a str,
         name: &'a str,
         args: &'a [UntypedArgVia],
         body: &'a UntypedExpr,
@@ -606,8 +647,9 @@ impl<'comments> Formatter<'comments> {
         on_test_failure: &'a OnTestFailure,
     ) -> Document<'a> {
         // Fn name and args
-        let head = "test "
+        let head = keyword
             .to_doc()
+            .append(" ")
             .append(name)
             .append(wrap_args(args.iter().map(|e| (self.fn_arg_via(e), false))))
             .append(match on_test_failure {
@@ -664,6 +706,7 @@ impl<'comments> Formatter<'comments> {
                     &handler.name,
                     &handler.arguments,
                     &handler.return_annotation,
+                    &handler.constraints,
                     &handler.body,
                     handler.end_position,
                     true,
@@ -687,6 +730,7 @@ impl<'comments> Formatter<'comments> {
                     &fallback.name,
                     &fallback.arguments,
                     &fallback.return_annotation,
+                    &fallback.constraints,
                     &fallback.body,
                     fallback.end_position,
                     true,
@@ -715,6 +759,87 @@ impl<'comments> Formatter<'comments> {
             .append("}")
     }
 
+    fn definition_interface<'a>(
+        &mut self,
+        public: bool,
+        name: &'a str,
+        parameter: &'a str,
+        methods: &'a [InterfaceMethod<()>],
+        location: &'a Span,
+    ) -> Document<'a> {
+        self.pop_empty_lines(location.start);
+
+        let methods = methods.iter().map(|method| {
+            let comments = self.pop_comments(method.location.start);
+
+            let doc_comments = self.doc_comments(method.location.start);
+
+            let doc = method
+                .name
+                .to_doc()
+                .append(": ")
+                .append(self.annotation(&method.annotation))
+                .append(",");
+
+            line()
+                .append(commented(doc_comments.append(doc).group(), comments))
+                .nest(INDENT)
+        });
+
+        pub_(public)
+            .append("interface ")
+            .append(name)
+            .append(wrap_generics([parameter.to_doc()]))
+            .append(" {")
+            .append(concat(methods.collect::<Vec<_>>()))
+            .append(line())
+            .append("}")
+    }
+
+    fn definition_instance<'a>(
+        &mut self,
+        interface: &'a Annotation,
+        methods: &'a [UntypedFunction],
+        end_position: usize,
+    ) -> Document<'a> {
+        let mut method_docs = vec![];
+
+        for method in methods.iter() {
+            let comments = self.pop_comments(method.location.start);
+            let doc_comments = self.doc_comments(method.location.start);
+
+            let doc = self
+                .definition_fn(
+                    &method.public,
+                    &method.name,
+                    &method.arguments,
+                    &method.return_annotation,
+                    &method.constraints,
+                    &method.body,
+                    method.end_position,
+                    false,
+                )
+                .group();
+
+            method_docs.push(commented(doc_comments.append(doc).group(), comments));
+        }
+
+        let body = line().append(join(method_docs, lines(2)));
+
+        let body = match printed_comments(self.pop_comments(end_position), false) {
+            Some(comments) => body.append(lines(2)).append(comments).nest(INDENT),
+            None => body.nest(INDENT),
+        };
+
+        "instance "
+            .to_doc()
+            .append(self.annotation(interface))
+            .append(" {")
+            .append(body)
+            .append(line())
+            .append("}")
+    }
+
     fn expr_fn<'a>(
         &mut self,
         args: &'a [UntypedArg],
@@ -1782,17 +1907,54 @@ impl<'comments> Formatter<'comments> {
         }
     }
 
+    pub fn docs_interface<'a, A>(
+        &mut self,
+        name: &'a str,
+        parameter: &'a str,
+        methods: &'a [InterfaceMethod<A>],
+    ) -> Document<'a> {
+        name.to_doc()
+            .append(wrap_generics([parameter.to_doc()]))
+            .append(" {")
+            .append(concat(methods.iter().map(|method| {
+                line()
+                    .append(
+                        method
+                            .name
+                            .to_doc()
+                            .append(": ")
+                            .append(self.annotation(&method.annotation))
+                            .append(","),
+                    )
+                    .nest(INDENT)
+            })))
+            .append(line())
+            .append("}")
+    }
+
     pub fn docs_fn_signature<'a>(
         &mut self,
         name: &'a str,
         args: &'a [TypedArg],
         return_annotation: &'a Option<Annotation>,
         return_type: Rc<Type>,
+        constraints: &'a [Annotation],
     ) -> Document<'a> {
         let head = name.to_doc().append(self.docs_fn_args(args)).append(" -> ");
 
         let tail = self.type_or_annotation(return_annotation, &return_type);
 
+        let tail = if constraints.is_empty() {
+            tail
+        } else {
+            tail.append(" where ").append(join(
+                constraints
+                    .iter()
+                    .map(|constraint| self.annotation(constraint)),
+                ", ".to_doc(),
+            ))
+        };
+
         let doc = head.append(tail.clone()).group();
 
         // Wrap arguments on multi-lines if they are lengthy.
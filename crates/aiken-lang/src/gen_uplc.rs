@@ -1,7 +1,10 @@
 pub mod air;
 pub mod builder;
+pub mod coverage;
 pub mod decision_tree;
 pub mod interner;
+pub mod profiler;
+pub mod source_map;
 pub mod stick_break_set;
 pub mod tree;
 
@@ -49,7 +52,11 @@ use indexmap::IndexMap;
 use interner::AirInterner;
 use itertools::Itertools;
 use petgraph::{algo, Graph};
-use std::{collections::HashMap, rc::Rc};
+use source_map::{SourceLocation, SourceMap, TermLocations};
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 use stick_break_set::{Builtins, TreeSet};
 use tree::Fields;
 use uplc::{
@@ -57,7 +64,9 @@ use uplc::{
     builder::{CONSTR_FIELDS_EXPOSER, CONSTR_INDEX_EXPOSER, EXPECT_ON_LIST},
     builtins::DefaultFunction,
     machine::cost_model::ExBudget,
-    optimize::{aiken_optimize_and_intern, interner::CodeGenInterner, shrinker::NO_INLINE},
+    optimize::{
+        aiken_optimize_and_intern_annotated, interner::CodeGenInterner, shrinker::NO_INLINE,
+    },
 };
 
 type Otherwise = Option<AirTree>;
@@ -86,6 +95,13 @@ pub struct CodeGenerator<'a> {
     /// mutable and reset as well
     interner: AirInterner,
     id_gen: IdGenerator,
+    /// source mapping, only recorded when enabled
+    emit_source_map: bool,
+    term_locations: TermLocations,
+    current_definition: Option<String>,
+    source_map: SourceMap,
+    /// coverage regions to instrument, identified by module and span
+    coverage_regions: HashSet<(String, usize, usize)>,
 }
 
 impl<'a> CodeGenerator<'a> {
@@ -116,15 +132,40 @@ impl<'a> CodeGenerator<'a> {
             cyclic_functions: IndexMap::new(),
             interner: AirInterner::new(),
             id_gen: IdGenerator::new(),
+            emit_source_map: false,
+            term_locations: TermLocations::new(),
+            current_definition: None,
+            source_map: SourceMap::new(),
+            coverage_regions: HashSet::new(),
         }
     }
 
+    /// Record a source map alongside each generated program. The map of the last generated
+    /// program is available through [`Self::source_map`].
+    pub fn emit_source_map(&mut self, enabled: bool) {
+        self.emit_source_map = enabled;
+    }
+
+    pub fn source_map(&self) -> &SourceMap {
+        &self.source_map
+    }
+
+    /// Instrument generated programs so that they emit a coverage marker (see
+    /// [`coverage::marker`]) whenever they enter one of the given regions.
+    pub fn instrument_coverage(&mut self, regions: &[coverage::Region]) {
+        self.coverage_regions = regions
+            .iter()
+            .map(|region| (region.module.clone(), region.span.start, region.span.end))
+            .collect();
+    }
+
     pub fn reset(&mut self, reset_special_functions: bool) {
         self.code_gen_functions = IndexMap::new();
         self.defined_functions = IndexMap::new();
         self.cyclic_functions = IndexMap::new();
         self.interner = AirInterner::new();
         self.id_gen = IdGenerator::new();
+        self.term_locations = TermLocations::new();
         if reset_special_functions {
             self.special_functions = CodeGenSpecialFuncs::new();
         }
@@ -139,6 +180,8 @@ impl<'a> CodeGenerator<'a> {
                 .for_each(|arg_name| self.interner.intern(arg_name.to_string()))
         });
 
+        self.current_definition = Some(validator.name.clone());
+
         let air_tree_fun = wrap_validator_condition(
             self.build(&validator.into_script_context_handler(), module_name, &[]),
             self.tracing,
@@ -165,7 +208,9 @@ impl<'a> CodeGenerator<'a> {
                 .for_each(|arg_name| self.interner.pop_text(arg_name.to_string()))
         });
 
-        self.finalize(term)
+        let root = self.source_location(module_name, Some(&validator.name), validator.location);
+
+        self.finalize(term, root)
     }
 
     pub fn generate_raw(
@@ -180,6 +225,8 @@ impl<'a> CodeGenerator<'a> {
                 .for_each(|arg_name| self.interner.intern(arg_name.to_string()))
         });
 
+        self.current_definition = None;
+
         let mut air_tree = self.build(body, module_name, &[]);
 
         air_tree = AirTree::no_op(air_tree);
@@ -203,7 +250,9 @@ impl<'a> CodeGenerator<'a> {
                 .for_each(|arg_name| self.interner.pop_text(arg_name.to_string()))
         });
 
-        self.finalize(term)
+        let root = self.source_location(module_name, None, body.location());
+
+        self.finalize(term, root)
     }
 
     fn new_program<T>(&self, term: Term<T>) -> Program<T> {
@@ -215,10 +264,18 @@ impl<'a> CodeGenerator<'a> {
         Program { version, term }
     }
 
-    fn finalize(&mut self, mut term: Term<Name>) -> Program<Name> {
+    fn finalize(&mut self, mut term: Term<Name>, root: Option<SourceLocation>) -> Program<Name> {
         term = self.special_functions.apply_used_functions(term);
 
-        let program = aiken_optimize_and_intern(self.new_program(term));
+        let mut annotations = self.term_locations.annotate(&term);
+
+        let program = aiken_optimize_and_intern_annotated(self.new_program(term), &mut annotations);
+
+        self.source_map = if self.emit_source_map {
+            std::mem::take(&mut self.term_locations).into_source_map(&annotations, root)
+        } else {
+            SourceMap::new()
+        };
 
         // This is very important to call here.
         // If this isn't done, re-using the same instance
@@ -232,11 +289,83 @@ impl<'a> CodeGenerator<'a> {
         program
     }
 
+    fn source_location(
+        &self,
+        module_name: &str,
+        name: Option<&str>,
+        span: Span,
+    ) -> Option<SourceLocation> {
+        if !self.emit_source_map || span == Span::empty() {
+            return None;
+        }
+
+        let (_, lines) = self.module_src.get(module_name)?;
+
+        let position = lines.line_and_column_number(span.start)?;
+
+        Some(SourceLocation {
+            module: module_name.to_string(),
+            name: name.map(|name| name.to_string()),
+            span,
+            line: position.line,
+            column: position.column,
+        })
+    }
+
     fn build(
         &mut self,
         body: &TypedExpr,
         module_build_name: &str,
         context: &[TypedExpr],
+    ) -> AirTree {
+        let mut air_tree = self.build_expr(body, module_build_name, context);
+
+        if let (TypedExpr::Call { location, .. }, AirTree::Call { location: call, .. }) =
+            (body, &mut air_tree)
+        {
+            *call = self.source_location(
+                module_build_name,
+                self.current_definition.as_deref(),
+                *location,
+            );
+        }
+
+        if context.is_empty() && !self.coverage_regions.is_empty() {
+            let location = body.location();
+
+            if self.coverage_regions.contains(&(
+                module_build_name.to_string(),
+                location.start,
+                location.end,
+            )) {
+                return AirTree::trace(
+                    AirTree::string(coverage::marker(module_build_name, location)),
+                    body.tipo(),
+                    air_tree,
+                );
+            }
+        }
+
+        air_tree
+    }
+
+    /// Build the body of a function, attributing the source locations recorded along the way to
+    /// that function.
+    fn build_function(&mut self, body: &TypedExpr, key: &FunctionAccessKey) -> AirTree {
+        let enclosing = self.current_definition.replace(key.function_name.clone());
+
+        let air_tree = self.build(body, &key.module_name, &[]);
+
+        self.current_definition = enclosing;
+
+        air_tree
+    }
+
+    fn build_expr(
+        &mut self,
+        body: &TypedExpr,
+        module_build_name: &str,
+        context: &[TypedExpr],
     ) -> AirTree {
         if !context.is_empty() {
             let TypedExpr::Assignment {
@@ -3576,8 +3705,7 @@ impl<'a> CodeGenerator<'a> {
                     );
 
                     let mut function_def_types = function_def
-                        .arguments
-                        .iter()
+                        .compiled_arguments()
                         .map(|arg| convert_opaque_type(&arg.tipo, &self.data_types, true))
                         .collect_vec();
 
@@ -3618,7 +3746,7 @@ impl<'a> CodeGenerator<'a> {
                         if let Some((path, _)) = func_variants.get_mut(&variant) {
                             *path = path.common_ancestor(tree_path);
                         } else {
-                            let args = function_def.arguments.clone();
+                            let args = function_def.compiled_arguments().cloned().collect_vec();
 
                             let params = args
                                 .iter()
@@ -3632,11 +3760,9 @@ impl<'a> CodeGenerator<'a> {
                                 })
                                 .collect_vec();
 
-                            let mut function_air_tree_body = AirTree::no_op(self.build(
-                                &function_def.body,
-                                &generic_function_key.module_name,
-                                &[],
-                            ));
+                            let mut function_air_tree_body = AirTree::no_op(
+                                self.build_function(&function_def.body, &generic_function_key),
+                            );
 
                             function_air_tree_body.traverse_tree_with(&mut |air_tree, _| {
                                 erase_opaque_type_operations(air_tree, &self.data_types);
@@ -3662,7 +3788,7 @@ impl<'a> CodeGenerator<'a> {
                             );
                         }
                     } else {
-                        let args = function_def.arguments.clone();
+                        let args = function_def.compiled_arguments().cloned().collect_vec();
 
                         let params = args
                             .iter()
@@ -3674,11 +3800,9 @@ impl<'a> CodeGenerator<'a> {
                             })
                             .collect_vec();
 
-                        let mut function_air_tree_body = AirTree::no_op(self.build(
-                            &function_def.body,
-                            &generic_function_key.module_name,
-                            &[],
-                        ));
+                        let mut function_air_tree_body = AirTree::no_op(
+                            self.build_function(&function_def.body, &generic_function_key),
+                        );
 
                         function_air_tree_body.traverse_tree_with(&mut |air_tree, _| {
                             erase_opaque_type_operations(air_tree, &self.data_types);
@@ -4084,8 +4208,10 @@ impl<'a> CodeGenerator<'a> {
                     Some(term)
                 }
             }
-            Air::Call { count, .. } => {
-                if count >= 1 {
+            Air::Call {
+                count, location, ..
+            } => {
+                let term = if count >= 1 {
                     let mut term = arg_stack.pop().unwrap();
 
                     for _ in 0..count {
@@ -4093,20 +4219,26 @@ impl<'a> CodeGenerator<'a> {
 
                         term = term.apply(arg);
                     }
-                    Some(term)
+                    term
                 } else {
                     let term = arg_stack.pop().unwrap();
 
                     match term.pierce_no_inlines() {
-                        Term::Var(_) => Some(term.force()),
-                        Term::Delay(inner_term) => Some(inner_term.as_ref().clone()),
-                        Term::Apply { .. } => Some(term.force()),
+                        Term::Var(_) => term.force(),
+                        Term::Delay(inner_term) => inner_term.as_ref().clone(),
+                        Term::Apply { .. } => term.force(),
                         _ => unreachable!(
                             "Shouldn't call anything other than var or apply\n{:#?}",
                             term
                         ),
                     }
+                };
+
+                if let Some(location) = location {
+                    self.term_locations.insert(&term, location);
                 }
+
+                Some(term)
             }
             Air::Builtin { func, tipo, count } => {
                 let mut arg_vec = vec![];
@@ -4269,6 +4401,16 @@ impl<'a> CodeGenerator<'a> {
                 variant_name,
                 variant,
             } => {
+                let location = self
+                    .functions
+                    .get(&FunctionAccessKey {
+                        module_name: module_name.clone(),
+                        function_name: func_name.clone(),
+                    })
+                    .and_then(|function| {
+                        self.source_location(&module_name, Some(&func_name), function.location)
+                    });
+
                 let func_name = if module_name.is_empty() {
                     format!("{func_name}{variant_name}")
                 } else {
@@ -4290,6 +4432,10 @@ impl<'a> CodeGenerator<'a> {
                             .rfold(func_body, |term, arg| term.lambda(arg))
                             .lambda(NO_INLINE);
 
+                        if let Some(location) = location {
+                            self.term_locations.insert(&func_body, location);
+                        }
+
                         Some(term.lambda(func_name).apply(func_body))
                     }
                     air::FunctionVariants::Recursive {
@@ -4313,6 +4459,12 @@ impl<'a> CodeGenerator<'a> {
                         let func_body = func_body.lambda(func_name.clone());
 
                         if no_statics {
+                            let func_body = func_body.lambda(NO_INLINE);
+
+                            if let Some(location) = location {
+                                self.term_locations.insert(&func_body, location);
+                            }
+
                             // If we don't have any recursive-static params, we can just emit the function as is
                             Some(
                                 term.lambda(func_name.clone())
@@ -4321,7 +4473,7 @@ impl<'a> CodeGenerator<'a> {
                                             .apply(Term::var(func_name.clone())),
                                     )
                                     .lambda(func_name)
-                                    .apply(func_body.lambda(NO_INLINE)),
+                                    .apply(func_body),
                             )
                         } else {
                             // If we have parameters that remain static in each recursive call,
@@ -4352,11 +4504,14 @@ impl<'a> CodeGenerator<'a> {
                                 .into_iter()
                                 .rfold(outer_func_body, |term, arg| term.lambda(arg));
 
+                            let outer_func_body = outer_func_body.lambda(NO_INLINE);
+
+                            if let Some(location) = location {
+                                self.term_locations.insert(&outer_func_body, location);
+                            }
+
                             // And finally, fold that definition into the rest of our program
-                            Some(
-                                term.lambda(&func_name)
-                                    .apply(outer_func_body.lambda(NO_INLINE)),
-                            )
+                            Some(term.lambda(&func_name).apply(outer_func_body))
                         }
                     }
                     air::FunctionVariants::Cyclic(contained_functions) => {
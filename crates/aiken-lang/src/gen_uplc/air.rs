@@ -1,3 +1,4 @@
+use super::source_map::SourceLocation;
 use crate::{
     ast::{BinOp, Curve, UnOp},
     tipo::{Type, ValueConstructor},
@@ -73,6 +74,7 @@ pub enum Air {
     Call {
         count: usize,
         tipo: Rc<Type>,
+        location: Option<SourceLocation>,
     },
     DefineFunc {
         func_name: String,
@@ -0,0 +1,383 @@
+use crate::{
+    ast::{Definition, Span, TypedDefinition},
+    expr::TypedExpr,
+    line_numbers::LineNumbers,
+};
+use std::{collections::BTreeMap, fmt::Write, path::Path};
+
+/// Traces emitted by instrumented programs start with this byte. Like labels in property-based
+/// tests, this is a convention which should be sufficient to prevent clashes with user traces.
+const MARKER: char = '\u{1}';
+
+/// A piece of Aiken code whose evaluation is tracked when instrumenting programs for coverage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub module: String,
+    /// The (top-level) function or validator handler the region belongs to.
+    pub function: String,
+    pub span: Span,
+    pub kind: RegionKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionKind {
+    /// The body of a function or validator handler.
+    Function,
+    /// One of the alternatives (by index) of the n-th `when` or `if` expression in a module.
+    Branch { block: usize, branch: usize },
+}
+
+impl Region {
+    fn key(&self) -> (String, usize, usize) {
+        (self.module.clone(), self.span.start, self.span.end)
+    }
+}
+
+/// Collect all coverage regions of a module: function and handler bodies, as well as each branch
+/// of `when` and `if` expressions. Tests and benchmarks are left out.
+pub fn regions<'a>(
+    module: &str,
+    definitions: impl Iterator<Item = &'a TypedDefinition>,
+) -> Vec<Region> {
+    let mut regions = Vec::new();
+
+    let mut blocks = 0;
+
+    let mut function = |name: String, body: &TypedExpr, regions: &mut Vec<Region>| {
+        regions.push(Region {
+            module: module.to_string(),
+            function: name.clone(),
+            span: body.location(),
+            kind: RegionKind::Function,
+        });
+        branches(module, &name, body, &mut blocks, regions);
+    };
+
+    for definition in definitions {
+        match definition {
+            Definition::Fn(fun) => function(fun.name.clone(), &fun.body, &mut regions),
+            Definition::Validator(validator) => {
+                for handler in validator.handlers.iter() {
+                    function(
+                        format!("{}.{}", validator.name, handler.name),
+                        &handler.body,
+                        &mut regions,
+                    );
+                }
+
+                // The default fallback isn't part of the source code.
+                if validator.fallback.location != validator.location {
+                    function(
+                        format!("{}.{}", validator.name, validator.fallback.name),
+                        &validator.fallback.body,
+                        &mut regions,
+                    );
+                }
+            }
+            Definition::Instance(instance) => {
+                for (key, method) in instance.into_function_definitions(module) {
+                    function(key.function_name, &method.body, &mut regions);
+                }
+            }
+            Definition::TypeAlias(_)
+            | Definition::DataType(_)
+            | Definition::Use(_)
+            | Definition::ModuleConstant(_)
+            | Definition::Test(_)
+            | Definition::Benchmark(_)
+            | Definition::Interface(_) => {}
+        }
+    }
+
+    regions
+}
+
+fn branches(
+    module: &str,
+    function: &str,
+    expr: &TypedExpr,
+    blocks: &mut usize,
+    regions: &mut Vec<Region>,
+) {
+    let branch = |block: usize, branch: usize, span: Span, regions: &mut Vec<Region>| {
+        regions.push(Region {
+            module: module.to_string(),
+            function: function.to_string(),
+            span,
+            kind: RegionKind::Branch { block, branch },
+        })
+    };
+
+    let children: Vec<&TypedExpr> = match expr {
+        TypedExpr::UInt { .. }
+        | TypedExpr::String { .. }
+        | TypedExpr::ByteArray { .. }
+        | TypedExpr::CurvePoint { .. }
+        | TypedExpr::Var { .. }
+        | TypedExpr::ModuleSelect { .. }
+        | TypedExpr::ErrorTerm { .. } => vec![],
+        TypedExpr::Sequence { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+            expressions.iter().collect()
+        }
+        TypedExpr::Fn { body, .. } => vec![body],
+        TypedExpr::List { elements, tail, .. } => elements
+            .iter()
+            .chain(tail.iter().map(|e| e.as_ref()))
+            .collect(),
+        TypedExpr::Call { fun, args, .. } => std::iter::once(fun.as_ref())
+            .chain(args.iter().map(|arg| &arg.value))
+            .collect(),
+        TypedExpr::BinOp { left, right, .. } => vec![left, right],
+        TypedExpr::Assignment { value, .. } => vec![value],
+        TypedExpr::Trace { then, text, .. } => vec![text, then],
+        TypedExpr::When {
+            subject, clauses, ..
+        } => {
+            let block = *blocks;
+            *blocks += 1;
+            for (index, clause) in clauses.iter().enumerate() {
+                branch(block, index, clause.then.location(), regions);
+            }
+            std::iter::once(subject.as_ref())
+                .chain(clauses.iter().map(|clause| &clause.then))
+                .collect()
+        }
+        TypedExpr::If {
+            branches,
+            final_else,
+            ..
+        } => {
+            let block = *blocks;
+            *blocks += 1;
+            for (index, if_branch) in branches.iter().enumerate() {
+                branch(block, index, if_branch.body.location(), regions);
+            }
+            branch(block, branches.len(), final_else.location(), regions);
+            branches
+                .iter()
+                .flat_map(|if_branch| [&if_branch.condition, &if_branch.body])
+                .chain(std::iter::once(final_else.as_ref()))
+                .collect()
+        }
+        TypedExpr::RecordAccess { record, .. } => vec![record],
+        TypedExpr::Tuple { elems, .. } => elems.iter().collect(),
+        TypedExpr::Pair { fst, snd, .. } => vec![fst, snd],
+        TypedExpr::TupleIndex { tuple, .. } => vec![tuple],
+        TypedExpr::RecordUpdate { spread, args, .. } => std::iter::once(spread.as_ref())
+            .chain(args.iter().map(|arg| &arg.value))
+            .collect(),
+        TypedExpr::UnOp { value, .. } => vec![value],
+    };
+
+    for child in children {
+        branches(module, function, child, blocks, regions);
+    }
+}
+
+/// The trace emitted by an instrumented program when entering a region.
+pub fn marker(module: &str, span: Span) -> String {
+    format!("{MARKER}{module}:{}:{}", span.start, span.end)
+}
+
+fn extract_marker(trace: &str) -> Option<(String, usize, usize)> {
+    let mut parts = trace.strip_prefix(MARKER)?.rsplitn(3, ':');
+    let end = parts.next()?.parse().ok()?;
+    let start = parts.next()?.parse().ok()?;
+    let module = parts.next()?.to_string();
+    Some((module, start, end))
+}
+
+/// How many times each region was entered, across one or many evaluations.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Coverage(BTreeMap<(String, usize, usize), usize>);
+
+impl Coverage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Record the coverage markers found in some traces, and return the other traces.
+    pub fn record(&mut self, traces: Vec<String>) -> Vec<String> {
+        traces
+            .into_iter()
+            .filter(|trace| match extract_marker(trace) {
+                Some(key) => {
+                    *self.0.entry(key).or_insert(0) += 1;
+                    false
+                }
+                None => true,
+            })
+            .collect()
+    }
+
+    pub fn merge(&mut self, other: &Coverage) {
+        for (key, hits) in other.0.iter() {
+            *self.0.entry(key.clone()).or_insert(0) += hits;
+        }
+    }
+
+    pub fn hits(&self, region: &Region) -> usize {
+        self.0.get(&region.key()).copied().unwrap_or(0)
+    }
+
+    /// Summarize the coverage of some regions, per module.
+    pub fn summary(&self, regions: &[Region]) -> BTreeMap<String, CoverageSummary> {
+        let mut summary: BTreeMap<String, CoverageSummary> = BTreeMap::new();
+
+        for region in regions {
+            let hit = self.hits(region) > 0;
+
+            let module = summary.entry(region.module.clone()).or_default();
+
+            let (total, covered) = match region.kind {
+                RegionKind::Function => (&mut module.functions, &mut module.functions_covered),
+                RegionKind::Branch { .. } => (&mut module.branches, &mut module.branches_covered),
+            };
+
+            *total += 1;
+            if hit {
+                *covered += 1;
+            }
+        }
+
+        summary
+    }
+
+    /// Render the coverage of a module's regions as an lcov tracefile record. Lines are those on
+    /// which regions start.
+    pub fn to_lcov(&self, path: &Path, line_numbers: &LineNumbers, regions: &[Region]) -> String {
+        let line = |span: Span| line_numbers.line_number(span.start).unwrap_or(1);
+
+        let mut lcov = String::new();
+
+        let _ = writeln!(lcov, "TN:");
+        let _ = writeln!(lcov, "SF:{}", path.display());
+
+        let functions = regions
+            .iter()
+            .filter(|region| region.kind == RegionKind::Function)
+            .collect::<Vec<_>>();
+
+        for region in functions.iter() {
+            let _ = writeln!(lcov, "FN:{},{}", line(region.span), region.function);
+        }
+        for region in functions.iter() {
+            let _ = writeln!(lcov, "FNDA:{},{}", self.hits(region), region.function);
+        }
+        let _ = writeln!(lcov, "FNF:{}", functions.len());
+        let _ = writeln!(
+            lcov,
+            "FNH:{}",
+            functions.iter().filter(|r| self.hits(r) > 0).count()
+        );
+
+        let mut branches = (0, 0);
+        for region in regions {
+            if let RegionKind::Branch { block, branch } = region.kind {
+                let hits = self.hits(region);
+                branches.0 += 1;
+                if hits > 0 {
+                    branches.1 += 1;
+                }
+                let _ = writeln!(lcov, "BRDA:{},{block},{branch},{hits}", line(region.span));
+            }
+        }
+        let _ = writeln!(lcov, "BRF:{}", branches.0);
+        let _ = writeln!(lcov, "BRH:{}", branches.1);
+
+        let mut lines: BTreeMap<usize, usize> = BTreeMap::new();
+        for region in regions {
+            *lines.entry(line(region.span)).or_insert(0) += self.hits(region);
+        }
+        for (line, hits) in lines.iter() {
+            let _ = writeln!(lcov, "DA:{line},{hits}");
+        }
+        let _ = writeln!(lcov, "LF:{}", lines.len());
+        let _ = writeln!(
+            lcov,
+            "LH:{}",
+            lines.values().filter(|hits| **hits > 0).count()
+        );
+
+        let _ = writeln!(lcov, "end_of_record");
+
+        lcov
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CoverageSummary {
+    pub functions: usize,
+    pub functions_covered: usize,
+    pub branches: usize,
+    pub branches_covered: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_marker, marker, Coverage, Region, RegionKind};
+    use crate::ast::Span;
+
+    fn region(start: usize, end: usize, kind: RegionKind) -> Region {
+        Region {
+            module: "foo/bar".to_string(),
+            function: "baz".to_string(),
+            span: Span::create(start, end - start),
+            kind,
+        }
+    }
+
+    #[test]
+    fn marker_roundtrip() {
+        let marker = marker("foo/bar", Span::create(14, 28));
+        assert_eq!(
+            extract_marker(&marker),
+            Some(("foo/bar".to_string(), 14, 42))
+        );
+        assert_eq!(extract_marker("foo/bar:14:42"), None);
+    }
+
+    #[test]
+    fn coverage_summary() {
+        let regions = vec![
+            region(0, 10, RegionKind::Function),
+            region(
+                2,
+                5,
+                RegionKind::Branch {
+                    block: 0,
+                    branch: 0,
+                },
+            ),
+            region(
+                5,
+                9,
+                RegionKind::Branch {
+                    block: 0,
+                    branch: 1,
+                },
+            ),
+        ];
+
+        let mut coverage = Coverage::new();
+
+        let traces = coverage.record(vec![
+            super::marker("foo/bar", Span::create(0, 10)),
+            "hello".to_string(),
+            super::marker("foo/bar", Span::create(2, 3)),
+            super::marker("foo/bar", Span::create(0, 10)),
+        ]);
+
+        assert_eq!(traces, vec!["hello".to_string()]);
+        assert_eq!(coverage.hits(&regions[0]), 2);
+
+        let summary = coverage.summary(&regions);
+        let summary = summary.get("foo/bar").unwrap();
+        assert_eq!((summary.functions, summary.functions_covered), (1, 1));
+        assert_eq!((summary.branches, summary.branches_covered), (2, 1));
+    }
+}
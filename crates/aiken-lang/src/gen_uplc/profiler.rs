@@ -0,0 +1,279 @@
+use super::source_map::{SourceMap, TermPath};
+use crate::plutus_version::PlutusVersion;
+use std::{collections::BTreeMap, collections::HashMap, fmt::Write};
+use uplc::{
+    ast::{NamedDeBruijn, Program, Term},
+    machine::{
+        cost_model::{CostModel, ExBudget},
+        debugger::pending_builtin,
+        Error, Machine, MachineState,
+    },
+};
+
+/// Execution costs of a program, attributed to stacks of Aiken functions and builtins.
+///
+/// Stacks are reconstructed from the source map of the program: every term is attributed to the
+/// closest Aiken definition enclosing it, and a definition is considered to have returned once
+/// the machine goes back to the frames that were there before entering it. This is only an
+/// approximation of the actual call stack (e.g. recursive calls are collapsed into one frame) but
+/// it is enough to find out where the budget goes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile(BTreeMap<Vec<String>, ExBudget>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Mem,
+    Cpu,
+}
+
+impl Profile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn add(&mut self, stack: Vec<String>, cost: ExBudget) {
+        if cost.mem == 0 && cost.cpu == 0 {
+            return;
+        }
+
+        let entry = self.0.entry(stack).or_insert(ExBudget { mem: 0, cpu: 0 });
+        entry.mem += cost.mem;
+        entry.cpu += cost.cpu;
+    }
+
+    pub fn merge(&mut self, other: Profile) {
+        for (stack, cost) in other.0 {
+            self.add(stack, cost);
+        }
+    }
+
+    pub fn total(&self) -> ExBudget {
+        self.0
+            .values()
+            .fold(ExBudget { mem: 0, cpu: 0 }, |total, cost| ExBudget {
+                mem: total.mem + cost.mem,
+                cpu: total.cpu + cost.cpu,
+            })
+    }
+
+    /// Costs spent directly within each frame (i.e. excluding callees), ordered from the most to
+    /// the least expensive for the given metric.
+    pub fn self_costs(&self, metric: Metric) -> Vec<(String, ExBudget)> {
+        let mut costs: BTreeMap<&str, ExBudget> = BTreeMap::new();
+
+        for (stack, cost) in self.0.iter() {
+            if let Some(frame) = stack.last() {
+                let entry = costs.entry(frame).or_insert(ExBudget { mem: 0, cpu: 0 });
+                entry.mem += cost.mem;
+                entry.cpu += cost.cpu;
+            }
+        }
+
+        let mut costs = costs
+            .into_iter()
+            .map(|(frame, cost)| (frame.to_string(), cost))
+            .collect::<Vec<_>>();
+
+        costs.sort_by_key(|(_, cost)| std::cmp::Reverse(metric.of(cost)));
+
+        costs
+    }
+
+    /// Render the profile in the 'folded stacks' format understood by most flame-graph tools;
+    /// one line per stack, with frames separated by semi-colons and followed by their cost.
+    pub fn to_folded(&self, metric: Metric) -> String {
+        let mut folded = String::new();
+
+        for (stack, cost) in self.0.iter() {
+            let _ = writeln!(folded, "{} {}", stack.join(";"), metric.of(cost));
+        }
+
+        folded
+    }
+}
+
+impl Metric {
+    fn of(&self, cost: &ExBudget) -> i64 {
+        match self {
+            Metric::Mem => cost.mem,
+            Metric::Cpu => cost.cpu,
+        }
+    }
+}
+
+/// Evaluate a program step by step, recording where its budget is spent. The `root` label names
+/// the outermost frame, used for anything that isn't attributable to a known definition.
+pub fn profile(
+    program: Program<NamedDeBruijn>,
+    source_map: &SourceMap,
+    root: &str,
+    plutus_version: &PlutusVersion,
+    cost_model: CostModel,
+) -> (Result<Term<NamedDeBruijn>, Error>, Profile) {
+    let labels = index_terms(&program.term, source_map, root);
+
+    // A slippage of 1 makes the machine spend its budget on every step, so that costs can be
+    // attributed to the step that incurred them.
+    let mut machine = Machine::new(plutus_version.into(), cost_model, ExBudget::max(), 1);
+
+    let mut profile = Profile::new();
+
+    let mut stack: Vec<(String, isize)> = vec![(root.to_string(), 0)];
+
+    // Depth of the machine's context, kept up to date step after step; walking the context on
+    // every step would make profiling quadratic in the depth of the evaluation.
+    let mut depth = 0;
+
+    let mut budget = machine.ex_budget;
+
+    let mut state = match machine.start(program.term) {
+        Ok(state) => state,
+        Err(err) => return (Err(err), profile),
+    };
+
+    profile.add(vec![root.to_string()], budget - machine.ex_budget);
+
+    loop {
+        budget = machine.ex_budget;
+
+        let builtin = match state {
+            MachineState::Done(term) => return (Ok(term), profile),
+            MachineState::Compute(_, _, ref term) => {
+                if let Some(label) = children_ptrs(term).find_map(|ptr| labels.get(&ptr)) {
+                    enter(&mut stack, label, depth);
+                }
+                None
+            }
+            MachineState::Return(..) => pending_builtin(&state),
+        };
+
+        depth += state.frames_delta();
+
+        let result = machine.step(state);
+
+        let mut frames = stack
+            .iter()
+            .map(|(label, _)| label.clone())
+            .collect::<Vec<_>>();
+        if let Some(builtin) = builtin {
+            frames.push(format!("builtin.{}", builtin.aiken_name()));
+        }
+
+        profile.add(frames, budget - machine.ex_budget);
+
+        state = match result {
+            Ok(state) => state,
+            Err(err) => return (Err(err), profile),
+        };
+    }
+}
+
+fn enter(stack: &mut Vec<(String, isize)>, label: &str, depth: isize) {
+    while stack.len() > 1 && stack.last().map(|(_, d)| *d > depth).unwrap_or(false) {
+        stack.pop();
+    }
+
+    match stack.iter().rposition(|(frame, _)| frame == label) {
+        Some(ix) => stack.truncate(ix + 1),
+        None => stack.push((label.to_string(), depth)),
+    }
+}
+
+/// Associate every shared sub-term of a program to the label of the definition enclosing it.
+/// Sub-terms are identified by address, which remains stable throughout the evaluation since the
+/// machine only ever clones the reference-counted pointers.
+fn index_terms(
+    term: &Term<NamedDeBruijn>,
+    source_map: &SourceMap,
+    root: &str,
+) -> HashMap<usize, String> {
+    let mut labels = HashMap::new();
+
+    let mut stack = vec![(TermPath::root(), term)];
+
+    while let Some((path, term)) = stack.pop() {
+        let children = match term {
+            Term::Delay(body) | Term::Force(body) | Term::Lambda { body, .. } => {
+                vec![(0, body.as_ref())]
+            }
+            Term::Apply { function, argument } => {
+                vec![(0, function.as_ref()), (1, argument.as_ref())]
+            }
+            Term::Case { constr, branches } => std::iter::once(constr.as_ref())
+                .chain(branches.iter())
+                .enumerate()
+                .collect(),
+            Term::Constr { fields, .. } => fields.iter().enumerate().collect(),
+            Term::Var(_) | Term::Constant(_) | Term::Builtin(_) | Term::Error => vec![],
+        };
+
+        for (index, child) in children {
+            let path = path.child(index);
+
+            if let Some(location) = source_map.lookup(&path) {
+                let label = match location.name {
+                    Some(ref name) => format!("{}.{name}", location.module),
+                    None => root.to_string(),
+                };
+                labels.insert(child as *const _ as usize, label);
+            }
+
+            stack.push((path, child));
+        }
+    }
+
+    labels
+}
+
+fn children_ptrs(term: &Term<NamedDeBruijn>) -> impl Iterator<Item = usize> + '_ {
+    let ptrs: Vec<usize> = match term {
+        Term::Delay(body) | Term::Force(body) | Term::Lambda { body, .. } => {
+            vec![body.as_ref() as *const _ as usize]
+        }
+        Term::Apply { function, argument } => vec![
+            function.as_ref() as *const _ as usize,
+            argument.as_ref() as *const _ as usize,
+        ],
+        Term::Case { constr, .. } => vec![constr.as_ref() as *const _ as usize],
+        Term::Constr { .. } | Term::Var(_) | Term::Constant(_) | Term::Builtin(_) | Term::Error => {
+            vec![]
+        }
+    };
+
+    ptrs.into_iter()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Metric, Profile};
+    use uplc::machine::cost_model::ExBudget;
+
+    fn stack(frames: &[&str]) -> Vec<String> {
+        frames.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn profile_folded() {
+        let mut profile = Profile::new();
+
+        profile.add(stack(&["test"]), ExBudget { mem: 1, cpu: 10 });
+        profile.add(stack(&["test", "foo.bar"]), ExBudget { mem: 2, cpu: 20 });
+        profile.add(stack(&["test"]), ExBudget { mem: 3, cpu: 30 });
+        profile.add(stack(&["test", "foo.baz"]), ExBudget { mem: 0, cpu: 0 });
+
+        assert_eq!(profile.to_folded(Metric::Cpu), "test 40\ntest;foo.bar 20\n");
+        assert_eq!(profile.to_folded(Metric::Mem), "test 4\ntest;foo.bar 2\n");
+        assert_eq!(profile.total(), ExBudget { mem: 6, cpu: 60 });
+        assert_eq!(
+            profile.self_costs(Metric::Cpu),
+            vec![
+                ("test".to_string(), ExBudget { mem: 4, cpu: 40 }),
+                ("foo.bar".to_string(), ExBudget { mem: 2, cpu: 20 }),
+            ]
+        );
+    }
+}
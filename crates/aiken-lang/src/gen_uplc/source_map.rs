@@ -0,0 +1,325 @@
+use crate::ast::Span;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    mem::{self, Discriminant},
+    rc::Rc,
+    str::FromStr,
+};
+use uplc::{
+    ast::{Name, Term},
+    optimize::annotations::Annotations,
+};
+
+/// A path from the root of a program down to one of its sub-terms. Each step selects a child of
+/// the current term:
+///
+/// - `delay`, `force` and `lam` have a single child: their body (0);
+/// - `apply` has two children: the function (0) and the argument (1);
+/// - `constr` has one child per field;
+/// - `case` has the scrutinee (0) followed by each branch (1..).
+///
+/// Paths are rendered as dot-separated indexes (e.g. `0.1.1`); the empty path designates the root.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TermPath(Vec<usize>);
+
+impl TermPath {
+    pub fn root() -> Self {
+        TermPath(vec![])
+    }
+
+    pub fn child(&self, index: usize) -> Self {
+        let mut steps = self.0.clone();
+        steps.push(index);
+        TermPath(steps)
+    }
+
+    pub fn steps(&self) -> &[usize] {
+        &self.0
+    }
+
+    /// Whether this path is a prefix of (or equal to) another one.
+    pub fn contains(&self, other: &TermPath) -> bool {
+        other.0.starts_with(&self.0)
+    }
+
+    /// Resolve the path against a term, if it leads anywhere.
+    pub fn resolve<'a, T>(&self, term: &'a Term<T>) -> Option<&'a Term<T>> {
+        self.0
+            .iter()
+            .try_fold(term, |term, index| children(term).into_iter().nth(*index))
+    }
+}
+
+impl fmt::Display for TermPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.0
+                .iter()
+                .map(|step| step.to_string())
+                .collect::<Vec<_>>()
+                .join(".")
+        )
+    }
+}
+
+impl FromStr for TermPath {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(TermPath::root());
+        }
+
+        s.split('.')
+            .map(|step| step.parse())
+            .collect::<Result<_, _>>()
+            .map(TermPath)
+    }
+}
+
+impl serde::Serialize for TermPath {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for TermPath {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Where a sub-term of a generated program originates from in the Aiken sources.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SourceLocation {
+    pub module: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub name: Option<String>,
+    pub span: Span,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Maps paths of a generated (and optimized) program to the Aiken sources they come from: the
+/// definitions of functions that survived optimizations, as well as function calls. Entries
+/// are named after the definition they belong to, so that any sub-term can be attributed to its
+/// closest enclosing Aiken definition.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct SourceMap(BTreeMap<TermPath, SourceLocation>);
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn insert(&mut self, path: TermPath, location: SourceLocation) {
+        self.0.insert(path, location);
+    }
+
+    pub fn get(&self, path: &TermPath) -> Option<&SourceLocation> {
+        self.0.get(path)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&TermPath, &SourceLocation)> {
+        self.0.iter()
+    }
+
+    /// Find the location of the closest term enclosing the given path.
+    pub fn lookup(&self, path: &TermPath) -> Option<&SourceLocation> {
+        self.0
+            .range(..=path.clone())
+            .rev()
+            .find(|(prefix, _)| prefix.contains(path))
+            .map(|(_, location)| location)
+    }
+}
+
+/// Locations of the terms produced while lowering AIR, recorded before optimizations.
+///
+/// Terms have no identity of their own; so a term is identified by the (reference-counted)
+/// sub-terms it is made of, which are kept alive until the program is complete so that their
+/// addresses can't be re-used by other terms.
+#[derive(Debug, Clone, Default)]
+pub struct TermLocations(Vec<(Anchor, SourceLocation)>);
+
+type Anchor = (Discriminant<Term<Name>>, Vec<Rc<Term<Name>>>);
+
+impl TermLocations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the location of a term. Leaves have no sub-terms to be identified by, and are
+    /// ignored.
+    pub fn insert(&mut self, term: &Term<Name>, location: SourceLocation) {
+        if let Some(anchor) = anchor(term) {
+            self.0.push((anchor, location));
+        }
+    }
+
+    /// Find the recorded terms in a complete program, annotating their paths with the index of
+    /// their location.
+    pub fn annotate(&self, term: &Term<Name>) -> Annotations {
+        let mut indexes = HashMap::new();
+
+        for (index, (anchor, _)) in self.0.iter().enumerate() {
+            indexes.entry(key(anchor)).or_insert(index);
+        }
+
+        let mut annotations = Annotations::new();
+
+        let mut stack = vec![(vec![], term)];
+
+        while let Some((path, term)) = stack.pop() {
+            if let Some(index) = anchor(term).and_then(|anchor| indexes.get(&key(&anchor))) {
+                annotations.insert(path.clone(), *index);
+            }
+
+            stack.extend(
+                children(term)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, child)| {
+                        let mut path = path.clone();
+                        path.push(index);
+                        (path, child)
+                    }),
+            );
+        }
+
+        annotations
+    }
+
+    /// Build the source map of a program from its (optimized) annotations, as obtained from
+    /// [`Self::annotate`].
+    pub fn into_source_map(
+        self,
+        annotations: &Annotations,
+        root: Option<SourceLocation>,
+    ) -> SourceMap {
+        let locations = self
+            .0
+            .into_iter()
+            .map(|(_, location)| location)
+            .collect::<Vec<_>>();
+
+        let mut source_map = SourceMap::new();
+
+        if let Some(root) = root {
+            source_map.insert(TermPath::root(), root);
+        }
+
+        for (path, index) in annotations.iter() {
+            if let Some(location) = locations.get(index).cloned() {
+                source_map.insert(TermPath(path.to_vec()), location);
+            }
+        }
+
+        source_map
+    }
+}
+
+fn anchor(term: &Term<Name>) -> Option<Anchor> {
+    let children = match term {
+        Term::Delay(body) | Term::Force(body) | Term::Lambda { body, .. } => vec![body.clone()],
+        Term::Apply { function, argument } => vec![function.clone(), argument.clone()],
+        _ => return None,
+    };
+
+    Some((mem::discriminant(term), children))
+}
+
+fn key((discriminant, children): &Anchor) -> (Discriminant<Term<Name>>, Vec<*const Term<Name>>) {
+    (*discriminant, children.iter().map(Rc::as_ptr).collect())
+}
+
+fn children<T>(term: &Term<T>) -> Vec<&Term<T>> {
+    match term {
+        Term::Delay(body) | Term::Force(body) | Term::Lambda { body, .. } => vec![body.as_ref()],
+        Term::Apply { function, argument } => vec![function.as_ref(), argument.as_ref()],
+        Term::Constr { fields, .. } => fields.iter().collect(),
+        Term::Case { constr, branches } => std::iter::once(constr.as_ref())
+            .chain(branches.iter())
+            .collect(),
+        Term::Var(_) | Term::Constant(_) | Term::Builtin(_) | Term::Error => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SourceLocation, TermLocations, TermPath};
+    use crate::ast::Span;
+    use uplc::ast::{Name, Term};
+
+    fn location(name: Option<&str>, start: usize) -> SourceLocation {
+        SourceLocation {
+            module: "foo".to_string(),
+            name: name.map(|name| name.to_string()),
+            span: Span::create(start, 1),
+            line: 1,
+            column: start + 1,
+        }
+    }
+
+    #[test]
+    fn term_path_roundtrip() {
+        for path in ["", "0", "0.1.1", "1.0.12"] {
+            assert_eq!(path.parse::<TermPath>().unwrap().to_string(), path);
+        }
+    }
+
+    #[test]
+    fn source_map_from_term_locations() {
+        let mut locations = TermLocations::new();
+
+        let call_main: Term<Name> = Term::var("foo_bar").apply(Term::integer(14.into()));
+        locations.insert(&call_main, location(Some("main"), 10));
+
+        let call_bar: Term<Name> = Term::var("g").apply(Term::var("x"));
+        locations.insert(&call_bar, location(Some("bar"), 20));
+
+        let bar = call_bar.lambda("x");
+        locations.insert(&bar, location(Some("bar"), 0));
+
+        // [(lam foo_bar [foo_bar (con integer 14)]) (lam x [g x])]
+        let term = call_main.lambda("foo_bar").apply(bar);
+
+        let annotations = locations.annotate(&term);
+
+        assert_eq!(annotations.len(), 3);
+
+        let source_map = locations.into_source_map(&annotations, Some(location(Some("main"), 5)));
+
+        assert_eq!(source_map.len(), 4);
+        assert_eq!(
+            source_map.get(&"1".parse().unwrap()),
+            Some(&location(Some("bar"), 0))
+        );
+        assert_eq!(
+            source_map.get(&"1.0".parse().unwrap()),
+            Some(&location(Some("bar"), 20))
+        );
+        assert_eq!(
+            source_map.get(&"0.0".parse().unwrap()),
+            Some(&location(Some("main"), 10))
+        );
+
+        let arg: TermPath = "1.0.1".parse().unwrap();
+        assert_eq!(arg.resolve(&term), Some(&Term::var("x")));
+        assert_eq!(source_map.lookup(&arg), Some(&location(Some("bar"), 20)));
+    }
+}
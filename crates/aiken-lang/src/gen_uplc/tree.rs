@@ -1,4 +1,7 @@
-use super::air::{Air, ExpectLevel, FunctionVariants};
+use super::{
+    air::{Air, ExpectLevel, FunctionVariants},
+    source_map::SourceLocation,
+};
 use crate::{
     ast::{BinOp, Curve, Span, UnOp},
     tipo::{Type, ValueConstructor, ValueConstructorVariant},
@@ -254,6 +257,7 @@ pub enum AirTree {
         tipo: Rc<Type>,
         func: Box<AirTree>,
         args: Vec<AirTree>,
+        location: Option<SourceLocation>,
     },
 
     Fn {
@@ -436,6 +440,7 @@ impl AirTree {
             tipo,
             func: func.into(),
             args,
+            location: None,
         }
     }
 
@@ -1093,10 +1098,16 @@ impl AirTree {
                 name: name.clone(),
                 variant_name: variant_name.clone(),
             }),
-            AirTree::Call { tipo, func, args } => {
+            AirTree::Call {
+                tipo,
+                func,
+                args,
+                location,
+            } => {
                 air_vec.push(Air::Call {
                     count: args.len(),
                     tipo: tipo.clone(),
+                    location: location.clone(),
                 });
                 func.create_air_vec(air_vec);
                 for arg in args {
@@ -1651,6 +1662,7 @@ impl AirTree {
                 tipo: _,
                 func,
                 args,
+                location: _,
             } => {
                 func.do_traverse_tree_with(tree_path, current_depth + 1, Fields::SecondField, with);
 
@@ -2117,6 +2129,7 @@ impl AirTree {
                     tipo: _,
                     func,
                     args,
+                    location: _,
                 } => match field {
                     Fields::SecondField => func.as_mut().do_find_air_tree_node(tree_path_iter),
                     Fields::ArgsField(index) => args
@@ -32,6 +32,17 @@ impl IdGenerator {
     pub fn next(&self) -> u64 {
         self.id.fetch_add(1, Ordering::Relaxed)
     }
+
+    /// The next id to be generated, without consuming it.
+    pub fn peek(&self) -> u64 {
+        self.id.load(Ordering::Relaxed)
+    }
+
+    /// Make sure that ids from now on are at least `id`; for example, to not overlap with ids
+    /// generated by a previous compilation.
+    pub fn skip_to(&self, id: u64) {
+        self.id.fetch_max(id, Ordering::Relaxed);
+    }
 }
 
 #[macro_export]
@@ -0,0 +1,69 @@
+use crate::{
+    ast,
+    ast::OnTestFailure,
+    expr::UntypedExpr,
+    parser::{error::ParseError, expr, token::Token},
+};
+use chumsky::prelude::*;
+
+use super::test::via;
+
+pub fn parser() -> impl Parser<Token, ast::UntypedDefinition, Error = ParseError> {
+    just(Token::Benchmark)
+        .ignore_then(select! {Token::Name {name} => name})
+        .then(
+            via()
+                .separated_by(just(Token::Comma))
+                .allow_trailing()
+                .delimited_by(just(Token::LeftParen), just(Token::RightParen)),
+        )
+        .map_with_span(|name, span| (name, span))
+        .then(
+            expr::sequence()
+                .or_not()
+                .delimited_by(just(Token::LeftBrace), just(Token::RightBrace)),
+        )
+        .map_with_span(|(((name, arguments), span_end), body), span| {
+            ast::UntypedDefinition::Benchmark(ast::Function {
+                arguments,
+                body: body.unwrap_or_else(|| UntypedExpr::todo(None, span)),
+                doc: None,
+                location: span_end,
+                end_position: span.end - 1,
+                name,
+                public: false,
+                return_annotation: None,
+                return_type: (),
+                on_test_failure: OnTestFailure::FailImmediately,
+                constraints: vec![],
+                dictionaries: vec![],
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_definition;
+
+    #[test]
+    fn def_benchmark() {
+        assert_definition!(
+            r#"
+            bench foo(x via fuzz.any_int) {
+                x + 1
+            }
+            "#
+        );
+    }
+
+    #[test]
+    fn def_benchmark_annotated_sampler() {
+        assert_definition!(
+            r#"
+            bench foo(x: List<Int> via list_of_size) {
+                sum(x)
+            }
+            "#
+        );
+    }
+}
@@ -17,13 +17,18 @@ pub fn parser() -> impl Parser<Token, ast::UntypedDefinition, Error = ParseError
                 .map_with_span(|arguments, span| (arguments, span)),
         )
         .then(just(Token::RArrow).ignore_then(annotation()).or_not())
+        .then(constraints())
         .then(
             expr::sequence()
                 .or_not()
                 .delimited_by(just(Token::LeftBrace), just(Token::RightBrace)),
         )
         .map_with_span(
-            |((((public, name), (arguments, args_span)), return_annotation), body), span| {
+            |(
+                ((((public, name), (arguments, args_span)), return_annotation), constraints),
+                body,
+            ),
+             span| {
                 ast::UntypedDefinition::Fn(ast::Function {
                     arguments,
                     body: body.unwrap_or_else(|| UntypedExpr::todo(None, span)),
@@ -41,11 +46,26 @@ pub fn parser() -> impl Parser<Token, ast::UntypedDefinition, Error = ParseError
                     return_annotation,
                     return_type: (),
                     on_test_failure: ast::OnTestFailure::FailImmediately,
+                    constraints,
+                    dictionaries: vec![],
                 })
             },
         )
 }
 
+/// An optional 'where' clause listing interface constraints, e.g. `where Show<a>, Eq<a>`.
+pub fn constraints() -> impl Parser<Token, Vec<ast::Annotation>, Error = ParseError> {
+    utils::contextual_keyword("where")
+        .ignore_then(
+            annotation()
+                .separated_by(just(Token::Comma))
+                .at_least(1)
+                .allow_trailing(),
+        )
+        .or_not()
+        .map(Option::unwrap_or_default)
+}
+
 pub fn param(is_validator_param: bool) -> impl Parser<Token, ast::UntypedArg, Error = ParseError> {
     choice((
         select! {Token::Name {name} => name}
@@ -156,4 +176,15 @@ mod tests {
             "#
         );
     }
+
+    #[test]
+    fn function_with_constraints() {
+        assert_definition!(
+            r#"
+            fn describe(xs: List<a>) -> ByteArray where Show<a>, Eq<a> {
+              todo
+            }
+            "#
+        );
+    }
 }
@@ -0,0 +1,52 @@
+use chumsky::prelude::*;
+
+use crate::{
+    ast,
+    parser::{annotation, error::ParseError, token::Token, utils},
+};
+
+pub fn parser() -> impl Parser<Token, ast::UntypedDefinition, Error = ParseError> {
+    let method = super::function().map(|definition| match definition {
+        ast::UntypedDefinition::Fn(function) => function,
+        _ => unreachable!("function parser returned something else than a function"),
+    });
+
+    utils::contextual_keyword("instance")
+        .ignore_then(annotation())
+        .then(
+            method
+                .repeated()
+                .delimited_by(just(Token::LeftBrace), just(Token::RightBrace)),
+        )
+        .map_with_span(|(interface, methods), span| {
+            ast::UntypedDefinition::Instance(ast::Instance {
+                doc: None,
+                end_position: span.end - 1,
+                location: ast::Span {
+                    start: span.start,
+                    end: interface.location().end,
+                },
+                interface,
+                methods,
+                tipo: (),
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_definition;
+
+    #[test]
+    fn instance_basic() {
+        assert_definition!(
+            r#"
+            instance Show<Int> {
+              fn show(n: Int) -> ByteArray {
+                from_int(n)
+              }
+            }
+            "#
+        );
+    }
+}
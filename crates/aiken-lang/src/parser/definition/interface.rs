@@ -0,0 +1,71 @@
+use chumsky::prelude::*;
+
+use crate::{
+    ast,
+    parser::{annotation, error::ParseError, token::Token, utils},
+};
+
+pub fn parser() -> impl Parser<Token, ast::UntypedDefinition, Error = ParseError> {
+    let method = select! {Token::Name { name } => name}
+        .then_ignore(just(Token::Colon))
+        .then(annotation())
+        .map_with_span(|(name, annotation), span| ast::InterfaceMethod {
+            annotation,
+            doc: None,
+            location: span,
+            name,
+            tipo: (),
+        });
+
+    utils::optional_flag(Token::Pub)
+        .then_ignore(utils::contextual_keyword("interface"))
+        .then(select! {Token::UpName { name } => name})
+        .then(
+            select! {Token::Name { name } => name}
+                .delimited_by(just(Token::Less), just(Token::Greater)),
+        )
+        .then(
+            method
+                .separated_by(just(Token::Comma))
+                .allow_trailing()
+                .delimited_by(just(Token::LeftBrace), just(Token::RightBrace)),
+        )
+        .map_with_span(|(((public, name), parameter), methods), span| {
+            ast::UntypedDefinition::Interface(ast::Interface {
+                doc: None,
+                location: span,
+                methods,
+                name,
+                parameter,
+                public,
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_definition;
+
+    #[test]
+    fn interface_basic() {
+        assert_definition!(
+            r#"
+            pub interface Show<a> {
+              show: fn(a) -> ByteArray,
+            }
+            "#
+        );
+    }
+
+    #[test]
+    fn interface_many_methods() {
+        assert_definition!(
+            r#"
+            interface Ord<a> {
+              compare: fn(a, a) -> Ordering,
+              max: fn(a, a) -> a
+            }
+            "#
+        );
+    }
+}
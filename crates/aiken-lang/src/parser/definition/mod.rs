@@ -1,18 +1,24 @@
 use chumsky::prelude::*;
 
+mod benchmark;
 pub mod constant;
 mod data_type;
 mod function;
 pub mod import;
+mod instance;
+mod interface;
 mod test;
 mod type_alias;
 mod validator;
 
 use super::{error::ParseError, token::Token};
 use crate::ast;
+pub use benchmark::parser as benchmark;
 pub use constant::parser as constant;
 pub use data_type::parser as data_type;
 pub use function::parser as function;
+pub use instance::parser as instance;
+pub use interface::parser as interface;
 pub use test::parser as test;
 pub use type_alias::parser as type_alias;
 pub use validator::parser as validator;
@@ -24,6 +30,9 @@ pub fn parser() -> impl Parser<Token, ast::UntypedDefinition, Error = ParseError
         validator(),
         function(),
         test(),
+        benchmark(),
         constant(),
+        interface(),
+        instance(),
     ))
 }
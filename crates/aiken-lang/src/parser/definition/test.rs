@@ -48,6 +48,8 @@ pub fn parser() -> impl Parser<Token, ast::UntypedDefinition, Error = ParseError
                 return_annotation: None,
                 return_type: (),
                 on_test_failure: fail.unwrap_or(OnTestFailure::FailImmediately),
+                constraints: vec![],
+                dictionaries: vec![],
             })
         })
 }
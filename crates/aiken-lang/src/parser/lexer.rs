@@ -231,6 +231,7 @@ pub fn lexer() -> impl Parser<char, Vec<(Token, Span)>, Error = ParseError> {
         "const" => Token::Const,
         "fn" => Token::Fn,
         "test" => Token::Test,
+        "bench" => Token::Benchmark,
         "if" => Token::If,
         "else" => Token::Else,
         "is" => Token::Is,
@@ -73,6 +73,7 @@ pub enum Token {
     NewLine,
     // Keywords (alphabetically):
     As,
+    Benchmark,
     Const,
     Fn,
     If,
@@ -163,6 +164,7 @@ impl fmt::Display for Token {
             Token::EmptyLine => "EMPTYLINE",
             Token::NewLine => "NEWLINE",
             Token::As => "as",
+            Token::Benchmark => "bench",
             Token::Expect => "expect",
             Token::When => "when",
             Token::Is => "is",
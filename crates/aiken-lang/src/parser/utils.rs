@@ -5,6 +5,12 @@ pub fn optional_flag(token: Token) -> impl Parser<Token, bool, Error = ParseErro
     just(token).ignored().or_not().map(|v| v.is_some())
 }
 
+/// A word that only has a special meaning in some positions (e.g. 'interface' at the top-level),
+/// and remains a valid identifier everywhere else.
+pub fn contextual_keyword(keyword: &'static str) -> impl Parser<Token, (), Error = ParseError> {
+    select! {Token::Name { name } if name == keyword => ()}
+}
+
 pub fn type_name_with_args() -> impl Parser<Token, (String, Option<Vec<String>>), Error = ParseError>
 {
     just(Token::Type).ignore_then(
@@ -1,8 +1,16 @@
 use crate::{
-    ast::{BinOp, DataTypeKey, IfBranch, OnTestFailure, Span, TypedArg, TypedDataType, TypedTest},
+    ast::{
+        BinOp, DataTypeKey, IfBranch, OnTestFailure, Span, TypedArg, TypedDataType, TypedTest,
+        TypedValidator,
+    },
     expr::{TypedExpr, UntypedExpr},
     format::Formatter,
-    gen_uplc::CodeGenerator,
+    gen_uplc::{
+        coverage::Coverage,
+        profiler::{self, Profile},
+        source_map::SourceMap,
+        CodeGenerator,
+    },
     plutus_version::PlutusVersion,
     tipo::{convert_opaque_type, Type},
 };
@@ -10,7 +18,10 @@ use cryptoxide::{blake2b::Blake2b, digest::Digest};
 use indexmap::IndexMap;
 use itertools::Itertools;
 use owo_colors::{OwoColorize, Stream, Stream::Stderr};
-use pallas_primitives::alonzo::{Constr, PlutusData};
+use pallas_primitives::{
+    alonzo::{Constr, PlutusData},
+    conway::Language,
+};
 use patricia_tree::PatriciaMap;
 use std::{
     borrow::Borrow,
@@ -24,7 +35,10 @@ use std::{
 };
 use uplc::{
     ast::{Constant, Data, Name, NamedDeBruijn, Program, Term},
-    machine::{cost_model::ExBudget, eval_result::EvalResult},
+    machine::{
+        cost_model::{CostModel, ExBudget},
+        eval_result::EvalResult,
+    },
 };
 use vec1::{vec1, Vec1};
 
@@ -33,6 +47,9 @@ use vec1::{vec1, Vec1};
 /// Aiken supports two kinds of tests: unit and property. A unit test is a simply
 /// UPLC program which returns must be a lambda that returns a boolean.
 ///
+/// Benchmarks are also modelled as tests, although they do not succeed or fail
+/// on a boolean outcome. See 'Benchmark' below.
+///
 /// A property on the other-hand is a template for generating tests, which is also
 /// a lambda but that takes an extra argument. The argument is generated from a
 /// fuzzer which is meant to yield random values in a pseudo-random (albeit seeded)
@@ -49,6 +66,7 @@ use vec1::{vec1, Vec1};
 pub enum Test {
     UnitTest(UnitTest),
     PropertyTest(PropertyTest),
+    Benchmark(Benchmark),
 }
 
 unsafe impl Send for Test {}
@@ -62,6 +80,8 @@ impl Test {
     ) -> Test {
         let program = generator.generate_raw(&test.body, &[], &module_name);
 
+        let source_map = generator.source_map().clone();
+
         let assertion = match test.body.try_into() {
             Err(..) => None,
             Ok(Assertion { bin_op, head, tail }) => {
@@ -93,6 +113,7 @@ impl Test {
             module: module_name,
             name: test.name,
             program,
+            source_map,
             assertion,
             on_test_failure: test.on_test_failure,
         })
@@ -161,6 +182,77 @@ impl Test {
             )
         }
     }
+
+    /// Replay an existing transaction against a validator. The replay is a unit test whose
+    /// program is the validator applied to the given arguments: its parameters (if any) followed
+    /// by what a script receives when evaluated for one of the transaction's redeemers (see
+    /// 'uplc::tx::eval::script_arguments').
+    pub fn replay(
+        generator: &mut CodeGenerator<'_>,
+        validator: &TypedValidator,
+        module_name: String,
+        input_path: PathBuf,
+        name: String,
+        on_test_failure: OnTestFailure,
+        arguments: Vec<PlutusData>,
+    ) -> Test {
+        let program = arguments.into_iter().fold(
+            generator.generate(validator, &module_name),
+            |program, arg| program.apply_data(arg),
+        );
+
+        Test::UnitTest(UnitTest {
+            input_path,
+            module: module_name,
+            name,
+            program,
+            source_map: generator.source_map().clone(),
+            assertion: None,
+            on_test_failure,
+        })
+    }
+
+    pub fn from_benchmark_definition(
+        generator: &mut CodeGenerator<'_>,
+        bench: TypedTest,
+        module_name: String,
+        input_path: PathBuf,
+    ) -> Test {
+        let parameter = bench
+            .arguments
+            .first()
+            .expect("benchmarks have exactly one argument")
+            .to_owned();
+
+        let via = parameter.via.clone();
+
+        let type_info = parameter.arg.tipo.clone();
+
+        let stripped_type_info = convert_opaque_type(&type_info, generator.data_types(), true);
+
+        let program = generator.clone().generate_raw(
+            &bench.body,
+            &[TypedArg {
+                tipo: stripped_type_info.clone(),
+                ..parameter.clone().into()
+            }],
+            &module_name,
+        );
+
+        let sampler = generator.clone().generate_raw(&via, &[], &module_name);
+
+        Test::Benchmark(Benchmark {
+            input_path,
+            module: module_name,
+            name: bench.name,
+            program,
+            sampler: Sampler {
+                program: sampler,
+                stripped_type_info,
+                type_info,
+            },
+        })
+    }
 }
 
 /// ----- UnitTest -----------------------------------------------------------------
@@ -172,6 +264,7 @@ pub struct UnitTest {
     pub name: String,
     pub on_test_failure: OnTestFailure,
     pub program: Program<Name>,
+    pub source_map: SourceMap,
     pub assertion: Option<Assertion<(Constant, Rc<Type>)>>,
 }
 
@@ -188,20 +281,40 @@ impl UnitTest {
             OnTestFailure::FailImmediately => false,
         });
 
+        let mut coverage = Coverage::new();
+
         let mut traces = Vec::new();
         if let Err(err) = eval_result.result() {
             traces.push(format!("{err}"))
         }
-        traces.extend(eval_result.logs());
+        traces.extend(coverage.record(eval_result.logs()));
 
         TestResult::UnitTestResult(UnitTestResult {
             success,
             test: self.to_owned(),
             spent_budget: eval_result.cost(),
             traces,
+            coverage,
             assertion: self.assertion,
         })
     }
+
+    /// Evaluate the test while recording where its budget is spent, according to the cost model of
+    /// the given Plutus version. The resulting profile is only as precise as the test's source
+    /// map, which is empty unless it was requested to the code generator.
+    pub fn profile(&self, plutus_version: &PlutusVersion) -> Profile {
+        let program = Program::<NamedDeBruijn>::try_from(self.program.clone()).unwrap();
+
+        let (_, profile) = profiler::profile(
+            program,
+            &self.source_map,
+            &format!("{}.{}", self.module, self.name),
+            plutus_version,
+            CostModel::from(&Language::from(plutus_version)),
+        );
+
+        profile
+    }
 }
 
 /// ----- PropertyTest -----------------------------------------------------------------
@@ -285,18 +398,20 @@ impl PropertyTest {
         plutus_version: &PlutusVersion,
     ) -> TestResult<U, PlutusData> {
         let mut labels = BTreeMap::new();
+        let mut coverage = Coverage::new();
         let mut remaining = n;
 
         let (traces, counterexample, iterations) = match self.run_n_times(
             &mut remaining,
             Prng::from_seed(seed),
             &mut labels,
+            &mut coverage,
             plutus_version,
         ) {
             Ok(None) => (Vec::new(), Ok(None), n),
             Ok(Some(counterexample)) => (
-                self.eval(&counterexample.value, plutus_version)
-                    .logs()
+                Coverage::new()
+                    .record(self.eval(&counterexample.value, plutus_version).logs())
                     .into_iter()
                     .filter(|s| PropertyTest::extract_label(s).is_none())
                     .collect(),
@@ -304,7 +419,8 @@ impl PropertyTest {
                 n - remaining,
             ),
             Err(FuzzerError { traces, uplc_error }) => (
-                traces
+                Coverage::new()
+                    .record(traces)
                     .into_iter()
                     .filter(|s| PropertyTest::extract_label(s).is_none())
                     .collect(),
@@ -319,6 +435,7 @@ impl PropertyTest {
             iterations,
             labels,
             traces,
+            coverage,
         })
     }
 
@@ -327,13 +444,14 @@ impl PropertyTest {
         remaining: &mut usize,
         initial_prng: Prng,
         labels: &mut BTreeMap<String, usize>,
+        coverage: &mut Coverage,
         plutus_version: &'a PlutusVersion,
     ) -> Result<Option<Counterexample<'a>>, FuzzerError> {
         let mut prng = initial_prng;
         let mut counterexample = None;
 
         while *remaining > 0 && counterexample.is_none() {
-            (prng, counterexample) = self.run_once(prng, labels, plutus_version)?;
+            (prng, counterexample) = self.run_once(prng, labels, coverage, plutus_version)?;
             *remaining -= 1;
         }
 
@@ -344,6 +462,7 @@ impl PropertyTest {
         &'a self,
         prng: Prng,
         labels: &mut BTreeMap<String, usize>,
+        coverage: &mut Coverage,
         plutus_version: &'a PlutusVersion,
     ) -> Result<(Prng, Option<Counterexample<'a>>), FuzzerError> {
         use OnTestFailure::*;
@@ -354,7 +473,7 @@ impl PropertyTest {
 
         let mut result = self.eval(&value, plutus_version);
 
-        for s in result.logs() {
+        for s in coverage.record(result.logs()) {
             // NOTE: There may be other log outputs that interefere with labels. So *by
             // convention*, we treat as label strings that starts with a NUL byte, which
             // should be a guard sufficient to prevent inadvertent clashes.
@@ -437,6 +556,108 @@ impl PropertyTest {
     }
 }
 
+/// ----- Benchmark -----------------------------------------------------------------
+///
+/// A benchmark measures the execution costs of a program over inputs of increasing sizes. Inputs
+/// are generated from a 'Sampler', which is a function from a size to a 'Fuzzer':
+///
+/// `type Sampler<a> = fn(Int) -> Fuzzer<a>`
+///
+/// For each size from 0 up to a maximum, the sampler yields a pseudo-random value (from a
+/// seeded PRNG) onto which the benchmark is evaluated. The resulting execution units form a
+/// curve which indicates how the cost of the program evolves with the size of its input.
+#[derive(Debug, Clone)]
+pub struct Benchmark {
+    pub input_path: PathBuf,
+    pub module: String,
+    pub name: String,
+    pub program: Program<Name>,
+    pub sampler: Sampler<Name>,
+}
+
+unsafe impl Send for Benchmark {}
+
+#[derive(Debug, Clone)]
+pub struct Sampler<T> {
+    pub program: Program<T>,
+
+    pub type_info: Rc<Type>,
+
+    /// A version of the Sampler's type that has gotten rid of
+    /// all erasable opaque type. This is needed in order to
+    /// generate Plutus data with the appropriate shape.
+    pub stripped_type_info: Rc<Type>,
+}
+
+impl Benchmark {
+    pub const DEFAULT_MAX_SIZE: usize = 30;
+
+    /// Run a benchmark from a given seed, for every size between 0 and 'max_size' (included). The
+    /// benchmark stops at the first error, whether it comes from the sampler or from the program.
+    pub fn run(
+        self,
+        seed: u32,
+        max_size: usize,
+        plutus_version: &PlutusVersion,
+    ) -> BenchmarkResult {
+        let mut measures = Vec::with_capacity(max_size + 1);
+        let mut traces = Vec::new();
+        let mut error = None;
+        let mut prng = Prng::from_seed(seed);
+
+        for size in 0..=max_size {
+            let fuzzer = self
+                .sampler
+                .program
+                .apply_term(&Term::Constant(Constant::Integer(size.into()).into()));
+
+            match prng.sample(&fuzzer) {
+                Ok(Some((next_prng, value))) => {
+                    prng = next_prng;
+
+                    let mut result = self.eval(&value, plutus_version);
+
+                    if let Err(uplc_error) = result.result() {
+                        traces = result.logs();
+                        error = Some(uplc_error);
+                        break;
+                    }
+
+                    measures.push((size, result.cost()));
+                }
+
+                Ok(None) => {
+                    panic!("A seeded PRNG returned 'None' which indicates a sampler is ill-formed and implemented wrongly; please contact library's authors.");
+                }
+
+                Err(FuzzerError {
+                    traces: sampler_traces,
+                    uplc_error,
+                }) => {
+                    traces = sampler_traces;
+                    error = Some(uplc_error);
+                    break;
+                }
+            }
+        }
+
+        BenchmarkResult {
+            bench: self,
+            measures,
+            traces,
+            error,
+        }
+    }
+
+    pub fn eval(&self, value: &PlutusData, plutus_version: &PlutusVersion) -> EvalResult {
+        let program = self.program.apply_data(value.clone());
+
+        Program::<NamedDeBruijn>::try_from(program)
+            .unwrap()
+            .eval_version(ExBudget::max(), &plutus_version.into())
+    }
+}
+
 /// ----- PRNG -----------------------------------------------------------------
 ///
 /// A Pseudo-random generator (PRNG) used to produce random values for fuzzers.
@@ -945,6 +1166,7 @@ where
 pub enum TestResult<U, T> {
     UnitTestResult(UnitTestResult<U>),
     PropertyTestResult(PropertyTestResult<T>),
+    BenchmarkResult(BenchmarkResult),
 }
 
 unsafe impl<U, T> Send for TestResult<U, T> {}
@@ -959,6 +1181,7 @@ impl TestResult<(Constant, Rc<Type>), PlutusData> {
             TestResult::PropertyTestResult(test) => {
                 TestResult::PropertyTestResult(test.reify(data_types))
             }
+            TestResult::BenchmarkResult(bench) => TestResult::BenchmarkResult(bench),
         }
     }
 }
@@ -981,6 +1204,7 @@ impl<U, T> TestResult<U, T> {
                 }
                 OnTestFailure::SucceedImmediately => counterexample.is_some(),
             },
+            TestResult::BenchmarkResult(BenchmarkResult { error, .. }) => error.is_none(),
         }
     }
 
@@ -990,6 +1214,7 @@ impl<U, T> TestResult<U, T> {
             TestResult::PropertyTestResult(PropertyTestResult { ref test, .. }) => {
                 test.module.as_str()
             }
+            TestResult::BenchmarkResult(BenchmarkResult { ref bench, .. }) => bench.module.as_str(),
         }
     }
 
@@ -999,15 +1224,25 @@ impl<U, T> TestResult<U, T> {
             TestResult::PropertyTestResult(PropertyTestResult { ref test, .. }) => {
                 test.name.as_str()
             }
+            TestResult::BenchmarkResult(BenchmarkResult { ref bench, .. }) => bench.name.as_str(),
         }
     }
 
     pub fn traces(&self) -> &[String] {
         match self {
             TestResult::UnitTestResult(UnitTestResult { ref traces, .. })
-            | TestResult::PropertyTestResult(PropertyTestResult { ref traces, .. }) => {
-                traces.as_slice()
+            | TestResult::PropertyTestResult(PropertyTestResult { ref traces, .. })
+            | TestResult::BenchmarkResult(BenchmarkResult { ref traces, .. }) => traces.as_slice(),
+        }
+    }
+
+    pub fn coverage(&self) -> Option<&Coverage> {
+        match self {
+            TestResult::UnitTestResult(UnitTestResult { ref coverage, .. })
+            | TestResult::PropertyTestResult(PropertyTestResult { ref coverage, .. }) => {
+                Some(coverage)
             }
+            TestResult::BenchmarkResult(..) => None,
         }
     }
 }
@@ -1017,6 +1252,7 @@ pub struct UnitTestResult<T> {
     pub success: bool,
     pub spent_budget: ExBudget,
     pub traces: Vec<String>,
+    pub coverage: Coverage,
     pub test: UnitTest,
     pub assertion: Option<Assertion<T>>,
 }
@@ -1032,6 +1268,7 @@ impl UnitTestResult<(Constant, Rc<Type>)> {
             success: self.success,
             spent_budget: self.spent_budget,
             traces: self.traces,
+            coverage: self.coverage,
             test: self.test,
             assertion: self.assertion.and_then(|assertion| {
                 // No need to spend time/cpu on reifying assertions for successful
@@ -1065,6 +1302,7 @@ pub struct PropertyTestResult<T> {
     pub iterations: usize,
     pub labels: BTreeMap<String, usize>,
     pub traces: Vec<String>,
+    pub coverage: Coverage,
 }
 
 unsafe impl<T> Send for PropertyTestResult<T> {}
@@ -1085,10 +1323,21 @@ impl PropertyTestResult<PlutusData> {
             test: self.test,
             labels: self.labels,
             traces: self.traces,
+            coverage: self.coverage,
         }
     }
 }
 
+#[derive(Debug)]
+pub struct BenchmarkResult {
+    pub bench: Benchmark,
+    pub measures: Vec<(usize, ExBudget)>,
+    pub traces: Vec<String>,
+    pub error: Option<uplc::machine::Error>,
+}
+
+unsafe impl Send for BenchmarkResult {}
+
 #[derive(Debug, Clone)]
 pub struct Assertion<T> {
     pub bin_op: BinOp,
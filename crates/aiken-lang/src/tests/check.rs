@@ -28,8 +28,9 @@ fn check_module(
     module_types.insert("aiken".to_string(), builtins::prelude(&id_gen));
     module_types.insert("aiken/builtin".to_string(), builtins::plutus(&id_gen));
 
-    for (package, module) in extra {
+    for (package, mut module) in extra {
         let mut warnings = vec![];
+        module.name.clone_from(&package);
         let typed_module = module
             .infer(
                 &id_gen,
@@ -1872,6 +1873,71 @@ fn fuzzer_err_unify_3() {
     ))
 }
 
+#[test]
+fn sampler_ok_basic() {
+    let source_code = r#"
+        fn list(size: Int) -> Fuzzer<List<Int>> { todo }
+        bench foo(xs via list) { xs }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok());
+}
+
+#[test]
+fn sampler_ok_annotated() {
+    let source_code = r#"
+        fn list(size: Int) -> Fuzzer<List<Int>> { todo }
+        fn sum(xs: List<Int>) -> Int { todo }
+        bench foo(xs: List<Int> via list) { sum(xs) }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok());
+}
+
+#[test]
+fn sampler_err_arity() {
+    let source_code = r#"
+        fn int(size: Int) -> Fuzzer<Int> { todo }
+        bench foo(a via int, b via int) { a + b }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::IncorrectBenchmarkArity { count: 2, .. }))
+    ))
+}
+
+#[test]
+fn sampler_err_no_args() {
+    let source_code = r#"
+        bench foo() { Void }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::IncorrectBenchmarkArity { count: 0, .. }))
+    ))
+}
+
+#[test]
+fn sampler_err_fuzzer() {
+    let source_code = r#"
+        fn int() -> Fuzzer<Int> { todo }
+        bench foo(n via int()) { n }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((
+            _,
+            Error::CouldNotUnify {
+                situation: None,
+                ..
+            }
+        ))
+    ))
+}
+
 #[test]
 fn utf8_hex_literal_warning() {
     let source_code = r#"
@@ -3445,3 +3511,326 @@ fn constant_generic_empty() {
     let source_code = r#"const foo: List<a> = []"#;
     assert!(check_validator(parse(source_code)).is_ok());
 }
+
+#[test]
+fn interface_instance_resolved() {
+    let source_code = r#"
+        pub interface Show<a> {
+          show: fn(a) -> ByteArray,
+        }
+
+        instance Show<Int> {
+          fn show(n: Int) -> ByteArray {
+            "int"
+          }
+        }
+
+        instance Show<Bool> {
+          fn show(b: Bool) -> ByteArray {
+            if b { "True" } else { "False" }
+          }
+        }
+
+        test show_int() {
+          show(42) == "int" && show(True) == "True"
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok());
+}
+
+#[test]
+fn interface_constrained_function() {
+    let source_code = r#"
+        pub interface Show<a> {
+          show: fn(a) -> ByteArray,
+        }
+
+        instance Show<Int> {
+          fn show(n: Int) -> ByteArray {
+            "int"
+          }
+        }
+
+        pub fn show_all(xs: List<a>) -> List<ByteArray> where Show<a> {
+          when xs is {
+            [] -> []
+            [x, ..rest] -> [show(x), ..show_all(rest)]
+          }
+        }
+
+        pub fn show_twice(x: a) -> List<ByteArray> where Show<a> {
+          show_all([x, x])
+        }
+
+        test show_many() {
+          show_twice(1) == ["int", "int"]
+        }
+    "#;
+
+    let (_, module) = check(parse(source_code)).expect("should type-check");
+
+    let arities = module
+        .definitions()
+        .filter_map(|def| match def {
+            Definition::Fn(f) => Some((f.name.as_str(), f.arguments.len(), f.dictionaries.len())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(arities, vec![("show_all", 1, 1), ("show_twice", 1, 1)]);
+}
+
+#[test]
+fn interface_missing_instance() {
+    let source_code = r#"
+        pub interface Show<a> {
+          show: fn(a) -> ByteArray,
+        }
+
+        instance Show<Int> {
+          fn show(n: Int) -> ByteArray {
+            "int"
+          }
+        }
+
+        test show_bool() {
+          show(True) == "True"
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::MissingInstance { .. }))
+    ))
+}
+
+#[test]
+fn interface_missing_constraint() {
+    let source_code = r#"
+        pub interface Show<a> {
+          show: fn(a) -> ByteArray,
+        }
+
+        pub fn show_first(xs: List<a>) -> ByteArray {
+          when xs is {
+            [] -> ""
+            [x, ..] -> show(x)
+          }
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::MissingInstance { .. }))
+    ))
+}
+
+#[test]
+fn interface_ambiguous_instance() {
+    let source_code = r#"
+        pub interface Default<a> {
+          default: fn() -> a,
+        }
+
+        instance Default<Int> {
+          fn default() -> Int {
+            0
+          }
+        }
+
+        pub fn foo() -> Bool {
+          let x = default()
+          x == x
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::AmbiguousInstance { .. }))
+    ))
+}
+
+#[test]
+fn interface_duplicate_instance() {
+    let source_code = r#"
+        pub interface Show<a> {
+          show: fn(a) -> ByteArray,
+        }
+
+        instance Show<Int> {
+          fn show(n: Int) -> ByteArray {
+            "int"
+          }
+        }
+
+        instance Show<Int> {
+          fn show(n: Int) -> ByteArray {
+            "other"
+          }
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::DuplicateInstance { .. }))
+    ))
+}
+
+#[test]
+fn interface_incomplete_instance() {
+    let source_code = r#"
+        pub interface Ord<a> {
+          compare: fn(a, a) -> Ordering,
+          max: fn(a, a) -> a,
+        }
+
+        instance Ord<Int> {
+          fn compare(left: Int, right: Int) -> Ordering {
+            Equal
+          }
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::MissingInstanceMethod { .. }))
+    ))
+}
+
+#[test]
+fn interface_instance_method_mismatch() {
+    let source_code = r#"
+        pub interface Show<a> {
+          show: fn(a) -> ByteArray,
+        }
+
+        instance Show<Int> {
+          fn show(n: Int) -> Int {
+            n
+          }
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::CouldNotUnify { .. }))
+    ))
+}
+
+#[test]
+fn interface_illegal_method() {
+    let source_code = r#"
+        pub interface Show<a> {
+          show: fn(Int) -> ByteArray,
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::IllegalInterfaceMethod { .. }))
+    ))
+}
+
+#[test]
+fn interface_constraint_not_in_signature() {
+    let source_code = r#"
+        pub interface Show<a> {
+          show: fn(a) -> ByteArray,
+        }
+
+        pub fn foo(x: Int) -> Int where Show<b> {
+          x
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::IllegalConstraint { .. }))
+    ))
+}
+
+#[test]
+fn interface_instance_from_dependency() {
+    let dependency = r#"
+        pub interface Show<a> {
+          show: fn(a) -> ByteArray,
+        }
+
+        instance Show<Int> {
+          fn show(n: Int) -> ByteArray {
+            "int"
+          }
+        }
+
+        pub fn show_all(xs: List<a>) -> List<ByteArray> where Show<a> {
+          when xs is {
+            [] -> []
+            [x, ..rest] -> [show(x), ..show_all(rest)]
+          }
+        }
+    "#;
+
+    let source_code = r#"
+        use foo/printing.{show}
+
+        test show_from_dependency() {
+          show(1) == "int" && printing.show_all([1, 2]) == ["int", "int"]
+        }
+    "#;
+
+    let result = check_with_deps(
+        parse(source_code),
+        vec![("foo/printing".to_string(), parse(dependency))],
+    );
+
+    assert!(result.is_ok(), "{result:#?}");
+}
+
+#[test]
+fn interface_instance_module_import_is_used() {
+    let printing = r#"
+        pub interface Show<a> {
+          show: fn(a) -> ByteArray,
+        }
+
+        pub fn show_twice(x: a) -> List<ByteArray> where Show<a> {
+          [show(x), show(x)]
+        }
+    "#;
+
+    let instances = r#"
+        use m/printing.{Show}
+
+        instance Show<Int> {
+          fn show(n: Int) -> ByteArray {
+            "int"
+          }
+        }
+    "#;
+
+    let source_code = r#"
+        use m/inst
+        use m/printing.{show_twice}
+
+        test show_from_imported_instance() {
+          show_twice(1) == ["int", "int"]
+        }
+    "#;
+
+    let (warnings, _) = check_with_deps(
+        parse(source_code),
+        vec![
+            ("m/printing".to_string(), parse(printing)),
+            ("m/inst".to_string(), parse(instances)),
+        ],
+    )
+    .expect("should type-check");
+
+    assert!(
+        !warnings
+            .iter()
+            .any(|warning| matches!(warning, Warning::UnusedImportedModule { .. })),
+        "{warnings:#?}"
+    );
+}
@@ -1420,3 +1420,27 @@ fn multiline_if_is_2() {
         "#
     );
 }
+
+#[test]
+fn format_interfaces() {
+    assert_format!(
+        r#"
+        /// Things that can be shown.
+        pub interface Show<a> {
+            /// Render a value.
+            show: fn(a) -> ByteArray, to_bytes: fn(a) -> ByteArray
+        }
+
+        instance Show<Int> {
+            fn show(n: Int) -> ByteArray { "int" }
+
+            // Same thing.
+            fn to_bytes(n: Int) -> ByteArray { show(n) }
+        }
+
+        pub fn show_all(xs: List<a>) -> List<ByteArray> where Show<a>, Eq<a> {
+            todo
+        }
+        "#
+    );
+}
@@ -11,6 +11,7 @@ use itertools::Itertools;
 use std::{cell::RefCell, collections::HashMap, ops::Deref, rc::Rc};
 use uplc::{ast::Type as UplcType, builtins::DefaultFunction};
 
+mod dictionary;
 pub(crate) mod environment;
 pub mod error;
 mod exhaustive;
@@ -1328,6 +1329,63 @@ pub struct TypeInfo {
     pub values: HashMap<String, ValueConstructor>,
     pub accessors: HashMap<String, AccessorsMap>,
     pub annotations: HashMap<Annotation, Rc<Type>>,
+    pub interfaces: HashMap<String, InterfaceInfo>,
+    pub instances: Vec<InstanceInfo>,
+    /// Interface constraints of the module's functions, by function name.
+    pub constraints: HashMap<String, Vec<Constraint>>,
+}
+
+/// An interface, with the (generic) types of its methods. Methods are also registered as module
+/// values, so that they can be referred to like any other function.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct InterfaceInfo {
+    pub name: String,
+    pub module: String,
+    pub public: bool,
+    pub location: Span,
+    /// The type variable standing for the interface parameter in method types.
+    pub parameter: Rc<Type>,
+    pub methods: Vec<(String, Rc<Type>)>,
+}
+
+impl InterfaceInfo {
+    pub fn method(&self, name: &str) -> Option<&Rc<Type>> {
+        self.methods
+            .iter()
+            .find_map(|(method, tipo)| if method == name { Some(tipo) } else { None })
+    }
+
+    /// The parameter's variable id, used to instantiate method types for a given type.
+    pub fn parameter_id(&self) -> u64 {
+        match self.parameter.deref() {
+            Type::Var { tipo, .. } => match tipo.borrow().deref() {
+                TypeVar::Generic { id } | TypeVar::Unbound { id } => *id,
+                TypeVar::Link { .. } => unreachable!("interface parameter is a link"),
+            },
+            _ => unreachable!("interface parameter isn't a type variable"),
+        }
+    }
+}
+
+/// An instance of an interface for a concrete type.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct InstanceInfo {
+    pub interface: String,
+    pub interface_module: String,
+    pub module: String,
+    pub location: Span,
+    pub tipo: Rc<Type>,
+}
+
+/// A constraint on a type parameter of a function, as in `where Show<a>`. Functions receive one
+/// extra (hidden) argument per method of the interface; those are filled in with the right
+/// instance methods at every call site.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Constraint {
+    pub interface: String,
+    pub interface_module: String,
+    pub location: Span,
+    pub tipo: Rc<Type>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -0,0 +1,466 @@
+use super::{
+    environment::Environment, error::Error, hydrator::Hydrator, Constraint, InterfaceInfo, Type,
+    TypeVar, ValueConstructor, ValueConstructorVariant,
+};
+use crate::{
+    ast::{CallArg, Span, TypedArg, TypedInstance, DICTIONARY_VARIABLE},
+    expr::TypedExpr,
+    tipo::ModuleValueConstructor,
+};
+use std::{collections::HashMap, ops::Deref, rc::Rc};
+
+/// Interfaces are compiled through dictionary passing: every constraint of a function turns into
+/// one extra argument per method of the interface, and every use of an interface method or of a
+/// constrained function is resolved, once the body of the enclosing function has been inferred,
+/// into either a concrete instance method or one of the enclosing function's own dictionaries.
+#[derive(Debug)]
+pub(crate) struct DictionaryPass<'a, 'b> {
+    environment: &'a mut Environment<'b>,
+
+    /// Constraints of the function being processed, along with their interface. Their
+    /// dictionaries are in scope as (hidden) arguments of the function.
+    constraints: Vec<(Constraint, InterfaceInfo)>,
+
+    /// Names of the function's type parameters, for error reporting.
+    rigid_type_names: HashMap<u64, String>,
+}
+
+impl<'a, 'b> DictionaryPass<'a, 'b> {
+    pub fn new(
+        environment: &'a mut Environment<'b>,
+        function: &str,
+        rigid_type_names: HashMap<u64, String>,
+    ) -> Self {
+        let constraints = environment
+            .module_constraints
+            .get(function)
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|constraint| {
+                let interface = environment
+                    .find_interface(&constraint.interface_module, &constraint.interface)
+                    .cloned()
+                    .expect("Could not find interface of a registered constraint");
+
+                (constraint, interface)
+            })
+            .collect();
+
+        Self {
+            environment,
+            constraints,
+            rigid_type_names,
+        }
+    }
+
+    /// The extra arguments of the function, one per method of each of its constraints.
+    pub fn arguments(&mut self) -> Vec<TypedArg> {
+        let mut arguments = vec![];
+
+        for (index, (constraint, interface)) in self.constraints.iter().enumerate() {
+            for (method, tipo) in interface.methods.iter() {
+                let mut arg = TypedArg::new(
+                    &dictionary_name(index, method),
+                    self.environment.instantiate(
+                        tipo.clone(),
+                        &mut HashMap::from([(interface.parameter_id(), constraint.tipo.clone())]),
+                        &Hydrator::new(),
+                    ),
+                );
+
+                arg.location = constraint.location;
+
+                arguments.push(arg);
+            }
+        }
+
+        arguments
+    }
+
+    /// Resolve all interface methods and constrained functions used in an expression.
+    #[allow(clippy::result_large_err)]
+    pub fn run(&mut self, expr: &mut TypedExpr) -> Result<(), Error> {
+        if let TypedExpr::Call { fun, args, .. } = expr {
+            if let Some(dictionaries) = self.function_dictionaries(fun)? {
+                for arg in args.iter_mut() {
+                    self.run(&mut arg.value)?;
+                }
+
+                extend_function_type(fun, &dictionaries);
+
+                args.extend(dictionaries.into_iter().map(|value| CallArg {
+                    label: None,
+                    location: value.location(),
+                    value,
+                }));
+
+                return Ok(());
+            }
+        }
+
+        if let Some((module, name, tipo, location)) = module_function(expr) {
+            if let Some(interface) = self.environment.interface_of_method(&module, &name) {
+                let interface = interface.clone();
+
+                let method_type = interface
+                    .method(&name)
+                    .expect("Could not find method of its own interface");
+
+                let parameter = find_generic(method_type, &tipo, interface.parameter_id())
+                    .expect("Could not find interface parameter in method type");
+
+                let index = interface
+                    .methods
+                    .iter()
+                    .position(|(method, _)| method == &name)
+                    .expect("Could not find method of its own interface");
+
+                *expr = self
+                    .resolve(&interface, parameter, location)?
+                    .swap_remove(index);
+
+                return Ok(());
+            }
+        }
+
+        if let Some(dictionaries) = self.function_dictionaries(expr)? {
+            *expr = eta_expand(expr.clone(), dictionaries);
+            return Ok(());
+        }
+
+        for child in expr.children_mut() {
+            self.run(child)?;
+        }
+
+        Ok(())
+    }
+
+    /// The dictionaries to pass to a constrained function at a given use site, if any.
+    #[allow(clippy::result_large_err)]
+    fn function_dictionaries(&mut self, fun: &TypedExpr) -> Result<Option<Vec<TypedExpr>>, Error> {
+        let Some((module, name, tipo, location)) = module_function(fun) else {
+            return Ok(None);
+        };
+
+        let Some(constraints) = self.environment.constraints_of(&module, &name).cloned() else {
+            return Ok(None);
+        };
+
+        let generic_type = self
+            .environment
+            .module_function_type(&module, &name)
+            .expect("Could not find type of a constrained function");
+
+        let mut dictionaries = vec![];
+
+        for constraint in constraints {
+            let interface = self
+                .environment
+                .find_interface(&constraint.interface_module, &constraint.interface)
+                .cloned()
+                .expect("Could not find interface of a registered constraint");
+
+            let id = constraint
+                .tipo
+                .get_generic()
+                .expect("Constraint on something else than a type parameter");
+
+            let parameter = find_generic(&generic_type, &tipo, id)
+                .expect("Could not find constrained parameter in function type");
+
+            dictionaries.extend(self.resolve(&interface, parameter, location)?);
+        }
+
+        Ok(Some(dictionaries))
+    }
+
+    /// Find the methods of an interface for a given type, in the order in which the interface
+    /// declares them. Those are either the methods of a visible instance when the type is
+    /// known, or the dictionaries of the enclosing function when the type is one of its
+    /// constrained type parameters.
+    #[allow(clippy::result_large_err)]
+    fn resolve(
+        &mut self,
+        interface: &InterfaceInfo,
+        tipo: Rc<Type>,
+        location: Span,
+    ) -> Result<Vec<TypedExpr>, Error> {
+        let tipo = resolve_links(tipo);
+
+        let missing_instance = || Error::MissingInstance {
+            location,
+            interface: interface.name.clone(),
+            tipo: tipo.clone(),
+            rigid_type_names: self.rigid_type_names.clone(),
+        };
+
+        let provider = if let Some(id) = tipo.get_generic() {
+            self.constraints
+                .iter()
+                .position(|(constraint, other)| {
+                    other.name == interface.name
+                        && other.module == interface.module
+                        && constraint.tipo.get_generic() == Some(id)
+                })
+                .map(Provider::Constraint)
+                .ok_or_else(missing_instance)?
+        } else if tipo.is_monomorphic() {
+            let (module, instance_type) = self
+                .environment
+                .visible_instances(&interface.name, &interface.module, &tipo)
+                .into_iter()
+                .find(|instance| resolve_links(instance.tipo.clone()) == tipo)
+                .map(|instance| (instance.module.clone(), instance.tipo.to_pretty(0)))
+                .ok_or_else(missing_instance)?;
+
+            // A module may be imported for its instances alone.
+            self.environment.use_module(&module);
+
+            Provider::Instance {
+                module,
+                tipo: instance_type,
+            }
+        } else if tipo.is_generic() {
+            return Err(missing_instance());
+        } else {
+            return Err(Error::AmbiguousInstance {
+                location,
+                interface: interface.name.clone(),
+            });
+        };
+
+        let mut methods = vec![];
+
+        for (method, method_type) in interface.methods.iter() {
+            let method_type = self.environment.instantiate(
+                method_type.clone(),
+                &mut HashMap::from([(interface.parameter_id(), tipo.clone())]),
+                &Hydrator::new(),
+            );
+
+            let (name, variant) = match &provider {
+                Provider::Constraint(index) => (
+                    dictionary_name(*index, method),
+                    ValueConstructorVariant::LocalVariable { location },
+                ),
+                Provider::Instance { module, tipo } => {
+                    let name = TypedInstance::method_name(&interface.name, tipo, method);
+
+                    let variant = ValueConstructorVariant::ModuleFn {
+                        name: name.clone(),
+                        field_map: None,
+                        module: module.clone(),
+                        arity: method_type.arg_types().map(|args| args.len()).unwrap_or(0),
+                        location,
+                        builtin: None,
+                    };
+
+                    (name, variant)
+                }
+            };
+
+            methods.push(TypedExpr::Var {
+                location,
+                constructor: ValueConstructor {
+                    public: true,
+                    variant,
+                    tipo: method_type,
+                },
+                name,
+            });
+        }
+
+        Ok(methods)
+    }
+}
+
+/// Where the methods of an interface come from, for a given type.
+enum Provider {
+    /// The dictionaries of one of the enclosing function's constraints, by index.
+    Constraint(usize),
+    /// The methods of an instance defined in some module, for a (pretty-printed) type.
+    Instance { module: String, tipo: String },
+}
+
+/// The name of the argument carrying a method of one of a function's constraints.
+pub fn dictionary_name(index: usize, method: &str) -> String {
+    format!("{DICTIONARY_VARIABLE}_{index}_{method}")
+}
+
+/// The module, name, type and location of a reference to a module function.
+fn module_function(expr: &TypedExpr) -> Option<(String, String, Rc<Type>, Span)> {
+    match expr {
+        TypedExpr::Var {
+            location,
+            constructor:
+                ValueConstructor {
+                    variant: ValueConstructorVariant::ModuleFn { name, module, .. },
+                    tipo,
+                    ..
+                },
+            ..
+        }
+        | TypedExpr::ModuleSelect {
+            location,
+            tipo,
+            constructor: ModuleValueConstructor::Fn { name, module, .. },
+            ..
+        } => Some((module.clone(), name.clone(), tipo.clone(), *location)),
+        _ => None,
+    }
+}
+
+/// Add the types of the dictionaries to the type of a reference to a constrained function, so
+/// that the function can be applied to them.
+fn extend_function_type(fun: &mut TypedExpr, dictionaries: &[TypedExpr]) {
+    let tipo = match fun {
+        TypedExpr::Var { constructor, .. } => &mut constructor.tipo,
+        TypedExpr::ModuleSelect { tipo, .. } => tipo,
+        _ => return,
+    };
+
+    let (mut args, ret) = tipo
+        .function_types()
+        .expect("Constrained function doesn't have a function type");
+
+    args.extend(dictionaries.iter().map(|dictionary| dictionary.tipo()));
+
+    *tipo = Type::function(args, ret);
+}
+
+/// Turn a constrained function used as a value into an anonymous function passing the
+/// dictionaries along, i.e. `f` becomes `fn(a, b) { f(a, b, dictionaries...) }`.
+fn eta_expand(mut fun: TypedExpr, dictionaries: Vec<TypedExpr>) -> TypedExpr {
+    let location = fun.location();
+    let tipo = fun.tipo();
+
+    let (args_types, ret) = tipo
+        .function_types()
+        .expect("Constrained function doesn't have a function type");
+
+    let args = args_types
+        .into_iter()
+        .enumerate()
+        .map(|(index, tipo)| {
+            let mut arg = TypedArg::new(&format!("{DICTIONARY_VARIABLE}_arg_{index}"), tipo);
+            arg.location = location;
+            arg
+        })
+        .collect::<Vec<_>>();
+
+    let mut call_args = args
+        .iter()
+        .map(|arg| CallArg {
+            label: None,
+            location,
+            value: TypedExpr::local_var(&arg.get_name(), arg.tipo.clone(), location),
+        })
+        .collect::<Vec<_>>();
+
+    extend_function_type(&mut fun, &dictionaries);
+
+    call_args.extend(dictionaries.into_iter().map(|value| CallArg {
+        label: None,
+        location,
+        value,
+    }));
+
+    TypedExpr::Fn {
+        location,
+        tipo,
+        is_capture: false,
+        args,
+        body: Box::new(TypedExpr::Call {
+            location,
+            tipo: ret,
+            fun: Box::new(fun),
+            args: call_args,
+        }),
+        return_annotation: None,
+    }
+}
+
+/// Find what a type parameter of a generic type stands for in an instance of that type.
+fn find_generic(generic: &Type, tipo: &Rc<Type>, id: u64) -> Option<Rc<Type>> {
+    if let Type::Var { tipo: var, .. } = generic {
+        return match var.borrow().deref() {
+            TypeVar::Generic { id: other } if *other == id => Some(tipo.clone()),
+            TypeVar::Link { tipo: generic } => find_generic(generic, tipo, id),
+            _ => None,
+        };
+    }
+
+    let tipo = resolve_links(tipo.clone());
+
+    let pairs: Vec<(&Rc<Type>, &Rc<Type>)> = match (generic, tipo.deref()) {
+        (Type::App { args, .. }, Type::App { args: others, .. })
+        | (Type::Tuple { elems: args, .. }, Type::Tuple { elems: others, .. }) => {
+            args.iter().zip(others.iter()).collect()
+        }
+        (
+            Type::Fn { args, ret, .. },
+            Type::Fn {
+                args: others,
+                ret: other,
+                ..
+            },
+        ) => args
+            .iter()
+            .zip(others.iter())
+            .chain([(ret, other)])
+            .collect(),
+        (
+            Type::Pair { fst, snd, .. },
+            Type::Pair {
+                fst: other_fst,
+                snd: other_snd,
+                ..
+            },
+        ) => vec![(fst, other_fst), (snd, other_snd)],
+        _ => vec![],
+    };
+
+    pairs
+        .into_iter()
+        .find_map(|(generic, tipo)| find_generic(generic, tipo, id))
+}
+
+/// Follow links throughout a type, so that it can be compared structurally.
+fn resolve_links(tipo: Rc<Type>) -> Rc<Type> {
+    match tipo.deref() {
+        Type::Var { tipo: var, alias } => match var.borrow().deref() {
+            TypeVar::Link { tipo } => Type::with_alias(resolve_links(tipo.clone()), alias.clone()),
+            _ => tipo.clone(),
+        },
+        Type::App {
+            public,
+            contains_opaque,
+            module,
+            name,
+            args,
+            alias,
+        } => Rc::new(Type::App {
+            public: *public,
+            contains_opaque: *contains_opaque,
+            module: module.clone(),
+            name: name.clone(),
+            args: args.iter().cloned().map(resolve_links).collect(),
+            alias: alias.clone(),
+        }),
+        Type::Fn { args, ret, alias } => Rc::new(Type::Fn {
+            args: args.iter().cloned().map(resolve_links).collect(),
+            ret: resolve_links(ret.clone()),
+            alias: alias.clone(),
+        }),
+        Type::Tuple { elems, alias } => Rc::new(Type::Tuple {
+            elems: elems.iter().cloned().map(resolve_links).collect(),
+            alias: alias.clone(),
+        }),
+        Type::Pair { fst, snd, alias } => Rc::new(Type::Pair {
+            fst: resolve_links(fst.clone()),
+            snd: resolve_links(snd.clone()),
+            alias: alias.clone(),
+        }),
+    }
+}
@@ -2,19 +2,21 @@ use super::{
     error::{Error, Warning},
     exhaustive::{simplify, Matrix, PatternStack},
     hydrator::Hydrator,
-    AccessorsMap, RecordAccessor, Type, TypeConstructor, TypeInfo, TypeVar, ValueConstructor,
-    ValueConstructorVariant,
+    AccessorsMap, Constraint, InstanceInfo, InterfaceInfo, RecordAccessor, Type, TypeConstructor,
+    TypeInfo, TypeVar, ValueConstructor, ValueConstructorVariant,
 };
 use crate::{
     ast::{
-        self, Annotation, CallArg, DataType, Definition, Function, ModuleConstant, ModuleKind,
-        RecordConstructor, RecordConstructorArg, Span, TypeAlias, TypedDefinition, TypedFunction,
-        TypedPattern, TypedValidator, UnqualifiedImport, UntypedArg, UntypedDefinition,
-        UntypedFunction, Use, Validator, PIPE_VARIABLE,
+        self, Annotation, CallArg, DataType, Definition, Function, Instance, Interface,
+        InterfaceMethod, ModuleConstant, ModuleKind, RecordConstructor, RecordConstructorArg, Span,
+        TypeAlias, TypedDefinition, TypedFunction, TypedInstance, TypedPattern, TypedValidator,
+        UnqualifiedImport, UntypedArg, UntypedDefinition, UntypedFunction, UntypedInstance, Use,
+        Validator, PIPE_VARIABLE,
     },
     tipo::{fields::FieldMap, TypeAliasAnnotation},
     IdGenerator,
 };
+use itertools::Itertools;
 use std::{
     collections::{HashMap, HashSet},
     ops::Deref,
@@ -61,6 +63,15 @@ pub struct Environment<'a> {
     /// Top-level validator definitions from the module
     pub module_validators: HashMap<String, (Span, Vec<String>)>,
 
+    /// Interfaces defined in the current module, or imported unqualified
+    pub module_interfaces: HashMap<String, InterfaceInfo>,
+
+    /// Instances defined in the current module
+    pub module_instances: Vec<InstanceInfo>,
+
+    /// Interface constraints of the functions defined in the current module
+    pub module_constraints: HashMap<String, Vec<Constraint>>,
+
     /// Top-level functions that have been inferred
     pub inferred_functions: HashMap<String, TypedFunction>,
 
@@ -263,6 +274,8 @@ impl<'a> Environment<'a> {
                 return_type,
                 end_position,
                 on_test_failure,
+                constraints,
+                dictionaries,
             }) => {
                 // Lookup the inferred function information
                 let function = self
@@ -308,6 +321,8 @@ impl<'a> Environment<'a> {
                     body,
                     end_position,
                     on_test_failure,
+                    constraints,
+                    dictionaries,
                 })
             }
             Definition::Validator(Validator {
@@ -367,7 +382,10 @@ impl<'a> Environment<'a> {
             | Definition::DataType { .. }
             | Definition::Use { .. }
             | Definition::Test { .. }
-            | Definition::ModuleConstant { .. }) => definition,
+            | Definition::Benchmark { .. }
+            | Definition::ModuleConstant { .. }
+            | Definition::Interface { .. }
+            | Definition::Instance { .. }) => definition,
         }
     }
 
@@ -788,6 +806,9 @@ impl<'a> Environment<'a> {
             module_values: HashMap::new(),
             module_functions: HashMap::new(),
             module_validators: HashMap::new(),
+            module_interfaces: HashMap::new(),
+            module_instances: Vec::new(),
+            module_constraints: HashMap::new(),
             imported_modules: HashMap::new(),
             unused_modules: HashMap::new(),
             unqualified_imported_names: HashMap::new(),
@@ -871,6 +892,7 @@ impl<'a> Environment<'a> {
                 {
                     let mut type_imported = false;
                     let mut value_imported = false;
+                    let mut interface_imported = false;
                     let mut variant = None;
 
                     let imported_name = as_name.as_ref().unwrap_or(name);
@@ -913,6 +935,14 @@ impl<'a> Environment<'a> {
                         type_imported = true;
                     }
 
+                    // Register the unqualified import if it is an interface
+                    if let Some(interface) = module_info.interfaces.get(name) {
+                        self.module_interfaces
+                            .insert(imported_name.clone(), interface.clone());
+
+                        interface_imported = true;
+                    }
+
                     if value_imported && type_imported {
                         self.init_usage(
                             imported_name.to_string(),
@@ -940,7 +970,7 @@ impl<'a> Environment<'a> {
                                 *location,
                             ),
                         };
-                    } else if !value_imported {
+                    } else if !interface_imported {
                         // Error if no type or value was found with that name
                         return Err(Error::UnknownModuleField {
                             location: *location,
@@ -1061,7 +1091,10 @@ impl<'a> Environment<'a> {
                         | Definition::Validator { .. }
                         | Definition::Use { .. }
                         | Definition::ModuleConstant { .. }
-                        | Definition::Test { .. } => None,
+                        | Definition::Test { .. }
+                        | Definition::Benchmark { .. }
+                        | Definition::Interface { .. }
+                        | Definition::Instance { .. } => None,
                     })
                     .collect::<Vec<Span>>();
 
@@ -1184,8 +1217,11 @@ impl<'a> Environment<'a> {
             Definition::Fn { .. }
             | Definition::Validator { .. }
             | Definition::Test { .. }
+            | Definition::Benchmark { .. }
             | Definition::Use { .. }
-            | Definition::ModuleConstant { .. } => {}
+            | Definition::ModuleConstant { .. }
+            | Definition::Interface { .. }
+            | Definition::Instance { .. } => {}
         }
 
         Ok(())
@@ -1273,6 +1309,40 @@ impl<'a> Environment<'a> {
                     &fun.location,
                 )?;
 
+                if !fun.constraints.is_empty() {
+                    let hydrator = hydrators
+                        .get_mut(&fun.name)
+                        .expect("Could not find hydrator for register_values function");
+
+                    let constraints =
+                        self.constraints_from_annotations(&fun.constraints, hydrator)?;
+
+                    let parameters = self
+                        .get_variable(&fun.name)
+                        .expect("Could not find preregistered function")
+                        .tipo
+                        .collect_generics()
+                        .iter()
+                        .filter_map(|var| var.get_generic())
+                        .collect::<HashSet<_>>();
+
+                    // Constraints must apply to type parameters of the function's signature, or
+                    // there would be no way to find out which instance to pick at call sites.
+                    if let Some(constraint) = constraints.iter().find(|constraint| {
+                        !constraint
+                            .tipo
+                            .get_generic()
+                            .is_some_and(|id| parameters.contains(&id))
+                    }) {
+                        return Err(Error::IllegalConstraint {
+                            location: constraint.location,
+                        });
+                    }
+
+                    self.module_constraints
+                        .insert(fun.name.clone(), constraints);
+                }
+
                 self.module_functions.insert(fun.name.clone(), fun);
 
                 if !fun.public {
@@ -1387,7 +1457,7 @@ impl<'a> Environment<'a> {
                 })
             }
 
-            Definition::Test(test) => {
+            Definition::Test(test) | Definition::Benchmark(test) => {
                 let arguments = test
                     .arguments
                     .iter()
@@ -1514,11 +1584,435 @@ impl<'a> Environment<'a> {
                 assert_unique_const_name(names, name, location)?;
             }
 
-            Definition::Use { .. } | Definition::TypeAlias { .. } => {}
+            Definition::Instance(instance) => {
+                self.register_instance(instance, module_name, hydrators, names)?;
+            }
+
+            Definition::Use { .. }
+            | Definition::TypeAlias { .. }
+            | Definition::Interface { .. } => {}
+        }
+        Ok(())
+    }
+
+    /// Register an interface and its methods. This happens after types are registered, since
+    /// method signatures may refer to any type, and before values, since functions and instances
+    /// may refer to interfaces.
+    #[allow(clippy::result_large_err)]
+    pub fn register_interface(
+        &mut self,
+        def: &'a UntypedDefinition,
+        module_name: &str,
+        type_names: &mut HashMap<&'a str, &'a Span>,
+        value_names: &mut HashMap<String, &'a Span>,
+    ) -> Result<(), Error> {
+        let Definition::Interface(Interface {
+            name,
+            parameter,
+            methods,
+            public,
+            location,
+            doc: _,
+        }) = def
+        else {
+            return Ok(());
+        };
+
+        assert_unique_type_name(type_names, name, location)?;
+
+        let mut hydrator = Hydrator::new();
+
+        let parameter_type = hydrator.type_from_annotation(
+            &Annotation::Var {
+                location: *location,
+                name: parameter.clone(),
+            },
+            self,
+        )?;
+
+        hydrator.disallow_new_type_variables();
+
+        let mut interface = InterfaceInfo {
+            name: name.clone(),
+            module: module_name.to_string(),
+            public: *public,
+            location: *location,
+            parameter: parameter_type,
+            methods: Vec::with_capacity(methods.len()),
+        };
+
+        for InterfaceMethod {
+            name: method,
+            annotation,
+            location,
+            ..
+        } in methods
+        {
+            assert_unique_value_name(value_names, method, location)?;
+
+            let tipo = hydrator.type_from_annotation(annotation, self)?;
+
+            let illegal_method = || Error::IllegalInterfaceMethod {
+                location: *location,
+                parameter: parameter.clone(),
+            };
+
+            let arity = match tipo.deref() {
+                Type::Fn { args, .. } => args.len(),
+                _ => return Err(illegal_method()),
+            };
+
+            if !tipo
+                .collect_generics()
+                .iter()
+                .any(|var| var.get_generic() == Some(interface.parameter_id()))
+            {
+                return Err(illegal_method());
+            }
+
+            let variant = ValueConstructorVariant::ModuleFn {
+                name: method.clone(),
+                field_map: None,
+                module: module_name.to_string(),
+                arity,
+                location: *location,
+                builtin: None,
+            };
+
+            self.insert_module_value(
+                method,
+                ValueConstructor {
+                    public: *public,
+                    variant: variant.clone(),
+                    tipo: tipo.clone(),
+                },
+            );
+
+            self.insert_variable(method.clone(), variant, tipo.clone());
+
+            interface.methods.push((method.clone(), tipo));
+        }
+
+        self.module_interfaces.insert(name.clone(), interface);
+
+        Ok(())
+    }
+
+    /// Lookup an interface, either qualified by a module alias or in the current scope.
+    #[allow(clippy::result_large_err)]
+    pub fn get_interface(
+        &self,
+        module_alias: &Option<String>,
+        name: &str,
+        location: Span,
+    ) -> Result<InterfaceInfo, Error> {
+        let interfaces = match module_alias {
+            None => &self.module_interfaces,
+            Some(alias) => {
+                let (_, module) =
+                    self.imported_modules
+                        .get(alias)
+                        .ok_or_else(|| Error::UnknownModule {
+                            location,
+                            name: alias.to_string(),
+                            known_modules: self
+                                .importable_modules
+                                .keys()
+                                .map(|t| t.to_string())
+                                .collect(),
+                        })?;
+
+                &module.interfaces
+            }
+        };
+
+        interfaces
+            .get(name)
+            .filter(|interface| interface.public || interface.module == *self.current_module)
+            .cloned()
+            .ok_or_else(|| Error::UnknownInterface {
+                location,
+                name: name.to_string(),
+                interfaces: interfaces.keys().cloned().collect(),
+            })
+    }
+
+    /// Turn the annotations of a 'where' clause into constraints on the function's type
+    /// parameters.
+    #[allow(clippy::result_large_err)]
+    fn constraints_from_annotations(
+        &mut self,
+        annotations: &[Annotation],
+        hydrator: &mut Hydrator,
+    ) -> Result<Vec<Constraint>, Error> {
+        annotations
+            .iter()
+            .map(|annotation| match annotation {
+                Annotation::Constructor {
+                    location,
+                    module,
+                    name,
+                    arguments,
+                } => match &arguments[..] {
+                    [parameter @ Annotation::Var { .. }] => {
+                        let interface = self.get_interface(module, name, *location)?;
+
+                        Ok(Constraint {
+                            interface: interface.name,
+                            interface_module: interface.module,
+                            location: *location,
+                            tipo: hydrator.type_from_annotation(parameter, self)?,
+                        })
+                    }
+                    _ => Err(Error::IllegalConstraint {
+                        location: *location,
+                    }),
+                },
+                _ => Err(Error::IllegalConstraint {
+                    location: annotation.location(),
+                }),
+            })
+            .collect()
+    }
+
+    /// Register the methods of an instance as (hidden) functions, once their types have been
+    /// checked against the interface.
+    #[allow(clippy::result_large_err)]
+    fn register_instance(
+        &mut self,
+        instance: &'a UntypedInstance,
+        module_name: &String,
+        hydrators: &mut HashMap<String, Hydrator>,
+        names: &mut HashMap<String, &'a Span>,
+    ) -> Result<(), Error> {
+        let (interface, tipo) = self.instance_head(instance)?;
+
+        if !tipo.is_monomorphic() {
+            return Err(Error::IllegalInstanceType {
+                location: instance.interface.location(),
+                interface: interface.name,
+                tipo,
+            });
+        }
+
+        if let Some(previous) = self.module_instances.iter().find(|previous| {
+            previous.interface == interface.name
+                && previous.interface_module == interface.module
+                && previous.tipo == tipo
+        }) {
+            return Err(Error::DuplicateInstance {
+                location: instance.location,
+                previous_location: previous.location,
+                interface: interface.name,
+                tipo,
+            });
+        }
+
+        for method in instance.methods.iter() {
+            if !method.constraints.is_empty() {
+                return Err(Error::IllegalConstraint {
+                    location: method.constraints[0].location(),
+                });
+            }
+
+            let Some(method_type) = interface.method(&method.name) else {
+                return Err(Error::UnknownInstanceMethod {
+                    location: method.location,
+                    interface: interface.name.clone(),
+                    method: method.name.clone(),
+                    methods: interface
+                        .methods
+                        .iter()
+                        .map(|(name, _)| name.clone())
+                        .collect(),
+                });
+            };
+
+            let name =
+                TypedInstance::method_name(&interface.name, &tipo.to_pretty(0), &method.name);
+
+            self.register_function(
+                &name,
+                &method.arguments,
+                &method.return_annotation,
+                module_name,
+                hydrators,
+                names,
+                &method.location,
+            )?;
+
+            let expected = self.instantiate(
+                method_type.clone(),
+                &mut HashMap::from([(interface.parameter_id(), tipo.clone())]),
+                &Hydrator::new(),
+            );
+
+            let registered = self
+                .get_variable(&name)
+                .expect("Could not find preregistered instance method")
+                .tipo
+                .clone();
+
+            self.unify(expected, registered, method.location, false)?;
+        }
+
+        if let Some((missing, _)) = interface
+            .methods
+            .iter()
+            .find(|(name, _)| !instance.methods.iter().any(|method| &method.name == name))
+        {
+            return Err(Error::MissingInstanceMethod {
+                location: instance.location,
+                interface: interface.name.clone(),
+                method: missing.clone(),
+            });
         }
+
+        self.module_instances.push(InstanceInfo {
+            interface: interface.name,
+            interface_module: interface.module,
+            module: module_name.clone(),
+            location: instance.location,
+            tipo,
+        });
+
         Ok(())
     }
 
+    /// Resolve the interface and the type an instance is defined for.
+    #[allow(clippy::result_large_err)]
+    pub fn instance_head<Arg, Expr>(
+        &mut self,
+        instance: &Instance<(), Arg, Expr>,
+    ) -> Result<(InterfaceInfo, Rc<Type>), Error> {
+        match &instance.interface {
+            Annotation::Constructor {
+                location,
+                module,
+                name,
+                arguments,
+            } => {
+                let interface = self.get_interface(module, name, *location)?;
+
+                match &arguments[..] {
+                    [argument] => {
+                        let tipo = Hydrator::new().type_from_annotation(argument, self)?;
+                        Ok((interface, tipo))
+                    }
+                    _ => Err(Error::IncorrectTypeArity {
+                        location: *location,
+                        name: name.clone(),
+                        expected: 1,
+                        given: arguments.len(),
+                    }),
+                }
+            }
+            annotation => Err(Error::IllegalInstanceType {
+                location: annotation.location(),
+                interface: String::new(),
+                tipo: Type::data(),
+            }),
+        }
+    }
+
+    /// Find the interface a module value is a method of, if any.
+    pub fn interface_of_method(&self, module: &str, name: &str) -> Option<&InterfaceInfo> {
+        let interfaces = if module == self.current_module {
+            &self.module_interfaces
+        } else {
+            &self.importable_modules.get(module)?.interfaces
+        };
+
+        interfaces
+            .values()
+            .find(|interface| interface.module == module && interface.method(name).is_some())
+    }
+
+    /// Find an interface by its name and the module defining it.
+    pub fn find_interface(&self, module: &str, name: &str) -> Option<&InterfaceInfo> {
+        if module == self.current_module {
+            self.module_interfaces
+                .values()
+                .find(|interface| interface.module == module && interface.name == name)
+        } else {
+            self.importable_modules.get(module)?.interfaces.get(name)
+        }
+    }
+
+    /// The (generic) type of a module function.
+    pub fn module_function_type(&self, module: &str, name: &str) -> Option<Rc<Type>> {
+        if module == self.current_module {
+            self.scope
+                .get(name)
+                .or_else(|| self.module_values.get(name))
+                .map(|value| value.tipo.clone())
+        } else {
+            self.importable_modules
+                .get(module)?
+                .values
+                .get(name)
+                .map(|value| value.tipo.clone())
+        }
+    }
+
+    /// The constraints of a module function, if any.
+    pub fn constraints_of(&self, module: &str, name: &str) -> Option<&Vec<Constraint>> {
+        if module == self.current_module {
+            self.module_constraints.get(name)
+        } else {
+            self.importable_modules.get(module)?.constraints.get(name)
+        }
+    }
+
+    /// Instances of an interface that are visible from the current module: those of the current
+    /// module and of imported modules, as well as those defined alongside the interface or
+    /// alongside the (head of the) type.
+    pub fn visible_instances(
+        &self,
+        interface: &str,
+        interface_module: &str,
+        tipo: &Type,
+    ) -> Vec<&InstanceInfo> {
+        let type_module = match tipo {
+            Type::App { module, .. } => Some(module.as_str()),
+            _ => None,
+        };
+
+        let mut modules = vec![interface_module];
+        modules.extend(type_module);
+        modules.extend(
+            self.imported_modules
+                .values()
+                .map(|(_, info)| info.name.as_str()),
+        );
+
+        let mut instances = self.module_instances.iter().collect::<Vec<_>>();
+
+        for module in modules.into_iter().unique() {
+            if module == self.current_module {
+                continue;
+            }
+            if let Some(info) = self.importable_modules.get(module) {
+                instances.extend(info.instances.iter());
+            }
+        }
+
+        instances.retain(|instance| {
+            instance.interface == interface && instance.interface_module == interface_module
+        });
+
+        instances
+    }
+
+    /// Mark the imports of a module, by its full name, as used.
+    pub fn use_module(&mut self, module: &str) {
+        for (name, (_, info)) in self.imported_modules.iter() {
+            if info.name == module {
+                self.unused_modules.remove(name);
+            }
+        }
+    }
+
     /// Unify two types that should be the same.
     /// Any unbound type variables will be linked to the other type as they are the same.
     ///
@@ -1029,6 +1029,19 @@ The best thing to do from here is to remove it."#))]
         location: Span,
     },
 
+    #[error("I caught a benchmark with an incorrect number of arguments.\n")]
+    #[diagnostic(code("illegal::benchmark::arity"))]
+    #[diagnostic(help(
+        "Benchmarks must have exactly one argument, drawn {via} a {Sampler}. Here I've found a benchmark definition with {count} arguments. If you need to provide multiple values to a benchmark, use a Record or a Tuple.",
+        via = "via".if_supports_color(Stderr, |s| s.yellow()),
+        Sampler = "Sampler".if_supports_color(Stderr, |s| s.cyan()),
+    ))]
+    IncorrectBenchmarkArity {
+        count: usize,
+        #[label("{} arguments", if *count == 0 { "not enough" } else { "too many" })]
+        location: Span,
+    },
+
     #[error("I caught a test with an illegal return type.\n")]
     #[diagnostic(code("illegal::test::return"))]
     #[diagnostic(help(
@@ -1053,7 +1066,7 @@ The best thing to do from here is to remove it."#))]
 
     #[error("Cannot infer caller without inferring callee first")]
     MustInferFirst {
-        function: UntypedFunction,
+        function: Box<UntypedFunction>,
         location: Span,
     },
 
@@ -1097,6 +1110,138 @@ The best thing to do from here is to remove it."#))]
         #[label("redundant fallback handler")]
         fallback: Span,
     },
+
+    #[error("I couldn't find any interface named '{}'.\n", name.if_supports_color(Stdout, |s| s.cyan()))]
+    #[diagnostic(code("unknown::interface"))]
+    #[diagnostic(help(
+        "{}",
+        suggest_neighbor(name, interfaces.iter(), "Did you forget to import it?")
+    ))]
+    UnknownInterface {
+        #[label("unknown interface")]
+        location: Span,
+        name: String,
+        interfaces: Vec<String>,
+    },
+
+    #[error(
+        "I found an interface method whose type doesn't depend on the interface parameter only.\n"
+    )]
+    #[diagnostic(code("illegal::interface::method"))]
+    #[diagnostic(help(
+        "The type of interface methods may only refer to the interface's own type parameter {parameter}, and to concrete types. It must also mention that parameter at least once, or I would have no way to tell which instance to pick.",
+        parameter = parameter.if_supports_color(Stdout, |s| s.cyan()),
+    ))]
+    IllegalInterfaceMethod {
+        #[label("illegal method type")]
+        location: Span,
+        parameter: String,
+    },
+
+    #[error("I found a constraint that I cannot satisfy.\n")]
+    #[diagnostic(code("illegal::constraint"))]
+    #[diagnostic(help(
+        "Constraints are written in a {keyword_where} clause of a function definition, and apply an interface to one of the function's type parameters, for example: {example}. They can't appear on instance methods.",
+        keyword_where = "where".if_supports_color(Stdout, |s| s.yellow()),
+        example = "where Show<a>".if_supports_color(Stdout, |s| s.cyan()),
+    ))]
+    IllegalConstraint {
+        #[label("illegal constraint")]
+        location: Span,
+    },
+
+    #[error(
+        "I found an instance of '{}' for a type that isn't fully known: {}.\n",
+        interface.if_supports_color(Stdout, |s| s.cyan()),
+        tipo.to_pretty(0).if_supports_color(Stdout, |s| s.cyan()),
+    )]
+    #[diagnostic(code("illegal::instance::type"))]
+    #[diagnostic(help(
+        "Instances must be defined for concrete types only, without any type variable."
+    ))]
+    IllegalInstanceType {
+        #[label("generic instance")]
+        location: Span,
+        interface: String,
+        tipo: Rc<Type>,
+    },
+
+    #[error(
+        "I found more than one instance of '{}' for {}.\n",
+        interface.if_supports_color(Stdout, |s| s.cyan()),
+        tipo.to_pretty(0).if_supports_color(Stdout, |s| s.cyan()),
+    )]
+    #[diagnostic(code("duplicate::instance"))]
+    #[diagnostic(help("There can be at most one instance of an interface for any given type."))]
+    DuplicateInstance {
+        #[label("duplicate instance")]
+        location: Span,
+        #[label("previously defined here")]
+        previous_location: Span,
+        interface: String,
+        tipo: Rc<Type>,
+    },
+
+    #[error(
+        "I found an instance of '{}' that doesn't define '{}'.\n",
+        interface.if_supports_color(Stdout, |s| s.cyan()),
+        method.if_supports_color(Stdout, |s| s.purple()),
+    )]
+    #[diagnostic(code("missing::instance::method"))]
+    #[diagnostic(help("Instances must define every method of their interface."))]
+    MissingInstanceMethod {
+        #[label("incomplete instance")]
+        location: Span,
+        interface: String,
+        method: String,
+    },
+
+    #[error(
+        "I found an instance of '{}' defining an unknown method '{}'.\n",
+        interface.if_supports_color(Stdout, |s| s.cyan()),
+        method.if_supports_color(Stdout, |s| s.purple()),
+    )]
+    #[diagnostic(code("unknown::instance::method"))]
+    #[diagnostic(help("{}", suggest_neighbor(method, methods.iter(), "Instances may only define the methods of their interface.")))]
+    UnknownInstanceMethod {
+        #[label("unknown method")]
+        location: Span,
+        interface: String,
+        method: String,
+        methods: Vec<String>,
+    },
+
+    #[error(
+        "I couldn't find any instance of '{}' for {}.\n",
+        interface.if_supports_color(Stdout, |s| s.cyan()),
+        tipo.to_pretty_with_names(rigid_type_names.clone(), 0).if_supports_color(Stdout, |s| s.cyan()),
+    )]
+    #[diagnostic(code("missing::instance"))]
+    #[diagnostic(help(
+        "Instances are looked up in the current module, in imported modules, and in the modules defining the interface or the type. When the type is a type parameter of the enclosing function, add a constraint for it, for example: {example}.",
+        example = "where Show<a>".if_supports_color(Stdout, |s| s.cyan()),
+    ))]
+    MissingInstance {
+        #[label("no instance")]
+        location: Span,
+        interface: String,
+        tipo: Rc<Type>,
+        rigid_type_names: HashMap<u64, String>,
+    },
+
+    #[error(
+        "I couldn't figure out which instance of '{}' to use here.\n",
+        interface.if_supports_color(Stdout, |s| s.cyan()),
+    )]
+    #[diagnostic(code("ambiguous::instance"))]
+    #[diagnostic(help(
+        "The type this interface is applied to isn't known at this point. Try adding a type annotation to the surrounding expression."
+    ))]
+    AmbiguousInstance {
+        #[label("ambiguous instance")]
+        location: Span,
+        interface: String,
+    },
 }
 
 impl ExtraData for Error {
@@ -1150,6 +1295,7 @@ impl ExtraData for Error {
             | Error::UpdateMultiConstructorType { .. }
             | Error::ValidatorImported { .. }
             | Error::IncorrectTestArity { .. }
+            | Error::IncorrectBenchmarkArity { .. }
             | Error::IllegalTestType { .. }
             | Error::GenericLeftAtBoundary { .. }
             | Error::UnexpectedMultiPatternAssignment { .. }
@@ -1158,6 +1304,15 @@ impl ExtraData for Error {
             | Error::UnknownPurpose { .. }
             | Error::UnknownValidatorHandler { .. }
             | Error::UnexpectedValidatorFallback { .. }
+            | Error::UnknownInterface { .. }
+            | Error::IllegalInterfaceMethod { .. }
+            | Error::IllegalConstraint { .. }
+            | Error::IllegalInstanceType { .. }
+            | Error::DuplicateInstance { .. }
+            | Error::MissingInstanceMethod { .. }
+            | Error::UnknownInstanceMethod { .. }
+            | Error::MissingInstance { .. }
+            | Error::AmbiguousInstance { .. }
             | Error::MustInferFirst { .. } => None,
 
             Error::UnknownType { name, .. }
@@ -1539,7 +1694,9 @@ fn suggest_import_constructor() -> String {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, thiserror::Error, Diagnostic)]
+#[derive(
+    Debug, PartialEq, Clone, thiserror::Error, Diagnostic, serde::Serialize, serde::Deserialize,
+)]
 pub enum Warning {
     #[error("I found a record update using all fields; thus redundant.")]
     #[diagnostic(url("https://aiken-lang.org/language-tour/custom-types#record-updates"))]
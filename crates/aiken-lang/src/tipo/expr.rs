@@ -1,4 +1,5 @@
 use super::{
+    dictionary::DictionaryPass,
     environment::{
         assert_no_labeled_arguments, collapse_links, generalise, EntityKind, Environment,
     },
@@ -54,7 +55,9 @@ pub(crate) fn infer_function(
         return_annotation,
         end_position,
         on_test_failure,
+        constraints,
         return_type: _,
+        dictionaries: _,
     } = fun;
 
     let mut extra_let_assignments = Vec::new();
@@ -158,7 +161,7 @@ pub(crate) fn infer_function(
         return infer_function(fun, module_name, hydrators, environment, tracing);
     }
 
-    let (arguments, body, return_type) = inferred?;
+    let (arguments, mut body, return_type) = inferred?;
 
     let args_types = arguments.iter().map(|a| a.tipo.clone()).collect();
 
@@ -166,9 +169,20 @@ pub(crate) fn infer_function(
 
     let safe_to_generalise = !expr_typer.ungeneralised_function_used;
 
+    let rigid_type_names = expr_typer.hydrator.rigid_names();
+
     environment.close_scope(initial_scope);
     // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
 
+    // Resolve interface methods now that all types in the body are known, and collect the
+    // dictionaries of the function's own constraints, passed as extra arguments. Those don't show
+    // in the function's type, since they are filled in at every call site.
+    let dictionaries = {
+        let mut pass = DictionaryPass::new(environment, name, rigid_type_names);
+        pass.run(&mut body)?;
+        pass.arguments()
+    };
+
     // Assert that the inferred type matches the type of any recursive call
     environment.unify(preregistered_type, tipo.clone(), *location, false)?;
 
@@ -207,6 +221,8 @@ pub(crate) fn infer_function(
         body,
         on_test_failure: on_test_failure.clone(),
         end_position: *end_position,
+        constraints: constraints.clone(),
+        dictionaries,
     };
 
     environment
@@ -2512,7 +2528,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                         // encountered it.
                         if self.not_yet_inferred.contains(&fun.name) {
                             return Err(Error::MustInferFirst {
-                                function: fun.clone(),
+                                function: Box::new(fun.clone()),
                                 location: *location,
                             });
                         }
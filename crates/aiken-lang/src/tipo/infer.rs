@@ -7,10 +7,11 @@ use super::{
 };
 use crate::{
     ast::{
-        Annotation, ArgBy, ArgName, ArgVia, DataType, Definition, Function, ModuleConstant,
-        ModuleKind, RecordConstructor, RecordConstructorArg, Tracing, TypeAlias, TypedArg,
-        TypedDefinition, TypedModule, TypedValidator, UntypedArg, UntypedDefinition, UntypedModule,
-        UntypedPattern, UntypedValidator, Use, Validator,
+        Annotation, ArgBy, ArgName, ArgVia, DataType, Definition, Function, Instance, Interface,
+        InterfaceMethod, ModuleConstant, ModuleKind, RecordConstructor, RecordConstructorArg,
+        Tracing, TypeAlias, TypedArg, TypedDefinition, TypedInstance, TypedModule, TypedValidator,
+        UntypedArg, UntypedArgVia, UntypedDefinition, UntypedModule, UntypedPattern,
+        UntypedValidator, Use, Validator,
     },
     expr::{TypedExpr, UntypedAssignmentKind},
     tipo::{expr::infer_function, Span, Type, TypeVar},
@@ -56,6 +57,12 @@ impl UntypedModule {
             &mut type_names,
         )?;
 
+        // Register interfaces so they can be used in functions and instances anywhere in the
+        // module.
+        for def in self.definitions() {
+            environment.register_interface(def, &module_name, &mut type_names, &mut value_names)?;
+        }
+
         // Register values so they can be used in functions earlier in the module.
         for def in self.definitions() {
             environment.register_values(
@@ -81,9 +88,12 @@ impl UntypedModule {
                 Definition::Validator { .. } => (),
                 Definition::Fn { .. }
                 | Definition::Test { .. }
+                | Definition::Benchmark { .. }
                 | Definition::TypeAlias { .. }
                 | Definition::DataType { .. }
-                | Definition::Use { .. } => not_consts.push(def),
+                | Definition::Use { .. }
+                | Definition::Interface { .. }
+                | Definition::Instance { .. } => not_consts.push(def),
             }
         }
 
@@ -120,6 +130,10 @@ impl UntypedModule {
             .accessors
             .retain(|_, accessors| accessors.public);
 
+        environment
+            .module_interfaces
+            .retain(|_, info| info.public && info.module == module_name);
+
         // Ensure no exported values have private types in their type signature
         for value in environment.module_values.values() {
             if let Some(leaked) = value.tipo.find_private_type() {
@@ -136,6 +150,9 @@ impl UntypedModule {
             module_values: values,
             accessors,
             annotations,
+            module_interfaces: interfaces,
+            module_instances: instances,
+            module_constraints: constraints,
             ..
         } = environment;
 
@@ -152,6 +169,9 @@ impl UntypedModule {
                 values,
                 accessors,
                 annotations,
+                interfaces,
+                instances,
+                constraints,
                 kind,
                 package: package.to_string(),
             },
@@ -346,62 +366,8 @@ fn infer_definition(
                         });
                     }
 
-                    let typed_via = ExprTyper::new(environment, tracing).infer(arg.via.clone())?;
-
-                    let hydrator: &mut Hydrator = hydrators.get_mut(&f.name).unwrap();
-
-                    let provided_inner_type = arg
-                        .arg
-                        .annotation
-                        .as_ref()
-                        .map(|ann| hydrator.type_from_annotation(ann, environment))
-                        .transpose()?;
-
-                    let (inferred_annotation, inferred_inner_type) = infer_fuzzer(
-                        environment,
-                        provided_inner_type.clone(),
-                        &typed_via.tipo(),
-                        &arg.via.location(),
-                    )?;
-
-                    // Ensure that the annotation, if any, matches the type inferred from the
-                    // Fuzzer.
-                    if let Some(provided_inner_type) = provided_inner_type {
-                        if !arg
-                            .arg
-                            .annotation
-                            .as_ref()
-                            .unwrap()
-                            .is_logically_equal(&inferred_annotation)
-                        {
-                            return Err(Error::CouldNotUnify {
-                                location: arg.arg.location,
-                                expected: inferred_inner_type.clone(),
-                                given: provided_inner_type.clone(),
-                                situation: Some(UnifyErrorSituation::FuzzerAnnotationMismatch),
-                                rigid_type_names: hydrator.rigid_names(),
-                            });
-                        }
-                    }
-
-                    // Replace the pre-registered type for the test function, to allow inferring
-                    // the function body with the right type arguments.
-                    let scope = environment
-                        .scope
-                        .get_mut(&f.name)
-                        .expect("Could not find preregistered type for test");
-                    if let Type::Fn {
-                        ref ret,
-                        ref alias,
-                        args: _,
-                    } = scope.tipo.as_ref()
-                    {
-                        scope.tipo = Rc::new(Type::Fn {
-                            ret: ret.clone(),
-                            args: vec![inferred_inner_type.clone()],
-                            alias: alias.clone(),
-                        })
-                    }
+                    let (typed_via, inferred_inner_type, inferred_annotation) =
+                        infer_arg_via(arg, &f.name, hydrators, environment, tracing, infer_fuzzer)?;
 
                     Ok((
                         Some((typed_via, inferred_inner_type)),
@@ -461,6 +427,133 @@ fn infer_definition(
                 body: typed_f.body,
                 on_test_failure: typed_f.on_test_failure,
                 end_position: typed_f.end_position,
+                constraints: typed_f.constraints,
+                dictionaries: vec![],
+            }))
+        }
+
+        Definition::Benchmark(f) => {
+            let arg = match &f.arguments[..] {
+                [arg] => arg,
+                _ => {
+                    return Err(Error::IncorrectBenchmarkArity {
+                        count: f.arguments.len(),
+                        location: f
+                            .arguments
+                            .get(1)
+                            .map(|arg| arg.arg.location)
+                            .unwrap_or(f.location),
+                    })
+                }
+            };
+
+            let (typed_via, inferred_inner_type, annotation) =
+                infer_arg_via(arg, &f.name, hydrators, environment, tracing, infer_sampler)?;
+
+            let typed_f = infer_function(&f.into(), module_name, hydrators, environment, tracing)?;
+
+            let arg = typed_f
+                .arguments
+                .first()
+                .expect("has exactly one argument")
+                .to_owned();
+
+            Ok(Definition::Benchmark(Function {
+                doc: typed_f.doc,
+                location: typed_f.location,
+                name: typed_f.name,
+                public: typed_f.public,
+                arguments: vec![ArgVia {
+                    arg: TypedArg {
+                        tipo: inferred_inner_type,
+                        annotation: Some(annotation),
+                        ..arg
+                    },
+                    via: typed_via,
+                }],
+                return_annotation: typed_f.return_annotation,
+                return_type: typed_f.return_type,
+                body: typed_f.body,
+                on_test_failure: typed_f.on_test_failure,
+                end_position: typed_f.end_position,
+                constraints: typed_f.constraints,
+                dictionaries: vec![],
+            }))
+        }
+
+        Definition::Interface(Interface {
+            doc,
+            location,
+            methods,
+            name,
+            parameter,
+            public,
+        }) => {
+            let info = environment
+                .module_interfaces
+                .get(&name)
+                .expect("Could not find preregistered interface")
+                .clone();
+
+            let methods = methods
+                .into_iter()
+                .map(|method| InterfaceMethod {
+                    tipo: info
+                        .method(&method.name)
+                        .cloned()
+                        .expect("Could not find preregistered interface method"),
+                    annotation: method.annotation,
+                    doc: method.doc,
+                    location: method.location,
+                    name: method.name,
+                })
+                .collect();
+
+            Ok(Definition::Interface(Interface {
+                doc,
+                location,
+                methods,
+                name,
+                parameter,
+                public,
+            }))
+        }
+
+        Definition::Instance(instance) => {
+            let (interface, tipo) = environment.instance_head(&instance)?;
+
+            let Instance {
+                doc,
+                end_position,
+                interface: annotation,
+                location,
+                methods,
+                tipo: _,
+            } = instance;
+
+            let mut typed_methods = Vec::with_capacity(methods.len());
+
+            for mut method in methods {
+                let old_name = method.name;
+
+                method.name =
+                    TypedInstance::method_name(&interface.name, &tipo.to_pretty(0), &old_name);
+
+                let mut typed_method =
+                    infer_function(&method, module_name, hydrators, environment, tracing)?;
+
+                typed_method.name = old_name;
+
+                typed_methods.push(typed_method);
+            }
+
+            Ok(Definition::Instance(Instance {
+                doc,
+                end_position,
+                interface: annotation,
+                location,
+                methods: typed_methods,
+                tipo,
             }))
         }
 
@@ -689,6 +782,87 @@ fn infer_definition(
     }
 }
 
+/// Infer the type of an argument drawn 'via' some generator (e.g. a Fuzzer for property tests
+/// or a Sampler for benchmarks), ensuring that its annotation (if any) matches the generated
+/// type. Also replaces the pre-registered type of the surrounding definition so that its body
+/// can be inferred with the right argument type.
+#[allow(clippy::result_large_err)]
+fn infer_arg_via<F>(
+    arg: &UntypedArgVia,
+    name: &str,
+    hydrators: &mut HashMap<String, Hydrator>,
+    environment: &mut Environment<'_>,
+    tracing: Tracing,
+    infer_via: F,
+) -> Result<(TypedExpr, Rc<Type>, Annotation), Error>
+where
+    F: FnOnce(
+        &mut Environment<'_>,
+        Option<Rc<Type>>,
+        &Rc<Type>,
+        &Span,
+    ) -> Result<(Annotation, Rc<Type>), Error>,
+{
+    let typed_via = ExprTyper::new(environment, tracing).infer(arg.via.clone())?;
+
+    let hydrator: &mut Hydrator = hydrators.get_mut(name).unwrap();
+
+    let provided_inner_type = arg
+        .arg
+        .annotation
+        .as_ref()
+        .map(|ann| hydrator.type_from_annotation(ann, environment))
+        .transpose()?;
+
+    let (inferred_annotation, inferred_inner_type) = infer_via(
+        environment,
+        provided_inner_type.clone(),
+        &typed_via.tipo(),
+        &arg.via.location(),
+    )?;
+
+    // Ensure that the annotation, if any, matches the type inferred from the
+    // Fuzzer (or Sampler).
+    if let Some(provided_inner_type) = provided_inner_type {
+        if !arg
+            .arg
+            .annotation
+            .as_ref()
+            .unwrap()
+            .is_logically_equal(&inferred_annotation)
+        {
+            return Err(Error::CouldNotUnify {
+                location: arg.arg.location,
+                expected: inferred_inner_type.clone(),
+                given: provided_inner_type.clone(),
+                situation: Some(UnifyErrorSituation::FuzzerAnnotationMismatch),
+                rigid_type_names: hydrator.rigid_names(),
+            });
+        }
+    }
+
+    // Replace the pre-registered type for the test (or benchmark) function, to allow
+    // inferring the function body with the right type arguments.
+    let scope = environment
+        .scope
+        .get_mut(name)
+        .expect("Could not find preregistered type for test");
+    if let Type::Fn {
+        ref ret,
+        ref alias,
+        args: _,
+    } = scope.tipo.as_ref()
+    {
+        scope.tipo = Rc::new(Type::Fn {
+            ret: ret.clone(),
+            args: vec![inferred_inner_type.clone()],
+            alias: alias.clone(),
+        })
+    }
+
+    Ok((typed_via, inferred_inner_type, inferred_annotation))
+}
+
 #[allow(clippy::result_large_err)]
 fn infer_fuzzer(
     environment: &mut Environment<'_>,
@@ -766,6 +940,57 @@ fn infer_fuzzer(
     }
 }
 
+#[allow(clippy::result_large_err)]
+fn infer_sampler(
+    environment: &mut Environment<'_>,
+    expected_inner_type: Option<Rc<Type>>,
+    tipo: &Rc<Type>,
+    location: &Span,
+) -> Result<(Annotation, Rc<Type>), Error> {
+    let could_not_unify = || Error::CouldNotUnify {
+        location: *location,
+        expected: Type::sampler(
+            expected_inner_type
+                .clone()
+                .unwrap_or_else(|| Type::generic_var(0)),
+        ),
+        given: tipo.clone(),
+        situation: None,
+        rigid_type_names: HashMap::new(),
+    };
+
+    match tipo.borrow() {
+        Type::Fn {
+            ret,
+            args,
+            alias: _,
+        } => match &args[..] {
+            [size] => {
+                environment
+                    .unify(size.clone(), Type::int(), *location, false)
+                    .map_err(|_| could_not_unify())?;
+
+                infer_fuzzer(environment, expected_inner_type.clone(), ret, location)
+            }
+            _ => Err(could_not_unify()),
+        },
+
+        Type::Var { tipo, alias } => match &*tipo.deref().borrow() {
+            TypeVar::Link { tipo } => infer_sampler(
+                environment,
+                expected_inner_type,
+                &Type::with_alias(tipo.clone(), alias.clone()),
+                location,
+            ),
+            _ => Err(Error::GenericLeftAtBoundary {
+                location: *location,
+            }),
+        },
+
+        Type::App { .. } | Type::Tuple { .. } | Type::Pair { .. } => Err(could_not_unify()),
+    }
+}
+
 #[allow(clippy::result_large_err)]
 fn annotate_fuzzer(tipo: &Type, location: &Span) -> Result<Annotation, Error> {
     match tipo {
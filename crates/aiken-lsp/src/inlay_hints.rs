@@ -0,0 +1,396 @@
+use crate::server::settings;
+use aiken_lang::{
+    ast::{ArgName, Definition, Located, Pattern, Span, TypedArg, TypedPattern},
+    expr::TypedExpr,
+    tipo::{pretty::Printer, Type},
+};
+use aiken_project::module::CheckedModule;
+use itertools::Itertools;
+use std::rc::Rc;
+
+/// The inferred type of a binding, argument or intermediate value, to be shown at a given byte
+/// index of a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlayHint {
+    pub position: usize,
+    pub label: String,
+    pub kind: InlayHintKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InlayHintKind {
+    /// Shown right after a name, as an annotation would be (e.g. `x: Int`).
+    Annotation,
+    /// Shown after an expression, apart from it.
+    Value,
+}
+
+/// Inlay hints of a module, for the kinds enabled in the given settings, in order of position.
+pub fn inlay_hints(module: &CheckedModule, settings: &settings::InlayHints) -> Vec<InlayHint> {
+    let mut collector = Collector {
+        code: &module.code,
+        settings,
+        hints: Vec::new(),
+    };
+
+    for definition in module.ast.definitions() {
+        collector.definition(definition);
+    }
+
+    collector.hints.sort_by_key(|hint| hint.position);
+
+    collector.hints
+}
+
+struct Collector<'a> {
+    code: &'a str,
+    settings: &'a settings::InlayHints,
+    hints: Vec<InlayHint>,
+}
+
+impl Collector<'_> {
+    fn definition(&mut self, definition: &Definition<Rc<Type>, TypedArg, TypedExpr, String>) {
+        match definition {
+            Definition::Fn(function) => {
+                function.arguments.iter().for_each(|arg| self.arg(arg));
+                self.expr(&function.body);
+            }
+
+            Definition::ModuleConstant(constant) => self.expr(&constant.value),
+
+            Definition::Test(test) | Definition::Benchmark(test) => {
+                for arg_via in test.arguments.iter() {
+                    self.arg(&arg_via.arg);
+                    self.expr(&arg_via.via);
+                }
+                self.expr(&test.body);
+            }
+
+            Definition::Validator(validator) => {
+                validator.params.iter().for_each(|arg| self.arg(arg));
+                for handler in validator
+                    .handlers
+                    .iter()
+                    .chain(std::iter::once(&validator.fallback))
+                {
+                    handler.arguments.iter().for_each(|arg| self.arg(arg));
+                    self.expr(&handler.body);
+                }
+            }
+
+            Definition::Instance(instance) => {
+                for method in instance.methods.iter() {
+                    method.arguments.iter().for_each(|arg| self.arg(arg));
+                    self.expr(&method.body);
+                }
+            }
+
+            Definition::DataType(_)
+            | Definition::TypeAlias(_)
+            | Definition::Use(_)
+            | Definition::Interface(_) => {}
+        }
+    }
+
+    fn arg(&mut self, arg: &TypedArg) {
+        if !self.settings.function_arguments || arg.annotation.is_some() {
+            return;
+        }
+
+        if let ArgName::Named { name, location, .. } = &arg.arg_name {
+            self.annotation(name, *location, &arg.tipo);
+        }
+    }
+
+    fn expr(&mut self, expr: &TypedExpr) {
+        match expr {
+            TypedExpr::Fn {
+                args,
+                body,
+                is_capture,
+                ..
+            } => {
+                // NOTE: The argument of a capture (e.g. `add(_, 1)`) isn't written by the user.
+                if !is_capture {
+                    args.iter().for_each(|arg| self.arg(arg));
+                }
+                self.expr(body);
+            }
+
+            TypedExpr::Assignment { value, pattern, .. } => {
+                self.expr(value);
+                if self.settings.bindings && !self.is_annotated(pattern) {
+                    self.pattern(pattern, &value.tipo());
+                }
+            }
+
+            TypedExpr::Pipeline {
+                expressions,
+                location,
+            } => {
+                let multiline = self
+                    .code
+                    .get(location.start..location.end)
+                    .is_some_and(|code| code.contains('\n'));
+
+                for expression in expressions.iter() {
+                    match expression {
+                        // NOTE: Intermediate values of the pipeline are bound to a variable that
+                        // isn't written by the user; only the value itself is of interest.
+                        TypedExpr::Assignment { value, .. } => {
+                            self.expr(value);
+                            if self.settings.pipelines && multiline {
+                                self.hints.push(InlayHint {
+                                    position: value.location().end,
+                                    label: pretty_print(&value.tipo()),
+                                    kind: InlayHintKind::Value,
+                                });
+                            }
+                        }
+                        expression => self.expr(expression),
+                    }
+                }
+            }
+
+            TypedExpr::When {
+                subject, clauses, ..
+            } => {
+                self.expr(subject);
+                clauses.iter().for_each(|clause| self.expr(&clause.then));
+            }
+
+            TypedExpr::If {
+                branches,
+                final_else,
+                ..
+            } => {
+                for branch in branches.iter() {
+                    self.expr(&branch.condition);
+                    self.expr(&branch.body);
+                }
+                self.expr(final_else);
+            }
+
+            TypedExpr::Trace { then, text, .. } => {
+                self.expr(text);
+                self.expr(then);
+            }
+
+            TypedExpr::Sequence { expressions, .. } => {
+                expressions.iter().for_each(|e| self.expr(e));
+            }
+
+            TypedExpr::List { elements, tail, .. } => {
+                elements.iter().for_each(|e| self.expr(e));
+                if let Some(tail) = tail {
+                    self.expr(tail);
+                }
+            }
+
+            TypedExpr::Call { fun, args, .. } => {
+                self.expr(fun);
+                args.iter().for_each(|arg| self.expr(&arg.value));
+            }
+
+            TypedExpr::BinOp { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+            }
+
+            TypedExpr::Tuple { elems, .. } => elems.iter().for_each(|e| self.expr(e)),
+
+            TypedExpr::Pair { fst, snd, .. } => {
+                self.expr(fst);
+                self.expr(snd);
+            }
+
+            TypedExpr::RecordUpdate { spread, args, .. } => {
+                self.expr(spread);
+                args.iter().for_each(|arg| self.expr(&arg.value));
+            }
+
+            TypedExpr::RecordAccess { record, .. } => self.expr(record),
+
+            TypedExpr::TupleIndex { tuple, .. } => self.expr(tuple),
+
+            TypedExpr::UnOp { value, .. } => self.expr(value),
+
+            TypedExpr::Var { .. }
+            | TypedExpr::ModuleSelect { .. }
+            | TypedExpr::UInt { .. }
+            | TypedExpr::String { .. }
+            | TypedExpr::ByteArray { .. }
+            | TypedExpr::CurvePoint { .. }
+            | TypedExpr::ErrorTerm { .. } => {}
+        }
+    }
+
+    /// Hint the type of each variable bound by a pattern, given the type of the value it
+    /// destructures.
+    fn pattern(&mut self, pattern: &TypedPattern, tipo: &Rc<Type>) {
+        // NOTE: An alias of the whole pattern has the type of the value itself.
+        if let Pattern::Assign { name, location, .. } = pattern {
+            let alias = Span {
+                start: location.end.saturating_sub(name.len()),
+                end: location.end,
+            };
+
+            return self.annotation(name, alias, tipo);
+        }
+
+        let mut variables = Vec::new();
+
+        variables_of(pattern, &mut variables);
+
+        for (name, location) in variables {
+            if let Some(Located::Pattern(_, tipo)) = pattern.find_node(location.start, tipo) {
+                self.annotation(name, location, &tipo);
+            }
+        }
+    }
+
+    /// Whether an assignment's pattern is followed by an annotation, which the typed AST doesn't
+    /// retain.
+    fn is_annotated(&self, pattern: &TypedPattern) -> bool {
+        self.code
+            .get(pattern.location().end..)
+            .is_some_and(|rest| rest.trim_start().starts_with(':'))
+    }
+
+    /// Hint the type of a name, unless it was introduced by the compiler rather than written by
+    /// the user (e.g. the variables of a pipeline).
+    fn annotation(&mut self, name: &str, location: Span, tipo: &Type) {
+        if self.code.get(location.start..location.end) != Some(name) {
+            return;
+        }
+
+        self.hints.push(InlayHint {
+            position: location.end,
+            label: format!(": {}", pretty_print(tipo)),
+            kind: InlayHintKind::Annotation,
+        });
+    }
+}
+
+/// Variables bound by a pattern. Those of aliased patterns (e.g. `(a, b) as pair`) are left out,
+/// as the types of their sub-patterns can't be told apart from that of the whole.
+fn variables_of<'a>(pattern: &'a TypedPattern, variables: &mut Vec<(&'a str, Span)>) {
+    match pattern {
+        Pattern::Var { name, location } => variables.push((name, *location)),
+
+        Pattern::List { elements, tail, .. } => {
+            for element in elements.iter().chain(tail.as_deref()) {
+                variables_of(element, variables);
+            }
+        }
+
+        Pattern::Tuple { elems, .. } => elems
+            .iter()
+            .for_each(|element| variables_of(element, variables)),
+
+        Pattern::Pair { fst, snd, .. } => {
+            variables_of(fst, variables);
+            variables_of(snd, variables);
+        }
+
+        Pattern::Constructor { arguments, .. } => arguments
+            .iter()
+            .for_each(|argument| variables_of(&argument.value, variables)),
+
+        Pattern::Assign { .. }
+        | Pattern::Int { .. }
+        | Pattern::ByteArray { .. }
+        | Pattern::Discard { .. } => {}
+    }
+}
+
+/// Print a type on a single line, as hints can't span several.
+fn pretty_print(tipo: &Type) -> String {
+    Printer::new()
+        .pretty_print(tipo, 0)
+        .lines()
+        .map(str::trim)
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{check, offset};
+    use indoc::indoc;
+
+    const CODE: &str = indoc! {r#"
+        pub fn total(xs: List<Int>) -> Int {
+          let (a, b) = (1, #"00")
+          let c: Int = a
+          let sum =
+            xs
+              |> foldr(0, fn(x, acc) { x + acc })
+              |> fn(n) { n + c }
+          trace b
+          sum
+        }
+
+        fn foldr(xs: List<a>, zero: b, with: fn(a, b) -> b) -> b {
+          when xs is {
+            [] -> zero
+            [x, ..rest] -> with(x, foldr(rest, zero, with))
+          }
+        }
+    "#};
+
+    fn hints(settings: settings::InlayHints) -> Vec<(usize, String)> {
+        let modules = check(&[("main", CODE)]);
+
+        inlay_hints(&modules["main"], &settings)
+            .into_iter()
+            .map(|hint| (hint.position, hint.label))
+            .collect()
+    }
+
+    fn after(text: &str, nth: usize) -> usize {
+        offset(CODE, text, nth) + text.len()
+    }
+
+    #[test]
+    fn all_hints() {
+        assert_eq!(
+            hints(settings::InlayHints::default()),
+            vec![
+                (after("(a", 0), ": Int".to_string()),
+                (after(", b", 0), ": ByteArray".to_string()),
+                (after("let sum", 0), ": Int".to_string()),
+                (after("    xs", 0), "List<Int>".to_string()),
+                (after("fn(x", 0), ": Int".to_string()),
+                (after(", acc", 0), ": Int".to_string()),
+                (after("acc })", 0), "Int".to_string()),
+                (after("fn(n", 0), ": Int".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn disabled_hints() {
+        assert_eq!(
+            hints(settings::InlayHints {
+                bindings: false,
+                pipelines: false,
+                function_arguments: true,
+            }),
+            vec![
+                (after("fn(x", 0), ": Int".to_string()),
+                (after(", acc", 0), ": Int".to_string()),
+                (after("fn(n", 0), ": Int".to_string()),
+            ]
+        );
+
+        assert_eq!(
+            hints(settings::InlayHints {
+                bindings: false,
+                pipelines: false,
+                function_arguments: false,
+            }),
+            vec![]
+        );
+    }
+}
@@ -0,0 +1,1177 @@
+use crate::utils::identifier_span;
+use aiken_lang::{
+    ast::{
+        ArgName, Definition, Span, TypedArg, TypedFunction, TypedPattern, Use, DICTIONARY_VARIABLE,
+    },
+    expr::TypedExpr,
+    parser::{lexer, token::Token},
+    tipo::{ModuleValueConstructor, Type, ValueConstructorVariant},
+};
+use aiken_project::module::CheckedModule;
+use std::{collections::HashMap, rc::Rc};
+
+/// A value that can be referred to by name: a function, a constant, a constructor or a local
+/// variable. Symbols are identified by the module defining them and the location of their name
+/// in that module.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Symbol {
+    pub module: String,
+    pub name: String,
+    pub span: Span,
+    pub kind: SymbolKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    Value,
+    Constructor,
+    Local,
+}
+
+/// An occurrence of a symbol, in the source of a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub symbol: Symbol,
+    pub module: String,
+    pub span: Span,
+    pub kind: ReferenceKind,
+    /// Whether the occurrence also stands for the label of a record field, as in `Box { inner }`.
+    pub punned: bool,
+    /// Whether the occurrence is qualified by a module (as in `list.map`) or imported under an
+    /// alias, in which case it doesn't bring the symbol's name into scope.
+    pub qualified: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    /// Where the symbol is defined.
+    Definition,
+    /// A use of the symbol, under its own name.
+    Usage,
+    /// A use of the symbol under another name, given by an aliased import.
+    Alias,
+    /// An unqualified import of the symbol, which also imports a type of the same name.
+    SharedImport,
+}
+
+/// Find the symbol at the given byte index of a module.
+pub fn symbol_at(
+    modules: &HashMap<String, CheckedModule>,
+    module: &CheckedModule,
+    byte_index: usize,
+) -> Option<Reference> {
+    collect(modules, module)
+        .into_iter()
+        .find(|reference| reference.span.start <= byte_index && byte_index <= reference.span.end)
+}
+
+/// Find all occurrences of a symbol across modules, including its definition.
+pub fn find_references(
+    modules: &HashMap<String, CheckedModule>,
+    symbol: &Symbol,
+) -> Vec<Reference> {
+    let mut references = Vec::new();
+
+    for module in modules.values() {
+        // Local variables can't escape the module they're defined in.
+        if symbol.kind == SymbolKind::Local && module.name != symbol.module {
+            continue;
+        }
+
+        references.extend(
+            collect(modules, module)
+                .into_iter()
+                .filter(|reference| &reference.symbol == symbol),
+        );
+    }
+
+    references.sort_by(|a, b| (&a.module, a.span.start).cmp(&(&b.module, b.span.start)));
+
+    references
+}
+
+/// Where renaming a symbol would change the meaning of the program: either another definition of
+/// the new name, or an occurrence of a symbol that would then refer to another one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub module: String,
+    pub span: Span,
+}
+
+/// Check that a symbol can be renamed without clashing with other definitions: the new name must
+/// neither be defined already where the symbol is in scope, nor be bound by a local variable
+/// shadowing some occurrence of the symbol; conversely, a renamed local variable mustn't shadow
+/// occurrences of other symbols of the new name.
+pub fn rename_conflict(
+    all_modules: &HashMap<String, CheckedModule>,
+    symbol: &Symbol,
+    new_name: &str,
+) -> Option<Conflict> {
+    if symbol.name == new_name {
+        return None;
+    }
+
+    // NOTE: The module defining the symbol comes first, so that conflicts with its other
+    // definitions are reported before those found where the symbol is used.
+    let mut modules = all_modules.values().collect::<Vec<_>>();
+    modules.sort_by_key(|module| (module.name != symbol.module, &module.name));
+
+    for module in modules {
+        if symbol.kind == SymbolKind::Local && module.name != symbol.module {
+            continue;
+        }
+
+        let Collector {
+            references,
+            bindings,
+            ..
+        } = collect_with_bindings(all_modules, module);
+
+        let occurrences = references
+            .iter()
+            .filter(|reference| {
+                &reference.symbol == symbol && reference.kind != ReferenceKind::Alias
+            })
+            .collect::<Vec<_>>();
+
+        if occurrences.is_empty() && module.name != symbol.module {
+            continue;
+        }
+
+        let conflict = |span: Span| {
+            Some(Conflict {
+                module: module.name.clone(),
+                span,
+            })
+        };
+
+        if symbol.kind != SymbolKind::Local
+            && (module.name == symbol.module
+                || occurrences.iter().any(|reference| !reference.qualified))
+        {
+            if let Some(span) = top_level_definition(module, new_name) {
+                return conflict(span);
+            }
+        }
+
+        let scopes = bindings
+            .iter()
+            .filter(|(binding, _)| binding == symbol)
+            .map(|(_, scope)| *scope)
+            .collect::<Vec<_>>();
+
+        // NOTE: Only bindings nested within the scope of a renamed variable can shadow it; the
+        // variable itself shadows those enclosing it.
+        let shadowed = occurrences
+            .iter()
+            .filter(|reference| reference.kind != ReferenceKind::Definition && !reference.qualified)
+            .find_map(|occurrence| {
+                bindings.iter().find(|(binding, scope)| {
+                    binding.name == new_name
+                        && binding != symbol
+                        && includes(*scope, occurrence.span)
+                        && (symbol.kind != SymbolKind::Local
+                            || scopes
+                                .iter()
+                                .any(|outer| outer != scope && includes(*outer, *scope)))
+                })
+            });
+
+        if let Some((binding, _)) = shadowed {
+            return conflict(binding.span);
+        }
+
+        if symbol.kind == SymbolKind::Local {
+            let duplicate = bindings
+                .iter()
+                .find(|(binding, scope)| binding.name == new_name && scopes.contains(scope));
+
+            if let Some((binding, _)) = duplicate {
+                return conflict(binding.span);
+            }
+
+            let captured = references.iter().find(|reference| {
+                reference.symbol.name == new_name
+                    && &reference.symbol != symbol
+                    && reference.kind == ReferenceKind::Usage
+                    && !reference.qualified
+                    && scopes.iter().any(|scope| includes(*scope, reference.span))
+            });
+
+            if let Some(reference) = captured {
+                return conflict(reference.span);
+            }
+        }
+    }
+
+    None
+}
+
+/// Whether a name is valid for the given kind of symbol, i.e. a lowercase name for values and
+/// variables, or a capitalised name for constructors. Keywords aren't valid names.
+pub fn is_valid_name(name: &str, kind: SymbolKind) -> bool {
+    let Ok(lexed) = lexer::run(name) else {
+        return false;
+    };
+
+    match (lexed.tokens.as_slice(), kind) {
+        ([(Token::Name { name: lexed }, _)], SymbolKind::Value | SymbolKind::Local) => {
+            lexed == name
+        }
+        ([(Token::UpName { name: lexed }, _)], SymbolKind::Constructor) => lexed == name,
+        _ => false,
+    }
+}
+
+fn collect(modules: &HashMap<String, CheckedModule>, module: &CheckedModule) -> Vec<Reference> {
+    collect_with_bindings(modules, module).references
+}
+
+fn collect_with_bindings<'a>(
+    modules: &'a HashMap<String, CheckedModule>,
+    module: &'a CheckedModule,
+) -> Collector<'a> {
+    let mut collector = Collector {
+        modules,
+        module,
+        references: Vec::new(),
+        bindings: Vec::new(),
+        punned: Vec::new(),
+        qualified: Vec::new(),
+        sequence_end: 0,
+        constraints: Vec::new(),
+    };
+
+    for definition in module.ast.definitions() {
+        collector.definition(definition);
+    }
+
+    for reference in collector.references.iter_mut() {
+        reference.punned = collector.punned.contains(&reference.span);
+        reference.qualified = collector.qualified.contains(&reference.span);
+    }
+
+    collector
+}
+
+/// Where a value is defined at the top-level of a module, by any of its definitions or imports.
+fn top_level_definition(module: &CheckedModule, name: &str) -> Option<Span> {
+    module
+        .ast
+        .definitions()
+        .find_map(|definition| match definition {
+            Definition::Fn(function) if function.name == name => Some(function.location),
+            Definition::ModuleConstant(constant) if constant.name == name => {
+                Some(constant.location)
+            }
+            Definition::DataType(data_type) => data_type
+                .constructors
+                .iter()
+                .find(|constructor| constructor.name == name)
+                .map(|constructor| constructor.location),
+            Definition::Interface(interface) => interface
+                .methods
+                .iter()
+                .find(|method| method.name == name)
+                .map(|method| method.location),
+            Definition::Use(import) => import
+                .unqualified
+                .iter()
+                .find(|unqualified| unqualified.variable_name() == name)
+                .map(|unqualified| unqualified.location),
+            _ => None,
+        })
+        .and_then(|location| identifier_span(&module.code, location, name))
+}
+
+/// Whether a span lies within another.
+fn includes(outer: Span, inner: Span) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+struct Collector<'a> {
+    modules: &'a HashMap<String, CheckedModule>,
+    module: &'a CheckedModule,
+    references: Vec<Reference>,
+    /// Local variables, along with the region of the module where they are in scope.
+    bindings: Vec<(Symbol, Span)>,
+    /// Locations of record fields given by a variable of the same name (e.g. `Box { inner }`).
+    punned: Vec<Span>,
+    /// Locations of qualified occurrences and aliased imports.
+    qualified: Vec<Span>,
+    /// End of the innermost sequence of expressions, up to which 'let' bindings are in scope.
+    sequence_end: usize,
+    /// Interfaces constraining the function being visited, as (module, name).
+    constraints: Vec<(String, String)>,
+}
+
+impl Collector<'_> {
+    fn definition(&mut self, definition: &Definition<Rc<Type>, TypedArg, TypedExpr, String>) {
+        match definition {
+            Definition::Fn(function) => {
+                self.define(&function.name, function.location, SymbolKind::Value);
+
+                self.constraints = self
+                    .module
+                    .ast
+                    .type_info
+                    .constraints
+                    .get(&function.name)
+                    .into_iter()
+                    .flatten()
+                    .map(|constraint| {
+                        (
+                            constraint.interface_module.clone(),
+                            constraint.interface.clone(),
+                        )
+                    })
+                    .collect();
+
+                self.function(function);
+
+                self.constraints.clear();
+            }
+
+            Definition::ModuleConstant(constant) => {
+                self.define(&constant.name, constant.location, SymbolKind::Value);
+                self.expr(&constant.value);
+            }
+
+            Definition::DataType(data_type) => {
+                // NOTE: Constructors of records defined with the short-hand syntax have no name of
+                // their own; they're named after their type.
+                for constructor in data_type.constructors.iter().filter(|c| !c.sugar) {
+                    self.define(
+                        &constructor.name,
+                        constructor.location,
+                        SymbolKind::Constructor,
+                    );
+                }
+            }
+
+            Definition::Test(test) | Definition::Benchmark(test) => {
+                for arg_via in test.arguments.iter() {
+                    self.arg(&arg_via.arg, test.body.location());
+                    self.expr(&arg_via.via);
+                }
+                self.expr(&test.body);
+            }
+
+            Definition::Validator(validator) => {
+                let scope = Span {
+                    start: validator.location.start,
+                    end: validator.end_position,
+                };
+                validator.params.iter().for_each(|arg| self.arg(arg, scope));
+                for handler in validator
+                    .handlers
+                    .iter()
+                    .chain(std::iter::once(&validator.fallback))
+                {
+                    self.function(handler);
+                }
+            }
+
+            Definition::Interface(interface) => {
+                for method in interface.methods.iter() {
+                    self.define(&method.name, method.location, SymbolKind::Value);
+                }
+            }
+
+            Definition::Instance(instance) => {
+                let interface = self.module.ast.type_info.instances.iter().find(|info| {
+                    info.module == self.module.name && info.location == instance.location
+                });
+
+                for method in instance.methods.iter() {
+                    // NOTE: Methods of instances are named after the methods of their interface;
+                    // renaming either renames both.
+                    let symbol = interface.and_then(|interface| {
+                        self.interface_method(
+                            &interface.interface_module,
+                            &interface.interface,
+                            &method.name,
+                        )
+                    });
+
+                    if let (Some(symbol), Some(span)) = (
+                        symbol,
+                        identifier_span(&self.module.code, method.location, &method.name),
+                    ) {
+                        self.reference(symbol, span, ReferenceKind::Usage);
+                    }
+
+                    self.function(method);
+                }
+            }
+
+            Definition::Use(import) => self.import(import),
+
+            Definition::TypeAlias(_) => {}
+        }
+    }
+
+    fn function(&mut self, function: &TypedFunction) {
+        let scope = function.body.location();
+        function
+            .arguments
+            .iter()
+            .for_each(|arg| self.arg(arg, scope));
+        self.expr(&function.body);
+    }
+
+    /// The interface method called by a variable. Method calls are resolved during type-checking,
+    /// into a method of some instance (e.g. `Show<Int>.show`) or a method given along with the
+    /// constraints of the enclosing function (e.g. `_dict_0_show`).
+    fn interface_method_call(
+        &self,
+        name: &str,
+        variant: &ValueConstructorVariant,
+    ) -> Option<Symbol> {
+        match variant {
+            ValueConstructorVariant::ModuleFn { module, .. } => {
+                let (instance, method) = name.rsplit_once('.')?;
+                let (interface, _) = instance.split_once('<')?;
+
+                let info = self
+                    .modules
+                    .get(module)?
+                    .ast
+                    .type_info
+                    .instances
+                    .iter()
+                    .find(|info| &info.module == module && info.interface == interface)?;
+
+                self.interface_method(&info.interface_module, interface, method)
+            }
+
+            ValueConstructorVariant::LocalVariable { .. } => {
+                let (index, method) = name
+                    .strip_prefix(DICTIONARY_VARIABLE)?
+                    .strip_prefix('_')?
+                    .split_once('_')?;
+
+                let (module, interface) = self.constraints.get(index.parse::<usize>().ok()?)?;
+
+                self.interface_method(module, interface, method)
+            }
+
+            ValueConstructorVariant::ModuleConstant { .. }
+            | ValueConstructorVariant::Record { .. } => None,
+        }
+    }
+
+    fn interface_method(&self, module: &str, interface: &str, name: &str) -> Option<Symbol> {
+        let module = self.modules.get(module)?;
+
+        module
+            .ast
+            .definitions()
+            .find_map(|definition| match definition {
+                Definition::Interface(other) if other.name == interface => {
+                    other.methods.iter().find(|method| method.name == name)
+                }
+                _ => None,
+            })
+            .and_then(|method| {
+                self.symbol(module, &method.name, method.location, SymbolKind::Value)
+            })
+    }
+
+    fn import(&mut self, import: &Use<String>) {
+        let module_name = import.module.join("/");
+
+        let Some(module) = self.modules.get(&module_name) else {
+            return;
+        };
+
+        for unqualified in import.unqualified.iter() {
+            let span = Span {
+                start: unqualified.location.start,
+                end: unqualified.location.start + unqualified.name.len(),
+            };
+
+            let mut shared_with_type = false;
+
+            let symbol = module.ast.definitions().find_map(|definition| match definition {
+                Definition::Fn(function) if function.name == unqualified.name => {
+                    self.symbol(module, &function.name, function.location, SymbolKind::Value)
+                }
+                Definition::ModuleConstant(constant) if constant.name == unqualified.name => {
+                    self.symbol(module, &constant.name, constant.location, SymbolKind::Value)
+                }
+                Definition::Interface(interface) => {
+                    let method = interface
+                        .methods
+                        .iter()
+                        .find(|method| method.name == unqualified.name)?;
+
+                    self.symbol(module, &method.name, method.location, SymbolKind::Value)
+                }
+                Definition::DataType(data_type) => {
+                    let constructor = data_type
+                        .constructors
+                        .iter()
+                        .find(|constructor| constructor.name == unqualified.name)?;
+
+                    shared_with_type = module.ast.definitions().any(|definition| {
+                        matches!(definition, Definition::DataType(other) if other.name == unqualified.name)
+                            || matches!(definition, Definition::TypeAlias(alias) if alias.alias == unqualified.name)
+                    });
+
+                    self.symbol(
+                        module,
+                        &constructor.name,
+                        constructor.location,
+                        SymbolKind::Constructor,
+                    )
+                }
+                _ => None,
+            });
+
+            if let Some(symbol) = symbol {
+                let kind = if shared_with_type {
+                    ReferenceKind::SharedImport
+                } else {
+                    ReferenceKind::Usage
+                };
+
+                if unqualified.as_name.is_some() {
+                    self.qualified.push(span);
+                }
+
+                self.reference(symbol, span, kind);
+            }
+        }
+    }
+
+    fn arg(&mut self, arg: &TypedArg, scope: Span) {
+        if let ArgName::Named { name, location, .. } = &arg.arg_name {
+            self.bind(name, *location, scope);
+        }
+    }
+
+    fn expr(&mut self, expr: &TypedExpr) {
+        match expr {
+            TypedExpr::Var {
+                name,
+                location,
+                constructor,
+            } => {
+                if let Some(symbol) = self.interface_method_call(name, &constructor.variant) {
+                    if let Some(span) = identifier_span(&self.module.code, *location, &symbol.name)
+                    {
+                        if !self.module.code[location.start..span.start].is_empty() {
+                            self.qualified.push(span);
+                        }
+                        self.reference(symbol, span, ReferenceKind::Usage);
+                    }
+                    return;
+                }
+
+                let symbol = match &constructor.variant {
+                    ValueConstructorVariant::LocalVariable { location } => {
+                        self.symbol(self.module, name, *location, SymbolKind::Local)
+                    }
+                    ValueConstructorVariant::ModuleFn {
+                        name,
+                        module,
+                        location,
+                        ..
+                    }
+                    | ValueConstructorVariant::ModuleConstant {
+                        name,
+                        module,
+                        location,
+                    } => self.global(module, name, *location, SymbolKind::Value),
+                    ValueConstructorVariant::Record {
+                        name,
+                        module,
+                        location,
+                        ..
+                    } => self.global(module, name, *location, SymbolKind::Constructor),
+                };
+
+                if let Some(symbol) = symbol {
+                    let kind = if &symbol.name == name {
+                        ReferenceKind::Usage
+                    } else {
+                        ReferenceKind::Alias
+                    };
+
+                    self.reference(symbol, *location, kind);
+                }
+            }
+
+            TypedExpr::ModuleSelect {
+                location,
+                label,
+                module_name,
+                constructor,
+                ..
+            } => {
+                let (name, kind) = match constructor {
+                    ModuleValueConstructor::Record { name, .. } => (name, SymbolKind::Constructor),
+                    ModuleValueConstructor::Fn { name, .. }
+                    | ModuleValueConstructor::Constant { name, .. } => (name, SymbolKind::Value),
+                };
+
+                let span = Span {
+                    start: location.end.saturating_sub(label.len()),
+                    end: location.end,
+                };
+
+                if let Some(symbol) = self.global(module_name, name, constructor.location(), kind) {
+                    self.qualified.push(span);
+                    self.reference(symbol, span, ReferenceKind::Usage);
+                }
+            }
+
+            TypedExpr::Fn { args, body, .. } => {
+                args.iter().for_each(|arg| self.arg(arg, body.location()));
+                self.expr(body);
+            }
+
+            TypedExpr::Assignment {
+                value,
+                pattern,
+                location,
+                ..
+            } => {
+                self.expr(value);
+                self.pattern(
+                    pattern,
+                    Span {
+                        start: location.end,
+                        end: self.sequence_end.max(location.end),
+                    },
+                );
+            }
+
+            TypedExpr::When {
+                subject, clauses, ..
+            } => {
+                self.expr(subject);
+                for clause in clauses.iter() {
+                    self.pattern(&clause.pattern, clause.then.location());
+                    self.expr(&clause.then);
+                }
+            }
+
+            TypedExpr::If {
+                branches,
+                final_else,
+                ..
+            } => {
+                for branch in branches.iter() {
+                    self.expr(&branch.condition);
+                    if let Some((pattern, _)) = &branch.is {
+                        self.pattern(pattern, branch.body.location());
+                    }
+                    self.expr(&branch.body);
+                }
+                self.expr(final_else);
+            }
+
+            TypedExpr::Trace { then, text, .. } => {
+                self.expr(text);
+                self.expr(then);
+            }
+
+            TypedExpr::Sequence {
+                expressions,
+                location,
+            } => {
+                let enclosing = std::mem::replace(&mut self.sequence_end, location.end);
+                expressions.iter().for_each(|e| self.expr(e));
+                self.sequence_end = enclosing;
+            }
+
+            TypedExpr::Pipeline { expressions, .. } => {
+                expressions.iter().for_each(|e| self.expr(e));
+            }
+
+            TypedExpr::List { elements, tail, .. } => {
+                elements.iter().for_each(|e| self.expr(e));
+                if let Some(tail) = tail {
+                    self.expr(tail);
+                }
+            }
+
+            TypedExpr::Call { fun, args, .. } => {
+                self.expr(fun);
+                for arg in args.iter() {
+                    if arg.label.is_some()
+                        && matches!(&arg.value, TypedExpr::Var { location, .. } if *location == arg.location)
+                    {
+                        self.punned.push(arg.location);
+                    }
+                    self.expr(&arg.value);
+                }
+            }
+
+            TypedExpr::BinOp { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+            }
+
+            TypedExpr::Tuple { elems, .. } => elems.iter().for_each(|e| self.expr(e)),
+
+            TypedExpr::Pair { fst, snd, .. } => {
+                self.expr(fst);
+                self.expr(snd);
+            }
+
+            TypedExpr::RecordUpdate { spread, args, .. } => {
+                self.expr(spread);
+                args.iter().for_each(|arg| self.expr(&arg.value));
+            }
+
+            TypedExpr::RecordAccess { record, .. } => self.expr(record),
+
+            TypedExpr::TupleIndex { tuple, .. } => self.expr(tuple),
+
+            TypedExpr::UnOp { value, .. } => self.expr(value),
+
+            TypedExpr::UInt { .. }
+            | TypedExpr::String { .. }
+            | TypedExpr::ByteArray { .. }
+            | TypedExpr::CurvePoint { .. }
+            | TypedExpr::ErrorTerm { .. } => {}
+        }
+    }
+
+    fn pattern(&mut self, pattern: &TypedPattern, scope: Span) {
+        match pattern {
+            TypedPattern::Var { name, location } => {
+                self.bind(name, *location, scope);
+            }
+
+            TypedPattern::Assign {
+                name,
+                location,
+                pattern,
+            } => {
+                self.bind(name, *location, scope);
+                self.pattern(pattern, scope);
+            }
+
+            TypedPattern::Constructor {
+                name,
+                location,
+                arguments,
+                tipo,
+                module,
+                ..
+            } => {
+                if let Some(symbol) = self.constructor(tipo, name) {
+                    if let Some(span) = identifier_span(&self.module.code, *location, name) {
+                        if module.is_some() {
+                            self.qualified.push(span);
+                        }
+                        self.reference(symbol, span, ReferenceKind::Usage);
+                    }
+                }
+
+                for arg in arguments.iter() {
+                    if arg.label.is_some()
+                        && matches!(&arg.value, TypedPattern::Var { location, .. } if *location == arg.location)
+                    {
+                        self.punned.push(arg.location);
+                    }
+                    self.pattern(&arg.value, scope);
+                }
+            }
+
+            TypedPattern::List { elements, tail, .. } => {
+                elements.iter().for_each(|e| self.pattern(e, scope));
+                if let Some(tail) = tail {
+                    self.pattern(tail, scope);
+                }
+            }
+
+            TypedPattern::Tuple { elems, .. } => elems.iter().for_each(|e| self.pattern(e, scope)),
+
+            TypedPattern::Pair { fst, snd, .. } => {
+                self.pattern(fst, scope);
+                self.pattern(snd, scope);
+            }
+
+            TypedPattern::Int { .. }
+            | TypedPattern::ByteArray { .. }
+            | TypedPattern::Discard { .. } => {}
+        }
+    }
+
+    /// The symbol of a constructor found in a pattern, after the type it constructs.
+    fn constructor(&self, tipo: &Rc<Type>, name: &str) -> Option<Symbol> {
+        let (module_name, type_name) = tipo.return_type().unwrap_or(tipo.clone()).qualifier()?;
+
+        let module = self.modules.get(&module_name)?;
+
+        module
+            .ast
+            .definitions()
+            .find_map(|definition| match definition {
+                Definition::DataType(data_type) if data_type.name == type_name => data_type
+                    .constructors
+                    .iter()
+                    .find(|constructor| constructor.name == name),
+                _ => None,
+            })
+            .and_then(|constructor| {
+                self.symbol(
+                    module,
+                    &constructor.name,
+                    constructor.location,
+                    SymbolKind::Constructor,
+                )
+            })
+    }
+
+    fn global(&self, module: &str, name: &str, location: Span, kind: SymbolKind) -> Option<Symbol> {
+        self.symbol(self.modules.get(module)?, name, location, kind)
+    }
+
+    fn symbol(
+        &self,
+        module: &CheckedModule,
+        name: &str,
+        location: Span,
+        kind: SymbolKind,
+    ) -> Option<Symbol> {
+        Some(Symbol {
+            module: module.name.clone(),
+            name: name.to_string(),
+            span: identifier_span(&module.code, location, name)?,
+            kind,
+        })
+    }
+
+    fn define(&mut self, name: &str, location: Span, kind: SymbolKind) {
+        if let Some(symbol) = self.symbol(self.module, name, location, kind) {
+            let span = symbol.span;
+            self.reference(symbol, span, ReferenceKind::Definition);
+        }
+    }
+
+    /// Define a local variable, in scope within the given region of the module.
+    fn bind(&mut self, name: &str, location: Span, scope: Span) {
+        if let Some(symbol) = self.symbol(self.module, name, location, SymbolKind::Local) {
+            let span = symbol.span;
+            self.bindings.push((symbol.clone(), scope));
+            self.reference(symbol, span, ReferenceKind::Definition);
+        }
+    }
+
+    fn reference(&mut self, symbol: Symbol, span: Span, kind: ReferenceKind) {
+        let name = match kind {
+            ReferenceKind::Alias => None,
+            ReferenceKind::Definition | ReferenceKind::Usage | ReferenceKind::SharedImport => {
+                Some(symbol.name.as_str())
+            }
+        };
+
+        // Nodes introduced by the compiler (e.g. when desugaring pipelines or captures) don't
+        // correspond to anything in the source code.
+        let in_source = self
+            .module
+            .code
+            .get(span.start..span.end)
+            .is_some_and(|text| name.map_or(is_identifier(text), |name| text == name));
+
+        if in_source {
+            self.references.push(Reference {
+                symbol,
+                module: self.module.name.clone(),
+                span,
+                kind,
+                punned: false,
+                qualified: false,
+            });
+        }
+    }
+}
+
+fn is_identifier(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{check, offset};
+    use indoc::indoc;
+
+    const MATH: &str = indoc! {r#"
+        pub fn double(n: Int) -> Int {
+          n * 2
+        }
+
+        pub fn quadruple(n: Int) -> Int {
+          let twice = double(n)
+          double(twice)
+        }
+    "#};
+
+    const MAIN: &str = indoc! {r#"
+        use math.{double}
+
+        pub fn sum(xs: List<Int>) -> Int {
+          when xs is {
+            [] -> 0
+            [x, ..rest] -> double(x) + math.quadruple(sum(rest))
+          }
+        }
+
+        pub fn triple(quadruple: Int) -> Int {
+          double(quadruple) + quadruple
+        }
+    "#};
+
+    fn symbol(modules: &HashMap<String, CheckedModule>, module: &str, text: &str) -> Symbol {
+        let module = &modules[module];
+        symbol_at(modules, module, offset(&module.code, text, 0))
+            .expect("no symbol")
+            .symbol
+    }
+
+    #[test]
+    fn references_across_modules() {
+        let modules = check(&[("math", MATH), ("main", MAIN)]);
+
+        let double = symbol(&modules, "math", "double");
+
+        let references = find_references(&modules, &double)
+            .into_iter()
+            .map(|reference| (reference.module, reference.span.start, reference.kind))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            references,
+            vec![
+                (
+                    "main".to_string(),
+                    offset(MAIN, "double", 0),
+                    ReferenceKind::Usage
+                ),
+                (
+                    "main".to_string(),
+                    offset(MAIN, "double", 1),
+                    ReferenceKind::Usage
+                ),
+                (
+                    "main".to_string(),
+                    offset(MAIN, "double", 2),
+                    ReferenceKind::Usage
+                ),
+                (
+                    "math".to_string(),
+                    offset(MATH, "double", 0),
+                    ReferenceKind::Definition
+                ),
+                (
+                    "math".to_string(),
+                    offset(MATH, "double", 1),
+                    ReferenceKind::Usage
+                ),
+                (
+                    "math".to_string(),
+                    offset(MATH, "double", 2),
+                    ReferenceKind::Usage
+                ),
+            ]
+        );
+
+        let quadruple = symbol(&modules, "math", "quadruple");
+
+        let qualified = find_references(&modules, &quadruple)
+            .into_iter()
+            .filter(|reference| reference.qualified)
+            .map(|reference| (reference.module, reference.span.start))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            qualified,
+            vec![("main".to_string(), offset(MAIN, "quadruple", 0))]
+        );
+    }
+
+    #[test]
+    fn local_references() {
+        let modules = check(&[("math", MATH), ("main", MAIN)]);
+
+        let rest = symbol(&modules, "main", "rest");
+
+        assert_eq!(rest.kind, SymbolKind::Local);
+        assert_eq!(
+            find_references(&modules, &rest)
+                .into_iter()
+                .map(|reference| reference.span.start)
+                .collect::<Vec<_>>(),
+            vec![offset(MAIN, "rest", 0), offset(MAIN, "rest", 1)]
+        );
+    }
+
+    #[test]
+    fn rename_conflicts() {
+        let modules = check(&[("math", MATH), ("main", MAIN)]);
+
+        let double = symbol(&modules, "math", "double");
+        let twice = symbol(&modules, "math", "twice");
+        let quadruple = symbol(&modules, "math", "quadruple");
+        let x = symbol(&modules, "main", "x,");
+        let sum = symbol(&modules, "main", "sum");
+
+        // Already defined next to the renamed function.
+        assert_eq!(
+            rename_conflict(&modules, &double, "quadruple"),
+            Some(Conflict {
+                module: "math".to_string(),
+                span: Span {
+                    start: offset(MATH, "quadruple", 0),
+                    end: offset(MATH, "quadruple", 0) + "quadruple".len(),
+                },
+            })
+        );
+
+        // Already defined where the function is imported.
+        assert_eq!(
+            rename_conflict(&modules, &double, "sum").map(|conflict| conflict.module),
+            Some("main".to_string())
+        );
+
+        // Shadowed by a variable or an argument at one of the call sites.
+        assert_eq!(
+            rename_conflict(&modules, &sum, "x"),
+            Some(Conflict {
+                module: "main".to_string(),
+                span: Span {
+                    start: offset(MAIN, "[x", 0) + 1,
+                    end: offset(MAIN, "[x", 0) + 2,
+                },
+            })
+        );
+        assert_eq!(
+            rename_conflict(&modules, &double, "n"),
+            Some(Conflict {
+                module: "math".to_string(),
+                span: Span {
+                    start: offset(MATH, "n: Int", 1),
+                    end: offset(MATH, "n: Int", 1) + 1,
+                },
+            })
+        );
+
+        // Qualified occurrences can't be shadowed.
+        assert_eq!(rename_conflict(&modules, &quadruple, "triple"), None);
+
+        // Capturing another variable in the scope of the renamed one, or binding it twice.
+        assert_eq!(
+            rename_conflict(&modules, &x, "rest").map(|conflict| conflict.span.start),
+            Some(offset(MAIN, "rest", 0))
+        );
+
+        // Shadowing variables that aren't used afterwards.
+        assert_eq!(rename_conflict(&modules, &twice, "n"), None);
+
+        // Unrelated names.
+        assert_eq!(rename_conflict(&modules, &double, "twofold"), None);
+        assert_eq!(rename_conflict(&modules, &twice, "doubled"), None);
+        assert_eq!(rename_conflict(&modules, &x, "head"), None);
+    }
+
+    #[test]
+    fn interface_methods() {
+        let printing = indoc! {r#"
+            pub interface Show<a> {
+              show: fn(a) -> ByteArray,
+            }
+
+            instance Show<Int> {
+              fn show(n: Int) -> ByteArray {
+                if n < 0 {
+                  "-"
+                } else {
+                  "+"
+                }
+              }
+            }
+        "#};
+
+        let main = indoc! {r#"
+            use printing.{Show, show}
+
+            instance Show<Bool> {
+              fn show(b: Bool) -> ByteArray {
+                if b {
+                  "T"
+                } else {
+                  "F"
+                }
+              }
+            }
+
+            pub fn main() -> ByteArray {
+              show(42)
+            }
+        "#};
+
+        let modules = check(&[("printing", printing), ("main", main)]);
+
+        let show = symbol(&modules, "main", "show(42)");
+
+        assert_eq!(show.module, "printing");
+
+        let references = find_references(&modules, &show)
+            .into_iter()
+            .map(|reference| (reference.module, reference.span.start, reference.kind))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            references,
+            vec![
+                (
+                    "main".to_string(),
+                    offset(main, "show", 0),
+                    ReferenceKind::Usage
+                ),
+                (
+                    "main".to_string(),
+                    offset(main, "show", 1),
+                    ReferenceKind::Usage
+                ),
+                (
+                    "main".to_string(),
+                    offset(main, "show", 2),
+                    ReferenceKind::Usage
+                ),
+                (
+                    "printing".to_string(),
+                    offset(printing, "show", 0),
+                    ReferenceKind::Definition
+                ),
+                (
+                    "printing".to_string(),
+                    offset(printing, "show", 1),
+                    ReferenceKind::Usage
+                ),
+            ]
+        );
+
+        assert_eq!(
+            rename_conflict(&modules, &show, "main").map(|conflict| conflict.module),
+            Some("main".to_string())
+        );
+    }
+
+    #[test]
+    fn valid_names() {
+        assert!(is_valid_name("foo_bar", SymbolKind::Value));
+        assert!(is_valid_name("Foo", SymbolKind::Constructor));
+        assert!(!is_valid_name("Foo", SymbolKind::Local));
+        assert!(!is_valid_name("foo", SymbolKind::Constructor));
+        assert!(!is_valid_name("when", SymbolKind::Value));
+        assert!(!is_valid_name("foo bar", SymbolKind::Value));
+    }
+}
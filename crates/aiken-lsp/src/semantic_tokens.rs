@@ -0,0 +1,968 @@
+use crate::utils::identifier_span;
+use aiken_lang::{
+    ast::{
+        Annotation, ArgName, CallArg, Definition, Pattern, Span, TypedArg, TypedDefinition,
+        TypedPattern, Use,
+    },
+    expr::TypedExpr,
+    parser::{lexer, token::Token},
+    tipo::{ModuleValueConstructor, ValueConstructorVariant},
+};
+use aiken_project::module::CheckedModule;
+use lsp_types::{SemanticTokenModifier, SemanticTokenType, SemanticTokensLegend};
+use std::collections::{HashMap, HashSet};
+
+/// Token types, in the order of the legend announced to the client; each type is referred to by
+/// its index in the legend.
+const TOKEN_TYPES: [SemanticTokenType; 16] = [
+    SemanticTokenType::NAMESPACE,
+    SemanticTokenType::TYPE,
+    SemanticTokenType::TYPE_PARAMETER,
+    SemanticTokenType::INTERFACE,
+    SemanticTokenType::CLASS,
+    SemanticTokenType::ENUM_MEMBER,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::METHOD,
+    SemanticTokenType::KEYWORD,
+    SemanticTokenType::COMMENT,
+    SemanticTokenType::STRING,
+    SemanticTokenType::NUMBER,
+    SemanticTokenType::OPERATOR,
+];
+
+/// Token modifiers, in the order of the legend announced to the client; each modifier is referred
+/// to by the bit of its index in the legend.
+const TOKEN_MODIFIERS: [SemanticTokenModifier; 3] = [
+    SemanticTokenModifier::DECLARATION,
+    SemanticTokenModifier::READONLY,
+    SemanticTokenModifier::DOCUMENTATION,
+];
+
+const DECLARATION: u32 = 1 << 0;
+const READONLY: u32 = 1 << 1;
+const DOCUMENTATION: u32 = 1 << 2;
+
+/// Keywords which are only such in some contexts, and otherwise lexed as names.
+const CONTEXTUAL_KEYWORDS: [&str; 3] = ["interface", "instance", "where"];
+
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: TOKEN_MODIFIERS.to_vec(),
+    }
+}
+
+/// A classified span of a module's source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub span: Span,
+    /// Index of the token's type in the legend.
+    pub token_type: u32,
+    /// Bit set of the token's modifiers in the legend.
+    pub modifiers: u32,
+}
+
+/// Semantic tokens of a module, in order of position. Names are classified after what they refer
+/// to in the typed AST, and other tokens after their kind.
+pub fn semantic_tokens(
+    modules: &HashMap<String, CheckedModule>,
+    module: &CheckedModule,
+) -> Option<Vec<SemanticToken>> {
+    let lexed = lexer::run(&module.code).ok()?;
+
+    let mut collector = Collector {
+        modules,
+        code: &module.code,
+        names: HashMap::new(),
+        parameters: HashSet::new(),
+    };
+
+    for definition in module.ast.definitions() {
+        collector.definition(definition);
+    }
+
+    let mut tokens = Vec::new();
+
+    let comments = lexed
+        .extra
+        .comments
+        .iter()
+        .map(|span| (span, 0))
+        .chain(
+            lexed
+                .extra
+                .doc_comments
+                .iter()
+                .map(|span| (span, DOCUMENTATION)),
+        )
+        .chain(
+            lexed
+                .extra
+                .module_comments
+                .iter()
+                .map(|span| (span, DOCUMENTATION)),
+        );
+
+    for (span, modifiers) in comments {
+        // NOTE: The span of empty comments excludes their slashes.
+        let start = module.code[..span.start].trim_end_matches('/').len();
+
+        tokens.push(SemanticToken {
+            span: Span {
+                start,
+                end: span.end,
+            },
+            token_type: token_type(SemanticTokenType::COMMENT),
+            modifiers,
+        });
+    }
+
+    let mut previous: Option<&Token> = None;
+
+    let mut type_arguments = 0;
+
+    for (token, span) in lexed.tokens.iter() {
+        let classified = match token {
+            Token::Name { name } | Token::UpName { name } => {
+                collector.names.get(&span.start).copied().or_else(|| {
+                    let default = if CONTEXTUAL_KEYWORDS.contains(&name.as_str()) {
+                        SemanticTokenType::KEYWORD
+                    } else if matches!(token, Token::UpName { .. }) {
+                        SemanticTokenType::TYPE
+                    } else {
+                        SemanticTokenType::VARIABLE
+                    };
+                    Some((token_type(default), 0))
+                })
+            }
+
+            Token::DiscardName { .. } => collector
+                .names
+                .get(&span.start)
+                .copied()
+                .or(Some((token_type(SemanticTokenType::VARIABLE), 0))),
+
+            // NOTE: After a type's name, these delimit the arguments of the type (e.g. `List<Int>`)
+            // rather than compare integers.
+            Token::Less if matches!(previous, Some(Token::UpName { .. })) => {
+                type_arguments += 1;
+                None
+            }
+
+            Token::Greater if type_arguments > 0 => {
+                type_arguments -= 1;
+                None
+            }
+
+            Token::Int { .. } | Token::Ordinal { .. } => {
+                Some((token_type(SemanticTokenType::NUMBER), 0))
+            }
+
+            Token::String { .. } | Token::ByteString { .. } => {
+                Some((token_type(SemanticTokenType::STRING), 0))
+            }
+
+            Token::Plus
+            | Token::Minus
+            | Token::NewLineMinus
+            | Token::Star
+            | Token::Slash
+            | Token::Less
+            | Token::Greater
+            | Token::LessEqual
+            | Token::GreaterEqual
+            | Token::Percent
+            | Token::PlusDot
+            | Token::MinusDot
+            | Token::StarDot
+            | Token::SlashDot
+            | Token::LessDot
+            | Token::GreaterDot
+            | Token::LessEqualDot
+            | Token::GreaterEqualDot
+            | Token::Bang
+            | Token::EqualEqual
+            | Token::NotEqual
+            | Token::VbarVbar
+            | Token::AmperAmper
+            | Token::NewLinePipe
+            | Token::Pipe => Some((token_type(SemanticTokenType::OPERATOR), 0)),
+
+            Token::As
+            | Token::And
+            | Token::Or
+            | Token::Benchmark
+            | Token::Const
+            | Token::Fn
+            | Token::If
+            | Token::Else
+            | Token::Fail
+            | Token::Once
+            | Token::Expect
+            | Token::Is
+            | Token::Let
+            | Token::Opaque
+            | Token::Pub
+            | Token::Use
+            | Token::Test
+            | Token::Todo
+            | Token::Type
+            | Token::When
+            | Token::Trace
+            | Token::Validator
+            | Token::Via => Some((token_type(SemanticTokenType::KEYWORD), 0)),
+
+            _ => None,
+        };
+
+        if let Some((token_type, modifiers)) = classified {
+            tokens.push(SemanticToken {
+                span: *span,
+                token_type,
+                modifiers,
+            });
+        }
+
+        previous = Some(token);
+    }
+
+    tokens.sort_by_key(|token| token.span.start);
+
+    Some(tokens)
+}
+
+fn token_type(token_type: SemanticTokenType) -> u32 {
+    TOKEN_TYPES
+        .iter()
+        .position(|known| known == &token_type)
+        .expect("token types are all part of the legend") as u32
+}
+
+struct Collector<'a> {
+    modules: &'a HashMap<String, CheckedModule>,
+    code: &'a str,
+    /// Classification of names, by the byte index they start at.
+    names: HashMap<usize, (u32, u32)>,
+    /// Locations of the arguments of functions, telling parameters apart from other variables.
+    parameters: HashSet<Span>,
+}
+
+impl Collector<'_> {
+    fn definition(&mut self, definition: &TypedDefinition) {
+        match definition {
+            Definition::Fn(function) => {
+                self.name_in(
+                    &function.name,
+                    function.location,
+                    SemanticTokenType::FUNCTION,
+                    DECLARATION,
+                );
+                self.function(
+                    &function.arguments,
+                    &function.return_annotation,
+                    &function.body,
+                );
+                function
+                    .constraints
+                    .iter()
+                    .for_each(|constraint| self.annotation(constraint));
+            }
+
+            Definition::Test(test) | Definition::Benchmark(test) => {
+                self.name_in(
+                    &test.name,
+                    test.location,
+                    SemanticTokenType::FUNCTION,
+                    DECLARATION,
+                );
+                for arg_via in test.arguments.iter() {
+                    self.arg(&arg_via.arg);
+                    self.expr(&arg_via.via);
+                }
+                self.expr(&test.body);
+            }
+
+            Definition::TypeAlias(alias) => {
+                self.name_in(
+                    &alias.alias,
+                    alias.location,
+                    SemanticTokenType::TYPE,
+                    DECLARATION,
+                );
+                self.annotation(&alias.annotation);
+            }
+
+            Definition::DataType(data_type) => {
+                self.name_in(
+                    &data_type.name,
+                    data_type.location,
+                    SemanticTokenType::TYPE,
+                    DECLARATION,
+                );
+                for constructor in data_type.constructors.iter() {
+                    if !constructor.sugar {
+                        self.name_in(
+                            &constructor.name,
+                            constructor.location,
+                            SemanticTokenType::ENUM_MEMBER,
+                            DECLARATION,
+                        );
+                    }
+                    for argument in constructor.arguments.iter() {
+                        if let Some(label) = &argument.label {
+                            self.name_in(
+                                label,
+                                argument.location,
+                                SemanticTokenType::PROPERTY,
+                                DECLARATION,
+                            );
+                        }
+                        self.annotation(&argument.annotation);
+                    }
+                }
+            }
+
+            Definition::ModuleConstant(constant) => {
+                self.name_in(
+                    &constant.name,
+                    constant.location,
+                    SemanticTokenType::VARIABLE,
+                    DECLARATION | READONLY,
+                );
+                if let Some(annotation) = &constant.annotation {
+                    self.annotation(annotation);
+                }
+                self.expr(&constant.value);
+            }
+
+            Definition::Validator(validator) => {
+                self.name_in(
+                    &validator.name,
+                    Span {
+                        start: validator.location.start,
+                        end: validator.end_position,
+                    },
+                    SemanticTokenType::CLASS,
+                    DECLARATION,
+                );
+                validator.params.iter().for_each(|arg| self.arg(arg));
+                for handler in validator.handlers.iter() {
+                    self.name_in(
+                        &handler.name,
+                        handler.location,
+                        SemanticTokenType::METHOD,
+                        DECLARATION,
+                    );
+                    self.function(
+                        &handler.arguments,
+                        &handler.return_annotation,
+                        &handler.body,
+                    );
+                }
+                let fallback = &validator.fallback;
+                if fallback.location != validator.location {
+                    self.function(
+                        &fallback.arguments,
+                        &fallback.return_annotation,
+                        &fallback.body,
+                    );
+                }
+            }
+
+            Definition::Interface(interface) => {
+                self.name_in(
+                    &interface.name,
+                    interface.location,
+                    SemanticTokenType::INTERFACE,
+                    DECLARATION,
+                );
+                self.name_in(
+                    &interface.parameter,
+                    interface.location,
+                    SemanticTokenType::TYPE_PARAMETER,
+                    DECLARATION,
+                );
+                for method in interface.methods.iter() {
+                    self.name_in(
+                        &method.name,
+                        method.location,
+                        SemanticTokenType::METHOD,
+                        DECLARATION,
+                    );
+                    self.annotation(&method.annotation);
+                }
+            }
+
+            Definition::Instance(instance) => {
+                if let Annotation::Constructor {
+                    location,
+                    arguments,
+                    ..
+                } = &instance.interface
+                {
+                    self.name_at(location.start, SemanticTokenType::INTERFACE, 0);
+                    arguments
+                        .iter()
+                        .for_each(|argument| self.annotation(argument));
+                }
+                for method in instance.methods.iter() {
+                    self.name_in(
+                        &method.name,
+                        method.location,
+                        SemanticTokenType::METHOD,
+                        DECLARATION,
+                    );
+                    self.function(&method.arguments, &method.return_annotation, &method.body);
+                }
+            }
+
+            Definition::Use(import) => self.import(import),
+        }
+    }
+
+    fn import(&mut self, import: &Use<String>) {
+        let mut cursor = import.location.start;
+
+        for segment in import.module.iter().chain(import.as_name.iter()) {
+            let Some(offset) = self
+                .code
+                .get(cursor..import.location.end)
+                .and_then(|rest| rest.find(segment.as_str()))
+            else {
+                break;
+            };
+
+            cursor += offset;
+            self.name_at(cursor, SemanticTokenType::NAMESPACE, 0);
+            cursor += segment.len();
+        }
+
+        let module = self.modules.get(&import.module.join("/"));
+
+        for unqualified in import.unqualified.iter() {
+            let name = unqualified.name.as_str();
+
+            let (token_type, modifiers) = if name.starts_with(char::is_uppercase) {
+                let is_constructor = module.is_some_and(|module| {
+                    module.ast.definitions().any(|definition| {
+                        matches!(definition, Definition::DataType(data_type) if data_type.constructors.iter().any(|c| !c.sugar && c.name == name))
+                    })
+                });
+                let is_type = module.is_some_and(|module| {
+                    module.ast.definitions().any(|definition| {
+                        matches!(definition, Definition::DataType(data_type) if data_type.name == name)
+                            || matches!(definition, Definition::TypeAlias(alias) if alias.alias == name)
+                    })
+                });
+
+                if is_constructor && !is_type {
+                    (SemanticTokenType::ENUM_MEMBER, 0)
+                } else {
+                    (SemanticTokenType::TYPE, 0)
+                }
+            } else {
+                let is_constant = module.is_some_and(|module| {
+                    module.ast.definitions().any(|definition| {
+                        matches!(definition, Definition::ModuleConstant(constant) if constant.name == name)
+                    })
+                });
+
+                if is_constant {
+                    (SemanticTokenType::VARIABLE, READONLY)
+                } else {
+                    (SemanticTokenType::FUNCTION, 0)
+                }
+            };
+
+            self.name_in(name, unqualified.location, token_type.clone(), modifiers);
+
+            if let Some(as_name) = &unqualified.as_name {
+                let start = unqualified.location.end.saturating_sub(as_name.len());
+                self.name_at(start, token_type, modifiers);
+            }
+        }
+    }
+
+    fn function(
+        &mut self,
+        arguments: &[TypedArg],
+        return_annotation: &Option<Annotation>,
+        body: &TypedExpr,
+    ) {
+        arguments.iter().for_each(|arg| self.arg(arg));
+        if let Some(annotation) = return_annotation {
+            self.annotation(annotation);
+        }
+        self.expr(body);
+    }
+
+    fn arg(&mut self, arg: &TypedArg) {
+        // NOTE: Variables refer to the location of the whole argument, including its annotation.
+        self.parameters.insert(arg.location);
+
+        match &arg.arg_name {
+            ArgName::Named {
+                name,
+                label,
+                location,
+            }
+            | ArgName::Discarded {
+                name,
+                label,
+                location,
+            } => {
+                if label != name {
+                    self.name_at(location.start, SemanticTokenType::PARAMETER, 0);
+                }
+                self.name_in(name, *location, SemanticTokenType::PARAMETER, DECLARATION);
+            }
+        }
+        if let Some(annotation) = &arg.annotation {
+            self.annotation(annotation);
+        }
+    }
+
+    fn annotation(&mut self, annotation: &Annotation) {
+        match annotation {
+            Annotation::Constructor {
+                location,
+                module,
+                name,
+                arguments,
+            } => {
+                let mut start = location.start;
+                if let Some(module) = module {
+                    self.name_at(start, SemanticTokenType::NAMESPACE, 0);
+                    start += module.len() + 1;
+                }
+                self.name_in(
+                    name,
+                    Span {
+                        start,
+                        end: location.end,
+                    },
+                    SemanticTokenType::TYPE,
+                    0,
+                );
+                arguments
+                    .iter()
+                    .for_each(|argument| self.annotation(argument));
+            }
+
+            Annotation::Fn { arguments, ret, .. } => {
+                arguments
+                    .iter()
+                    .for_each(|argument| self.annotation(argument));
+                self.annotation(ret);
+            }
+
+            Annotation::Var { location, .. } => {
+                self.name_at(location.start, SemanticTokenType::TYPE_PARAMETER, 0);
+            }
+
+            Annotation::Tuple { elems, .. } => elems.iter().for_each(|elem| self.annotation(elem)),
+
+            Annotation::Pair { fst, snd, .. } => {
+                self.annotation(fst);
+                self.annotation(snd);
+            }
+
+            Annotation::Hole { .. } => {}
+        }
+    }
+
+    fn expr(&mut self, expr: &TypedExpr) {
+        match expr {
+            // NOTE: Variables introduced by the compiler (e.g. the value piped into a function) are
+            // located at the expressions they stand for, and aren't written as such.
+            TypedExpr::Var { location, name, .. }
+                if self.code.get(location.start..location.end) != Some(name.as_str()) => {}
+
+            TypedExpr::Var {
+                location,
+                constructor,
+                ..
+            } => {
+                let (token_type, modifiers) = match &constructor.variant {
+                    ValueConstructorVariant::LocalVariable { location } => {
+                        if self.parameters.contains(location) {
+                            (SemanticTokenType::PARAMETER, 0)
+                        } else {
+                            (SemanticTokenType::VARIABLE, 0)
+                        }
+                    }
+                    ValueConstructorVariant::ModuleFn { .. } => (SemanticTokenType::FUNCTION, 0),
+                    ValueConstructorVariant::ModuleConstant { .. } => {
+                        (SemanticTokenType::VARIABLE, READONLY)
+                    }
+                    ValueConstructorVariant::Record { .. } => (SemanticTokenType::ENUM_MEMBER, 0),
+                };
+
+                self.name_at(location.start, token_type, modifiers);
+            }
+
+            TypedExpr::ModuleSelect {
+                location,
+                label,
+                module_alias,
+                constructor,
+                ..
+            } => {
+                self.name_in(module_alias, *location, SemanticTokenType::NAMESPACE, 0);
+
+                let (token_type, modifiers) = match constructor {
+                    ModuleValueConstructor::Record { .. } => (SemanticTokenType::ENUM_MEMBER, 0),
+                    ModuleValueConstructor::Fn { .. } => (SemanticTokenType::FUNCTION, 0),
+                    ModuleValueConstructor::Constant { .. } => {
+                        (SemanticTokenType::VARIABLE, READONLY)
+                    }
+                };
+
+                self.name_at(
+                    location.end.saturating_sub(label.len()),
+                    token_type,
+                    modifiers,
+                );
+            }
+
+            TypedExpr::Fn {
+                args,
+                body,
+                return_annotation,
+                is_capture,
+                ..
+            } => {
+                if !is_capture {
+                    args.iter().for_each(|arg| self.arg(arg));
+                }
+                if let Some(annotation) = return_annotation {
+                    self.annotation(annotation);
+                }
+                self.expr(body);
+            }
+
+            TypedExpr::Assignment { value, pattern, .. } => {
+                self.expr(value);
+                self.pattern(pattern);
+            }
+
+            TypedExpr::When {
+                subject, clauses, ..
+            } => {
+                self.expr(subject);
+                for clause in clauses.iter() {
+                    self.pattern(&clause.pattern);
+                    self.expr(&clause.then);
+                }
+            }
+
+            TypedExpr::If {
+                branches,
+                final_else,
+                ..
+            } => {
+                for branch in branches.iter() {
+                    self.expr(&branch.condition);
+                    if let Some((pattern, _)) = &branch.is {
+                        self.pattern(pattern);
+                    }
+                    self.expr(&branch.body);
+                }
+                self.expr(final_else);
+            }
+
+            TypedExpr::Trace { then, text, .. } => {
+                self.expr(text);
+                self.expr(then);
+            }
+
+            TypedExpr::Sequence { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+                expressions.iter().for_each(|e| self.expr(e));
+            }
+
+            TypedExpr::List { elements, tail, .. } => {
+                elements.iter().for_each(|e| self.expr(e));
+                if let Some(tail) = tail {
+                    self.expr(tail);
+                }
+            }
+
+            TypedExpr::Call { fun, args, .. } => {
+                self.expr(fun);
+
+                // NOTE: Labels of a record's construction are its fields, whereas those of a
+                // function call are its parameters.
+                let label_type = if is_constructor(fun) {
+                    SemanticTokenType::PROPERTY
+                } else {
+                    SemanticTokenType::PARAMETER
+                };
+
+                for arg in args.iter() {
+                    self.label(arg, label_type.clone());
+                    self.expr(&arg.value);
+                }
+            }
+
+            TypedExpr::BinOp { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+            }
+
+            TypedExpr::Tuple { elems, .. } => elems.iter().for_each(|e| self.expr(e)),
+
+            TypedExpr::Pair { fst, snd, .. } => {
+                self.expr(fst);
+                self.expr(snd);
+            }
+
+            TypedExpr::RecordUpdate { spread, args, .. } => {
+                self.expr(spread);
+                for arg in args.iter() {
+                    self.name_at(arg.location.start, SemanticTokenType::PROPERTY, 0);
+                    self.expr(&arg.value);
+                }
+            }
+
+            TypedExpr::RecordAccess {
+                location,
+                label,
+                record,
+                ..
+            } => {
+                self.expr(record);
+                self.name_at(
+                    location.end.saturating_sub(label.len()),
+                    SemanticTokenType::PROPERTY,
+                    0,
+                );
+            }
+
+            TypedExpr::TupleIndex { tuple, .. } => self.expr(tuple),
+
+            TypedExpr::UnOp { value, .. } => self.expr(value),
+
+            TypedExpr::UInt { .. }
+            | TypedExpr::String { .. }
+            | TypedExpr::ByteArray { .. }
+            | TypedExpr::CurvePoint { .. }
+            | TypedExpr::ErrorTerm { .. } => {}
+        }
+    }
+
+    fn pattern(&mut self, pattern: &TypedPattern) {
+        match pattern {
+            Pattern::Var { location, name } => {
+                if self.code.get(location.start..location.end) == Some(name.as_str()) {
+                    self.name_at(location.start, SemanticTokenType::VARIABLE, DECLARATION);
+                }
+            }
+
+            Pattern::Assign {
+                name,
+                location,
+                pattern,
+            } => {
+                self.pattern(pattern);
+                self.name_at(
+                    location.end.saturating_sub(name.len()),
+                    SemanticTokenType::VARIABLE,
+                    DECLARATION,
+                );
+            }
+
+            Pattern::Constructor {
+                location,
+                module,
+                arguments,
+                ..
+            } => {
+                let mut start = location.start;
+                if let Some(module) = module {
+                    self.name_at(start, SemanticTokenType::NAMESPACE, 0);
+                    start += module.len() + 1;
+                }
+                self.name_at(start, SemanticTokenType::ENUM_MEMBER, 0);
+
+                for argument in arguments.iter() {
+                    self.label(argument, SemanticTokenType::PROPERTY);
+                    self.pattern(&argument.value);
+                }
+            }
+
+            Pattern::List { elements, tail, .. } => {
+                elements.iter().for_each(|element| self.pattern(element));
+                if let Some(tail) = tail {
+                    self.pattern(tail);
+                }
+            }
+
+            Pattern::Tuple { elems, .. } => elems.iter().for_each(|elem| self.pattern(elem)),
+
+            Pattern::Pair { fst, snd, .. } => {
+                self.pattern(fst);
+                self.pattern(snd);
+            }
+
+            Pattern::Int { .. } | Pattern::ByteArray { .. } | Pattern::Discard { .. } => {}
+        }
+    }
+
+    /// Classify the label of an argument, unless it is punned (e.g. `Box { inner }`), in which case
+    /// the label is also the value.
+    fn label<A>(&mut self, arg: &CallArg<A>, token_type: SemanticTokenType) {
+        if let Some(label) = &arg.label {
+            let is_labelled = self
+                .code
+                .get(arg.location.start..)
+                .and_then(|rest| rest.strip_prefix(label.as_str()))
+                .is_some_and(|rest| rest.trim_start().starts_with(':'));
+
+            if is_labelled {
+                self.name_at(arg.location.start, token_type, 0);
+            }
+        }
+    }
+
+    /// Classify the first occurrence of a name within a location, e.g. a definition's name within
+    /// its head.
+    fn name_in(
+        &mut self,
+        name: &str,
+        location: Span,
+        token_type: SemanticTokenType,
+        modifiers: u32,
+    ) {
+        if let Some(span) = identifier_span(self.code, location, name) {
+            self.name_at(span.start, token_type, modifiers);
+        }
+    }
+
+    fn name_at(&mut self, start: usize, token_type: SemanticTokenType, modifiers: u32) {
+        self.names
+            .insert(start, (self::token_type(token_type), modifiers));
+    }
+}
+
+fn is_constructor(fun: &TypedExpr) -> bool {
+    match fun {
+        TypedExpr::Var { constructor, .. } => {
+            matches!(constructor.variant, ValueConstructorVariant::Record { .. })
+        }
+        TypedExpr::ModuleSelect { constructor, .. } => {
+            matches!(constructor, ModuleValueConstructor::Record { .. })
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{check, offset};
+    use indoc::indoc;
+
+    const MATH: &str = indoc! {r#"
+        pub fn double(n: Int) -> Int {
+          n * 2
+        }
+    "#};
+
+    const MAIN: &str = indoc! {r#"
+        use math
+
+        /// A color.
+        pub type Color {
+          Red
+          Rgb { red: Int }
+        }
+
+        // The answer.
+        pub const answer: Int = 42
+
+        pub fn brightness(color: Color) -> Int {
+          when color is {
+            Red -> math.double(answer)
+            Rgb { red } -> red
+          }
+        }
+    "#};
+
+    /// The type and modifiers of the token at the nth occurrence of some text.
+    fn token(tokens: &[SemanticToken], text: &str, nth: usize) -> (String, u32) {
+        let start = offset(MAIN, text, nth);
+
+        let token = tokens
+            .iter()
+            .find(|token| token.span.start == start)
+            .unwrap_or_else(|| panic!("no token at '{text}' #{nth}"));
+
+        (
+            TOKEN_TYPES[token.token_type as usize].as_str().to_string(),
+            token.modifiers,
+        )
+    }
+
+    #[test]
+    fn classified_tokens() {
+        let modules = check(&[("math", MATH), ("main", MAIN)]);
+
+        let tokens = semantic_tokens(&modules, &modules["main"]).expect("failed to lex");
+
+        assert!(tokens
+            .windows(2)
+            .all(|pair| pair[0].span.end <= pair[1].span.start));
+
+        assert_eq!(token(&tokens, "use", 0), ("keyword".to_string(), 0));
+        assert_eq!(token(&tokens, "math", 0), ("namespace".to_string(), 0));
+        assert_eq!(
+            token(&tokens, "/// A color.", 0),
+            ("comment".to_string(), DOCUMENTATION)
+        );
+        assert_eq!(
+            token(&tokens, "// The answer.", 0),
+            ("comment".to_string(), 0)
+        );
+        assert_eq!(
+            token(&tokens, "Color", 0),
+            ("type".to_string(), DECLARATION)
+        );
+        assert_eq!(
+            token(&tokens, "Red", 0),
+            ("enumMember".to_string(), DECLARATION)
+        );
+        assert_eq!(
+            token(&tokens, "red", 0),
+            ("property".to_string(), DECLARATION)
+        );
+        assert_eq!(
+            token(&tokens, "answer:", 0),
+            ("variable".to_string(), DECLARATION | READONLY)
+        );
+        assert_eq!(token(&tokens, "42", 0), ("number".to_string(), 0));
+        assert_eq!(
+            token(&tokens, "brightness", 0),
+            ("function".to_string(), DECLARATION)
+        );
+        assert_eq!(
+            token(&tokens, "color:", 0),
+            ("parameter".to_string(), DECLARATION)
+        );
+        assert_eq!(token(&tokens, "color is", 0), ("parameter".to_string(), 0));
+        assert_eq!(token(&tokens, "Red", 1), ("enumMember".to_string(), 0));
+        assert_eq!(token(&tokens, "math", 1), ("namespace".to_string(), 0));
+        assert_eq!(token(&tokens, "double", 0), ("function".to_string(), 0));
+        assert_eq!(
+            token(&tokens, "answer)", 0),
+            ("variable".to_string(), READONLY)
+        );
+        assert_eq!(
+            token(&tokens, "red", 1),
+            ("variable".to_string(), DECLARATION)
+        );
+        assert_eq!(token(&tokens, "red", 2), ("variable".to_string(), 0));
+    }
+}
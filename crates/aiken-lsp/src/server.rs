@@ -1,9 +1,12 @@
-use self::lsp_project::LspProject;
+use self::{lsp_project::LspProject, settings::Settings};
 use crate::{
     cast::{cast_notification, cast_request},
     error::Error as ServerError,
+    inlay_hints::{self, InlayHintKind},
     quickfix,
     quickfix::Quickfix,
+    references::{self, ReferenceKind, SymbolKind},
+    semantic_tokens, signature_help, symbols,
     utils::{
         path_to_uri, span_to_lsp_range, text_edit_replace, uri_to_module_name,
         COMPILING_PROGRESS_TOKEN, CREATE_COMPILING_PROGRESS_TOKEN,
@@ -26,12 +29,14 @@ use itertools::Itertools;
 use lsp_server::{Connection, Message};
 use lsp_types::{
     notification::{
-        DidChangeTextDocument, DidChangeWatchedFiles, DidCloseTextDocument, DidSaveTextDocument,
-        Notification, Progress, PublishDiagnostics, ShowMessage,
+        DidChangeConfiguration, DidChangeTextDocument, DidChangeWatchedFiles, DidCloseTextDocument,
+        DidSaveTextDocument, Notification, Progress, PublishDiagnostics, ShowMessage,
     },
     request::{
-        CodeActionRequest, Completion, Formatting, GotoDefinition, HoverRequest, Request,
-        WorkDoneProgressCreate,
+        CodeActionRequest, Completion, DocumentSymbolRequest, Formatting, GotoDefinition,
+        HoverRequest, InlayHintRequest, PrepareRenameRequest, References, Rename, Request,
+        SemanticTokensFullRequest, SemanticTokensRangeRequest, SignatureHelpRequest,
+        WorkDoneProgressCreate, WorkspaceSymbolRequest,
     },
     DocumentFormattingParams, InitializeParams, TextEdit,
 };
@@ -43,6 +48,7 @@ use std::{
 };
 
 pub mod lsp_project;
+pub mod settings;
 pub mod telemetry;
 
 #[allow(dead_code)]
@@ -71,6 +77,9 @@ pub struct Server {
 
     /// An instance of a LspProject
     compiler: Option<LspProject>,
+
+    /// Settings given by the client
+    settings: Settings,
 }
 
 impl Server {
@@ -234,6 +243,14 @@ impl Server {
                 Ok(())
             }
 
+            DidChangeConfiguration::METHOD => {
+                let params = cast_notification::<DidChangeConfiguration>(notification)?;
+
+                self.settings = Settings::from_json(Some(params.settings));
+
+                Ok(())
+            }
+
             DidChangeWatchedFiles::METHOD => {
                 if let Ok(config) = Config::load(&self.root) {
                     self.config = Some(config);
@@ -376,6 +393,123 @@ impl Server {
                 })
             }
 
+            References::METHOD => {
+                let params = cast_request::<References>(request)?;
+
+                let locations = self.references(params)?;
+
+                Ok(lsp_server::Response {
+                    id,
+                    error: None,
+                    result: Some(serde_json::to_value(locations)?),
+                })
+            }
+
+            PrepareRenameRequest::METHOD => {
+                let params = cast_request::<PrepareRenameRequest>(request)?;
+
+                let range = self.prepare_rename(params);
+
+                Ok(lsp_server::Response {
+                    id,
+                    error: None,
+                    result: Some(serde_json::to_value(range)?),
+                })
+            }
+
+            Rename::METHOD => {
+                let params = cast_request::<Rename>(request)?;
+
+                match self.rename(params)? {
+                    Ok(edit) => Ok(lsp_server::Response {
+                        id,
+                        error: None,
+                        result: Some(serde_json::to_value(edit)?),
+                    }),
+                    Err(message) => Ok(lsp_server::Response::new_err(
+                        id,
+                        lsp_server::ErrorCode::RequestFailed as i32,
+                        message,
+                    )),
+                }
+            }
+
+            DocumentSymbolRequest::METHOD => {
+                let params = cast_request::<DocumentSymbolRequest>(request)?;
+
+                let symbols = self.document_symbols(params);
+
+                Ok(lsp_server::Response {
+                    id,
+                    error: None,
+                    result: Some(serde_json::to_value(symbols)?),
+                })
+            }
+
+            WorkspaceSymbolRequest::METHOD => {
+                let params = cast_request::<WorkspaceSymbolRequest>(request)?;
+
+                let symbols = self.workspace_symbols(params)?;
+
+                Ok(lsp_server::Response {
+                    id,
+                    error: None,
+                    result: Some(serde_json::to_value(symbols)?),
+                })
+            }
+
+            InlayHintRequest::METHOD => {
+                let params = cast_request::<InlayHintRequest>(request)?;
+
+                let hints = self.inlay_hints(params);
+
+                Ok(lsp_server::Response {
+                    id,
+                    error: None,
+                    result: Some(serde_json::to_value(hints)?),
+                })
+            }
+
+            SemanticTokensFullRequest::METHOD => {
+                let params = cast_request::<SemanticTokensFullRequest>(request)?;
+
+                let tokens = self
+                    .semantic_tokens(&params.text_document.uri, None)
+                    .map(lsp_types::SemanticTokensResult::Tokens);
+
+                Ok(lsp_server::Response {
+                    id,
+                    error: None,
+                    result: Some(serde_json::to_value(tokens)?),
+                })
+            }
+
+            SemanticTokensRangeRequest::METHOD => {
+                let params = cast_request::<SemanticTokensRangeRequest>(request)?;
+
+                let tokens = self
+                    .semantic_tokens(&params.text_document.uri, Some(params.range))
+                    .map(lsp_types::SemanticTokensRangeResult::Tokens);
+
+                Ok(lsp_server::Response {
+                    id,
+                    error: None,
+                    result: Some(serde_json::to_value(tokens)?),
+                })
+            }
+
+            SignatureHelpRequest::METHOD => {
+                let params = cast_request::<SignatureHelpRequest>(request)?;
+
+                let signature_help = self.signature_help(params);
+
+                Ok(lsp_server::Response {
+                    id,
+                    error: None,
+                    result: Some(serde_json::to_value(signature_help)?),
+                })
+            }
+
             unsupported => Err(ServerError::UnsupportedLspRequest {
                 request: unsupported.to_string(),
             }),
@@ -481,6 +615,420 @@ impl Server {
         Ok(Some(lsp_types::Location { uri, range }))
     }
 
+    #[allow(clippy::result_large_err)]
+    fn references(
+        &self,
+        params: lsp_types::ReferenceParams,
+    ) -> Result<Option<Vec<lsp_types::Location>>, ServerError> {
+        let (compiler, reference) = match self.symbol_at_position(&params.text_document_position) {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+
+        let mut locations = Vec::new();
+
+        for reference in references::find_references(&compiler.modules, &reference.symbol) {
+            if reference.kind == ReferenceKind::Definition && !params.context.include_declaration {
+                continue;
+            }
+
+            if let Some(location) = self.location(&reference.module, reference.span)? {
+                locations.push(location);
+            }
+        }
+
+        Ok(Some(locations))
+    }
+
+    fn prepare_rename(
+        &self,
+        params: lsp_types::TextDocumentPositionParams,
+    ) -> Option<lsp_types::PrepareRenameResponse> {
+        let (compiler, reference) = self.symbol_at_position(&params)?;
+
+        if reference.kind == ReferenceKind::Alias || !self.is_renameable(&reference.symbol) {
+            return None;
+        }
+
+        let source = compiler.sources.get(&reference.module)?;
+
+        Some(lsp_types::PrepareRenameResponse::RangeWithPlaceholder {
+            range: span_to_lsp_range(reference.span, &source.line_numbers),
+            placeholder: reference.symbol.name,
+        })
+    }
+
+    /// Rename a symbol and all its occurrences across the project's modules. Errors meant for
+    /// the user (e.g. an invalid new name) are returned as the inner result.
+    #[allow(clippy::result_large_err)]
+    fn rename(
+        &self,
+        params: lsp_types::RenameParams,
+    ) -> Result<Result<Option<lsp_types::WorkspaceEdit>, String>, ServerError> {
+        let (compiler, reference) = match self.symbol_at_position(&params.text_document_position) {
+            Some(found) => found,
+            None => return Ok(Ok(None)),
+        };
+
+        let symbol = reference.symbol;
+
+        if reference.kind == ReferenceKind::Alias {
+            return Ok(Err(format!(
+                "This is an alias of '{}'; rename the alias in its import instead.",
+                symbol.name
+            )));
+        }
+
+        if !self.is_renameable(&symbol) {
+            return Ok(Err(format!(
+                "'{}' is defined in a dependency and cannot be renamed.",
+                symbol.name
+            )));
+        }
+
+        let new_name = params.new_name;
+
+        if !references::is_valid_name(&new_name, symbol.kind) {
+            return Ok(Err(match symbol.kind {
+                SymbolKind::Constructor => format!(
+                    "'{new_name}' isn't a valid constructor name; constructors must start with an uppercase letter."
+                ),
+                SymbolKind::Value | SymbolKind::Local => format!(
+                    "'{new_name}' isn't a valid name; names must start with a lowercase letter and not be a keyword."
+                ),
+            }));
+        }
+
+        if let Some(conflict) = references::rename_conflict(&compiler.modules, &symbol, &new_name) {
+            let line = compiler
+                .sources
+                .get(&conflict.module)
+                .and_then(|source| source.line_numbers.line_number(conflict.span.start))
+                .unwrap_or_default();
+
+            return Ok(Err(format!(
+                "'{new_name}' is already in use (in {}, line {line}); renaming '{}' would change what some names refer to.",
+                conflict.module, symbol.name
+            )));
+        }
+
+        let references = references::find_references(&compiler.modules, &symbol);
+
+        if !references
+            .iter()
+            .any(|reference| reference.kind == ReferenceKind::Definition)
+        {
+            return Ok(Err(format!(
+                "I couldn't find where '{}' is defined, so I can't rename it.",
+                symbol.name
+            )));
+        }
+
+        let mut changes: HashMap<lsp_types::Url, Vec<TextEdit>> = HashMap::new();
+
+        for reference in references {
+            let new_text = match reference.kind {
+                ReferenceKind::Definition | ReferenceKind::Usage if reference.punned => {
+                    format!("{}: {new_name}", symbol.name)
+                }
+                ReferenceKind::Definition | ReferenceKind::Usage => new_name.clone(),
+                // NOTE: The import also brings the type of the same name into scope, so we keep
+                // it and import the renamed constructor alongside.
+                ReferenceKind::SharedImport => format!("{}, {new_name}", symbol.name),
+                ReferenceKind::Alias => continue,
+            };
+
+            if let Some(location) = self.location(&reference.module, reference.span)? {
+                changes.entry(location.uri).or_default().push(TextEdit {
+                    range: location.range,
+                    new_text,
+                });
+            }
+        }
+
+        Ok(Ok(Some(lsp_types::WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        })))
+    }
+
+    fn document_symbols(
+        &self,
+        params: lsp_types::DocumentSymbolParams,
+    ) -> Option<lsp_types::DocumentSymbolResponse> {
+        let module = self.module_for_uri(&params.text_document.uri)?;
+
+        let line_numbers = LineNumbers::new(&module.code);
+
+        Some(lsp_types::DocumentSymbolResponse::Nested(document_symbols(
+            &symbols::outline(module),
+            &line_numbers,
+        )))
+    }
+
+    /// Definitions of the project's own modules (i.e. not of its dependencies) whose name matches
+    /// the query.
+    #[allow(clippy::result_large_err)]
+    fn workspace_symbols(
+        &self,
+        params: lsp_types::WorkspaceSymbolParams,
+    ) -> Result<Option<lsp_types::WorkspaceSymbolResponse>, ServerError> {
+        let (compiler, config) = match (self.compiler.as_ref(), self.config.as_ref()) {
+            (Some(compiler), Some(config)) => (compiler, config),
+            _ => return Ok(None),
+        };
+
+        let package = config.name.to_string();
+
+        let mut symbols = Vec::new();
+
+        for module in compiler
+            .modules
+            .values()
+            .filter(|module| module.package == package)
+            .sorted_by(|a, b| a.name.cmp(&b.name))
+        {
+            let outline = symbols::outline(module);
+
+            for (container, outline) in symbols::Outline::flatten(&outline, &module.name) {
+                if !symbols::matches_query(&outline.name, &params.query) {
+                    continue;
+                }
+
+                if let Some(location) = self.location(&module.name, outline.name_span)? {
+                    #[allow(deprecated)]
+                    symbols.push(lsp_types::SymbolInformation {
+                        name: outline.name.clone(),
+                        kind: outline.kind,
+                        tags: None,
+                        deprecated: None,
+                        location,
+                        container_name: Some(container.to_string()),
+                    });
+                }
+            }
+        }
+
+        Ok(Some(lsp_types::WorkspaceSymbolResponse::Flat(symbols)))
+    }
+
+    fn inlay_hints(&self, params: lsp_types::InlayHintParams) -> Option<Vec<lsp_types::InlayHint>> {
+        let module = self.module_for_uri(&params.text_document.uri)?;
+
+        let line_numbers = LineNumbers::new(&module.code);
+
+        let hints = inlay_hints::inlay_hints(module, &self.settings.inlay_hints)
+            .into_iter()
+            .filter_map(|hint| {
+                let position = span_to_lsp_range(
+                    Span {
+                        start: hint.position,
+                        end: hint.position,
+                    },
+                    &line_numbers,
+                )
+                .start;
+
+                if position < params.range.start || position > params.range.end {
+                    return None;
+                }
+
+                Some(lsp_types::InlayHint {
+                    position,
+                    label: lsp_types::InlayHintLabel::String(hint.label),
+                    kind: Some(lsp_types::InlayHintKind::TYPE),
+                    text_edits: None,
+                    tooltip: None,
+                    padding_left: Some(hint.kind == InlayHintKind::Value),
+                    padding_right: None,
+                    data: None,
+                })
+            })
+            .collect();
+
+        Some(hints)
+    }
+
+    /// Semantic tokens of a module, or of a range of it, encoded relative to one another. Tokens
+    /// spanning several lines (e.g. multi-line strings) are split into one token per line.
+    fn semantic_tokens(
+        &self,
+        uri: &url::Url,
+        range: Option<lsp_types::Range>,
+    ) -> Option<lsp_types::SemanticTokens> {
+        let compiler = self.compiler.as_ref()?;
+
+        let module = self.module_for_uri(uri)?;
+
+        let line_numbers = LineNumbers::new(&module.code);
+
+        let mut data = Vec::new();
+
+        let mut previous = lsp_types::Position::default();
+
+        for token in semantic_tokens::semantic_tokens(&compiler.modules, module)? {
+            let text = &module.code[token.span.start..token.span.end];
+
+            let mut start = token.span.start;
+
+            for line in text.split('\n') {
+                let span = Span {
+                    start,
+                    end: start + line.trim_end_matches('\r').len(),
+                };
+
+                start += line.len() + 1;
+
+                let lsp_range = span_to_lsp_range(span, &line_numbers);
+
+                if span.start == span.end
+                    || range.is_some_and(|range| {
+                        lsp_range.end < range.start || lsp_range.start > range.end
+                    })
+                {
+                    continue;
+                }
+
+                let delta_line = lsp_range.start.line - previous.line;
+
+                data.push(lsp_types::SemanticToken {
+                    delta_line,
+                    delta_start: if delta_line == 0 {
+                        lsp_range.start.character - previous.character
+                    } else {
+                        lsp_range.start.character
+                    },
+                    length: (span.end - span.start) as u32,
+                    token_type: token.token_type,
+                    token_modifiers_bitset: token.modifiers,
+                });
+
+                previous = lsp_range.start;
+            }
+        }
+
+        Some(lsp_types::SemanticTokens {
+            result_id: None,
+            data,
+        })
+    }
+
+    /// The signature of the call under the cursor. Calls are looked up in the code as being edited,
+    /// which may not compile yet.
+    fn signature_help(
+        &self,
+        params: lsp_types::SignatureHelpParams,
+    ) -> Option<lsp_types::SignatureHelp> {
+        let params = params.text_document_position_params;
+
+        let compiler = self.compiler.as_ref()?;
+
+        let module = self.module_for_uri(&params.text_document.uri)?;
+
+        let code = self
+            .edited
+            .get(params.text_document.uri.path())
+            .unwrap_or(&module.code);
+
+        let byte_index = LineNumbers::new(code).byte_index(
+            params.position.line as usize,
+            params.position.character as usize,
+        );
+
+        let signature =
+            signature_help::signature_help(&compiler.modules, module, code, byte_index)?;
+
+        // NOTE: Offsets of parameters within the signature are in UTF-16 code units.
+        let utf16_offset = |index: usize| signature.label[..index].encode_utf16().count() as u32;
+
+        let documentation = |doc: Option<String>| {
+            doc.map(|value| {
+                lsp_types::Documentation::MarkupContent(lsp_types::MarkupContent {
+                    kind: lsp_types::MarkupKind::Markdown,
+                    value,
+                })
+            })
+        };
+
+        let active_parameter = signature.active_parameter.map(|index| index as u32);
+
+        Some(lsp_types::SignatureHelp {
+            signatures: vec![lsp_types::SignatureInformation {
+                parameters: Some(
+                    signature
+                        .parameters
+                        .iter()
+                        .map(|parameter| lsp_types::ParameterInformation {
+                            label: lsp_types::ParameterLabel::LabelOffsets([
+                                utf16_offset(parameter.range.start),
+                                utf16_offset(parameter.range.end),
+                            ]),
+                            documentation: documentation(parameter.doc.clone()),
+                        })
+                        .collect(),
+                ),
+                documentation: documentation(signature.doc.clone()),
+                label: signature.label.clone(),
+                active_parameter,
+            }],
+            active_signature: Some(0),
+            active_parameter,
+        })
+    }
+
+    /// Only symbols defined in the project's own modules can be renamed.
+    fn is_renameable(&self, symbol: &references::Symbol) -> bool {
+        match (self.compiler.as_ref(), self.config.as_ref()) {
+            (Some(compiler), Some(config)) => compiler
+                .modules
+                .get(&symbol.module)
+                .is_some_and(|module| module.package == config.name.to_string()),
+            _ => false,
+        }
+    }
+
+    fn symbol_at_position(
+        &self,
+        params: &lsp_types::TextDocumentPositionParams,
+    ) -> Option<(&LspProject, references::Reference)> {
+        let compiler = self.compiler.as_ref()?;
+
+        let module = self.module_for_uri(&params.text_document.uri)?;
+
+        let line_numbers = LineNumbers::new(&module.code);
+
+        let byte_index = line_numbers.byte_index(
+            params.position.line as usize,
+            params.position.character as usize,
+        );
+
+        let reference = references::symbol_at(&compiler.modules, module, byte_index)?;
+
+        Some((compiler, reference))
+    }
+
+    /// The location, as understood by the client, of a span in one of the project's modules.
+    #[allow(clippy::result_large_err)]
+    fn location(
+        &self,
+        module: &str,
+        span: Span,
+    ) -> Result<Option<lsp_types::Location>, ServerError> {
+        let source = match self
+            .compiler
+            .as_ref()
+            .and_then(|compiler| compiler.sources.get(module))
+        {
+            Some(source) => source,
+            None => return Ok(None),
+        };
+
+        Ok(Some(lsp_types::Location {
+            uri: path_to_uri(PathBuf::from(&source.path))?,
+            range: span_to_lsp_range(span, &source.line_numbers),
+        }))
+    }
+
     fn node_at_position(
         &self,
         params: &lsp_types::TextDocumentPositionParams,
@@ -606,6 +1154,8 @@ impl Server {
         config: Option<config::Config>,
         root: PathBuf,
     ) -> Self {
+        let settings = Settings::from_json(initialize_params.initialization_options.clone());
+
         let mut server = Server {
             root,
             config,
@@ -615,6 +1165,7 @@ impl Server {
             stored_diagnostics: HashMap::new(),
             stored_messages: Vec::new(),
             compiler: None,
+            settings,
         };
 
         server.create_new_compiler();
@@ -864,3 +1415,25 @@ impl Server {
         Ok(())
     }
 }
+
+fn document_symbols(
+    outlines: &[symbols::Outline],
+    line_numbers: &LineNumbers,
+) -> Vec<lsp_types::DocumentSymbol> {
+    outlines
+        .iter()
+        .map(|outline| {
+            #[allow(deprecated)]
+            lsp_types::DocumentSymbol {
+                name: outline.name.clone(),
+                detail: outline.detail.clone(),
+                kind: outline.kind,
+                tags: None,
+                deprecated: None,
+                range: span_to_lsp_range(outline.span, line_numbers),
+                selection_range: span_to_lsp_range(outline.name_span, line_numbers),
+                children: Some(document_symbols(&outline.children, line_numbers)),
+            }
+        })
+        .collect()
+}
@@ -36,6 +36,8 @@ impl LspProject {
             false,
             u32::default(),
             PropertyTest::DEFAULT_MAX_SUCCESS,
+            false,
+            false,
             Tracing::verbose(),
             None,
         );
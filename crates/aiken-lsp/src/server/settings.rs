@@ -0,0 +1,53 @@
+use serde::Deserialize;
+
+/// Settings of the language server, given by the client as initialization options and updated
+/// through 'workspace/didChangeConfiguration'. Settings may be given as is, or nested under an
+/// 'aiken' section.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Settings {
+    pub inlay_hints: InlayHints,
+}
+
+/// Kinds of inlay hints to show, all enabled by default.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct InlayHints {
+    /// Types of 'let' and 'expect' bindings without annotation.
+    pub bindings: bool,
+    /// Types of the intermediate values of pipelines spanning several lines.
+    pub pipelines: bool,
+    /// Types of function arguments without annotation.
+    pub function_arguments: bool,
+}
+
+impl Default for InlayHints {
+    fn default() -> Self {
+        Self {
+            bindings: true,
+            pipelines: true,
+            function_arguments: true,
+        }
+    }
+}
+
+impl Settings {
+    /// Settings from the client; unknown or invalid settings are ignored.
+    pub fn from_json(value: Option<serde_json::Value>) -> Self {
+        let value = match value {
+            Some(serde_json::Value::Object(mut object)) => match object.remove("aiken") {
+                Some(section) => section,
+                None => serde_json::Value::Object(object),
+            },
+            _ => return Settings::default(),
+        };
+
+        match serde_json::from_value(value) {
+            Ok(settings) => settings,
+            Err(error) => {
+                tracing::warn!("invalid settings: {error}");
+                Settings::default()
+            }
+        }
+    }
+}
@@ -0,0 +1,494 @@
+use aiken_lang::{
+    ast::{ArgName, DataType, Definition, TypedFunction},
+    tipo::{pretty::Printer, Type},
+};
+use aiken_project::module::CheckedModule;
+use itertools::Itertools;
+use std::{collections::HashMap, ops::Range, rc::Rc};
+
+/// The signature of a function (or record constructor) being called.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    /// The whole signature, e.g. `map(self: List<a>, with: fn(a) -> b) -> List<b>`.
+    pub label: String,
+    pub parameters: Vec<Parameter>,
+    pub doc: Option<String>,
+    /// The parameter the argument under the cursor stands for, if any.
+    pub active_parameter: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parameter {
+    /// Where the parameter lies within the label of its signature, in bytes.
+    pub range: Range<usize>,
+    pub doc: Option<String>,
+}
+
+/// The signature of the call surrounding a byte index of some source code, if any.
+///
+/// The call is found from the source itself, rather than the typed AST, since the code being
+/// written is rarely valid. The function being called is however resolved against the module as of
+/// its last successful compilation, along with its imports.
+pub fn signature_help(
+    modules: &HashMap<String, CheckedModule>,
+    module: &CheckedModule,
+    code: &str,
+    byte_index: usize,
+) -> Option<Signature> {
+    let call = call_at(code, byte_index)?;
+
+    let (name, callee) = match call.callee.split_once('.') {
+        Some((alias, name)) => {
+            let imported = module
+                .ast
+                .definitions()
+                .find_map(|definition| match definition {
+                    Definition::Use(import) => {
+                        let module_alias = import
+                            .as_name
+                            .as_deref()
+                            .or(import.module.last().map(String::as_str));
+
+                        (module_alias == Some(alias)).then(|| import.module.join("/"))
+                    }
+                    _ => None,
+                })?;
+
+            (name, find_callee(modules.get(&imported)?, name, true)?)
+        }
+
+        None => {
+            let name = call.callee;
+
+            let callee = find_callee(module, name, false).or_else(|| {
+                module
+                    .ast
+                    .definitions()
+                    .find_map(|definition| match definition {
+                        Definition::Use(import) => import
+                            .unqualified
+                            .iter()
+                            .find(|unqualified| unqualified.variable_name() == name)
+                            .and_then(|unqualified| {
+                                find_callee(
+                                    modules.get(&import.module.join("/"))?,
+                                    &unqualified.name,
+                                    true,
+                                )
+                            }),
+                        _ => None,
+                    })
+            })?;
+
+            (name, callee)
+        }
+    };
+
+    Some(callee.signature(name, &call))
+}
+
+/// A call, as written up to the cursor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Call<'a> {
+    /// The name of the function or constructor called, possibly qualified (e.g. `list.map`).
+    callee: &'a str,
+    /// The label of each argument given so far, up to and including the one under the cursor.
+    arguments: Vec<Option<&'a str>>,
+    /// Whether the first argument is supplied by a pipeline (e.g. `xs |> list.map(...)`).
+    piped: bool,
+}
+
+/// Find the innermost call enclosing a byte index, by looking backwards for an opening
+/// parenthesis (or brace, for record constructors) that isn't closed yet. Delimiters within
+/// strings and comments are ignored.
+fn call_at(code: &str, byte_index: usize) -> Option<Call<'_>> {
+    let code = code.get(..byte_index)?;
+
+    let masked = mask_strings_and_comments(code);
+
+    let mut depth = 0;
+
+    let mut separators = Vec::new();
+
+    for (index, c) in masked.char_indices().rev() {
+        match c {
+            ')' | ']' | '}' => depth += 1,
+            '(' | '[' | '{' if depth > 0 => depth -= 1,
+            '(' | '{' => {
+                let head = if c == '{' {
+                    masked[..index].trim_end()
+                } else {
+                    &masked[..index]
+                };
+
+                let callee = &code[head.len() - callee_suffix(head).len()..head.len()];
+
+                let before = code[..head.len() - callee.len()].trim_end();
+
+                let name = callee.rsplit('.').next().unwrap_or(callee);
+
+                let is_callable = if c == '{' {
+                    name.starts_with(|c: char| c.is_ascii_uppercase())
+                } else {
+                    name.starts_with(|c: char| c.is_ascii_alphabetic())
+                };
+
+                // NOTE: The parameters of a definition (or of an anonymous function) aren't the
+                // arguments of a call; nor are any enclosing call's.
+                let keyword = callee_suffix(before);
+                if callee == "fn" || ["fn", "test", "bench", "validator"].contains(&keyword) {
+                    return None;
+                }
+
+                if !is_callable {
+                    separators.clear();
+                    continue;
+                }
+
+                let mut start = index + c.len_utf8();
+
+                let mut arguments = Vec::new();
+
+                for end in separators.iter().rev().chain(std::iter::once(&code.len())) {
+                    arguments.push(argument_label(&code[start..*end]));
+                    start = end + 1;
+                }
+
+                return Some(Call {
+                    callee,
+                    arguments,
+                    piped: before.ends_with("|>"),
+                });
+            }
+            '[' => separators.clear(),
+            ',' if depth == 0 => separators.push(index),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// The trailing (possibly qualified) name of some code, if any.
+fn callee_suffix(code: &str) -> &str {
+    let start = code
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphanumeric() || *c == '_' || *c == '.')
+        .last()
+        .map(|(start, _)| start)
+        .unwrap_or(code.len());
+
+    &code[start..]
+}
+
+/// The label of an argument, as in `with: fn(x) { x + 1 }`.
+fn argument_label(argument: &str) -> Option<&str> {
+    let (label, _) = argument.split_once(':')?;
+
+    let label = label.trim();
+
+    (!label.is_empty() && label.chars().all(|c| c.is_alphanumeric() || c == '_')).then_some(label)
+}
+
+/// Replace the content of strings and comments with spaces, so that they are left out when
+/// looking for delimiters. Byte indexes of the code are preserved.
+fn mask_strings_and_comments(code: &str) -> String {
+    let mut masked = String::with_capacity(code.len());
+
+    let mut chars = code.chars().peekable();
+
+    let mut in_string = false;
+
+    let mut in_comment = false;
+
+    let mask =
+        |masked: &mut String, c: char| masked.extend(std::iter::repeat(' ').take(c.len_utf8()));
+
+    while let Some(c) = chars.next() {
+        if in_comment {
+            in_comment = c != '\n';
+            if in_comment {
+                mask(&mut masked, c);
+            } else {
+                masked.push(c);
+            }
+        } else if in_string {
+            if c == '\\' {
+                mask(&mut masked, c);
+                if let Some(escaped) = chars.next() {
+                    mask(&mut masked, escaped);
+                }
+            } else {
+                in_string = c != '"';
+                mask(&mut masked, c);
+            }
+        } else if c == '"' {
+            in_string = true;
+            mask(&mut masked, c);
+        } else if c == '/' && chars.peek() == Some(&'/') {
+            in_comment = true;
+            mask(&mut masked, c);
+        } else {
+            masked.push(c);
+        }
+    }
+
+    masked
+}
+
+/// A function or record constructor, as defined.
+enum Callee<'a> {
+    Function(&'a TypedFunction),
+    Constructor(&'a DataType<Rc<Type>>, usize),
+}
+
+/// Find a function or record constructor defined in a module.
+fn find_callee<'a>(module: &'a CheckedModule, name: &str, public: bool) -> Option<Callee<'a>> {
+    module
+        .ast
+        .definitions()
+        .find_map(|definition| match definition {
+            Definition::Fn(function) if function.name == name && (function.public || !public) => {
+                Some(Callee::Function(function))
+            }
+            Definition::DataType(data_type)
+                if (data_type.public && !data_type.opaque) || !public =>
+            {
+                data_type
+                    .constructors
+                    .iter()
+                    .position(|constructor| constructor.name == name)
+                    .map(|index| Callee::Constructor(data_type, index))
+            }
+            _ => None,
+        })
+}
+
+impl Callee<'_> {
+    fn signature(&self, name: &str, call: &Call<'_>) -> Signature {
+        let mut printer = Printer::new();
+
+        let mut print = |tipo: &Type| {
+            printer
+                .pretty_print(tipo, 0)
+                .lines()
+                .map(str::trim)
+                .join(" ")
+        };
+
+        let (parameters, return_type, doc) = match self {
+            Callee::Function(function) => (
+                function
+                    .arguments
+                    .iter()
+                    .map(|argument| {
+                        let label = match &argument.arg_name {
+                            ArgName::Named { label, .. } => Some(label.as_str()),
+                            ArgName::Discarded { .. } => None,
+                        };
+
+                        (label, print(&argument.tipo), argument.doc.clone())
+                    })
+                    .collect::<Vec<_>>(),
+                print(&function.return_type),
+                function.doc.clone(),
+            ),
+
+            Callee::Constructor(data_type, index) => {
+                let constructor = &data_type.constructors[*index];
+
+                let parameters = constructor
+                    .arguments
+                    .iter()
+                    .map(|argument| {
+                        (
+                            argument.label.as_deref(),
+                            print(&argument.tipo),
+                            argument.doc.clone(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                let return_type = if data_type.typed_parameters.is_empty() {
+                    data_type.name.clone()
+                } else {
+                    format!(
+                        "{}<{}>",
+                        data_type.name,
+                        data_type
+                            .typed_parameters
+                            .iter()
+                            .map(|parameter| print(parameter))
+                            .join(", ")
+                    )
+                };
+
+                (
+                    parameters,
+                    return_type,
+                    constructor.doc.clone().or_else(|| data_type.doc.clone()),
+                )
+            }
+        };
+
+        let mut label = format!("{name}(");
+
+        let parameter_ranges = parameters
+            .iter()
+            .enumerate()
+            .map(|(index, (parameter_label, tipo, doc))| {
+                if index > 0 {
+                    label.push_str(", ");
+                }
+
+                let start = label.len();
+
+                if let Some(parameter_label) = parameter_label {
+                    label.push_str(parameter_label);
+                    label.push_str(": ");
+                }
+
+                label.push_str(tipo);
+
+                Parameter {
+                    range: start..label.len(),
+                    doc: doc.clone(),
+                }
+            })
+            .collect();
+
+        label.push_str(") -> ");
+        label.push_str(&return_type);
+
+        let labels = parameters
+            .iter()
+            .map(|(label, _, _)| *label)
+            .collect::<Vec<_>>();
+
+        Signature {
+            label,
+            parameters: parameter_ranges,
+            doc,
+            active_parameter: active_parameter(&labels, call),
+        }
+    }
+}
+
+/// The parameter an argument stands for: the one with the same label, or else the next one that
+/// isn't given by a labeled argument, in order.
+fn active_parameter(labels: &[Option<&str>], call: &Call<'_>) -> Option<usize> {
+    let (active, given) = call.arguments.split_last()?;
+
+    if let Some(active) = active {
+        return labels.iter().position(|label| label == &Some(*active));
+    }
+
+    let positional = given.iter().filter(|label| label.is_none()).count() + usize::from(call.piped);
+
+    labels
+        .iter()
+        .enumerate()
+        .filter(|(_, label)| label.is_none() || !given.contains(label))
+        .nth(positional)
+        .map(|(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::check;
+    use indoc::indoc;
+
+    const MATH: &str = indoc! {r#"
+        /// Clamp a number between two bounds.
+        pub fn clamp(n: Int, min: Int, max: Int) -> Int {
+          if n < min {
+            min
+          } else if n > max {
+            max
+          } else {
+            n
+          }
+        }
+
+        pub type Range {
+          Range { lower: Int, upper: Int }
+        }
+    "#};
+
+    const MAIN: &str = indoc! {r#"
+        use math.{clamp}
+    "#};
+
+    /// The signature help at the end of a piece of code, written in the main module.
+    fn help(code: &str) -> Option<(String, Option<usize>)> {
+        let modules = check(&[("math", MATH), ("main", MAIN)]);
+
+        signature_help(&modules, &modules["main"], code, code.len())
+            .map(|signature| (signature.label, signature.active_parameter))
+    }
+
+    const CLAMP: &str = "clamp(n: Int, min: Int, max: Int) -> Int";
+
+    #[test]
+    fn positional_arguments() {
+        assert_eq!(help("math.clamp("), Some((CLAMP.to_string(), Some(0))));
+        assert_eq!(help("clamp(x, 0"), Some((CLAMP.to_string(), Some(1))));
+        assert_eq!(
+            help("clamp(f(x, y), \"a, b\", "),
+            Some((CLAMP.to_string(), Some(2)))
+        );
+    }
+
+    #[test]
+    fn labeled_and_piped_arguments() {
+        assert_eq!(help("clamp(max: 10, "), Some((CLAMP.to_string(), Some(0))));
+        assert_eq!(
+            help("clamp(max: 10, x, "),
+            Some((CLAMP.to_string(), Some(1)))
+        );
+        assert_eq!(help("clamp(x, min: "), Some((CLAMP.to_string(), Some(1))));
+        assert_eq!(help("x |> math.clamp("), Some((CLAMP.to_string(), Some(1))));
+    }
+
+    #[test]
+    fn record_constructors() {
+        assert_eq!(
+            help("math.Range { lower: 0, "),
+            Some((
+                "Range(lower: Int, upper: Int) -> Range".to_string(),
+                Some(1)
+            ))
+        );
+    }
+
+    #[test]
+    fn no_signature() {
+        assert_eq!(help("fn clamp("), None);
+        assert_eq!(help("clamp(x)"), None);
+        assert_eq!(help("unknown("), None);
+        assert_eq!(help("// clamp("), None);
+    }
+
+    #[test]
+    fn parameter_ranges_and_docs() {
+        let modules = check(&[("math", MATH), ("main", MAIN)]);
+
+        let signature = signature_help(&modules, &modules["main"], "clamp(", 6).unwrap();
+
+        assert_eq!(
+            signature
+                .parameters
+                .iter()
+                .map(|parameter| &signature.label[parameter.range.clone()])
+                .collect::<Vec<_>>(),
+            vec!["n: Int", "min: Int", "max: Int"]
+        );
+
+        assert_eq!(
+            signature.doc.as_deref(),
+            Some(" Clamp a number between two bounds.")
+        );
+    }
+}
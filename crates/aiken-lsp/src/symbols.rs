@@ -0,0 +1,376 @@
+use crate::utils::identifier_span;
+use aiken_lang::{
+    ast::{
+        well_known, DataType, Definition, RecordConstructorArg, Span, TypedDefinition,
+        TypedFunction, TypedTest, TypedValidator,
+    },
+    tipo::{pretty::Printer, Type},
+};
+use aiken_project::module::CheckedModule;
+use lsp_types::SymbolKind;
+use std::rc::Rc;
+
+/// A definition of a module, as shown in the outline of that module.
+#[derive(Debug, Clone)]
+pub struct Outline {
+    pub name: String,
+    pub detail: Option<String>,
+    pub kind: SymbolKind,
+    /// The whole definition, including its body.
+    pub span: Span,
+    /// The name of the definition, within its span.
+    pub name_span: Span,
+    pub children: Vec<Outline>,
+}
+
+impl Outline {
+    /// All definitions of the outline, paired with the name of the definition containing them.
+    pub fn flatten<'a>(outlines: &'a [Outline], container: &'a str) -> Vec<(&'a str, &'a Outline)> {
+        outlines
+            .iter()
+            .flat_map(|outline| {
+                std::iter::once((container, outline))
+                    .chain(Outline::flatten(&outline.children, &outline.name))
+            })
+            .collect()
+    }
+}
+
+/// The outline of a module: its functions, types, constants, validators (and their handlers),
+/// interfaces and instances, in order of definition.
+pub fn outline(module: &CheckedModule) -> Vec<Outline> {
+    let mut outlines = module
+        .ast
+        .definitions()
+        .filter_map(|definition| definition_outline(&module.code, definition))
+        .collect::<Vec<_>>();
+
+    // Type-checking reorders definitions (e.g. constants come first).
+    outlines.sort_by_key(|outline| outline.span.start);
+
+    outlines
+}
+
+/// Whether a name matches a query, i.e. all characters of the query appear in the name in the
+/// same order, regardless of case.
+pub fn matches_query(name: &str, query: &str) -> bool {
+    let mut name = name.chars().flat_map(char::to_lowercase);
+
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|c| name.any(|n| n == c))
+}
+
+fn definition_outline(code: &str, definition: &TypedDefinition) -> Option<Outline> {
+    match definition {
+        Definition::Fn(function) => Some(function_outline(code, function, SymbolKind::FUNCTION)),
+
+        Definition::Test(test) => Some(test_outline(code, test, "test")),
+
+        Definition::Benchmark(benchmark) => Some(test_outline(code, benchmark, "bench")),
+
+        Definition::TypeAlias(alias) => Some(outline_of(
+            code,
+            &alias.alias,
+            code.get(alias.annotation.location().start..alias.annotation.location().end)
+                .map(str::to_string),
+            SymbolKind::TYPE_PARAMETER,
+            alias.location,
+        )),
+
+        Definition::DataType(data_type) => Some(data_type_outline(code, data_type)),
+
+        Definition::ModuleConstant(constant) => Some(outline_of(
+            code,
+            &constant.name,
+            Some(pretty_print(&constant.value.tipo())),
+            SymbolKind::CONSTANT,
+            constant.location,
+        )),
+
+        Definition::Validator(validator) => Some(validator_outline(code, validator)),
+
+        Definition::Interface(interface) => Some(Outline {
+            children: interface
+                .methods
+                .iter()
+                .map(|method| {
+                    outline_of(
+                        code,
+                        &method.name,
+                        Some(pretty_print(&method.tipo)),
+                        SymbolKind::METHOD,
+                        method.location,
+                    )
+                })
+                .collect(),
+            ..outline_of(
+                code,
+                &interface.name,
+                None,
+                SymbolKind::INTERFACE,
+                interface.location,
+            )
+        }),
+
+        Definition::Instance(instance) => {
+            let location = instance.interface.location();
+
+            let name = code.get(location.start..location.end)?.to_string();
+
+            Some(Outline {
+                name,
+                detail: Some("instance".to_string()),
+                kind: SymbolKind::OBJECT,
+                span: Span {
+                    start: instance.location.start,
+                    end: instance.end_position + 1,
+                },
+                name_span: location,
+                children: instance
+                    .methods
+                    .iter()
+                    .map(|method| function_outline(code, method, SymbolKind::METHOD))
+                    .collect(),
+            })
+        }
+
+        Definition::Use(_) => None,
+    }
+}
+
+fn function_outline(code: &str, function: &TypedFunction, kind: SymbolKind) -> Outline {
+    Outline {
+        span: Span {
+            start: function.location.start,
+            end: function.end_position + 1,
+        },
+        ..outline_of(
+            code,
+            &function.name,
+            Some(pretty_print(&function_type(function))),
+            kind,
+            function.location,
+        )
+    }
+}
+
+fn test_outline(code: &str, test: &TypedTest, detail: &str) -> Outline {
+    Outline {
+        span: Span {
+            start: test.location.start,
+            end: test.end_position + 1,
+        },
+        ..outline_of(
+            code,
+            &test.name,
+            Some(detail.to_string()),
+            SymbolKind::FUNCTION,
+            test.location,
+        )
+    }
+}
+
+fn validator_outline(code: &str, validator: &TypedValidator) -> Outline {
+    let mut handlers = validator
+        .handlers
+        .iter()
+        .map(|handler| function_outline(code, handler, SymbolKind::METHOD))
+        .collect::<Vec<_>>();
+
+    // NOTE: Validators without an explicit fallback get a default one, located at the validator's
+    // head; only fallbacks written by the user are shown. The location of those starts after the
+    // 'else' keyword, which we use as their name.
+    if validator.fallback.location != validator.location {
+        let fallback = function_outline(code, &validator.fallback, SymbolKind::METHOD);
+
+        let name_span = code[..fallback.span.start]
+            .rfind(well_known::VALIDATOR_ELSE)
+            .map(|start| Span {
+                start,
+                end: start + well_known::VALIDATOR_ELSE.len(),
+            })
+            .unwrap_or(fallback.name_span);
+
+        handlers.push(Outline {
+            span: Span {
+                start: name_span.start,
+                end: fallback.span.end,
+            },
+            name_span,
+            ..fallback
+        });
+    }
+
+    // NOTE: The location of a validator only spans its head up to its parameters, which doesn't
+    // include its name when it has none.
+    Outline {
+        children: handlers,
+        ..outline_of(
+            code,
+            &validator.name,
+            Some("validator".to_string()),
+            SymbolKind::CLASS,
+            Span {
+                start: validator.location.start,
+                end: validator.end_position + 1,
+            },
+        )
+    }
+}
+
+fn data_type_outline(code: &str, data_type: &DataType<Rc<Type>>) -> Outline {
+    let fields = |arguments: &[RecordConstructorArg<Rc<Type>>]| {
+        arguments
+            .iter()
+            .filter_map(|argument| {
+                argument.label.as_ref().map(|label| {
+                    outline_of(
+                        code,
+                        label,
+                        Some(pretty_print(&argument.tipo)),
+                        SymbolKind::FIELD,
+                        argument.location,
+                    )
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let children = match data_type.constructors.as_slice() {
+        // NOTE: Records defined with the short-hand syntax have a single constructor named after
+        // the type, so their fields are shown directly under the type.
+        [constructor] if constructor.sugar => fields(&constructor.arguments),
+        constructors => constructors
+            .iter()
+            .map(|constructor| Outline {
+                children: fields(&constructor.arguments),
+                ..outline_of(
+                    code,
+                    &constructor.name,
+                    None,
+                    SymbolKind::CONSTRUCTOR,
+                    constructor.location,
+                )
+            })
+            .collect(),
+    };
+
+    let kind = if data_type.constructors.len() == 1 {
+        SymbolKind::STRUCT
+    } else {
+        SymbolKind::ENUM
+    };
+
+    Outline {
+        children,
+        ..outline_of(code, &data_type.name, None, kind, data_type.location)
+    }
+}
+
+fn outline_of(
+    code: &str,
+    name: &str,
+    detail: Option<String>,
+    kind: SymbolKind,
+    location: Span,
+) -> Outline {
+    Outline {
+        name: name.to_string(),
+        detail,
+        kind,
+        span: location,
+        name_span: identifier_span(code, location, name).unwrap_or(location),
+        children: Vec::new(),
+    }
+}
+
+fn function_type(function: &TypedFunction) -> Rc<Type> {
+    Type::function(
+        function
+            .arguments
+            .iter()
+            .map(|argument| argument.tipo.clone())
+            .collect(),
+        function.return_type.clone(),
+    )
+}
+
+fn pretty_print(tipo: &Type) -> String {
+    Printer::new().pretty_print(tipo, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::check;
+    use indoc::indoc;
+
+    #[test]
+    fn outline_of_module() {
+        let modules = check(&[(
+            "main",
+            indoc! {r#"
+                pub type Color {
+                  Red
+                  Green
+                }
+
+                pub const answer: Int = 42
+
+                pub fn double(n: Int) -> Int {
+                  n * 2
+                }
+
+                test double_answer() {
+                  double(answer) == 84
+                }
+            "#},
+        )]);
+
+        let outline = outline(&modules["main"]);
+
+        let flattened = Outline::flatten(&outline, "")
+            .into_iter()
+            .map(|(container, outline)| {
+                (
+                    container,
+                    outline.name.as_str(),
+                    outline.kind,
+                    outline.detail.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            flattened,
+            vec![
+                ("", "Color", SymbolKind::ENUM, None),
+                ("Color", "Red", SymbolKind::CONSTRUCTOR, None),
+                ("Color", "Green", SymbolKind::CONSTRUCTOR, None),
+                ("", "answer", SymbolKind::CONSTANT, Some("Int")),
+                ("", "double", SymbolKind::FUNCTION, Some("fn(Int) -> Int")),
+                ("", "double_answer", SymbolKind::FUNCTION, Some("test")),
+            ]
+        );
+
+        let code = &modules["main"].code;
+        for (_, outline) in Outline::flatten(&outline, "") {
+            assert_eq!(
+                &code[outline.name_span.start..outline.name_span.end],
+                outline.name
+            );
+        }
+    }
+
+    #[test]
+    fn fuzzy_queries() {
+        assert!(matches_query("quadruple", ""));
+        assert!(matches_query("quadruple", "qdr"));
+        assert!(matches_query("QuadRuple", "quadr"));
+        assert!(matches_query("quadruple", "QUAD"));
+        assert!(!matches_query("quadruple", "rq"));
+        assert!(!matches_query("double", "doubles"));
+    }
+}
@@ -0,0 +1,69 @@
+use aiken_lang::{
+    ast::{ModuleKind, TraceLevel, Tracing},
+    builtins, parser, IdGenerator,
+};
+use aiken_project::module::CheckedModule;
+use std::{collections::HashMap, path::PathBuf};
+
+pub const PACKAGE: &str = "test/project";
+
+/// Parse and type-check the modules of a test project, in order, such that modules may import
+/// those that come before them. Modules under 'validators/' are checked as validator modules.
+pub fn check(modules: &[(&str, &str)]) -> HashMap<String, CheckedModule> {
+    let id_gen = IdGenerator::new();
+
+    let mut module_types = HashMap::new();
+    module_types.insert("aiken".to_string(), builtins::prelude(&id_gen));
+    module_types.insert("aiken/builtin".to_string(), builtins::plutus(&id_gen));
+
+    let mut checked = HashMap::new();
+
+    for (name, code) in modules {
+        let kind = if name.starts_with("validators/") {
+            ModuleKind::Validator
+        } else {
+            ModuleKind::Lib
+        };
+
+        let (mut ast, extra) = parser::module(code, kind).expect("Failed to parse module");
+        ast.name = name.to_string();
+
+        let ast = ast
+            .infer(
+                &id_gen,
+                kind,
+                PACKAGE,
+                &module_types,
+                Tracing::All(TraceLevel::Verbose),
+                &mut vec![],
+                None,
+            )
+            .expect("Failed to type-check module");
+
+        module_types.insert(name.to_string(), ast.type_info.clone());
+
+        let mut module = CheckedModule {
+            name: name.to_string(),
+            code: code.to_string(),
+            input_path: PathBuf::from(format!("{name}.ak")),
+            kind,
+            package: PACKAGE.to_string(),
+            ast,
+            extra,
+        };
+
+        module.attach_doc_and_module_comments();
+
+        checked.insert(name.to_string(), module);
+    }
+
+    checked
+}
+
+/// The byte index of the nth occurrence (starting from 0) of some text in a piece of code.
+pub fn offset(code: &str, text: &str, nth: usize) -> usize {
+    code.match_indices(text)
+        .nth(nth)
+        .map(|(index, _)| index)
+        .unwrap_or_else(|| panic!("no occurrence #{nth} of '{text}'"))
+}
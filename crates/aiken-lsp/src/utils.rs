@@ -55,6 +55,23 @@ pub fn span_to_lsp_range(location: Span, line_numbers: &LineNumbers) -> lsp_type
     }
 }
 
+/// The location of a name within the location of the node introducing it (e.g. a function's name
+/// within its head, or a variable within an argument and its annotation).
+pub fn identifier_span(code: &str, location: Span, name: &str) -> Option<Span> {
+    let text = code.get(location.start..location.end.min(code.len()))?;
+
+    text.match_indices(name).find_map(|(offset, _)| {
+        let start = location.start + offset;
+        let end = start + name.len();
+
+        let is_boundary =
+            |neighbour: Option<char>| !neighbour.is_some_and(|c| c.is_alphanumeric() || c == '_');
+
+        (is_boundary(code[..start].chars().next_back()) && is_boundary(code[end..].chars().next()))
+            .then_some(Span { start, end })
+    })
+}
+
 pub fn uri_to_module_name(uri: &url::Url, root: &Path) -> Option<String> {
     let path = if cfg!(target_os = "windows") {
         let mut uri_path = decode(&uri.path().replace('/', "\\"))
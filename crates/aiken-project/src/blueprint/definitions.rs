@@ -64,6 +64,51 @@ impl<T> Definitions<T> {
         self.inner.insert(reference.as_key(), Some(schema));
     }
 
+    /// Iterate over all resolved definitions, with their key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &T)> {
+        self.inner
+            .iter()
+            .filter_map(|(key, value)| value.as_ref().map(|value| (key.as_str(), value)))
+    }
+
+    /// Readable identifiers for every definition, suitable for generated code. Module paths are
+    /// dropped unless two definitions would otherwise end up with the same identifier, e.g.
+    /// 'aiken/interval/Interval$Int' becomes 'IntervalInt'.
+    pub fn identifiers(&self) -> BTreeMap<String, String> {
+        fn identifier(key: &str, with_modules: bool) -> String {
+            // Type names start with an uppercase letter, whereas module names never do.
+            key.split(['$', '_', '/'])
+                .filter(|part| with_modules || part.starts_with(char::is_uppercase))
+                .map(capitalize)
+                .collect()
+        }
+
+        fn capitalize(s: &str) -> String {
+            let mut chars = s.chars();
+            match chars.next() {
+                Some(head) => head.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        }
+
+        let short = self
+            .inner
+            .keys()
+            .map(|key| (key.clone(), identifier(key, false)))
+            .collect::<BTreeMap<_, _>>();
+
+        short
+            .iter()
+            .map(|(key, name)| {
+                if short.values().filter(|other| *other == name).count() > 1 {
+                    (key.clone(), identifier(key, true))
+                } else {
+                    (key.clone(), name.clone())
+                }
+            })
+            .collect()
+    }
+
     /// Register a new definition only if it doesn't exist. This uses a strategy of
     /// mark-and-insert such that recursive definitions are only built once.
     pub fn register<F, E>(
@@ -7,7 +7,7 @@ use miette::{Diagnostic, NamedSource};
 use owo_colors::{OwoColorize, Stream::Stdout};
 use pallas_codec::minicbor as cbor;
 use std::fmt::Debug;
-use uplc::ast::Constant;
+use uplc::{ast::Constant, PlutusData};
 
 #[derive(Debug, thiserror::Error, Diagnostic)]
 pub enum Error {
@@ -84,10 +84,10 @@ pub enum Error {
     ))]
     UnresolvedSchemaReference { reference: Reference },
 
-    #[error("I caught a parameter application that seems off.")]
+    #[error("I caught a value that doesn't match its schema at {path}.")]
     #[diagnostic(code("aiken::blueprint::apply::mismatch"))]
     #[diagnostic(help(
-        "When applying parameters to a validator, I control that the shape of the parameter you give me matches what is specified in the blueprint. Unfortunately, it didn't match in this case.\n\nI am looking at the following value:\n\n{term}\n\nbut failed to match it against the specified schema:\n\n{expected}\n\n\nNOTE: this may only represent part of a bigger whole as I am validating the parameter incrementally.",
+        "When applying parameters to a validator or validating data, I control that the shape of the value you give me matches what is specified in the blueprint. Unfortunately, it didn't match in this case.\n\nI am looking at the following value:\n\n{term}\n\nbut failed to match it against the specified schema:\n\n{expected}\n\n\nNOTE: this may only represent part of a bigger whole as I am validating the value incrementally.",
         expected = serde_json::to_string_pretty(&schema).unwrap().if_supports_color(Stdout, |s| s.green()),
         term = {
             let mut buf = vec![];
@@ -100,10 +100,14 @@ pub enum Error {
             }
         }.if_supports_color(Stdout, |s| s.red()),
     ))]
-    SchemaMismatch { schema: Schema, term: Constant },
+    SchemaMismatch {
+        path: String,
+        schema: Box<Schema>,
+        term: Constant,
+    },
 
     #[error(
-        "I discovered a discrepancy of elements between a given tuple and its declared schema."
+        "I discovered a discrepancy of elements between a given tuple and its declared schema at {path}."
     )]
     #[diagnostic(code("aiken::blueprint::apply::tuple::mismatch"))]
     #[diagnostic(help(
@@ -111,12 +115,55 @@ pub enum Error {
         expected = expected.if_supports_color(Stdout, |s| s.green()),
         found = found.if_supports_color(Stdout, |s| s.red()),
     ))]
-    TupleItemsMismatch { expected: usize, found: usize },
+    TupleItemsMismatch {
+        path: String,
+        expected: usize,
+        found: usize,
+    },
 
     #[error("I failed to convert some input into a valid parameter")]
     #[diagnostic(code("aiken::blueprint::parse::parameter"))]
     #[diagnostic(help("{hint}"))]
     MalformedParameter { hint: String },
+
+    #[error("I couldn't find any schema for {target}.")]
+    #[diagnostic(code("aiken::blueprint::schema::missing"))]
+    #[diagnostic(help(
+        "Definitions are designated by their key in the blueprint, such as '{example}'. Validators only have a schema for their datum when they handle spending, and for as many parameters as they take."
+        , example = "aiken/interval/Interval$Int".if_supports_color(Stdout, |s| s.purple())
+    ))]
+    MissingSchema { target: String },
+
+    #[error("I couldn't encode the given value: it doesn't match its schema at {path}.")]
+    #[diagnostic(code("aiken::blueprint::encode::mismatch"))]
+    #[diagnostic(help(
+        "I expected {expected} but found:\n\n{found}",
+        expected = expected.if_supports_color(Stdout, |s| s.green()),
+        found = found.if_supports_color(Stdout, |s| s.red()),
+    ))]
+    JsonMismatch {
+        path: String,
+        expected: String,
+        found: String,
+    },
+
+    #[error("I couldn't decode the given data: it doesn't match its schema at {path}.")]
+    #[diagnostic(code("aiken::blueprint::decode::mismatch"))]
+    #[diagnostic(help(
+        "I expected {expected} but found:\n\n{found}",
+        expected = expected.if_supports_color(Stdout, |s| s.green()),
+        found = {
+            let mut buf = vec![];
+            cbor::encode(found, &mut buf).unwrap();
+            let found = cbor::display(&buf).to_string();
+            found
+        }.if_supports_color(Stdout, |s| s.red()),
+    ))]
+    DataMismatch {
+        path: String,
+        expected: String,
+        found: PlutusData,
+    },
 }
 
 unsafe impl Send for Error {}
@@ -0,0 +1,820 @@
+use super::{
+    definitions::Definitions,
+    error::Error,
+    schema::{Annotated, Constructor, Data, Declaration, Items, Schema},
+};
+use num_bigint::BigInt;
+use serde_json::{Map, Value};
+use std::str::FromStr;
+use uplc::{
+    ast::Data as UplcData,
+    machine::{
+        runtime::convert_tag_to_constr,
+        value::{from_pallas_bigint, to_pallas_bigint},
+    },
+    PlutusData,
+};
+
+/// Encode a human-readable JSON value into Plutus data, following a schema.
+///
+/// - integers are JSON numbers, or strings of digits when too large;
+/// - byte arrays are hex-encoded strings;
+/// - lists, tuples and pairs are arrays; maps are arrays of `[key, value]` pairs;
+/// - booleans are JSON booleans and unit is `null`;
+/// - constructors without fields are their title, e.g. `"None"`;
+/// - records are objects of their fields, or arrays when fields have no label;
+/// - other constructors are objects with their title as sole key, e.g. `{ "Some": [42] }`;
+/// - opaque data is CBOR, hex-encoded.
+pub fn encode(
+    value: &Value,
+    schema: &Declaration<Schema>,
+    definitions: &Definitions<Annotated<Schema>>,
+) -> Result<PlutusData, Error> {
+    Codec { definitions }.encode_declaration(value, schema, "$", Codec::encode_schema)
+}
+
+/// Decode Plutus data into a human-readable JSON value, following a schema. This is the converse
+/// of [`encode`].
+pub fn decode(
+    data: &PlutusData,
+    schema: &Declaration<Schema>,
+    definitions: &Definitions<Annotated<Schema>>,
+) -> Result<Value, Error> {
+    Codec { definitions }.decode_declaration(data, schema, "$", Codec::decode_schema)
+}
+
+struct Codec<'a> {
+    definitions: &'a Definitions<Annotated<Schema>>,
+}
+
+impl<'a> Codec<'a> {
+    fn resolve<T>(&self, declaration: &'a Declaration<T>) -> Result<&'a Schema, Error> {
+        let reference = declaration
+            .reference()
+            .expect("only called on referenced declarations");
+
+        self.definitions
+            .lookup(reference)
+            .map(|schema| &schema.annotated)
+            .ok_or_else(|| Error::UnresolvedSchemaReference {
+                reference: reference.clone(),
+            })
+    }
+
+    // ---------- Encoding
+
+    fn encode_declaration<T>(
+        &self,
+        value: &Value,
+        declaration: &'a Declaration<T>,
+        path: &str,
+        inline: fn(&Self, &Value, &'a T, &str) -> Result<PlutusData, Error>,
+    ) -> Result<PlutusData, Error> {
+        match declaration {
+            Declaration::Referenced(..) => {
+                self.encode_schema(value, self.resolve(declaration)?, path)
+            }
+            Declaration::Inline(schema) => inline(self, value, schema, path),
+        }
+    }
+
+    fn encode_schema(
+        &self,
+        value: &Value,
+        schema: &'a Schema,
+        path: &str,
+    ) -> Result<PlutusData, Error> {
+        match schema {
+            Schema::Data(data) => self.encode_data(value, data, path),
+            Schema::Unit => match value {
+                Value::Null => Ok(UplcData::constr(0, vec![])),
+                _ => Err(json_mismatch(path, "null", value)),
+            },
+            Schema::Boolean => encode_bool(value, path),
+            Schema::Integer => encode_integer(value, path),
+            Schema::Bytes => encode_bytes(value, path),
+            Schema::String => match value {
+                Value::String(text) => Ok(UplcData::bytestring(text.as_bytes().to_vec())),
+                _ => Err(json_mismatch(path, "a string", value)),
+            },
+            Schema::Pair(left, right) => {
+                let elements = expect_array(value, 2, path)?;
+                Ok(UplcData::list(vec![
+                    self.encode_declaration(
+                        &elements[0],
+                        left,
+                        &format!("{path}[0]"),
+                        Self::encode_schema,
+                    )?,
+                    self.encode_declaration(
+                        &elements[1],
+                        right,
+                        &format!("{path}[1]"),
+                        Self::encode_schema,
+                    )?,
+                ]))
+            }
+            Schema::List(items) => self.encode_items(value, items, path, Self::encode_schema),
+        }
+    }
+
+    fn encode_data(&self, value: &Value, data: &'a Data, path: &str) -> Result<PlutusData, Error> {
+        match data {
+            Data::Integer => encode_integer(value, path),
+            Data::Bytes => encode_bytes(value, path),
+            Data::List(items) => self.encode_items(value, items, path, Self::encode_data),
+            Data::Map(keys, values) => {
+                let Value::Array(entries) = value else {
+                    return Err(json_mismatch(path, "an array of [key, value] pairs", value));
+                };
+
+                entries
+                    .iter()
+                    .enumerate()
+                    .map(|(index, entry)| {
+                        let path = format!("{path}[{index}]");
+                        let entry = expect_array(entry, 2, &path)?;
+                        Ok((
+                            self.encode_declaration(
+                                &entry[0],
+                                keys,
+                                &format!("{path}[0]"),
+                                Self::encode_data,
+                            )?,
+                            self.encode_declaration(
+                                &entry[1],
+                                values,
+                                &format!("{path}[1]"),
+                                Self::encode_data,
+                            )?,
+                        ))
+                    })
+                    .collect::<Result<_, _>>()
+                    .map(UplcData::map)
+            }
+            Data::AnyOf(constructors) => self.encode_constructors(value, constructors, path),
+            Data::Opaque => {
+                let bytes = match value {
+                    Value::String(cbor) => hex::decode(cbor).ok(),
+                    _ => None,
+                };
+
+                bytes
+                    .and_then(|bytes| uplc::plutus_data(&bytes).ok())
+                    .ok_or_else(|| json_mismatch(path, "hex-encoded CBOR Plutus data", value))
+            }
+        }
+    }
+
+    fn encode_items<T>(
+        &self,
+        value: &Value,
+        items: &'a Items<T>,
+        path: &str,
+        inline: fn(&Self, &Value, &'a T, &str) -> Result<PlutusData, Error>,
+    ) -> Result<PlutusData, Error> {
+        let elements = match items {
+            Items::One(item) => {
+                let Value::Array(elements) = value else {
+                    return Err(json_mismatch(path, "an array", value));
+                };
+
+                elements
+                    .iter()
+                    .enumerate()
+                    .map(|(index, element)| {
+                        self.encode_declaration(element, item, &format!("{path}[{index}]"), inline)
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+            Items::Many(items) => expect_array(value, items.len(), path)?
+                .iter()
+                .zip(items)
+                .enumerate()
+                .map(|(index, (element, item))| {
+                    self.encode_declaration(element, item, &format!("{path}[{index}]"), inline)
+                })
+                .collect::<Result<_, _>>()?,
+        };
+
+        Ok(UplcData::list(elements))
+    }
+
+    fn encode_constructors(
+        &self,
+        value: &Value,
+        constructors: &'a [Annotated<Constructor>],
+        path: &str,
+    ) -> Result<PlutusData, Error> {
+        if is_bool(constructors) {
+            return encode_bool(value, path);
+        }
+
+        if let [constructor] = constructors {
+            return self.encode_constructor(value, constructor, path);
+        }
+
+        let found = match value {
+            Value::String(title) => constructors.iter().find(|constructor| {
+                constructor.annotated.fields.is_empty() && constructor_title(constructor) == *title
+            }),
+            Value::Object(object) if object.len() == 1 => constructors.iter().find(|constructor| {
+                !constructor.annotated.fields.is_empty()
+                    && object.contains_key(&constructor_title(constructor))
+            }),
+            _ => None,
+        };
+
+        match (found, value) {
+            (Some(constructor), Value::Object(object)) => {
+                let title = constructor_title(constructor);
+                self.encode_constructor(&object[&title], constructor, &format!("{path}.{title}"))
+            }
+            (Some(constructor), _) => self.encode_constructor(value, constructor, path),
+            (None, _) => Err(json_mismatch(
+                path,
+                format!(
+                    "one of {}",
+                    constructors
+                        .iter()
+                        .map(|constructor| {
+                            let title = constructor_title(constructor);
+                            if constructor.annotated.fields.is_empty() {
+                                format!("\"{title}\"")
+                            } else {
+                                format!("{{ \"{title}\": ... }}")
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                value,
+            )),
+        }
+    }
+
+    fn encode_constructor(
+        &self,
+        value: &Value,
+        constructor: &'a Annotated<Constructor>,
+        path: &str,
+    ) -> Result<PlutusData, Error> {
+        let index = constructor.annotated.index as u64;
+        let fields = &constructor.annotated.fields;
+
+        let encoded = if fields.is_empty() {
+            let title = constructor_title(constructor);
+            if value.as_str() != Some(title.as_str()) {
+                return Err(json_mismatch(path, format!("\"{title}\""), value));
+            }
+            vec![]
+        } else if has_labels(constructor) {
+            let Value::Object(object) = value else {
+                return Err(json_mismatch(path, expected_object(constructor), value));
+            };
+
+            if let Some(unknown) = object.keys().find(|key| {
+                !fields
+                    .iter()
+                    .any(|field| field.title.as_deref() == Some(key.as_str()))
+            }) {
+                return Err(json_mismatch(
+                    &format!("{path}.{unknown}"),
+                    format!("no such field; {}", expected_object(constructor)),
+                    &object[unknown],
+                ));
+            }
+
+            fields
+                .iter()
+                .map(|field| {
+                    let label = field.title.as_deref().unwrap_or_default();
+                    let path = format!("{path}.{label}");
+                    match object.get(label) {
+                        Some(value) => self.encode_declaration(
+                            value,
+                            &field.annotated,
+                            &path,
+                            Self::encode_data,
+                        ),
+                        None => Err(json_mismatch(&path, "a value", &Value::Null)),
+                    }
+                })
+                .collect::<Result<_, _>>()?
+        } else {
+            expect_array(value, fields.len(), path)?
+                .iter()
+                .zip(fields)
+                .enumerate()
+                .map(|(index, (value, field))| {
+                    self.encode_declaration(
+                        value,
+                        &field.annotated,
+                        &format!("{path}[{index}]"),
+                        Self::encode_data,
+                    )
+                })
+                .collect::<Result<_, _>>()?
+        };
+
+        Ok(UplcData::constr(index, encoded))
+    }
+
+    // ---------- Decoding
+
+    fn decode_declaration<T>(
+        &self,
+        data: &PlutusData,
+        declaration: &'a Declaration<T>,
+        path: &str,
+        inline: fn(&Self, &PlutusData, &'a T, &str) -> Result<Value, Error>,
+    ) -> Result<Value, Error> {
+        match declaration {
+            Declaration::Referenced(..) => {
+                self.decode_schema(data, self.resolve(declaration)?, path)
+            }
+            Declaration::Inline(schema) => inline(self, data, schema, path),
+        }
+    }
+
+    fn decode_schema(
+        &self,
+        data: &PlutusData,
+        schema: &'a Schema,
+        path: &str,
+    ) -> Result<Value, Error> {
+        match schema {
+            Schema::Data(inner) => self.decode_data(data, inner, path),
+            Schema::Unit => {
+                expect_fields(data, 0, 0, path)?;
+                Ok(Value::Null)
+            }
+            Schema::Boolean => decode_bool(data, path),
+            Schema::Integer => decode_integer(data, path),
+            Schema::Bytes => decode_bytes(data, path),
+            Schema::String => match data {
+                PlutusData::BoundedBytes(bytes) => String::from_utf8(bytes.to_vec())
+                    .map(Value::String)
+                    .map_err(|_| data_mismatch(path, "UTF-8 encoded bytes", data)),
+                _ => Err(data_mismatch(path, "UTF-8 encoded bytes", data)),
+            },
+            Schema::Pair(left, right) => {
+                let elements = expect_list(data, Some(2), path)?;
+                Ok(Value::Array(vec![
+                    self.decode_declaration(
+                        &elements[0],
+                        left,
+                        &format!("{path}[0]"),
+                        Self::decode_schema,
+                    )?,
+                    self.decode_declaration(
+                        &elements[1],
+                        right,
+                        &format!("{path}[1]"),
+                        Self::decode_schema,
+                    )?,
+                ]))
+            }
+            Schema::List(items) => self.decode_items(data, items, path, Self::decode_schema),
+        }
+    }
+
+    fn decode_data(&self, data: &PlutusData, schema: &'a Data, path: &str) -> Result<Value, Error> {
+        match schema {
+            Data::Integer => decode_integer(data, path),
+            Data::Bytes => decode_bytes(data, path),
+            Data::List(items) => self.decode_items(data, items, path, Self::decode_data),
+            Data::Map(keys, values) => {
+                let PlutusData::Map(entries) = data else {
+                    return Err(data_mismatch(path, "a map", data));
+                };
+
+                entries
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (k, v))| {
+                        let path = format!("{path}[{index}]");
+                        Ok(Value::Array(vec![
+                            self.decode_declaration(
+                                k,
+                                keys,
+                                &format!("{path}[0]"),
+                                Self::decode_data,
+                            )?,
+                            self.decode_declaration(
+                                v,
+                                values,
+                                &format!("{path}[1]"),
+                                Self::decode_data,
+                            )?,
+                        ]))
+                    })
+                    .collect::<Result<_, _>>()
+                    .map(Value::Array)
+            }
+            Data::AnyOf(constructors) => self.decode_constructors(data, constructors, path),
+            Data::Opaque => Ok(Value::String(UplcData::to_hex(data.clone()))),
+        }
+    }
+
+    fn decode_items<T>(
+        &self,
+        data: &PlutusData,
+        items: &'a Items<T>,
+        path: &str,
+        inline: fn(&Self, &PlutusData, &'a T, &str) -> Result<Value, Error>,
+    ) -> Result<Value, Error> {
+        let elements = match items {
+            Items::One(item) => expect_list(data, None, path)?
+                .iter()
+                .enumerate()
+                .map(|(index, element)| {
+                    self.decode_declaration(element, item, &format!("{path}[{index}]"), inline)
+                })
+                .collect::<Result<_, _>>()?,
+            Items::Many(items) => expect_list(data, Some(items.len()), path)?
+                .iter()
+                .zip(items)
+                .enumerate()
+                .map(|(index, (element, item))| {
+                    self.decode_declaration(element, item, &format!("{path}[{index}]"), inline)
+                })
+                .collect::<Result<_, _>>()?,
+        };
+
+        Ok(Value::Array(elements))
+    }
+
+    fn decode_constructors(
+        &self,
+        data: &PlutusData,
+        constructors: &'a [Annotated<Constructor>],
+        path: &str,
+    ) -> Result<Value, Error> {
+        if is_bool(constructors) {
+            return decode_bool(data, path);
+        }
+
+        let index = constr_index(data).ok_or_else(|| data_mismatch(path, "a constructor", data))?;
+
+        let Some(constructor) = constructors
+            .iter()
+            .find(|constructor| constructor.annotated.index as u64 == index)
+        else {
+            return Err(data_mismatch(
+                path,
+                format!("a constructor with index below {}", constructors.len()),
+                data,
+            ));
+        };
+
+        let title = constructor_title(constructor);
+
+        if constructors.len() == 1 || constructor.annotated.fields.is_empty() {
+            return self.decode_constructor(data, constructor, path);
+        }
+
+        let mut object = Map::new();
+        let value = self.decode_constructor(data, constructor, &format!("{path}.{title}"))?;
+        object.insert(title, value);
+        Ok(Value::Object(object))
+    }
+
+    fn decode_constructor(
+        &self,
+        data: &PlutusData,
+        constructor: &'a Annotated<Constructor>,
+        path: &str,
+    ) -> Result<Value, Error> {
+        let fields = &constructor.annotated.fields;
+
+        let elements = expect_fields(data, constructor.annotated.index as u64, fields.len(), path)?;
+
+        if fields.is_empty() {
+            return Ok(Value::String(constructor_title(constructor)));
+        }
+
+        if has_labels(constructor) {
+            return fields
+                .iter()
+                .zip(elements)
+                .map(|(field, element)| {
+                    let label = field.title.clone().unwrap_or_default();
+                    let value = self.decode_declaration(
+                        element,
+                        &field.annotated,
+                        &format!("{path}.{label}"),
+                        Self::decode_data,
+                    )?;
+                    Ok((label, value))
+                })
+                .collect::<Result<_, _>>()
+                .map(Value::Object);
+        }
+
+        fields
+            .iter()
+            .zip(elements)
+            .enumerate()
+            .map(|(index, (field, element))| {
+                self.decode_declaration(
+                    element,
+                    &field.annotated,
+                    &format!("{path}[{index}]"),
+                    Self::decode_data,
+                )
+            })
+            .collect::<Result<_, _>>()
+            .map(Value::Array)
+    }
+}
+
+fn encode_bool(value: &Value, path: &str) -> Result<PlutusData, Error> {
+    match value {
+        Value::Bool(b) => Ok(UplcData::constr(u64::from(*b), vec![])),
+        _ => Err(json_mismatch(path, "a boolean", value)),
+    }
+}
+
+fn encode_integer(value: &Value, path: &str) -> Result<PlutusData, Error> {
+    let n = match value {
+        Value::Number(n) => BigInt::from_str(&n.to_string()).ok(),
+        Value::String(digits) => BigInt::from_str(digits).ok(),
+        _ => None,
+    };
+
+    n.map(|n| PlutusData::BigInt(to_pallas_bigint(&n)))
+        .ok_or_else(|| json_mismatch(path, "an integer", value))
+}
+
+fn encode_bytes(value: &Value, path: &str) -> Result<PlutusData, Error> {
+    match value {
+        Value::String(bytes) => hex::decode(bytes).ok().map(UplcData::bytestring),
+        _ => None,
+    }
+    .ok_or_else(|| json_mismatch(path, "a hex-encoded byte array", value))
+}
+
+fn expect_array<'v>(value: &'v Value, len: usize, path: &str) -> Result<&'v [Value], Error> {
+    match value {
+        Value::Array(elements) if elements.len() == len => Ok(elements),
+        _ => Err(json_mismatch(
+            path,
+            format!("an array of {len} element(s)"),
+            value,
+        )),
+    }
+}
+
+fn decode_bool(data: &PlutusData, path: &str) -> Result<Value, Error> {
+    match constr_index(data) {
+        Some(0) => expect_fields(data, 0, 0, path).map(|_| Value::Bool(false)),
+        Some(1) => expect_fields(data, 1, 0, path).map(|_| Value::Bool(true)),
+        _ => Err(data_mismatch(path, "a boolean", data)),
+    }
+}
+
+fn decode_integer(data: &PlutusData, path: &str) -> Result<Value, Error> {
+    match data {
+        PlutusData::BigInt(n) => {
+            let n = from_pallas_bigint(n);
+            Ok(match i64::try_from(&n) {
+                Ok(n) => Value::from(n),
+                Err(..) => Value::String(n.to_string()),
+            })
+        }
+        _ => Err(data_mismatch(path, "an integer", data)),
+    }
+}
+
+fn decode_bytes(data: &PlutusData, path: &str) -> Result<Value, Error> {
+    match data {
+        PlutusData::BoundedBytes(bytes) => Ok(Value::String(hex::encode(bytes.as_slice()))),
+        _ => Err(data_mismatch(path, "a byte array", data)),
+    }
+}
+
+fn expect_list<'d>(
+    data: &'d PlutusData,
+    len: Option<usize>,
+    path: &str,
+) -> Result<&'d [PlutusData], Error> {
+    match (data, len) {
+        (PlutusData::Array(elements), None) => Ok(elements),
+        (PlutusData::Array(elements), Some(len)) if elements.len() == len => Ok(elements),
+        (_, None) => Err(data_mismatch(path, "a list", data)),
+        (_, Some(len)) => Err(data_mismatch(
+            path,
+            format!("a list of {len} element(s)"),
+            data,
+        )),
+    }
+}
+
+fn expect_fields<'d>(
+    data: &'d PlutusData,
+    index: u64,
+    arity: usize,
+    path: &str,
+) -> Result<&'d [PlutusData], Error> {
+    match data {
+        PlutusData::Constr(constr)
+            if constr_index(data) == Some(index) && constr.fields.len() == arity =>
+        {
+            Ok(&constr.fields)
+        }
+        _ => Err(data_mismatch(
+            path,
+            format!("a constructor with index {index} and {arity} field(s)"),
+            data,
+        )),
+    }
+}
+
+fn constr_index(data: &PlutusData) -> Option<u64> {
+    match data {
+        PlutusData::Constr(constr) if constr.tag == 102 => constr.any_constructor,
+        PlutusData::Constr(constr) => convert_tag_to_constr(constr.tag),
+        _ => None,
+    }
+}
+
+fn json_mismatch(path: &str, expected: impl ToString, found: &Value) -> Error {
+    Error::JsonMismatch {
+        path: path.to_string(),
+        expected: expected.to_string(),
+        found: found.to_string(),
+    }
+}
+
+fn data_mismatch(path: &str, expected: impl ToString, found: &PlutusData) -> Error {
+    Error::DataMismatch {
+        path: path.to_string(),
+        expected: expected.to_string(),
+        found: found.clone(),
+    }
+}
+
+fn is_bool(constructors: &[Annotated<Constructor>]) -> bool {
+    matches!(
+        constructors,
+        [false_, true_] if constructor_title(false_) == "False"
+            && constructor_title(true_) == "True"
+            && false_.annotated.fields.is_empty()
+            && true_.annotated.fields.is_empty()
+    )
+}
+
+fn has_labels(constructor: &Annotated<Constructor>) -> bool {
+    constructor
+        .annotated
+        .fields
+        .iter()
+        .all(|field| field.title.is_some())
+}
+
+fn expected_object(constructor: &Annotated<Constructor>) -> String {
+    format!(
+        "an object with fields {}",
+        constructor
+            .annotated
+            .fields
+            .iter()
+            .filter_map(|field| field.title.as_deref())
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn constructor_title(constructor: &Annotated<Constructor>) -> String {
+    constructor
+        .title
+        .clone()
+        .unwrap_or_else(|| format!("Constr{}", constructor.annotated.index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{definitions::Reference, Blueprint},
+        *,
+    };
+    use crate::{config::Config, module::CheckedModules, tests::TestProject};
+    use aiken_lang::ast::{TraceLevel, Tracing};
+    use serde_json::json;
+
+    const SOURCE_CODE: &str = indoc::indoc! {r#"
+        pub type Action {
+          Deposit
+          Withdraw { amount: Int }
+          Swap(ByteArray, Option<Int>)
+        }
+
+        pub type Datum {
+          owner: ByteArray,
+          amounts: Pairs<ByteArray, Int>,
+          locked: Bool,
+          limits: (Int, ByteArray),
+          extra: Data,
+        }
+
+        validator vault {
+          spend(datum: Option<Datum>, redeemer: Action, output_reference: Data, transaction: Data) {
+            True
+          }
+        }
+    "#};
+
+    fn blueprint() -> Blueprint {
+        let mut project = TestProject::new();
+
+        let modules = CheckedModules::singleton(project.check(project.parse(SOURCE_CODE)));
+
+        let mut generator = project.new_generator(Tracing::All(TraceLevel::Verbose));
+
+        Blueprint::new(&Config::default(&project.package), &modules, &mut generator).unwrap()
+    }
+
+    fn round_trip(key: &str, value: Value, cbor: &str) {
+        let blueprint = blueprint();
+
+        let schema = Declaration::Referenced(Reference::new(key));
+
+        let data = encode(&value, &schema, &blueprint.definitions).unwrap();
+
+        assert_eq!(UplcData::to_hex(data.clone()), cbor);
+
+        assert_eq!(
+            decode(&data, &schema, &blueprint.definitions).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn round_trip_record() {
+        round_trip(
+            "test_module/Datum",
+            json!({
+                "owner": "abcd",
+                "amounts": [["01", 5], ["", "-123456789012345678901234567890"]],
+                "locked": true,
+                "limits": [7, "09"],
+                "extra": "182a",
+            }),
+            "d8799f42abcda241010540c34d018ee90ff6c373e0ee4e3f0ad1d87a809f074109ff182aff",
+        );
+    }
+
+    #[test]
+    fn round_trip_constructors() {
+        round_trip("test_module/Action", json!("Deposit"), "d87980");
+
+        round_trip(
+            "test_module/Action",
+            json!({ "Withdraw": { "amount": 42 } }),
+            "d87a9f182aff",
+        );
+
+        round_trip(
+            "test_module/Action",
+            json!({ "Swap": ["ff", { "Some": [1] }] }),
+            "d87b9f41ffd8799f01ffff",
+        );
+    }
+
+    #[test]
+    fn encode_mismatch() {
+        let blueprint = blueprint();
+
+        let schema = Declaration::Referenced(Reference::new("test_module/Action"));
+
+        let result = encode(
+            &json!({ "Withdraw": { "amount": "forty-two" } }),
+            &schema,
+            &blueprint.definitions,
+        );
+
+        assert!(
+            matches!(result, Err(Error::JsonMismatch { ref path, .. }) if path == "$.Withdraw.amount"),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn decode_mismatch() {
+        let blueprint = blueprint();
+
+        let schema = Declaration::Referenced(Reference::new("test_module/Action"));
+
+        let result = decode(
+            &UplcData::constr(1, vec![UplcData::bytestring(vec![])]),
+            &schema,
+            &blueprint.definitions,
+        );
+
+        assert!(
+            matches!(result, Err(Error::DataMismatch { ref path, .. }) if path == "$.Withdraw.amount"),
+            "{result:?}"
+        );
+    }
+}
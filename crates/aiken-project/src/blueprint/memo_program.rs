@@ -1,13 +1,25 @@
-use aiken_lang::{ast::TypedValidator, gen_uplc::CodeGenerator};
+use aiken_lang::{
+    ast::TypedValidator,
+    gen_uplc::{source_map::SourceMap, CodeGenerator},
+};
 use uplc::ast::{DeBruijn, Program};
 
 pub struct MemoProgram {
     program: Option<Program<DeBruijn>>,
+    source_map: SourceMap,
 }
 
 impl MemoProgram {
     pub fn new() -> Self {
-        Self { program: None }
+        Self {
+            program: None,
+            source_map: SourceMap::new(),
+        }
+    }
+
+    /// Source map of the memoized program, if any was generated.
+    pub fn source_map(&self) -> &SourceMap {
+        &self.source_map
     }
 
     pub fn get(
@@ -20,6 +32,8 @@ impl MemoProgram {
             None => {
                 let new_program = generator.generate(def, module_name).to_debruijn().unwrap();
 
+                self.source_map = generator.source_map().clone();
+
                 self.program.replace(new_program.clone());
 
                 new_program
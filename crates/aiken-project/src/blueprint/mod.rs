@@ -1,8 +1,11 @@
 pub mod definitions;
 pub mod error;
+pub mod json;
 mod memo_program;
 pub mod parameter;
+pub mod rust;
 pub mod schema;
+pub mod typescript;
 pub mod validator;
 
 use crate::{
@@ -13,7 +16,7 @@ use aiken_lang::gen_uplc::CodeGenerator;
 use definitions::Definitions;
 pub use error::Error;
 use schema::{Annotated, Schema};
-use std::fmt::Debug;
+use std::fmt::{self, Debug, Display};
 use validator::Validator;
 
 #[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
@@ -50,6 +53,28 @@ pub struct Compiler {
     pub version: String,
 }
 
+/// Designates a schema within a blueprint.
+#[derive(Debug, PartialEq, Clone)]
+pub enum SchemaTarget {
+    /// A definition, by key; e.g. 'aiken/interval/Interval$Int'.
+    Definition(String),
+    Datum,
+    Redeemer,
+    /// A validator's parameter, by position.
+    Parameter(usize),
+}
+
+impl Display for SchemaTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemaTarget::Definition(key) => write!(f, "definition '{key}'"),
+            SchemaTarget::Datum => f.write_str("the datum"),
+            SchemaTarget::Redeemer => f.write_str("the redeemer"),
+            SchemaTarget::Parameter(index) => write!(f, "parameter #{index}"),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum LookupResult<'a, T> {
     One(String, &'a T),
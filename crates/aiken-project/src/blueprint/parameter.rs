@@ -32,28 +32,38 @@ impl Parameter {
         definitions: &Definitions<Annotated<Schema>>,
         constant: &Constant,
     ) -> Result<(), Error> {
-        let schema = match &self.schema {
-            Declaration::Inline(schema) => schema,
-            Declaration::Referenced(ref link) => {
-                &definitions
-                    .lookup(link)
-                    .map(Ok)
-                    .unwrap_or_else(|| {
-                        Err(Error::UnresolvedSchemaReference {
-                            reference: link.clone(),
-                        })
-                    })?
-                    .annotated
-            }
-        };
+        validate(&self.schema, definitions, constant)
+    }
+}
 
-        validate_schema(schema, definitions, constant)
+/// Check that a term matches the given schema declaration. Mismatches are reported along with
+/// the path leading to the offending part of the term, in the same notation as the JSON codec
+/// (e.g. `$.owner[1]`).
+pub fn validate(
+    schema: &Declaration<Schema>,
+    definitions: &Definitions<Annotated<Schema>>,
+    term: &Constant,
+) -> Result<(), Error> {
+    validate_schema(
+        schema
+            .schema(definitions)
+            .ok_or_else(|| unresolved(schema))?,
+        definitions,
+        term,
+        "$",
+    )
+}
+
+fn unresolved<T>(declaration: &Declaration<T>) -> Error {
+    Error::UnresolvedSchemaReference {
+        reference: declaration.reference().unwrap().clone(),
     }
 }
 
-fn mismatch(term: &Constant, schema: Schema) -> Error {
+fn mismatch(term: &Constant, schema: Schema, path: &str) -> Error {
     Error::SchemaMismatch {
-        schema,
+        path: path.to_string(),
+        schema: Box::new(schema),
         term: term.clone(),
     }
 }
@@ -62,79 +72,63 @@ fn validate_schema(
     schema: &Schema,
     definitions: &Definitions<Annotated<Schema>>,
     term: &Constant,
+    path: &str,
 ) -> Result<(), Error> {
     match schema {
-        Schema::Data(data) => validate_data(data, definitions, term),
+        Schema::Data(data) => validate_data(data, definitions, term, path),
 
-        Schema::Unit => expect_unit(term),
+        Schema::Unit => expect_unit(term, path),
 
-        Schema::Integer => expect_integer(term),
+        Schema::Integer => expect_integer(term, path),
 
-        Schema::Bytes => expect_bytes(term),
+        Schema::Bytes => expect_bytes(term, path),
 
-        Schema::String => expect_string(term),
+        Schema::String => expect_string(term, path),
 
-        Schema::Boolean => expect_boolean(term),
+        Schema::Boolean => expect_boolean(term, path),
 
         Schema::Pair(left, right) => {
-            let (term_left, term_right) = expect_pair(term)?;
-
-            let left =
-                left.schema(definitions)
-                    .ok_or_else(|| Error::UnresolvedSchemaReference {
-                        reference: left.reference().unwrap().clone(),
-                    })?;
-            validate_schema(left, definitions, &term_left)?;
-
-            let right =
-                right
-                    .schema(definitions)
-                    .ok_or_else(|| Error::UnresolvedSchemaReference {
-                        reference: right.reference().unwrap().clone(),
-                    })?;
-            validate_schema(right, definitions, &term_right)?;
+            let (term_left, term_right) = expect_pair(term, path)?;
+
+            let left = left.schema(definitions).ok_or_else(|| unresolved(left))?;
+            validate_schema(left, definitions, &term_left, &format!("{path}[0]"))?;
+
+            let right = right.schema(definitions).ok_or_else(|| unresolved(right))?;
+            validate_schema(right, definitions, &term_right, &format!("{path}[1]"))?;
 
             Ok(())
         }
 
         Schema::List(Items::One(item)) => {
-            let terms = expect_list(term)?;
+            let terms = expect_list(term, path)?;
 
-            let item =
-                item.schema(definitions)
-                    .ok_or_else(|| Error::UnresolvedSchemaReference {
-                        reference: item.reference().unwrap().clone(),
-                    })?;
+            let item = item.schema(definitions).ok_or_else(|| unresolved(item))?;
 
-            for ref term in terms {
-                validate_schema(item, definitions, term)?;
+            for (index, ref term) in terms.into_iter().enumerate() {
+                validate_schema(item, definitions, term, &format!("{path}[{index}]"))?;
             }
 
             Ok(())
         }
 
         Schema::List(Items::Many(items)) => {
-            let terms = expect_list(term)?;
+            let terms = expect_list(term, path)?;
 
             let items = items
                 .iter()
-                .map(|item| {
-                    item.schema(definitions)
-                        .ok_or_else(|| Error::UnresolvedSchemaReference {
-                            reference: item.reference().unwrap().clone(),
-                        })
-                })
+                .map(|item| item.schema(definitions).ok_or_else(|| unresolved(item)))
                 .collect::<Result<Vec<_>, _>>()?;
 
             if terms.len() != items.len() {
                 return Err(Error::TupleItemsMismatch {
+                    path: path.to_string(),
                     expected: items.len(),
                     found: terms.len(),
                 });
             }
 
-            for (item, ref term) in iter::zip(items, terms) {
-                validate_schema(item, definitions, term)?;
+            for (index, (item, ref term)) in iter::zip(items, terms).enumerate() {
+                validate_schema(item, definitions, term, &format!("{path}[{index}]"))?;
             }
 
             Ok(())
@@ -146,109 +140,93 @@ fn validate_data(
     data: &Data,
     definitions: &Definitions<Annotated<Schema>>,
     term: &Constant,
+    path: &str,
 ) -> Result<(), Error> {
     match data {
-        Data::Opaque => expect_data(term),
+        Data::Opaque => expect_data(term, path),
 
-        Data::Integer => expect_data_integer(term),
+        Data::Integer => expect_data_integer(term, path),
 
-        Data::Bytes => expect_data_bytes(term),
+        Data::Bytes => expect_data_bytes(term, path),
 
         Data::List(Items::One(item)) => {
-            let terms = expect_data_list(term)?;
+            let terms = expect_data_list(term, path)?;
 
-            let item =
-                item.schema(definitions)
-                    .ok_or_else(|| Error::UnresolvedSchemaReference {
-                        reference: item.reference().unwrap().clone(),
-                    })?;
+            let item = item.schema(definitions).ok_or_else(|| unresolved(item))?;
 
-            for ref term in terms {
-                validate_data(item, definitions, term)?;
+            for (index, ref term) in terms.into_iter().enumerate() {
+                validate_data(item, definitions, term, &format!("{path}[{index}]"))?;
             }
 
             Ok(())
         }
 
         Data::List(Items::Many(items)) => {
-            let terms = expect_data_list(term)?;
+            let terms = expect_data_list(term, path)?;
 
             let items = items
                 .iter()
-                .map(|item| {
-                    item.schema(definitions)
-                        .ok_or_else(|| Error::UnresolvedSchemaReference {
-                            reference: item.reference().unwrap().clone(),
-                        })
-                })
+                .map(|item| item.schema(definitions).ok_or_else(|| unresolved(item)))
                 .collect::<Result<Vec<_>, _>>()?;
 
             if terms.len() != items.len() {
                 return Err(Error::TupleItemsMismatch {
+                    path: path.to_string(),
                     expected: items.len(),
                     found: terms.len(),
                 });
             }
 
-            for (item, ref term) in iter::zip(items, terms) {
-                validate_data(item, definitions, term)?;
+            for (index, (item, ref term)) in iter::zip(items, terms).enumerate() {
+                validate_data(item, definitions, term, &format!("{path}[{index}]"))?;
             }
 
             Ok(())
         }
 
         Data::Map(keys, values) => {
-            let terms = expect_data_map(term)?;
-
-            let keys =
-                keys.schema(definitions)
-                    .ok_or_else(|| Error::UnresolvedSchemaReference {
-                        reference: keys.reference().unwrap().clone(),
-                    })?;
-
-            let values =
-                values
-                    .schema(definitions)
-                    .ok_or_else(|| Error::UnresolvedSchemaReference {
-                        reference: values.reference().unwrap().clone(),
-                    })?;
-
-            for (ref k, ref v) in terms {
-                validate_data(keys, definitions, k)?;
-                validate_data(values, definitions, v)?;
+            let terms = expect_data_map(term, path)?;
+
+            let keys = keys.schema(definitions).ok_or_else(|| unresolved(keys))?;
+
+            let values = values
+                .schema(definitions)
+                .ok_or_else(|| unresolved(values))?;
+
+            for (index, (ref k, ref v)) in terms.into_iter().enumerate() {
+                validate_data(keys, definitions, k, &format!("{path}[{index}][0]"))?;
+                validate_data(values, definitions, v, &format!("{path}[{index}][1]"))?;
             }
 
             Ok(())
         }
 
         Data::AnyOf(constructors) => {
-            let constructors: Vec<(usize, Vec<&Data>)> = constructors
-                .iter()
-                .map(|constructor| {
-                    constructor
-                        .annotated
-                        .fields
-                        .iter()
-                        .map(|field| {
-                            field.annotated.schema(definitions).ok_or_else(|| {
-                                Error::UnresolvedSchemaReference {
-                                    reference: field.annotated.reference().unwrap().clone(),
-                                }
-                            })
-                        })
-                        .collect::<Result<_, _>>()
-                        .map(|fields| (constructor.annotated.index, fields))
-                })
-                .collect::<Result<_, _>>()?;
+            for constructor in constructors.iter() {
+                let index = constructor.annotated.index;
+
+                if let Some(fields) = expect_data_constr(term, index) {
+                    let fields_schema = &constructor.annotated.fields;
 
-            for (index, fields_schema) in constructors.iter() {
-                if let Ok(fields) = expect_data_constr(term, *index) {
                     if fields_schema.len() != fields.len() {
-                        panic!("fields length different");
+                        break;
                     }
 
-                    for (instance, schema) in iter::zip(fields, fields_schema) {
-                        validate_data(schema, definitions, &instance)?;
+                    let path = match constructor.title {
+                        Some(ref title) if constructors.len() > 1 => format!("{path}.{title}"),
+                        _ => path.to_string(),
+                    };
+
+                    for (ix, (instance, field)) in iter::zip(fields, fields_schema).enumerate() {
+                        let schema = field
+                            .annotated
+                            .schema(definitions)
+                            .ok_or_else(|| unresolved(&field.annotated))?;
+                        let path = match field.title {
+                            Some(ref label) => format!("{path}.{label}"),
+                            None => format!("{path}[{ix}]"),
+                        };
+                        validate_data(schema, definitions, &instance, &path)?;
                     }
 
                     return Ok(());
@@ -260,52 +238,59 @@ fn validate_data(
                 Schema::Data(Data::AnyOf(
                     constructors
                         .iter()
-                        .map(|(index, fields)| {
-                            Constructor {
-                                index: *index,
-                                fields: fields
+                        .map(|constructor| Annotated {
+                            title: constructor.title.clone(),
+                            description: None,
+                            annotated: Constructor {
+                                index: constructor.annotated.index,
+                                fields: constructor
+                                    .annotated
+                                    .fields
                                     .iter()
-                                    .map(|_| Declaration::Inline(Box::new(Data::Opaque)).into())
+                                    .map(|field| Annotated {
+                                        title: field.title.clone(),
+                                        description: None,
+                                        annotated: Declaration::Inline(Box::new(Data::Opaque)),
+                                    })
                                     .collect(),
-                            }
-                            .into()
+                            },
                         })
                         .collect(),
                 )),
+                path,
             ))
         }
     }
 }
-
-fn expect_data(term: &Constant) -> Result<(), Error> {
+fn expect_data(term: &Constant, path: &str) -> Result<(), Error> {
     if matches!(term, Constant::Data(..)) {
         return Ok(());
     }
 
-    Err(mismatch(term, Schema::Data(Data::Opaque)))
+    Err(mismatch(term, Schema::Data(Data::Opaque), path))
 }
 
-fn expect_data_integer(term: &Constant) -> Result<(), Error> {
+fn expect_data_integer(term: &Constant, path: &str) -> Result<(), Error> {
     if let Constant::Data(data) = term {
         if matches!(data, PlutusData::BigInt(..)) {
             return Ok(());
         }
     }
 
-    Err(mismatch(term, Schema::Data(Data::Integer)))
+    Err(mismatch(term, Schema::Data(Data::Integer), path))
 }
 
-fn expect_data_bytes(term: &Constant) -> Result<(), Error> {
+fn expect_data_bytes(term: &Constant, path: &str) -> Result<(), Error> {
     if let Constant::Data(data) = term {
         if matches!(data, PlutusData::BoundedBytes(..)) {
             return Ok(());
         }
     }
 
-    Err(mismatch(term, Schema::Data(Data::Bytes)))
+    Err(mismatch(term, Schema::Data(Data::Bytes), path))
 }
 
-fn expect_data_list(term: &Constant) -> Result<Vec<Constant>, Error> {
+fn expect_data_list(term: &Constant, path: &str) -> Result<Vec<Constant>, Error> {
     if let Constant::Data(PlutusData::Array(elems)) = term {
         return Ok(elems
             .iter()
@@ -318,10 +303,11 @@ fn expect_data_list(term: &Constant) -> Result<Vec<Constant>, Error> {
         Schema::Data(Data::List(Items::One(Declaration::Inline(Box::new(
             Data::Opaque,
         ))))),
+        path,
     ))
 }
 
-fn expect_data_map(term: &Constant) -> Result<Vec<(Constant, Constant)>, Error> {
+fn expect_data_map(term: &Constant, path: &str) -> Result<Vec<(Constant, Constant)>, Error> {
     if let Constant::Data(PlutusData::Map(pairs)) = term {
         return Ok(pairs
             .iter()
@@ -335,73 +321,69 @@ fn expect_data_map(term: &Constant) -> Result<Vec<(Constant, Constant)>, Error>
             Declaration::Inline(Box::new(Data::Opaque)),
             Declaration::Inline(Box::new(Data::Opaque)),
         )),
+        path,
     ))
 }
 
-fn expect_data_constr(term: &Constant, index: usize) -> Result<Vec<Constant>, Error> {
+fn expect_data_constr(term: &Constant, index: usize) -> Option<Vec<Constant>> {
     if let Constant::Data(PlutusData::Constr(constr)) = term {
         if let PlutusData::Constr(expected) = UplcData::constr(index as u64, vec![]) {
             if expected.tag == constr.tag && expected.any_constructor == constr.any_constructor {
-                return Ok(constr
-                    .fields
-                    .iter()
-                    .map(|field| Constant::Data(field.to_owned()))
-                    .collect());
+                return Some(
+                    constr
+                        .fields
+                        .iter()
+                        .map(|field| Constant::Data(field.to_owned()))
+                        .collect(),
+                );
             }
         }
     }
 
-    Err(mismatch(
-        term,
-        Schema::Data(Data::AnyOf(vec![Constructor {
-            index,
-            fields: vec![],
-        }
-        .into()])),
-    ))
+    None
 }
 
-fn expect_unit(term: &Constant) -> Result<(), Error> {
+fn expect_unit(term: &Constant, path: &str) -> Result<(), Error> {
     if matches!(term, Constant::Unit) {
         return Ok(());
     }
 
-    Err(mismatch(term, Schema::Unit))
+    Err(mismatch(term, Schema::Unit, path))
 }
 
-fn expect_integer(term: &Constant) -> Result<(), Error> {
+fn expect_integer(term: &Constant, path: &str) -> Result<(), Error> {
     if matches!(term, Constant::Integer(..)) {
         return Ok(());
     }
 
-    Err(mismatch(term, Schema::Integer))
+    Err(mismatch(term, Schema::Integer, path))
 }
 
-fn expect_bytes(term: &Constant) -> Result<(), Error> {
+fn expect_bytes(term: &Constant, path: &str) -> Result<(), Error> {
     if matches!(term, Constant::ByteString(..)) {
         return Ok(());
     }
 
-    Err(mismatch(term, Schema::Bytes))
+    Err(mismatch(term, Schema::Bytes, path))
 }
 
-fn expect_string(term: &Constant) -> Result<(), Error> {
+fn expect_string(term: &Constant, path: &str) -> Result<(), Error> {
     if matches!(term, Constant::String(..)) {
         return Ok(());
     }
 
-    Err(mismatch(term, Schema::String))
+    Err(mismatch(term, Schema::String, path))
 }
 
-fn expect_boolean(term: &Constant) -> Result<(), Error> {
+fn expect_boolean(term: &Constant, path: &str) -> Result<(), Error> {
     if matches!(term, Constant::Bool(..)) {
         return Ok(());
     }
 
-    Err(mismatch(term, Schema::Boolean))
+    Err(mismatch(term, Schema::Boolean, path))
 }
 
-fn expect_pair(term: &Constant) -> Result<(Constant, Constant), Error> {
+fn expect_pair(term: &Constant, path: &str) -> Result<(Constant, Constant), Error> {
     if let Constant::ProtoPair(_, _, left, right) = term {
         return Ok((left.deref().clone(), right.deref().clone()));
     }
@@ -412,10 +394,11 @@ fn expect_pair(term: &Constant) -> Result<(Constant, Constant), Error> {
             Declaration::Inline(Box::new(Schema::Data(Data::Opaque))),
             Declaration::Inline(Box::new(Schema::Data(Data::Opaque))),
         ),
+        path,
     ))
 }
 
-fn expect_list(term: &Constant) -> Result<Vec<Constant>, Error> {
+fn expect_list(term: &Constant, path: &str) -> Result<Vec<Constant>, Error> {
     if let Constant::ProtoList(_, elems) = term {
         return Ok(elems.to_owned());
     }
@@ -425,5 +408,6 @@ fn expect_list(term: &Constant) -> Result<Vec<Constant>, Error> {
         Schema::List(Items::One(Declaration::Inline(Box::new(Schema::Data(
             Data::Opaque,
         ))))),
+        path,
     ))
 }
@@ -0,0 +1,1077 @@
+use super::{
+    definitions::Reference,
+    schema::{Annotated, Constructor, Data, Declaration, Items, Schema},
+    validator::Validator,
+    Blueprint,
+};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+use uplc::ast::SerializableProgram;
+
+/// Crates the generated code depends on, along with compatible versions. Generated types are
+/// made of `uplc` values, hence the very version of `uplc` this Aiken is built with (Aiken's crates
+/// are all versioned together).
+pub const DEPENDENCIES: [(&str, &str); 3] = [
+    ("uplc", concat!("=", env!("CARGO_PKG_VERSION"))),
+    ("pallas-addresses", "0.31"),
+    ("num-bigint", "0.4"),
+];
+
+/// Conversions shared by every generated type, for primitives and containers.
+const PRELUDE: &str = r#"use uplc::PlutusData;
+
+/// Conversion of off-chain values to and from Plutus data.
+pub trait AsData: Sized {
+    fn to_data(&self) -> PlutusData;
+
+    fn from_data(data: &PlutusData) -> Result<Self, DecodeError>;
+}
+
+/// Plutus data that doesn't have the expected shape.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeError {
+    pub expected: String,
+    pub found: PlutusData,
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expected {} but got {:?}", self.expected, self.found)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+fn mismatch<T>(expected: impl Into<String>, data: &PlutusData) -> Result<T, DecodeError> {
+    Err(DecodeError {
+        expected: expected.into(),
+        found: data.clone(),
+    })
+}
+
+/// The index of a constructor, recovered from its CBOR tag.
+pub fn constr_index(data: &PlutusData) -> Result<u64, DecodeError> {
+    match data {
+        PlutusData::Constr(constr) => match (constr.tag, constr.any_constructor) {
+            (121..=127, _) => Ok(constr.tag - 121),
+            (1280..=1400, _) => Ok(constr.tag - 1280 + 7),
+            (102, Some(index)) => Ok(index),
+            _ => mismatch("a constructor", data),
+        },
+        _ => mismatch("a constructor", data),
+    }
+}
+
+/// The fields of a constructor, provided it has the given index and arity.
+pub fn constr_fields(
+    data: &PlutusData,
+    index: u64,
+    arity: usize,
+) -> Result<&[PlutusData], DecodeError> {
+    match data {
+        PlutusData::Constr(constr)
+            if constr_index(data).ok() == Some(index) && constr.fields.len() == arity =>
+        {
+            Ok(&constr.fields[..])
+        }
+        _ => mismatch(format!("constructor {index} with {arity} field(s)"), data),
+    }
+}
+
+/// Key-value pairs, encoded as a Plutus map.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pairs<K, V>(pub Vec<(K, V)>);
+
+impl AsData for PlutusData {
+    fn to_data(&self) -> PlutusData {
+        self.clone()
+    }
+
+    fn from_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        Ok(data.clone())
+    }
+}
+
+impl AsData for () {
+    fn to_data(&self) -> PlutusData {
+        uplc::ast::Data::constr(0, vec![])
+    }
+
+    fn from_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        constr_fields(data, 0, 0).map(|_| ())
+    }
+}
+
+impl AsData for bool {
+    fn to_data(&self) -> PlutusData {
+        uplc::ast::Data::constr(u64::from(*self), vec![])
+    }
+
+    fn from_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        match constr_index(data)? {
+            0 => constr_fields(data, 0, 0).map(|_| false),
+            1 => constr_fields(data, 1, 0).map(|_| true),
+            _ => mismatch("a boolean", data),
+        }
+    }
+}
+
+impl AsData for num_bigint::BigInt {
+    fn to_data(&self) -> PlutusData {
+        PlutusData::BigInt(uplc::machine::value::to_pallas_bigint(self))
+    }
+
+    fn from_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        match data {
+            PlutusData::BigInt(n) => Ok(uplc::machine::value::from_pallas_bigint(n)),
+            _ => mismatch("an integer", data),
+        }
+    }
+}
+
+impl AsData for Vec<u8> {
+    fn to_data(&self) -> PlutusData {
+        uplc::ast::Data::bytestring(self.clone())
+    }
+
+    fn from_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        match data {
+            PlutusData::BoundedBytes(bytes) => Ok(bytes.to_vec()),
+            _ => mismatch("bytes", data),
+        }
+    }
+}
+
+impl AsData for String {
+    fn to_data(&self) -> PlutusData {
+        uplc::ast::Data::bytestring(self.as_bytes().to_vec())
+    }
+
+    fn from_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        String::from_utf8(Vec::from_data(data)?).or_else(|_| mismatch("UTF-8 bytes", data))
+    }
+}
+
+impl<T: AsData> AsData for Box<T> {
+    fn to_data(&self) -> PlutusData {
+        self.as_ref().to_data()
+    }
+
+    fn from_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        T::from_data(data).map(Box::new)
+    }
+}
+
+impl<T: AsData> AsData for Vec<T> {
+    fn to_data(&self) -> PlutusData {
+        uplc::ast::Data::list(self.iter().map(AsData::to_data).collect())
+    }
+
+    fn from_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        match data {
+            PlutusData::Array(items) => items.iter().map(T::from_data).collect(),
+            _ => mismatch("a list", data),
+        }
+    }
+}
+
+impl<K: AsData, V: AsData> AsData for Pairs<K, V> {
+    fn to_data(&self) -> PlutusData {
+        uplc::ast::Data::map(
+            self.0
+                .iter()
+                .map(|(k, v)| (k.to_data(), v.to_data()))
+                .collect(),
+        )
+    }
+
+    fn from_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        match data {
+            PlutusData::Map(pairs) => pairs
+                .iter()
+                .map(|(k, v)| Ok((K::from_data(k)?, V::from_data(v)?)))
+                .collect::<Result<_, _>>()
+                .map(Pairs),
+            _ => mismatch("a map", data),
+        }
+    }
+}
+"#;
+
+/// Generate Rust types implementing conversions to and from Plutus data for every definition of
+/// a blueprint, along with a constructor for each validator applying its parameters and
+/// computing its hash and address.
+///
+/// The generated code relies on the `uplc`, `num-bigint` and `pallas-addresses` crates.
+pub fn generate(blueprint: &Blueprint) -> String {
+    let generator = Generator::new(blueprint);
+
+    let mut definitions = String::new();
+
+    for (key, schema) in blueprint.definitions.iter() {
+        definitions.push('\n');
+        generator.definition(&mut definitions, key, schema);
+    }
+
+    let validators = blueprint
+        .validators
+        .iter()
+        .map(|validator| {
+            let mut out = String::new();
+            generator.validator(&mut out, validator);
+            out
+        })
+        .collect::<Vec<_>>();
+
+    let mut out = format!(
+        "// Generated by Aiken from the blueprint of {} v{}. Do not edit by hand.\n//\n// Requires the following dependencies:\n//\n",
+        blueprint.preamble.title, blueprint.preamble.version,
+    );
+
+    for (name, version) in DEPENDENCIES {
+        writeln!(out, "//   {name} = \"{version}\"").unwrap();
+    }
+
+    out.push('\n');
+    out.push_str(PRELUDE);
+
+    for arity in generator.tuples.borrow().iter() {
+        out.push('\n');
+        tuple(&mut out, *arity);
+    }
+
+    out.push_str(&definitions);
+
+    if !validators.is_empty() {
+        let prelude = if blueprint
+            .validators
+            .iter()
+            .any(|validator| !validator.parameters.is_empty())
+        {
+            "    use super::*;\n\n"
+        } else {
+            ""
+        };
+
+        writeln!(
+            out,
+            "\npub mod validators {{\n{prelude}{}\n}}",
+            indent(validators.join("\n").trim_end(), 1)
+        )
+        .unwrap();
+    }
+
+    out
+}
+
+struct Generator {
+    names: BTreeMap<String, String>,
+    /// Definitions directly contained in each definition, i.e. not behind a list or a map.
+    contains: BTreeMap<String, BTreeSet<String>>,
+    /// Arities of tuples in use, which need a conversion of their own.
+    tuples: RefCell<BTreeSet<usize>>,
+}
+
+impl Generator {
+    fn new(blueprint: &Blueprint) -> Self {
+        let contains = blueprint
+            .definitions
+            .iter()
+            .map(|(key, schema)| {
+                let mut refs = BTreeSet::new();
+                direct_references(&schema.annotated, &mut refs);
+                (key.to_string(), refs)
+            })
+            .collect();
+
+        Generator {
+            names: blueprint.definitions.identifiers(),
+            contains,
+            tuples: RefCell::new(BTreeSet::new()),
+        }
+    }
+
+    fn definition(&self, out: &mut String, key: &str, schema: &Annotated<Schema>) {
+        let name = &self.names[key];
+
+        doc(out, schema.description.as_deref());
+
+        match &schema.annotated {
+            Schema::Data(Data::AnyOf(constructors)) if is_bool(constructors) => {
+                writeln!(out, "pub type {name} = bool;").unwrap();
+            }
+
+            Schema::Data(Data::AnyOf(constructors)) => {
+                if let [constructor] = &constructors[..] {
+                    self.record(out, key, name, constructor);
+                } else {
+                    self.variants(out, key, name, constructors);
+                }
+            }
+
+            schema => {
+                writeln!(out, "pub type {name} = {};", self.schema_type(schema)).unwrap();
+            }
+        }
+    }
+
+    fn record(
+        &self,
+        out: &mut String,
+        key: &str,
+        name: &str,
+        constructor: &Annotated<Constructor>,
+    ) {
+        let fields = self.fields(key, constructor);
+        let index = constructor.annotated.index;
+        let arity = fields.len();
+
+        let (declaration, pattern, value) = match shape(constructor) {
+            Shape::Unit => (";".to_string(), String::new(), String::new()),
+            Shape::Tuple => (
+                format!(
+                    "({});",
+                    fields
+                        .iter()
+                        .map(|(_, tipo)| format!("pub {tipo}"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                format!(
+                    "({})",
+                    fields
+                        .iter()
+                        .map(|(field, _)| field.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                decode_tuple(arity),
+            ),
+            Shape::Named => (
+                format!(
+                    " {{\n{}\n}}",
+                    fields
+                        .iter()
+                        .map(|(field, tipo)| format!("    pub {field}: {tipo},"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                ),
+                format!(
+                    " {{ {} }}",
+                    fields
+                        .iter()
+                        .map(|(field, _)| field.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                decode_named(&fields),
+            ),
+        };
+
+        writeln!(
+            out,
+            "#[derive(Debug, Clone, PartialEq)]\npub struct {name}{declaration}\n"
+        )
+        .unwrap();
+
+        let bindings = if arity == 0 {
+            format!("constr_fields(data, {index}, 0)?;")
+        } else {
+            format!("let fields = constr_fields(data, {index}, {arity})?;")
+        };
+
+        let encode = if arity == 0 {
+            encode_fields(index, &fields)
+        } else {
+            format!(
+                "let Self{pattern} = self;\n{}",
+                encode_fields(index, &fields)
+            )
+        };
+
+        self.implementation(out, name, &encode, &format!("{bindings}\nOk(Self{value})"));
+    }
+
+    fn variants(
+        &self,
+        out: &mut String,
+        key: &str,
+        name: &str,
+        constructors: &[Annotated<Constructor>],
+    ) {
+        let mut declarations = vec![];
+        let mut encoders = vec![];
+        let mut decoders = vec![];
+
+        for constructor in constructors {
+            let title = constructor_title(constructor);
+            let fields = self.fields(key, constructor);
+            let index = constructor.annotated.index;
+            let arity = fields.len();
+
+            let (declaration, pattern, value) = match shape(constructor) {
+                Shape::Unit => (String::new(), String::new(), String::new()),
+                Shape::Tuple => (
+                    format!(
+                        "({})",
+                        fields
+                            .iter()
+                            .map(|(_, tipo)| tipo.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    format!(
+                        "({})",
+                        fields
+                            .iter()
+                            .map(|(field, _)| field.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    decode_tuple(arity),
+                ),
+                Shape::Named => (
+                    format!(
+                        " {{ {} }}",
+                        fields
+                            .iter()
+                            .map(|(field, tipo)| format!("{field}: {tipo}"))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    format!(
+                        " {{ {} }}",
+                        fields
+                            .iter()
+                            .map(|(field, _)| field.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    decode_named(&fields),
+                ),
+            };
+
+            declarations.push(format!("{title}{declaration},"));
+
+            encoders.push(format!(
+                "Self::{title}{pattern} => {},",
+                encode_fields(index, &fields)
+            ));
+
+            decoders.push(if arity == 0 {
+                format!("{index} => constr_fields(data, {index}, 0).map(|_| Self::{title}),")
+            } else {
+                format!(
+                    "{index} => {{\n    let fields = constr_fields(data, {index}, {arity})?;\n    Ok(Self::{title}{value})\n}}"
+                )
+            });
+        }
+
+        writeln!(
+            out,
+            "#[derive(Debug, Clone, PartialEq)]\npub enum {name} {{\n{}\n}}\n",
+            indent(&declarations.join("\n"), 1)
+        )
+        .unwrap();
+
+        decoders.push(format!("_ => mismatch(\"a constructor of {name}\", data),"));
+
+        self.implementation(
+            out,
+            name,
+            &format!("match self {{\n{}\n}}", indent(&encoders.join("\n"), 1)),
+            &format!(
+                "match constr_index(data)? {{\n{}\n}}",
+                indent(&decoders.join("\n"), 1)
+            ),
+        );
+    }
+
+    fn implementation(&self, out: &mut String, name: &str, encode: &str, decode: &str) {
+        writeln!(
+            out,
+            "impl AsData for {name} {{\n    fn to_data(&self) -> PlutusData {{\n{}\n    }}\n\n    fn from_data(data: &PlutusData) -> Result<Self, DecodeError> {{\n{}\n    }}\n}}",
+            indent(encode, 2),
+            indent(decode, 2),
+        )
+        .unwrap();
+    }
+
+    /// Field names and types of a constructor. Fields that would make the type infinitely large
+    /// are boxed.
+    fn fields(&self, key: &str, constructor: &Annotated<Constructor>) -> Vec<(String, String)> {
+        let tuple = shape(constructor) == Shape::Tuple;
+
+        constructor
+            .annotated
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let name = match &field.title {
+                    Some(title) if !tuple => identifier(title),
+                    _ => format!("field_{index}"),
+                };
+
+                let tipo = self.declaration_type(&field.annotated, Self::data_type);
+
+                let tipo = match &field.annotated {
+                    Declaration::Referenced(reference)
+                        if self.reaches(&reference.as_key(), key) =>
+                    {
+                        format!("Box<{tipo}>")
+                    }
+                    _ => tipo,
+                };
+
+                (name, tipo)
+            })
+            .collect()
+    }
+
+    /// Whether a definition directly contains another one, possibly through others.
+    fn reaches(&self, from: &str, to: &str) -> bool {
+        let mut visited = BTreeSet::new();
+        let mut queue = vec![from.to_string()];
+
+        while let Some(key) = queue.pop() {
+            if key == to {
+                return true;
+            }
+
+            if visited.insert(key.clone()) {
+                queue.extend(self.contains.get(&key).into_iter().flatten().cloned());
+            }
+        }
+
+        false
+    }
+
+    fn name(&self, reference: &Reference) -> Option<&str> {
+        self.names.get(&reference.as_key()).map(String::as_str)
+    }
+
+    // ---------- Validators
+
+    fn validator(&self, out: &mut String, validator: &Validator) {
+        let name = validator
+            .title
+            .split(['.', '_', '/'])
+            .map(capitalize)
+            .collect::<String>();
+
+        let mut arguments = vec![];
+
+        for (label, parameter) in [
+            ("datum", &validator.datum),
+            ("redeemer", &validator.redeemer),
+        ] {
+            if let Some(parameter) = parameter {
+                let name = match &parameter.schema {
+                    Declaration::Referenced(reference) => self.name(reference),
+                    Declaration::Inline(..) => None,
+                };
+
+                arguments.push(match name {
+                    Some(name) => format!("{label} [`{name}`](super::{name})"),
+                    None => format!(
+                        "{label} `{}`",
+                        self.declaration_type(&parameter.schema, Self::schema_type)
+                    ),
+                });
+            }
+        }
+
+        let summary = if arguments.is_empty() {
+            format!("Validator `{}`", validator.title)
+        } else {
+            format!(
+                "Validator `{}`, with {}",
+                validator.title,
+                arguments.join(" and ")
+            )
+        };
+
+        doc(out, Some(&format!("{summary}.")));
+        doc(out, validator.description.as_deref());
+
+        let parameters = validator
+            .parameters
+            .iter()
+            .enumerate()
+            .map(|(index, parameter)| {
+                let name = match &parameter.title {
+                    Some(title) => identifier(title),
+                    None => format!("param_{index}"),
+                };
+                let tipo = self.declaration_type(&parameter.schema, Self::schema_type);
+                (name, tipo)
+            })
+            .collect::<Vec<_>>();
+
+        let (compiled_code, _) = validator.program.compiled_code_and_hash();
+
+        let version = match validator.program {
+            SerializableProgram::PlutusV1Program(..) => "PlutusV1Program",
+            SerializableProgram::PlutusV2Program(..) => "PlutusV2Program",
+            SerializableProgram::PlutusV3Program(..) => "PlutusV3Program",
+        };
+
+        writeln!(
+            out,
+            "#[derive(Debug, Clone, PartialEq)]\npub struct {name} {{\n    pub program: uplc::ast::SerializableProgram,\n}}\n"
+        )
+        .unwrap();
+
+        let arguments = parameters
+            .iter()
+            .map(|(name, tipo)| format!("{name}: &{tipo}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let applications = parameters
+            .iter()
+            .map(|(name, _)| format!("\n        .apply_data({name}.to_data())"))
+            .collect::<String>();
+
+        let constructor = if parameters.is_empty() {
+            "/// Instantiate the validator."
+        } else {
+            "/// Instantiate the validator, applying its parameters in order."
+        };
+
+        writeln!(
+            out,
+            r#"impl {name} {{
+    pub const COMPILED_CODE: &'static str = "{compiled_code}";
+
+    {constructor}
+    pub fn new({arguments}) -> Self {{
+        let mut cbor_buffer = Vec::new();
+        let mut flat_buffer = Vec::new();
+
+        let program = uplc::ast::Program::<uplc::ast::DeBruijn>::from_hex(
+            Self::COMPILED_CODE,
+            &mut cbor_buffer,
+            &mut flat_buffer,
+        )
+        .expect("invalid compiled code"){applications};
+
+        Self {{
+            program: uplc::ast::SerializableProgram::{version}(program),
+        }}
+    }}
+
+    /// The hash of the script.
+    pub fn hash(&self) -> uplc::Hash<28> {{
+        self.program.compiled_code_and_hash().1
+    }}
+
+    /// The address of the script, with an optional delegation part.
+    pub fn address(
+        &self,
+        network: pallas_addresses::Network,
+        delegation: pallas_addresses::ShelleyDelegationPart,
+    ) -> pallas_addresses::ShelleyAddress {{
+        pallas_addresses::ShelleyAddress::new(
+            network,
+            pallas_addresses::ShelleyPaymentPart::Script(self.hash()),
+            delegation,
+        )
+    }}
+}}"#
+        )
+        .unwrap();
+
+        if parameters.is_empty() {
+            writeln!(
+                out,
+                "\nimpl Default for {name} {{\n    fn default() -> Self {{\n        Self::new()\n    }}\n}}"
+            )
+            .unwrap();
+        }
+    }
+
+    // ---------- Types
+
+    fn schema_type(&self, schema: &Schema) -> String {
+        match schema {
+            Schema::Unit => "()".to_string(),
+            Schema::Boolean => "bool".to_string(),
+            Schema::Integer => "num_bigint::BigInt".to_string(),
+            Schema::Bytes => "Vec<u8>".to_string(),
+            Schema::String => "String".to_string(),
+            Schema::Pair(left, right) => {
+                self.tuples.borrow_mut().insert(2);
+                format!(
+                    "({}, {})",
+                    self.declaration_type(left, Self::schema_type),
+                    self.declaration_type(right, Self::schema_type)
+                )
+            }
+            Schema::List(items) => self.items_type(items, Self::schema_type),
+            Schema::Data(data) => self.data_type(data),
+        }
+    }
+
+    /// Constructors nested in other types have no name, and are left as Plutus data.
+    fn data_type(&self, data: &Data) -> String {
+        match data {
+            Data::Integer => "num_bigint::BigInt".to_string(),
+            Data::Bytes => "Vec<u8>".to_string(),
+            Data::List(items) => self.items_type(items, Self::data_type),
+            Data::Map(keys, values) => format!(
+                "Pairs<{}, {}>",
+                self.declaration_type(keys, Self::data_type),
+                self.declaration_type(values, Self::data_type)
+            ),
+            Data::AnyOf(..) | Data::Opaque => "PlutusData".to_string(),
+        }
+    }
+
+    fn items_type<T>(&self, items: &Items<T>, inline: fn(&Self, &T) -> String) -> String {
+        match items {
+            Items::One(item) => format!("Vec<{}>", self.declaration_type(item, inline)),
+            Items::Many(items) => {
+                self.tuples.borrow_mut().insert(items.len());
+                let items = items
+                    .iter()
+                    .map(|item| self.declaration_type(item, inline))
+                    .collect::<Vec<_>>();
+                if let [item] = &items[..] {
+                    format!("({item},)")
+                } else {
+                    format!("({})", items.join(", "))
+                }
+            }
+        }
+    }
+
+    fn declaration_type<T>(
+        &self,
+        declaration: &Declaration<T>,
+        inline: fn(&Self, &T) -> String,
+    ) -> String {
+        match declaration {
+            Declaration::Referenced(reference) => {
+                self.name(reference).unwrap_or("PlutusData").to_string()
+            }
+            Declaration::Inline(schema) => inline(self, schema),
+        }
+    }
+}
+
+/// How the fields of a constructor are declared: Rust has no anonymous fields in named structs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Shape {
+    Unit,
+    Tuple,
+    Named,
+}
+
+fn shape(constructor: &Annotated<Constructor>) -> Shape {
+    let fields = &constructor.annotated.fields;
+
+    if fields.is_empty() {
+        Shape::Unit
+    } else if fields.iter().all(|field| field.title.is_some()) {
+        Shape::Named
+    } else {
+        Shape::Tuple
+    }
+}
+
+fn encode_fields(index: usize, fields: &[(String, String)]) -> String {
+    format!(
+        "uplc::ast::Data::constr({index}, vec![{}])",
+        fields
+            .iter()
+            .map(|(field, _)| format!("{field}.to_data()"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn decode_tuple(arity: usize) -> String {
+    format!(
+        "({})",
+        (0..arity)
+            .map(|index| format!("AsData::from_data(&fields[{index}])?"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn decode_named(fields: &[(String, String)]) -> String {
+    format!(
+        " {{ {} }}",
+        fields
+            .iter()
+            .enumerate()
+            .map(|(index, (field, _))| format!("{field}: AsData::from_data(&fields[{index}])?"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// Conversions for tuples of a given arity, encoded as lists.
+fn tuple(out: &mut String, arity: usize) {
+    let parameters = (0..arity)
+        .map(|index| format!("T{index}"))
+        .collect::<Vec<_>>();
+
+    let tipo = if arity == 1 {
+        "(T0,)".to_string()
+    } else {
+        format!("({})", parameters.join(", "))
+    };
+
+    let bounds = parameters
+        .iter()
+        .map(|parameter| format!("{parameter}: AsData"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let encode = (0..arity)
+        .map(|index| format!("self.{index}.to_data()"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let decode = parameters
+        .iter()
+        .enumerate()
+        .map(|(index, parameter)| format!("{parameter}::from_data(&items[{index}])?"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let decode = if arity == 1 {
+        format!("({decode},)")
+    } else {
+        format!("({decode})")
+    };
+
+    writeln!(
+        out,
+        r#"impl<{bounds}> AsData for {tipo} {{
+    fn to_data(&self) -> PlutusData {{
+        uplc::ast::Data::list(vec![{encode}])
+    }}
+
+    fn from_data(data: &PlutusData) -> Result<Self, DecodeError> {{
+        match data {{
+            PlutusData::Array(items) if items.len() == {arity} => Ok({decode}),
+            _ => mismatch("a list of {arity} element(s)", data),
+        }}
+    }}
+}}"#
+    )
+    .unwrap();
+}
+
+/// Collect definitions that are part of a schema's value, as opposed to behind an indirection.
+fn direct_references(schema: &Schema, refs: &mut BTreeSet<String>) {
+    fn declaration<T>(declaration: &Declaration<T>, refs: &mut BTreeSet<String>) {
+        if let Declaration::Referenced(reference) = declaration {
+            refs.insert(reference.as_key());
+        }
+    }
+
+    match schema {
+        Schema::Pair(left, right) => {
+            declaration(left, refs);
+            declaration(right, refs);
+        }
+        Schema::List(Items::Many(items)) => items.iter().for_each(|item| declaration(item, refs)),
+        Schema::Data(Data::List(Items::Many(items))) => {
+            items.iter().for_each(|item| declaration(item, refs))
+        }
+        Schema::Data(Data::AnyOf(constructors)) => constructors
+            .iter()
+            .flat_map(|constructor| constructor.annotated.fields.iter())
+            .for_each(|field| declaration(&field.annotated, refs)),
+        _ => {}
+    }
+}
+
+/// Whether constructors are those of a boolean, which maps onto Rust's own booleans.
+fn is_bool(constructors: &[Annotated<Constructor>]) -> bool {
+    matches!(
+        constructors,
+        [false_, true_] if constructor_title(false_) == "False"
+            && constructor_title(true_) == "True"
+            && false_.annotated.fields.is_empty()
+            && true_.annotated.fields.is_empty()
+    )
+}
+
+fn constructor_title(constructor: &Annotated<Constructor>) -> String {
+    constructor
+        .title
+        .clone()
+        .unwrap_or_else(|| format!("Constr{}", constructor.annotated.index))
+}
+
+/// Turn a label into a valid Rust identifier, escaping keywords.
+fn identifier(label: &str) -> String {
+    const KEYWORDS: [&str; 47] = [
+        "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do",
+        "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in",
+        "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+        "return", "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe",
+        "unsized", "use", "virtual", "where", "while",
+    ];
+
+    match label {
+        // These can't be raw identifiers.
+        "crate" | "self" | "Self" | "super" => format!("{label}_"),
+        _ if KEYWORDS.contains(&label) => format!("r#{label}"),
+        _ => label.to_string(),
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(head) => head.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn doc(out: &mut String, description: Option<&str>) {
+    if let Some(description) = description {
+        for line in description.trim().lines() {
+            writeln!(out, "{}", format!("/// {line}").trim_end()).unwrap();
+        }
+    }
+}
+
+fn indent(code: &str, level: usize) -> String {
+    code.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{line}", "    ".repeat(level))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::Blueprint, *};
+    use crate::{config::Config, module::CheckedModules, tests::TestProject};
+    use aiken_lang::ast::{TraceLevel, Tracing};
+
+    macro_rules! assert_rust {
+        ($code:expr) => {
+            let mut project = TestProject::new();
+
+            let modules =
+                CheckedModules::singleton(project.check(project.parse(indoc::indoc! { $code })));
+
+            let mut generator = project.new_generator(Tracing::All(TraceLevel::Verbose));
+
+            let blueprint = Blueprint::new(
+                &Config::default(&project.package),
+                &modules,
+                &mut generator,
+            )
+            .unwrap();
+
+            insta::with_settings!({
+                description => concat!("Code:\n\n", indoc::indoc! { $code }),
+                omit_expression => true
+            }, {
+                insta::assert_snapshot!(redact(generate(&blueprint)));
+            });
+        };
+    }
+
+    /// Leave out what changes from one build to another: compiled code and Aiken's version.
+    fn redact(code: String) -> String {
+        code.lines()
+            .map(
+                |line| match line.split_once("COMPILED_CODE: &'static str = ") {
+                    Some((prefix, _)) => {
+                        format!("{prefix}COMPILED_CODE: &'static str = \"<redacted>\";")
+                    }
+                    None if line.starts_with("//   uplc = ") => {
+                        "//   uplc = \"<redacted>\"".to_string()
+                    }
+                    None => line.to_string(),
+                },
+            )
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn dependencies_match_workspace() {
+        let workspace = include_str!("../../../../Cargo.toml");
+        let uplc = include_str!("../../../uplc/Cargo.toml");
+
+        for (name, version) in DEPENDENCIES {
+            let found = match name {
+                "uplc" => uplc.contains(&format!("version = \"{}", &version[1..])),
+                "pallas-addresses" => workspace.contains(&format!("{name} = \"{version}.")),
+                _ => uplc.contains(&format!("{name} = \"{version}.")),
+            };
+
+            assert!(found, "{name} isn't at version {version} in the workspace");
+        }
+    }
+
+    #[test]
+    fn records_enums_and_unions() {
+        assert_rust!(
+            r#"
+            /// A position on the board.
+            pub type Position {
+                x: Int,
+                y: Int,
+            }
+
+            pub type Color {
+                Red
+                Green
+                Blue
+            }
+
+            pub type Action {
+                Move(Position, Color)
+                Paint { color: Color, ref: ByteArray }
+                Close
+            }
+
+            validator game(seed: ByteArray) {
+              spend(datum: Option<Position>, redeemer: Action, output_reference: Data, transaction: Data) {
+                True
+              }
+            }
+            "#
+        );
+    }
+
+    #[test]
+    fn containers_and_recursion() {
+        assert_rust!(
+            r#"
+            pub type Tree {
+                Leaf(Int)
+                Node { left: Tree, right: Tree }
+            }
+
+            validator containers {
+              mint(redeemer: (Pairs<ByteArray, Tree>, List<Bool>, (Int, Int, Int)), policy_id: ByteArray, transaction: Data) {
+                True
+              }
+            }
+            "#
+        );
+    }
+}
@@ -540,7 +540,10 @@ fn find_data_type(name: &str, definitions: &[TypedDefinition]) -> Option<TypedDa
             | Definition::TypeAlias { .. }
             | Definition::Use { .. }
             | Definition::ModuleConstant { .. }
-            | Definition::Test { .. } => continue,
+            | Definition::Test { .. }
+            | Definition::Benchmark { .. }
+            | Definition::Interface { .. }
+            | Definition::Instance { .. } => continue,
         }
     }
     None
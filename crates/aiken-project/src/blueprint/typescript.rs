@@ -0,0 +1,891 @@
+use super::{
+    definitions::Reference,
+    schema::{Annotated, Constructor, Data, Declaration, Items, Schema},
+    Blueprint,
+};
+use std::{collections::BTreeMap, fmt::Write};
+
+/// A representation of Plutus data, in the JSON format of cardano-cli's detailed schema.
+const PLUTUS_DATA: &str = r#"/** Plutus data, as in the detailed JSON schema of cardano-cli. */
+export type PlutusData =
+  | { constructor: number; fields: PlutusData[] }
+  | { map: { k: PlutusData; v: PlutusData }[] }
+  | { list: PlutusData[] }
+  | { int: bigint }
+  | { bytes: string };
+"#;
+
+/// Serialize Plutus data to JSON. Neither `JSON.stringify` nor `JSON.parse` handle integers
+/// beyond 2^53 losslessly, so both directions go through integer literals of their own.
+const TO_JSON: &str = r#"function (data) {
+  if ("int" in data) return `{"int":${data.int}}`;
+  if ("bytes" in data) return `{"bytes":${JSON.stringify(data.bytes)}}`;
+  if ("list" in data) return `{"list":[${data.list.map((item) => toJSON(item)).join(",")}]}`;
+  if ("map" in data) {
+    const entries = data.map.map(({ k, v }) => `{"k":${toJSON(k)},"v":${toJSON(v)}}`);
+    return `{"map":[${entries.join(",")}]}`;
+  }
+  return `{"constructor":${data.constructor},"fields":[${data.fields.map((field) => toJSON(field)).join(",")}]}`;
+}"#;
+
+/// Parse Plutus data from JSON, see [`TO_JSON`]. Integers are quoted before parsing, and turned
+/// into `bigint`s by the reviver; no string of the detailed schema can contain `"int":`.
+const FROM_JSON: &str = r#"function (json) {
+  const quoted = json.replace(/("int"\s*:\s*)(-?\d+)/g, '$1"$2"');
+  return JSON.parse(quoted, (key, value) => (key === "int" ? BigInt(value) : value));
+}"#;
+
+/// Helpers to decode Plutus data safely, by name. Only those used by the generated code are
+/// emitted, and a helper may only depend on helpers that come before it.
+const HELPERS: [(&str, &str); 11] = [
+    (
+        "fail",
+        r#"function fail(expected: string, data: PlutusData): never {
+  throw new Error(`expected ${expected} but got ${toJSON(data)}`);
+}"#,
+    ),
+    (
+        "asInt",
+        r#"function asInt(data: PlutusData): bigint {
+  if ("int" in data && typeof data.int === "bigint") return data.int;
+  if ("int" in data && Number.isSafeInteger(data.int)) return BigInt(data.int);
+  return fail("an integer", data);
+}"#,
+    ),
+    (
+        "asBytes",
+        r#"function asBytes(data: PlutusData): string {
+  return "bytes" in data ? data.bytes : fail("bytes", data);
+}"#,
+    ),
+    (
+        "asList",
+        r#"function asList(data: PlutusData): PlutusData[] {
+  return "list" in data ? data.list : fail("a list", data);
+}"#,
+    ),
+    (
+        "asTuple",
+        r#"function asTuple(data: PlutusData, arity: number): PlutusData[] {
+  const items = asList(data);
+  return items.length === arity ? items : fail(`a list of ${arity} elements`, data);
+}"#,
+    ),
+    (
+        "asMap",
+        r#"function asMap(data: PlutusData): { k: PlutusData; v: PlutusData }[] {
+  return "map" in data ? data.map : fail("a map", data);
+}"#,
+    ),
+    (
+        "asConstr",
+        r#"function asConstr(data: PlutusData): number {
+  return "fields" in data ? data.constructor : fail("a constructor", data);
+}"#,
+    ),
+    (
+        "asFields",
+        r#"function asFields(data: PlutusData, index: number, arity: number): PlutusData[] {
+  return "fields" in data && data.constructor === index && data.fields.length === arity
+    ? data.fields
+    : fail(`constructor ${index} with ${arity} field(s)`, data);
+}"#,
+    ),
+    (
+        "asBool",
+        r#"function asBool(data: PlutusData): boolean {
+  return asConstr(data) === 1 ? (asFields(data, 1, 0), true) : (asFields(data, 0, 0), false);
+}"#,
+    ),
+    (
+        "toHex",
+        r#"function toHex(bytes: Uint8Array): string {
+  return Array.from(bytes, (byte) => byte.toString(16).padStart(2, "0")).join("");
+}"#,
+    ),
+    (
+        "fromHex",
+        r#"function fromHex(hex: string): Uint8Array {
+  return Uint8Array.from(hex.match(/../g) ?? [], (byte) => parseInt(byte, 16));
+}"#,
+    ),
+];
+
+/// Generate TypeScript types, along with encoders to and decoders from Plutus data, for every
+/// definition of a blueprint.
+pub fn generate(blueprint: &Blueprint) -> String {
+    let generator = Generator {
+        names: blueprint.definitions.identifiers(),
+    };
+
+    let mut definitions = String::new();
+
+    for (key, schema) in blueprint.definitions.iter() {
+        definitions.push('\n');
+        generator.definition(&mut definitions, key, schema);
+    }
+
+    let mut helpers: Vec<&str> = vec![];
+
+    for (name, helper) in HELPERS.iter().rev() {
+        let call = format!("{name}(");
+        if definitions.contains(&call) || helpers.iter().any(|other| other.contains(&call)) {
+            helpers.push(helper);
+        }
+    }
+
+    helpers.reverse();
+
+    let mut out = format!(
+        "// Generated by Aiken from the blueprint of {} v{}. Do not edit by hand.\n\n{PLUTUS_DATA}",
+        blueprint.preamble.title, blueprint.preamble.version,
+    );
+
+    writeln!(
+        out,
+        "\n/** Serialize Plutus data to JSON, integers included whatever their size. */\n\
+         export const toJSON: (data: PlutusData) => string = {TO_JSON};"
+    )
+    .unwrap();
+
+    writeln!(
+        out,
+        "\n/** Parse Plutus data from JSON, integers included whatever their size. */\n\
+         export const fromJSON: (json: string) => PlutusData = {FROM_JSON};"
+    )
+    .unwrap();
+
+    for helper in helpers {
+        writeln!(out, "\n{helper}").unwrap();
+    }
+
+    out.push_str(&definitions);
+
+    out
+}
+
+struct Generator {
+    names: BTreeMap<String, String>,
+}
+
+impl Generator {
+    fn definition(&self, out: &mut String, key: &str, schema: &Annotated<Schema>) {
+        let name = &self.names[key];
+
+        if let Some(description) = &schema.description {
+            writeln!(out, "/** {} */", description.trim().replace("*/", "*\\/")).unwrap();
+        }
+
+        match &schema.annotated {
+            Schema::Data(Data::AnyOf(constructors)) if is_bool(constructors) => {
+                writeln!(out, "export type {name} = boolean;\n").unwrap();
+                self.functions(
+                    out,
+                    name,
+                    "return { constructor: value ? 1 : 0, fields: [] };",
+                    "return asBool(data);",
+                );
+            }
+
+            Schema::Data(Data::AnyOf(constructors)) => {
+                writeln!(
+                    out,
+                    "export type {name} = {};\n",
+                    self.constructors_type(constructors)
+                )
+                .unwrap();
+                self.functions(
+                    out,
+                    name,
+                    &self.encode_constructors(constructors, "value", 0),
+                    &self.decode_constructors(constructors, name, "data", 0),
+                );
+            }
+
+            schema => {
+                writeln!(out, "export type {name} = {};\n", self.schema_type(schema)).unwrap();
+                self.functions(
+                    out,
+                    name,
+                    &format!("return {};", self.encode_schema(schema, "value", 0)),
+                    &format!("return {};", self.decode_schema(schema, "data", 0)),
+                );
+            }
+        }
+    }
+
+    fn functions(&self, out: &mut String, name: &str, encode: &str, decode: &str) {
+        writeln!(
+            out,
+            "export function encode{name}(value: {name}): PlutusData {{\n{}\n}}\n",
+            indent(encode, 1),
+        )
+        .unwrap();
+
+        writeln!(
+            out,
+            "export function decode{name}(data: PlutusData): {name} {{\n{}\n}}",
+            indent(decode, 1),
+        )
+        .unwrap();
+    }
+
+    fn name(&self, reference: &Reference) -> Option<&str> {
+        self.names.get(&reference.as_key()).map(String::as_str)
+    }
+
+    // ---------- Types
+
+    fn schema_type(&self, schema: &Schema) -> String {
+        match schema {
+            Schema::Unit => "undefined".to_string(),
+            Schema::Boolean => "boolean".to_string(),
+            Schema::Integer => "bigint".to_string(),
+            Schema::Bytes | Schema::String => "string".to_string(),
+            Schema::Pair(left, right) => format!(
+                "[{}, {}]",
+                self.declaration_type(left, Self::schema_type),
+                self.declaration_type(right, Self::schema_type)
+            ),
+            Schema::List(items) => self.items_type(items, Self::schema_type),
+            Schema::Data(data) => self.data_type(data),
+        }
+    }
+
+    fn data_type(&self, data: &Data) -> String {
+        match data {
+            Data::Integer => "bigint".to_string(),
+            Data::Bytes => "string".to_string(),
+            Data::List(items) => self.items_type(items, Self::data_type),
+            Data::Map(keys, values) => format!(
+                "[{}, {}][]",
+                self.declaration_type(keys, Self::data_type),
+                self.declaration_type(values, Self::data_type)
+            ),
+            Data::AnyOf(constructors) => self.constructors_type(constructors),
+            Data::Opaque => "PlutusData".to_string(),
+        }
+    }
+
+    fn constructors_type(&self, constructors: &[Annotated<Constructor>]) -> String {
+        if is_enum(constructors) {
+            return constructors
+                .iter()
+                .map(|constructor| format!("{:?}", constructor_title(constructor)))
+                .collect::<Vec<_>>()
+                .join(" | ");
+        }
+
+        if let [constructor] = constructors {
+            return self.fields_type(constructor, None);
+        }
+
+        constructors
+            .iter()
+            .map(|constructor| self.fields_type(constructor, Some(&constructor_title(constructor))))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+
+    fn fields_type(&self, constructor: &Annotated<Constructor>, tag: Option<&str>) -> String {
+        let tag = tag.map(|tag| format!("$constructor: {tag:?}"));
+
+        let fields = constructor
+            .annotated
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                format!(
+                    "{}: {}",
+                    field_name(field, index),
+                    self.declaration_type(&field.annotated, Self::data_type)
+                )
+            });
+
+        let fields = tag.into_iter().chain(fields).collect::<Vec<_>>();
+
+        format!("{{ {} }}", fields.join("; "))
+    }
+
+    fn items_type<T>(&self, items: &Items<T>, inline: fn(&Self, &T) -> String) -> String {
+        match items {
+            Items::One(item) => format!("{}[]", wrap(self.declaration_type(item, inline))),
+            Items::Many(items) => format!(
+                "[{}]",
+                items
+                    .iter()
+                    .map(|item| self.declaration_type(item, inline))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    fn declaration_type<T>(
+        &self,
+        declaration: &Declaration<T>,
+        inline: fn(&Self, &T) -> String,
+    ) -> String {
+        match declaration {
+            Declaration::Referenced(reference) => {
+                self.name(reference).unwrap_or("PlutusData").to_string()
+            }
+            Declaration::Inline(schema) => inline(self, schema),
+        }
+    }
+
+    // ---------- Encoders
+
+    fn encode_schema(&self, schema: &Schema, value: &str, depth: usize) -> String {
+        match schema {
+            Schema::Unit => "{ constructor: 0, fields: [] }".to_string(),
+            Schema::Boolean => format!("{{ constructor: {value} ? 1 : 0, fields: [] }}"),
+            Schema::Integer => format!("{{ int: {value} }}"),
+            Schema::Bytes => format!("{{ bytes: {value} }}"),
+            Schema::String => format!("{{ bytes: toHex(new TextEncoder().encode({value})) }}"),
+            Schema::Pair(left, right) => format!(
+                "{{ list: [{}, {}] }}",
+                self.encode_declaration(left, &format!("{value}[0]"), depth, Self::encode_schema),
+                self.encode_declaration(right, &format!("{value}[1]"), depth, Self::encode_schema),
+            ),
+            Schema::List(items) => self.encode_items(items, value, depth, Self::encode_schema),
+            Schema::Data(data) => self.encode_data(data, value, depth),
+        }
+    }
+
+    fn encode_data(&self, data: &Data, value: &str, depth: usize) -> String {
+        match data {
+            Data::Integer => format!("{{ int: {value} }}"),
+            Data::Bytes => format!("{{ bytes: {value} }}"),
+            Data::List(items) => self.encode_items(items, value, depth, Self::encode_data),
+            Data::Map(keys, values) => {
+                let entry = format!("entry{depth}");
+                format!(
+                    "{{ map: {value}.map(({entry}) => ({{ k: {}, v: {} }})) }}",
+                    self.encode_declaration(
+                        keys,
+                        &format!("{entry}[0]"),
+                        depth + 1,
+                        Self::encode_data
+                    ),
+                    self.encode_declaration(
+                        values,
+                        &format!("{entry}[1]"),
+                        depth + 1,
+                        Self::encode_data
+                    ),
+                )
+            }
+            Data::AnyOf(constructors) => {
+                let variable = format!("value{depth}");
+                format!(
+                    "(({variable}: {}): PlutusData => {{\n{}\n}})({value})",
+                    self.constructors_type(constructors),
+                    indent(
+                        &self.encode_constructors(constructors, &variable, depth + 1),
+                        1
+                    ),
+                )
+            }
+            Data::Opaque => value.to_string(),
+        }
+    }
+
+    fn encode_constructors(
+        &self,
+        constructors: &[Annotated<Constructor>],
+        value: &str,
+        depth: usize,
+    ) -> String {
+        if is_enum(constructors) {
+            let cases = constructors
+                .iter()
+                .map(|constructor| {
+                    format!(
+                        "case {:?}:\n  return {{ constructor: {}, fields: [] }};",
+                        constructor_title(constructor),
+                        constructor.annotated.index
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            return format!("switch ({value}) {{\n{}\n}}", indent(&cases.join("\n"), 1));
+        }
+
+        if let [constructor] = constructors {
+            return format!("return {};", self.encode_fields(constructor, value, depth));
+        }
+
+        let cases = constructors
+            .iter()
+            .map(|constructor| {
+                format!(
+                    "case {:?}:\n  return {};",
+                    constructor_title(constructor),
+                    self.encode_fields(constructor, value, depth)
+                )
+            })
+            .collect::<Vec<_>>();
+
+        format!(
+            "switch ({value}.$constructor) {{\n{}\n}}",
+            indent(&cases.join("\n"), 1)
+        )
+    }
+
+    fn encode_fields(
+        &self,
+        constructor: &Annotated<Constructor>,
+        value: &str,
+        depth: usize,
+    ) -> String {
+        let fields = constructor
+            .annotated
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                self.encode_declaration(
+                    &field.annotated,
+                    &format!("{value}.{}", field_name(field, index)),
+                    depth,
+                    Self::encode_data,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        format!(
+            "{{ constructor: {}, fields: [{}] }}",
+            constructor.annotated.index,
+            fields.join(", ")
+        )
+    }
+
+    fn encode_items<T>(
+        &self,
+        items: &Items<T>,
+        value: &str,
+        depth: usize,
+        inline: fn(&Self, &T, &str, usize) -> String,
+    ) -> String {
+        match items {
+            Items::One(item) => {
+                let variable = format!("item{depth}");
+                format!(
+                    "{{ list: {value}.map(({variable}) => {}) }}",
+                    self.encode_declaration(item, &variable, depth + 1, inline)
+                )
+            }
+            Items::Many(items) => format!(
+                "{{ list: [{}] }}",
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| {
+                        self.encode_declaration(item, &format!("{value}[{index}]"), depth, inline)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    fn encode_declaration<T>(
+        &self,
+        declaration: &Declaration<T>,
+        value: &str,
+        depth: usize,
+        inline: fn(&Self, &T, &str, usize) -> String,
+    ) -> String {
+        match declaration {
+            Declaration::Referenced(reference) => match self.name(reference) {
+                Some(name) => format!("encode{name}({value})"),
+                None => value.to_string(),
+            },
+            Declaration::Inline(schema) => inline(self, schema, value, depth),
+        }
+    }
+
+    // ---------- Decoders
+
+    fn decode_schema(&self, schema: &Schema, data: &str, depth: usize) -> String {
+        match schema {
+            Schema::Unit => format!("(asFields({data}, 0, 0), undefined)"),
+            Schema::Boolean => format!("asBool({data})"),
+            Schema::Integer => format!("asInt({data})"),
+            Schema::Bytes => format!("asBytes({data})"),
+            Schema::String => format!("new TextDecoder().decode(fromHex(asBytes({data})))"),
+            Schema::Pair(left, right) => {
+                let items = format!("items{depth}");
+                format!(
+                    "(({items}) => [{}, {}] as {})(asTuple({data}, 2))",
+                    self.decode_declaration(
+                        left,
+                        &format!("{items}[0]"),
+                        depth + 1,
+                        Self::decode_schema
+                    ),
+                    self.decode_declaration(
+                        right,
+                        &format!("{items}[1]"),
+                        depth + 1,
+                        Self::decode_schema
+                    ),
+                    self.schema_type(schema),
+                )
+            }
+            Schema::List(items) => {
+                self.decode_items(items, data, depth, Self::decode_schema, Self::schema_type)
+            }
+            Schema::Data(inner) => self.decode_data(inner, data, depth),
+        }
+    }
+
+    fn decode_data(&self, data: &Data, value: &str, depth: usize) -> String {
+        match data {
+            Data::Integer => format!("asInt({value})"),
+            Data::Bytes => format!("asBytes({value})"),
+            Data::List(items) => {
+                self.decode_items(items, value, depth, Self::decode_data, Self::data_type)
+            }
+            Data::Map(keys, values) => {
+                let entry = format!("entry{depth}");
+                format!(
+                    "asMap({value}).map(({entry}) => [{}, {}] as {})",
+                    self.decode_declaration(
+                        keys,
+                        &format!("{entry}.k"),
+                        depth + 1,
+                        Self::decode_data
+                    ),
+                    self.decode_declaration(
+                        values,
+                        &format!("{entry}.v"),
+                        depth + 1,
+                        Self::decode_data
+                    ),
+                    wrap(format!(
+                        "[{}, {}]",
+                        self.declaration_type(keys, Self::data_type),
+                        self.declaration_type(values, Self::data_type)
+                    )),
+                )
+            }
+            Data::AnyOf(constructors) => {
+                let variable = format!("data{depth}");
+                let tipo = self.constructors_type(constructors);
+                format!(
+                    "(({variable}: PlutusData): {tipo} => {{\n{}\n}})({value})",
+                    indent(
+                        &self.decode_constructors(constructors, &tipo, &variable, depth + 1),
+                        1
+                    ),
+                )
+            }
+            Data::Opaque => value.to_string(),
+        }
+    }
+
+    fn decode_constructors(
+        &self,
+        constructors: &[Annotated<Constructor>],
+        name: &str,
+        data: &str,
+        depth: usize,
+    ) -> String {
+        if let [constructor] = constructors {
+            if is_enum(constructors) {
+                return format!(
+                    "asFields({data}, {}, 0);\nreturn {:?};",
+                    constructor.annotated.index,
+                    constructor_title(constructor)
+                );
+            }
+
+            return format!(
+                "const fields = asFields({data}, {}, {});\nreturn {};",
+                constructor.annotated.index,
+                constructor.annotated.fields.len(),
+                self.decode_fields(constructor, None, depth),
+            );
+        }
+
+        let cases = constructors
+            .iter()
+            .map(|constructor| {
+                let index = constructor.annotated.index;
+                let arity = constructor.annotated.fields.len();
+                let title = constructor_title(constructor);
+
+                if is_enum(constructors) {
+                    format!("case {index}:\n  asFields({data}, {index}, 0);\n  return {title:?};")
+                } else if arity == 0 {
+                    format!(
+                        "case {index}:\n  asFields({data}, {index}, 0);\n  return {};",
+                        self.decode_fields(constructor, Some(&title), depth)
+                    )
+                } else {
+                    format!(
+                        "case {index}: {{\n  const fields = asFields({data}, {index}, {arity});\n  return {};\n}}",
+                        self.decode_fields(constructor, Some(&title), depth)
+                    )
+                }
+            })
+            .collect::<Vec<_>>();
+
+        format!(
+            "switch (asConstr({data})) {{\n{}\n}}\nreturn fail({:?}, {data});",
+            indent(&cases.join("\n"), 1),
+            format!("a constructor of {name}"),
+        )
+    }
+
+    fn decode_fields(
+        &self,
+        constructor: &Annotated<Constructor>,
+        tag: Option<&str>,
+        depth: usize,
+    ) -> String {
+        let tag = tag.map(|tag| format!("$constructor: {tag:?}"));
+
+        let fields = constructor
+            .annotated
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                format!(
+                    "{}: {}",
+                    field_name(field, index),
+                    self.decode_declaration(
+                        &field.annotated,
+                        &format!("fields[{index}]"),
+                        depth,
+                        Self::decode_data
+                    )
+                )
+            });
+
+        let fields = tag.into_iter().chain(fields).collect::<Vec<_>>();
+
+        format!("{{ {} }}", fields.join(", "))
+    }
+
+    fn decode_items<T>(
+        &self,
+        items: &Items<T>,
+        data: &str,
+        depth: usize,
+        inline: fn(&Self, &T, &str, usize) -> String,
+        inline_type: fn(&Self, &T) -> String,
+    ) -> String {
+        match items {
+            Items::One(item) => {
+                let variable = format!("item{depth}");
+                format!(
+                    "asList({data}).map(({variable}) => {})",
+                    self.decode_declaration(item, &variable, depth + 1, inline)
+                )
+            }
+            Items::Many(many) => {
+                let variable = format!("items{depth}");
+                format!(
+                    "(({variable}) => [{}] as {})(asTuple({data}, {}))",
+                    many.iter()
+                        .enumerate()
+                        .map(|(index, item)| {
+                            self.decode_declaration(
+                                item,
+                                &format!("{variable}[{index}]"),
+                                depth + 1,
+                                inline,
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    self.items_type(items, inline_type),
+                    many.len(),
+                )
+            }
+        }
+    }
+
+    fn decode_declaration<T>(
+        &self,
+        declaration: &Declaration<T>,
+        data: &str,
+        depth: usize,
+        inline: fn(&Self, &T, &str, usize) -> String,
+    ) -> String {
+        match declaration {
+            Declaration::Referenced(reference) => match self.name(reference) {
+                Some(name) => format!("decode{name}({data})"),
+                None => data.to_string(),
+            },
+            Declaration::Inline(schema) => inline(self, schema, data, depth),
+        }
+    }
+}
+
+/// Whether constructors are those of a boolean, which maps onto TypeScript's own booleans.
+fn is_bool(constructors: &[Annotated<Constructor>]) -> bool {
+    matches!(
+        constructors,
+        [false_, true_] if constructor_title(false_) == "False"
+            && constructor_title(true_) == "True"
+            && is_enum(constructors)
+    )
+}
+
+/// Whether none of the constructors have fields, in which case they map onto string literals.
+fn is_enum(constructors: &[Annotated<Constructor>]) -> bool {
+    constructors
+        .iter()
+        .all(|constructor| constructor.annotated.fields.is_empty())
+}
+
+fn constructor_title(constructor: &Annotated<Constructor>) -> String {
+    constructor
+        .title
+        .clone()
+        .unwrap_or_else(|| format!("Constr{}", constructor.annotated.index))
+}
+
+fn field_name(field: &Annotated<Declaration<Data>>, index: usize) -> String {
+    field.title.clone().unwrap_or_else(|| format!("_{index}"))
+}
+
+/// Parenthesize union types, so that they can be followed by '[]'.
+fn wrap(tipo: String) -> String {
+    if tipo.contains(" | ") {
+        format!("({tipo})")
+    } else {
+        tipo
+    }
+}
+
+fn indent(code: &str, level: usize) -> String {
+    code.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{line}", "  ".repeat(level))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::Blueprint, *};
+    use crate::{config::Config, module::CheckedModules, tests::TestProject};
+    use aiken_lang::ast::{TraceLevel, Tracing};
+
+    macro_rules! assert_typescript {
+        ($code:expr) => {
+            let mut project = TestProject::new();
+
+            let modules =
+                CheckedModules::singleton(project.check(project.parse(indoc::indoc! { $code })));
+
+            let mut generator = project.new_generator(Tracing::All(TraceLevel::Verbose));
+
+            let blueprint = Blueprint::new(
+                &Config::default(&project.package),
+                &modules,
+                &mut generator,
+            )
+            .unwrap();
+
+            insta::with_settings!({
+                description => concat!("Code:\n\n", indoc::indoc! { $code }),
+                omit_expression => true
+            }, {
+                insta::assert_snapshot!(generate(&blueprint));
+            });
+        };
+    }
+
+    #[test]
+    fn records_enums_and_unions() {
+        assert_typescript!(
+            r#"
+            /// A position on the board.
+            pub type Position {
+                x: Int,
+                y: Int,
+            }
+
+            pub type Color {
+                Red
+                Green
+                Blue
+            }
+
+            pub type Action {
+                Move(Position, Color)
+                Paint { color: Color, label: ByteArray }
+                Close
+            }
+
+            validator game {
+              spend(datum: Option<Position>, redeemer: Action, output_reference: Data, transaction: Data) {
+                True
+              }
+            }
+            "#
+        );
+    }
+
+    #[test]
+    fn containers_and_generics() {
+        assert_typescript!(
+            r#"
+            pub type Interval<a> {
+                Finite(a)
+                Infinite
+            }
+
+            validator containers {
+              spend(datum: Option<(Int, ByteArray)>, redeemer: (Pairs<ByteArray, Interval<Int>>, List<Bool>), output_reference: Data, transaction: Data) {
+                True
+              }
+            }
+            "#
+        );
+    }
+
+    /// The generated codec itself is covered by the snapshots above; this evaluates it, which
+    /// takes a node installation: run with `cargo test -- --ignored`.
+    #[test]
+    #[ignore = "requires node"]
+    fn json_round_trip_of_large_integers() {
+        let json = r#"{"constructor":0,"fields":[{"int":-123456789012345678901234567890},{"list":[{"map":[{"k":{"bytes":"cafe"},"v":{"int":9007199254740993}}]}]}]}"#;
+
+        let script = format!(
+            r#"
+            const toJSON = {TO_JSON};
+            const fromJSON = {FROM_JSON};
+            const json = {json:?};
+            const data = fromJSON(json);
+            if (data.fields[0].int !== -123456789012345678901234567890n) throw new Error(toJSON(data));
+            if (data.fields[1].list[0].map[0].v.int !== 9007199254740993n) throw new Error(toJSON(data));
+            if (toJSON(data) !== json) throw new Error(toJSON(data));
+            "#
+        );
+
+        let output = std::process::Command::new("node")
+            .args(["--eval", &script])
+            .output()
+            .expect("failed to run node");
+
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
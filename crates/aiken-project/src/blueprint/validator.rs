@@ -8,7 +8,7 @@ use super::{
 use crate::module::{CheckedModule, CheckedModules};
 use aiken_lang::{
     ast::{well_known, Annotation, TypedArg, TypedFunction, TypedValidator},
-    gen_uplc::CodeGenerator,
+    gen_uplc::{source_map::SourceMap, CodeGenerator},
     plutus_version::PlutusVersion,
     tipo::{collapse_links, Type},
 };
@@ -43,6 +43,10 @@ pub struct Validator {
     #[serde(skip_serializing_if = "Definitions::is_empty")]
     #[serde(default)]
     pub definitions: Definitions<Annotated<Schema>>,
+
+    /// Not part of the blueprint itself; written next to it when requested.
+    #[serde(skip)]
+    pub source_map: SourceMap,
 }
 
 impl Validator {
@@ -214,6 +218,7 @@ impl Validator {
                 PlutusVersion::V3 => SerializableProgram::PlutusV3Program,
             }(program.get(generator, def, &module.name)),
             definitions,
+            source_map: program.source_map().clone(),
         })
     }
 }
@@ -1013,4 +1018,87 @@ mod tests {
 
         assert!(matches!(param.validate(&definitions, &term), Ok { .. }))
     }
+
+    #[test]
+    fn validate_arguments_mismatch_path() {
+        let schema = Reference::new("Foo");
+
+        // #/definitions/Foo
+        //
+        // {
+        //   "anyOf": [
+        //      {
+        //          "title": "Bar",
+        //          "dataType": "constructor",
+        //          "index": 0,
+        //          "fields": []
+        //      },
+        //      {
+        //          "title": "Baz",
+        //          "dataType": "constructor",
+        //          "index": 1,
+        //          "fields": [{
+        //              "title": "amounts",
+        //              "$ref": "#/definitions/List$Int
+        //          }]
+        //      },
+        //   ]
+        // }
+        let mut definitions = fixture_definitions();
+        definitions.insert(
+            &Reference::new("List$Int"),
+            Schema::Data(Data::List(Items::One(Declaration::Referenced(
+                Reference::new("Int"),
+            ))))
+            .into(),
+        );
+        definitions.insert(
+            &schema,
+            Schema::Data(Data::AnyOf(vec![
+                Annotated {
+                    title: Some("Bar".to_string()),
+                    description: None,
+                    annotated: Constructor {
+                        index: 0,
+                        fields: vec![],
+                    },
+                },
+                Annotated {
+                    title: Some("Baz".to_string()),
+                    description: None,
+                    annotated: Constructor {
+                        index: 1,
+                        fields: vec![Annotated {
+                            title: Some("amounts".to_string()),
+                            description: None,
+                            annotated: Declaration::Referenced(Reference::new("List$Int")),
+                        }],
+                    },
+                },
+            ]))
+            .into(),
+        );
+
+        let param: Parameter = schema.into();
+
+        let term = Constant::Data(uplc_ast::Data::constr(
+            1,
+            vec![uplc_ast::Data::list(vec![
+                uplc_ast::Data::integer(42.into()),
+                uplc_ast::Data::bytestring(vec![102, 111, 111]),
+            ])],
+        ));
+
+        assert!(matches!(
+            param.validate(&definitions, &term),
+            Err(Error::SchemaMismatch { path, .. }) if path == "$.Baz.amounts[1]"
+        ));
+
+        let term = Constant::Data(uplc_ast::Data::constr(1, vec![]));
+
+        assert!(matches!(
+            param.validate(&definitions, &term),
+            Err(Error::SchemaMismatch { path, .. }) if path == "$"
+        ));
+    }
 }
@@ -12,7 +12,12 @@ use serde::{
     ser::{self, SerializeSeq, SerializeStruct},
     Deserialize, Serialize,
 };
-use std::{collections::BTreeMap, fmt::Display, fs, io, path::Path};
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
 
 #[derive(Deserialize, Serialize, Clone)]
 pub struct Config {
@@ -276,9 +281,10 @@ pub struct Repository {
     pub platform: Platform,
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Debug, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Platform {
+    #[default]
     Github,
     Gitlab,
     Bitbucket,
@@ -287,8 +293,20 @@ pub enum Platform {
 #[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug)]
 pub struct Dependency {
     pub name: PackageName,
+    /// A git commit hash, a tag, a branch name or a semver range over tags (e.g. `^1.2`). Unused
+    /// for local dependencies.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub version: String,
+    #[serde(default)]
     pub source: Platform,
+    /// A folder containing an Aiken project, relative to the project's root. When set, the
+    /// dependency is copied from there instead of being fetched from the source platform.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// A git repository to clone the dependency from, at the given version. When set, the
+    /// dependency is fetched from there instead of the source platform.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<String>,
 }
 
 impl Display for Platform {
@@ -325,6 +343,8 @@ impl Config {
                     _ => "1.5.0".to_string(),
                 },
                 source: Platform::Github,
+                path: None,
+                git: None,
             }],
             config: BTreeMap::new(),
         }
@@ -437,4 +457,46 @@ mod tests {
 
         }
     }
+
+    #[test]
+    fn local_and_git_dependencies() {
+        #[derive(Deserialize, Serialize)]
+        struct TestDependencies {
+            dependencies: Vec<Dependency>,
+        }
+
+        let src = r#"
+            [[dependencies]]
+            name = "acme/shared"
+            path = "../shared"
+
+            [[dependencies]]
+            name = "acme/other"
+            git = "https://example.com/acme/other.git"
+            version = "main"
+        "#;
+
+        let TestDependencies { dependencies } = toml::from_str(src).unwrap();
+
+        assert_eq!(dependencies[0].path, Some(PathBuf::from("../shared")));
+        assert_eq!(dependencies[0].version, "");
+        assert_eq!(dependencies[0].source, Platform::Github);
+        assert_eq!(
+            dependencies[1].git.as_deref(),
+            Some("https://example.com/acme/other.git")
+        );
+        assert_eq!(dependencies[1].version, "main");
+
+        let pretty = toml::to_string_pretty(&TestDependencies {
+            dependencies: dependencies.clone(),
+        })
+        .unwrap();
+        assert!(!pretty.contains("version = \"\""));
+        assert_eq!(
+            toml::from_str::<TestDependencies>(&pretty)
+                .unwrap()
+                .dependencies,
+            dependencies
+        );
+    }
 }
@@ -1,4 +1,8 @@
-use std::{collections::HashSet, fs, path::Path};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
 
 use aiken_lang::ast::Span;
 use miette::NamedSource;
@@ -14,27 +18,37 @@ use crate::{
 };
 
 use self::{
-    downloader::Downloader,
+    downloader::{copy_package, Downloader},
     manifest::{Manifest, Package},
 };
 
 pub mod downloader;
 pub mod manifest;
+pub mod resolver;
 
 pub enum UseManifest {
     Yes,
     No,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct LocalPackages {
     packages: Vec<Dependency>,
 }
 
 impl LocalPackages {
     pub fn load(root_path: &Path) -> Result<Self, Error> {
-        let path = root_path.join(paths::packages_toml());
+        Self::load_from(root_path.join(paths::packages_toml()))
+    }
 
+    /// Packages previously copied into the project's vendor directory.
+    #[allow(clippy::result_large_err)]
+    pub fn load_vendor(root_path: &Path) -> Result<Self, Error> {
+        Self::load_from(root_path.join(paths::vendor_toml()))
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn load_from(path: PathBuf) -> Result<Self, Error> {
         if !path.exists() {
             return Ok(Self {
                 packages: Vec::new(),
@@ -73,6 +87,27 @@ impl LocalPackages {
         Ok(())
     }
 
+    /// Whether a package can be taken as-is from the vendor directory. Local dependencies are
+    /// always taken from their path.
+    pub fn is_vendored(&self, package: &Package) -> bool {
+        package.path.is_none()
+            && self.packages.iter().any(|dep| {
+                dep.name == package.name
+                    && dep.version == package.version
+                    && dep.source == package.source
+                    && dep.git == package.git
+            })
+    }
+
+    /// Versions known for a given package.
+    pub fn versions(&self, name: &PackageName) -> Vec<String> {
+        self.packages
+            .iter()
+            .filter(|dep| &dep.name == name)
+            .map(|dep| dep.version.clone())
+            .collect()
+    }
+
     fn remove_extra_packages(&self, manifest: &Manifest, root_path: &Path) -> Result<(), Error> {
         for (package, _version) in self.extra_local_packages(manifest) {
             let path = root_path.join(paths::build_deps_package(&package));
@@ -126,6 +161,8 @@ impl From<&Manifest> for LocalPackages {
                     name: p.name.clone(),
                     version: p.version.clone(),
                     source: p.source,
+                    path: p.path.clone(),
+                    git: p.git.clone(),
                 })
                 .collect(),
         }
@@ -158,7 +195,8 @@ where
 
     let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio");
 
-    let (mut manifest, changed) = Manifest::load(event_listener, config, root_path)?;
+    let (mut manifest, changed) =
+        runtime.block_on(Manifest::load(event_listener, config, root_path))?;
 
     let local = LocalPackages::load(root_path)?;
 
@@ -181,6 +219,50 @@ where
     Ok(manifest)
 }
 
+/// Copy all the dependencies of a project into its vendor directory, so that they can be resolved
+/// later on without any access to the network. Dependencies are fetched first if needed.
+#[allow(clippy::result_large_err)]
+pub fn vendor<T>(
+    event_listener: &T,
+    root_path: &Path,
+    config: &Config,
+) -> Result<Vec<PackageName>, Error>
+where
+    T: EventListener,
+{
+    let manifest = download(event_listener, root_path, config)?;
+
+    let vendor_path = root_path.join(paths::vendor());
+
+    if vendor_path.exists() {
+        fs::remove_dir_all(&vendor_path)?;
+    }
+
+    fs::create_dir_all(&vendor_path)?;
+
+    let mut vendored = Vec::new();
+
+    for package in manifest.packages.iter() {
+        if package.name == config.name {
+            continue;
+        }
+
+        copy_package(
+            &root_path.join(paths::build_deps_package(&package.name)),
+            &root_path.join(paths::vendor_package(&package.name)),
+        )?;
+
+        vendored.push(package.name.clone());
+    }
+
+    let toml =
+        toml::to_string(&LocalPackages::from(&manifest)).expect("packages.toml serialization");
+
+    fs::write(root_path.join(paths::vendor_toml()), toml)?;
+
+    Ok(vendored)
+}
+
 async fn fetch_missing_packages<T>(
     manifest: &mut Manifest,
     local: &LocalPackages,
@@ -205,36 +287,62 @@ where
             name: format!("{project_name}"),
         });
 
-        let downloader = Downloader::new(root_path);
+        let downloader = Downloader::new(root_path, LocalPackages::load_vendor(root_path)?);
 
         let statuses = downloader
             .download_packages(event_listener, missing, &project_name, manifest)
             .await?;
 
-        let downloaded_from_network = statuses
-            .iter()
-            .filter(|(_, downloaded)| *downloaded)
-            .count();
-        if downloaded_from_network > 0 {
-            event_listener.handle_event(Event::PackagesDownloaded {
-                start,
-                count: downloaded_from_network,
-                source: DownloadSource::Network,
-            });
+        for source in [
+            DownloadSource::Network,
+            DownloadSource::Cache,
+            DownloadSource::Local,
+            DownloadSource::Vendor,
+        ] {
+            let count = statuses.iter().filter(|(_, s)| *s == source).count();
+            if count > 0 {
+                event_listener.handle_event(Event::PackagesDownloaded {
+                    start,
+                    count,
+                    source,
+                });
+            }
         }
+    }
 
-        let downloaded_from_cache = statuses
-            .iter()
-            .filter(|(_, downloaded)| !downloaded)
-            .count();
-        if downloaded_from_cache > 0 {
-            event_listener.handle_event(Event::PackagesDownloaded {
-                start,
-                count: downloaded_from_cache,
-                source: DownloadSource::Cache,
-            });
+    manifest.save(root_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Platform;
+
+    fn package(version: &str, path: Option<&str>) -> Package {
+        Package {
+            name: PackageName {
+                owner: "aiken-lang".to_string(),
+                repo: "stdlib".to_string(),
+            },
+            version: version.to_string(),
+            requirements: vec![],
+            source: Platform::Github,
+            path: path.map(PathBuf::from),
+            git: None,
+            checksum: None,
         }
     }
 
-    manifest.save(root_path)
+    #[test]
+    fn is_vendored() {
+        let vendored = LocalPackages::from(&Manifest {
+            requirements: vec![],
+            packages: vec![package("v2.0.0", None), package("", Some("../stdlib"))],
+            etags: Default::default(),
+        });
+
+        assert!(vendored.is_vendored(&package("v2.0.0", None)));
+        assert!(!vendored.is_vendored(&package("v1.9.0", None)));
+        assert!(!vendored.is_vendored(&package("", Some("../stdlib"))));
+    }
 }
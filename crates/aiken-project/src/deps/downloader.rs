@@ -1,10 +1,13 @@
 use std::{
+    fs,
     io::{self, Cursor, Read},
     path::{Path, PathBuf},
 };
 
-use futures::future;
+use futures::{future, FutureExt};
+use pallas_crypto::hash::Hasher;
 use reqwest::Client;
+use tokio::process::Command;
 use zip::result::ZipError;
 
 use crate::{
@@ -12,45 +15,244 @@ use crate::{
     error::Error,
     package_name::PackageName,
     paths::{self, CacheKey},
-    telemetry::EventListener,
+    telemetry::{DownloadSource, EventListener},
 };
 
-use super::manifest::Package;
+use super::{manifest::Package, LocalPackages};
 
 pub struct Downloader<'a> {
     http: Client,
     root_path: &'a Path,
+    vendored: LocalPackages,
+    git_cache: PathBuf,
 }
 
 impl<'a> Downloader<'a> {
-    pub fn new(root_path: &'a Path) -> Self {
+    pub fn new(root_path: &'a Path, vendored: LocalPackages) -> Self {
         Self {
             http: Client::new(),
             root_path,
+            vendored,
+            git_cache: paths::packages_cache_git(),
         }
     }
 
+    #[cfg(test)]
+    fn with_git_cache(self, git_cache: PathBuf) -> Self {
+        Self { git_cache, ..self }
+    }
+
     pub async fn download_packages<I, T>(
         &self,
         event_listener: &T,
         packages: I,
         project_name: &PackageName,
         manifest: &mut Manifest,
-    ) -> Result<Vec<(PackageName, bool)>, Error>
+    ) -> Result<Vec<(PackageName, DownloadSource)>, Error>
     where
         T: EventListener,
         I: Iterator<Item = &'a Package>,
     {
         let mut tasks = vec![];
+        let mut archives = vec![];
+        let mut pinned = vec![];
 
         for package in packages.filter(|package| project_name != &package.name) {
-            let cache_key =
-                paths::CacheKey::new(&self.http, event_listener, package, manifest).await?;
-            let task = self.ensure_package_in_build_directory(package, cache_key);
-            tasks.push(task);
+            if package.path.is_none() && paths::is_git_sha_or_tag(&package.version) {
+                pinned.push(package);
+            }
+
+            let vendored = self.root_path.join(paths::vendor_package(&package.name));
+
+            if self.vendored.is_vendored(package)
+                && vendored.join(paths::project_config()).is_file()
+            {
+                tasks.push(self.copy_vendored_package(package, vendored).boxed());
+            } else if let Some(path) = &package.path {
+                tasks.push(self.copy_local_package(package, path).boxed());
+            } else if let Some(url) = &package.git {
+                tasks.push(
+                    self.ensure_git_package_in_build_directory(package, url)
+                        .boxed(),
+                );
+            } else {
+                let cache_key =
+                    paths::CacheKey::new(&self.http, event_listener, package, manifest).await?;
+                archives.push(self.ensure_package_in_build_directory(package, cache_key));
+            }
+        }
+
+        let (mut statuses, archives) =
+            future::try_join(future::try_join_all(tasks), future::try_join_all(archives)).await?;
+
+        for (name, downloaded) in archives {
+            statuses.push((
+                name,
+                if downloaded {
+                    DownloadSource::Network
+                } else {
+                    DownloadSource::Cache
+                },
+            ));
+        }
+
+        for package in pinned {
+            let checksum = self.verify_package_checksum(package).await?;
+            manifest.insert_checksum(&package.name, checksum);
+        }
+
+        Ok(statuses)
+    }
+
+    /// Copy a dependency from the project's vendor directory, without any network access.
+    pub async fn copy_vendored_package(
+        &self,
+        package: &Package,
+        source: PathBuf,
+    ) -> Result<(PackageName, DownloadSource), Error> {
+        let destination = self
+            .root_path
+            .join(paths::build_deps_package(&package.name));
+
+        tokio::task::spawn_blocking(move || copy_package(&source, &destination)).await??;
+
+        Ok((package.name.clone(), DownloadSource::Vendor))
+    }
+
+    /// Copy a dependency living somewhere on the file-system, relative to the project's root.
+    pub async fn copy_local_package(
+        &self,
+        package: &Package,
+        path: &Path,
+    ) -> Result<(PackageName, DownloadSource), Error> {
+        let source = self.root_path.join(path);
+
+        if !source.join(paths::project_config()).is_file() {
+            return Err(Error::MissingLocalPackage {
+                package: package.clone(),
+                path: source,
+            });
+        }
+
+        let destination = self
+            .root_path
+            .join(paths::build_deps_package(&package.name));
+
+        tokio::task::spawn_blocking(move || copy_package(&source, &destination)).await??;
+
+        Ok((package.name.clone(), DownloadSource::Local))
+    }
+
+    /// Fetch a dependency from an arbitrary git repository, through the system's git. Clones are
+    /// kept in the packages cache, one per repository URL, and only fetched again when the version
+    /// refers to a branch, or to a commit or tag that the clone doesn't know of yet. The package is
+    /// extracted from an archive of the revision, so that the clone's worktree is never touched and
+    /// packages from the same repository can be fetched concurrently.
+    pub async fn ensure_git_package_in_build_directory(
+        &self,
+        package: &Package,
+        url: &str,
+    ) -> Result<(PackageName, DownloadSource), Error> {
+        let repository = paths::package_cache_git(&self.git_cache, url);
+
+        let git_error = |reason: String| Error::GitFetch {
+            package: package.clone(),
+            url: url.to_string(),
+            reason,
+        };
+
+        let mut source = DownloadSource::Cache;
+
+        if !repository.join(".git").is_dir() {
+            if repository.exists() {
+                tokio::fs::remove_dir_all(&repository).await?;
+            }
+            tokio::fs::create_dir_all(&repository).await?;
+            git(&repository, &["clone", "--quiet", url, "."])
+                .await
+                .map_err(git_error)?;
+            source = DownloadSource::Network;
+        } else if !paths::is_git_sha_or_tag(&package.version)
+            || git(
+                &repository,
+                &[
+                    "rev-parse",
+                    "--verify",
+                    "--quiet",
+                    &format!("{}^{{commit}}", package.version),
+                ],
+            )
+            .await
+            .is_err()
+        {
+            git(&repository, &["fetch", "--quiet", "--tags", "origin"])
+                .await
+                .map_err(git_error)?;
+            source = DownloadSource::Network;
         }
 
-        future::try_join_all(tasks).await
+        // Branches are only known as remote branches in a fresh clone, so prefer those when they
+        // exist; otherwise, let git figure out what the version refers to.
+        let remote_branch = format!("origin/{}^{{commit}}", package.version);
+        let rev = if package.version.is_empty() {
+            "origin/HEAD".to_string()
+        } else if git(
+            &repository,
+            &["rev-parse", "--verify", "--quiet", &remote_branch],
+        )
+        .await
+        .is_ok()
+        {
+            format!("origin/{}", package.version)
+        } else {
+            package.version.clone()
+        };
+
+        let commit = git(
+            &repository,
+            &[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{rev}^{{commit}}"),
+            ],
+        )
+        .await
+        .map_err(|_| git_error(format!("unknown revision '{}'", package.version)))?;
+
+        // The archive's entries are nested under a single top-level directory, as for GitHub's
+        // zipballs, which is what extract_zip expects.
+        let zipball = git_output(
+            &repository,
+            &[
+                "archive",
+                "--format=zip",
+                "--prefix=package/",
+                commit.trim(),
+            ],
+        )
+        .await
+        .map_err(git_error)?;
+
+        let destination = self
+            .root_path
+            .join(paths::build_deps_package(&package.name));
+
+        if destination.exists() {
+            tokio::fs::remove_dir_all(&destination).await?;
+        }
+
+        tokio::fs::create_dir_all(&destination).await?;
+
+        tokio::task::spawn_blocking(move || {
+            let mut archive =
+                zip::ZipArchive::new(Cursor::new(zipball)).expect("failed to load zip archive");
+
+            extract_zip(&mut archive, &destination)
+        })
+        .await??;
+
+        Ok((package.name.clone(), source))
     }
 
     pub async fn ensure_package_in_build_directory(
@@ -58,13 +260,31 @@ impl<'a> Downloader<'a> {
         package: &Package,
         cache_key: CacheKey,
     ) -> Result<(PackageName, bool), Error> {
-        let downloaded = self
-            .ensure_package_downloaded(package, &cache_key)
-            .await
-            .map(|downloaded| (package.name.clone(), downloaded))?;
+        let downloaded = self.ensure_package_downloaded(package, &cache_key).await?;
         self.extract_package_from_cache(&package.name, &cache_key)
             .await?;
-        Ok(downloaded)
+        Ok((package.name.clone(), downloaded))
+    }
+
+    /// Compare the checksum of a package, as found in the build directory, with the one recorded
+    /// in the manifest, if any. Only packages pinned to a tag or a commit are verified, whether
+    /// they come from an archive, a git repository or the vendor directory; others aren't
+    /// expected to be stable.
+    pub async fn verify_package_checksum(&self, package: &Package) -> Result<String, Error> {
+        let directory = self
+            .root_path
+            .join(paths::build_deps_package(&package.name));
+
+        let checksum = tokio::task::spawn_blocking(move || package_checksum(&directory)).await??;
+
+        match &package.checksum {
+            Some(expected) if expected != &checksum => Err(Error::PackageChecksumMismatch {
+                package: package.clone(),
+                expected: expected.clone(),
+                actual: checksum,
+            }),
+            _ => Ok(checksum),
+        }
     }
 
     pub async fn ensure_package_downloaded(
@@ -116,6 +336,10 @@ impl<'a> Downloader<'a> {
     ) -> Result<(), Error> {
         let destination = self.root_path.join(paths::build_deps_package(name));
 
+        if destination.exists() {
+            tokio::fs::remove_dir_all(&destination).await?;
+        }
+
         tokio::fs::create_dir_all(&destination).await?;
 
         let zipball_path = self.root_path.join(paths::package_cache_zipball(cache_key));
@@ -144,6 +368,100 @@ impl<'a> Downloader<'a> {
     }
 }
 
+pub(crate) async fn git(directory: &Path, args: &[&str]) -> Result<String, String> {
+    git_output(directory, args)
+        .await
+        .map(|stdout| String::from_utf8_lossy(&stdout).to_string())
+}
+
+async fn git_output(directory: &Path, args: &[&str]) -> Result<Vec<u8>, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(directory)
+        .output()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// The files of an Aiken project, in a stable order, leaving out its own build artifacts and git
+/// metadata.
+fn package_entries(source: &Path) -> impl Iterator<Item = walkdir::Result<walkdir::DirEntry>> {
+    walkdir::WalkDir::new(source)
+        .follow_links(true)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() != 1
+                || !(entry.file_name() == ".git" || entry.file_name() == paths::build().as_os_str())
+        })
+}
+
+/// A blake2b-256 digest of the files of an Aiken project, along with their paths relative to the
+/// project's root. It doesn't depend on how the package was obtained (e.g. from an archive or a
+/// git repository), nor on the file-system it lives on.
+pub(crate) fn package_checksum(source: &Path) -> io::Result<String> {
+    let mut hasher = Hasher::<256>::new();
+
+    for entry in package_entries(source) {
+        let entry = entry?;
+
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let path = entry
+            .path()
+            .strip_prefix(source)
+            .expect("walked entries are within the source")
+            .iter()
+            .map(|segment| segment.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let content = fs::read(entry.path())?;
+
+        hasher.input(path.as_bytes());
+        hasher.input(&[0]);
+        hasher.input(&(content.len() as u64).to_be_bytes());
+        hasher.input(&content);
+    }
+
+    Ok(hasher.finalize().to_string())
+}
+
+/// Copy an Aiken project into the build directory, leaving out its own build artifacts and git
+/// metadata.
+pub(crate) fn copy_package(source: &Path, destination: &Path) -> io::Result<()> {
+    if destination.exists() {
+        fs::remove_dir_all(destination)?;
+    }
+
+    for entry in package_entries(source) {
+        let entry = entry?;
+
+        let target = destination.join(
+            entry
+                .path()
+                .strip_prefix(source)
+                .expect("walked entries are within the source"),
+        );
+
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+
+    Ok(())
+}
+
 fn extract_zip<R: Read + io::Seek, P: AsRef<Path>>(
     archive: &mut zip::ZipArchive<R>,
     directory: P,
@@ -185,3 +503,193 @@ fn extract_zip<R: Read + io::Seek, P: AsRef<Path>>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Platform;
+    use tempfile::TempDir;
+
+    struct Silent;
+
+    impl EventListener for Silent {}
+
+    fn package(checksum: Option<&str>) -> Package {
+        Package {
+            name: PackageName {
+                owner: "acme".to_string(),
+                repo: "pinned".to_string(),
+            },
+            version: "v1.0.0".to_string(),
+            requirements: vec![],
+            source: Platform::Github,
+            path: None,
+            git: None,
+            checksum: checksum.map(String::from),
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn download(root: &Path, package: &Package) -> Result<Manifest, Error> {
+        let mut manifest = Manifest {
+            requirements: vec![],
+            packages: vec![package.clone()],
+            etags: Default::default(),
+        };
+
+        let vendored = LocalPackages::from(&manifest);
+
+        let project_name = PackageName {
+            owner: "acme".to_string(),
+            repo: "project".to_string(),
+        };
+
+        tokio::runtime::Runtime::new()
+            .expect("Unable to start Tokio")
+            .block_on(Downloader::new(root, vendored).download_packages(
+                &Silent,
+                std::iter::once(package),
+                &project_name,
+                &mut manifest,
+            ))?;
+
+        Ok(manifest)
+    }
+
+    #[test]
+    fn verify_vendored_package_checksum() {
+        let temp = TempDir::new().unwrap();
+
+        let root = temp.path();
+
+        let vendored = root.join(paths::vendor_package(&package(None).name));
+
+        fs::create_dir_all(vendored.join("lib")).unwrap();
+        fs::write(
+            vendored.join(paths::project_config()),
+            "name = \"acme/pinned\"\n",
+        )
+        .unwrap();
+        fs::write(
+            vendored.join("lib").join("pinned.ak"),
+            "pub const answer = 42\n",
+        )
+        .unwrap();
+
+        let manifest = download(root, &package(None)).expect("no checksum to verify yet");
+
+        let checksum = manifest.packages[0]
+            .checksum
+            .clone()
+            .expect("checksum should be recorded");
+
+        assert_eq!(checksum, package_checksum(&vendored).unwrap());
+
+        assert!(download(root, &package(Some(&checksum))).is_ok());
+
+        fs::write(
+            vendored.join("lib").join("pinned.ak"),
+            "pub const answer = 14\n",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            download(root, &package(Some(&checksum))),
+            Err(Error::PackageChecksumMismatch { expected, .. }) if expected == checksum
+        ));
+    }
+
+    #[test]
+    fn fetch_missing_git_revisions() {
+        let root = TempDir::new().unwrap();
+
+        let origin = TempDir::new().unwrap();
+
+        let git_cache = TempDir::new().unwrap();
+
+        let origin = origin.path();
+
+        let commit = |content: &str, tag: &str| {
+            fs::write(origin.join("lib").join("pinned.ak"), content).unwrap();
+
+            for args in [
+                vec!["add", "."],
+                vec![
+                    "-c",
+                    "user.name=aiken",
+                    "-c",
+                    "user.email=aiken@example.com",
+                    "commit",
+                    "--quiet",
+                    "-m",
+                    tag,
+                ],
+                vec!["tag", tag],
+            ] {
+                let status = std::process::Command::new("git")
+                    .args(args)
+                    .current_dir(origin)
+                    .status()
+                    .unwrap();
+                assert!(status.success());
+            }
+        };
+
+        fs::create_dir_all(origin.join("lib")).unwrap();
+        fs::write(
+            origin.join(paths::project_config()),
+            "name = \"acme/pinned\"\n",
+        )
+        .unwrap();
+
+        let status = std::process::Command::new("git")
+            .args(["init", "--quiet"])
+            .current_dir(origin)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        commit("pub const answer = 14\n", "v1.0.0");
+
+        let url = origin.to_string_lossy().to_string();
+
+        let fetch = |version: &str| {
+            let package = Package {
+                version: version.to_string(),
+                git: Some(url.clone()),
+                ..package(None)
+            };
+
+            tokio::runtime::Runtime::new()
+                .expect("Unable to start Tokio")
+                .block_on(
+                    Downloader::new(
+                        root.path(),
+                        LocalPackages::from(&Manifest {
+                            requirements: vec![],
+                            packages: vec![],
+                            etags: Default::default(),
+                        }),
+                    )
+                    .with_git_cache(git_cache.path().to_path_buf())
+                    .ensure_git_package_in_build_directory(&package, &url),
+                )
+                .expect("git package should be fetched");
+
+            fs::read_to_string(
+                root.path()
+                    .join(paths::build_deps_package(&package.name))
+                    .join("lib")
+                    .join("pinned.ak"),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(fetch("v1.0.0"), "pub const answer = 14\n");
+
+        commit("pub const answer = 42\n", "v1.1.0");
+
+        assert_eq!(fetch("v1.1.0"), "pub const answer = 42\n");
+        assert_eq!(fetch("v1.0.0"), "pub const answer = 14\n");
+    }
+}
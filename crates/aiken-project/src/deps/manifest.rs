@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
     fs,
-    path::Path,
+    path::{Path, PathBuf},
     time::{Duration, SystemTime},
 };
 
@@ -16,6 +16,8 @@ use crate::{
     telemetry::{Event, EventListener},
 };
 
+use super::resolver;
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct Manifest {
     pub requirements: Vec<Dependency>,
@@ -25,7 +27,7 @@ pub struct Manifest {
 }
 
 impl Manifest {
-    pub fn load<T>(
+    pub async fn load<T>(
         event_listener: &T,
         config: &Config,
         root_path: &Path,
@@ -40,7 +42,7 @@ impl Manifest {
         let should_resolve = !manifest_path.exists();
 
         if should_resolve {
-            let manifest = resolve_versions(config, event_listener)?;
+            let manifest = resolve_versions(config, None, root_path, event_listener).await?;
             return Ok((manifest, true));
         }
 
@@ -63,7 +65,8 @@ impl Manifest {
         if manifest.requirements == config.dependencies {
             Ok((manifest, false))
         } else {
-            let manifest = resolve_versions(config, event_listener)?;
+            let manifest =
+                resolve_versions(config, Some(&manifest), root_path, event_listener).await?;
             Ok((manifest, true))
         }
     }
@@ -99,6 +102,20 @@ impl Manifest {
         }
     }
 
+    /// The checksum recorded for a package, provided that it hasn't changed since.
+    pub fn locked_checksum(&self, package: &Package) -> Option<String> {
+        self.packages
+            .iter()
+            .find(|p| p.is_same_as(package))
+            .and_then(|p| p.checksum.clone())
+    }
+
+    pub fn insert_checksum(&mut self, name: &PackageName, checksum: String) {
+        if let Some(package) = self.packages.iter_mut().find(|p| &p.name == name) {
+            package.checksum.get_or_insert(checksum);
+        }
+    }
+
     pub fn insert_etag(&mut self, package: &Package, etag: String) {
         self.etags
             .insert(etag_key(package), (SystemTime::now(), etag));
@@ -118,28 +135,94 @@ pub struct Package {
     pub version: String,
     pub requirements: Vec<String>,
     pub source: Platform,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<String>,
+    /// A blake2b-256 digest of the package's files, for packages pinned to a tag or a commit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
 }
 
-fn resolve_versions<T>(config: &Config, event_listener: &T) -> Result<Manifest, Error>
+impl Package {
+    fn is_same_as(&self, other: &Package) -> bool {
+        self.name == other.name
+            && self.version == other.version
+            && self.source == other.source
+            && self.path == other.path
+            && self.git == other.git
+    }
+}
+
+async fn resolve_versions<T>(
+    config: &Config,
+    previous: Option<&Manifest>,
+    root_path: &Path,
+    event_listener: &T,
+) -> Result<Manifest, Error>
 where
     T: EventListener,
 {
     event_listener.handle_event(Event::ResolvingVersions);
 
-    let manifest = Manifest {
-        packages: config
-            .dependencies
-            .iter()
-            .map(|dep| Package {
-                name: dep.name.clone(),
-                version: dep.version.clone(),
-                requirements: vec![],
-                source: dep.source,
-            })
-            .collect(),
+    let mut manifest = Manifest {
+        packages: vec![],
         requirements: config.dependencies.clone(),
-        etags: BTreeMap::new(),
+        etags: previous
+            .map(|previous| previous.etags.clone())
+            .unwrap_or_default(),
     };
 
+    resolver::resolve(&mut manifest, previous, config, root_path, event_listener).await?;
+
     Ok(manifest)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(repo: &str, version: &str, checksum: Option<&str>) -> Package {
+        Package {
+            name: PackageName {
+                owner: "acme".to_string(),
+                repo: repo.to_string(),
+            },
+            version: version.to_string(),
+            requirements: vec![],
+            source: Platform::Github,
+            path: None,
+            git: None,
+            checksum: checksum.map(String::from),
+        }
+    }
+
+    #[test]
+    fn locked_checksums() {
+        let mut previous = Manifest {
+            requirements: vec![],
+            packages: vec![
+                package("stdlib", "v2.0.0", Some("0123")),
+                package("other", "v0.9.0", Some("4567")),
+                package("fresh", "v1.0.0", None),
+            ],
+            etags: BTreeMap::new(),
+        };
+
+        previous.insert_checksum(&package("fresh", "", None).name, "89ab".to_string());
+        previous.insert_checksum(&package("stdlib", "", None).name, "cdef".to_string());
+
+        assert_eq!(
+            previous.locked_checksum(&package("stdlib", "v2.0.0", None)),
+            Some("0123".to_string())
+        );
+        assert_eq!(
+            previous.locked_checksum(&package("other", "v1.0.0", None)),
+            None
+        );
+        assert_eq!(
+            previous.locked_checksum(&package("fresh", "v1.0.0", None)),
+            Some("89ab".to_string())
+        );
+    }
+}
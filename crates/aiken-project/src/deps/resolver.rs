@@ -0,0 +1,752 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Display,
+    fs,
+    path::Path,
+};
+
+use futures::{future::LocalBoxFuture, FutureExt};
+use reqwest::{header, Client};
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Dependency, Platform},
+    error::Error,
+    package_name::PackageName,
+    paths,
+    telemetry::EventListener,
+};
+
+use super::{
+    downloader::{self, Downloader},
+    manifest::{Manifest, Package},
+    LocalPackages,
+};
+
+/// A version constraint put on a package by another package (or the project itself).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Requirement {
+    pub from: PackageName,
+    pub version: String,
+}
+
+impl Display for Requirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} requires {}", self.from, self.version)
+    }
+}
+
+impl Requirement {
+    fn is_range(&self) -> bool {
+        is_range(&self.version)
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        VersionReq::parse(&self.version).is_ok_and(|req| req.matches(version))
+    }
+}
+
+/// Whether a version is a semver range (e.g. `^1.2` or `~0.4`), as opposed to a tag, a commit
+/// hash or a branch name.
+pub fn is_range(version: &str) -> bool {
+    version.starts_with(['^', '~', '=', '>', '<', '*'])
+}
+
+/// Interpret a tag as a semantic version, with or without a leading 'v'.
+fn parse_tag(tag: &str) -> Option<Version> {
+    Version::parse(tag.strip_prefix('v').unwrap_or(tag)).ok()
+}
+
+fn same_version(left: &str, right: &str) -> bool {
+    left == right || matches!((parse_tag(left), parse_tag(right)), (Some(l), Some(r)) if l == r)
+}
+
+/// Pick a version satisfying all the requirements on a package. Exact requirements (tags, commit
+/// hashes or branch names) must agree with one another and satisfy every range. Otherwise, the
+/// highest candidate within all ranges is chosen.
+pub fn pick_version(requirements: &[Requirement], candidates: &[String]) -> Option<String> {
+    let (ranges, exact): (Vec<_>, Vec<_>) = requirements.iter().partition(|r| r.is_range());
+
+    if let Some(first) = exact.first() {
+        let agree = exact
+            .iter()
+            .all(|r| same_version(&r.version, &first.version));
+
+        let within_ranges = ranges.is_empty()
+            || parse_tag(&first.version).is_some_and(|v| ranges.iter().all(|r| r.matches(&v)));
+
+        return (agree && within_ranges).then(|| first.version.clone());
+    }
+
+    candidates
+        .iter()
+        .filter_map(|tag| parse_tag(tag).map(|version| (version, tag)))
+        .filter(|(version, _)| ranges.iter().all(|r| r.matches(version)))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, tag)| tag.clone())
+}
+
+/// Where packages come from, as far as resolution is concerned: which versions of a package
+/// exist, and what a given version of a package depends on.
+trait Registry {
+    fn versions<'a>(
+        &'a self,
+        dep: &'a Dependency,
+    ) -> LocalBoxFuture<'a, Result<Vec<String>, Error>>;
+
+    fn dependencies<'a>(
+        &'a self,
+        package: &'a Package,
+        manifest: &'a mut Manifest,
+    ) -> LocalBoxFuture<'a, Result<Vec<Dependency>, Error>>;
+}
+
+/// Packages as found on their source platform (or git repository, or file-system), fetched into
+/// the build directory to discover their own dependencies.
+struct Remote<'a, T> {
+    http: Client,
+    vendored: LocalPackages,
+    project_name: &'a PackageName,
+    root_path: &'a Path,
+    event_listener: &'a T,
+}
+
+impl<T> Registry for Remote<'_, T>
+where
+    T: EventListener,
+{
+    fn versions<'a>(
+        &'a self,
+        dep: &'a Dependency,
+    ) -> LocalBoxFuture<'a, Result<Vec<String>, Error>> {
+        available_versions(&self.http, dep, &self.vendored).boxed_local()
+    }
+
+    fn dependencies<'a>(
+        &'a self,
+        package: &'a Package,
+        manifest: &'a mut Manifest,
+    ) -> LocalBoxFuture<'a, Result<Vec<Dependency>, Error>> {
+        async move {
+            Downloader::new(self.root_path, self.vendored.clone())
+                .download_packages(
+                    self.event_listener,
+                    std::iter::once(package),
+                    self.project_name,
+                    manifest,
+                )
+                .await?;
+
+            let config = Config::load(
+                &self
+                    .root_path
+                    .join(paths::build_deps_package(&package.name)),
+            )?;
+
+            Ok(config.dependencies)
+        }
+        .boxed_local()
+    }
+}
+
+/// Resolve the versions of all packages in the dependency graph of a project, starting from its
+/// direct dependencies. Packages are fetched along the way so that their own dependencies can be
+/// discovered.
+pub async fn resolve<T>(
+    manifest: &mut Manifest,
+    previous: Option<&Manifest>,
+    config: &Config,
+    root_path: &Path,
+    event_listener: &T,
+) -> Result<(), Error>
+where
+    T: EventListener,
+{
+    let registry = Remote {
+        http: Client::new(),
+        vendored: LocalPackages::load_vendor(root_path)?,
+        project_name: &config.name,
+        root_path,
+        event_listener,
+    };
+
+    resolve_from(
+        manifest,
+        previous,
+        &config.name,
+        &config.dependencies,
+        &registry,
+    )
+    .await
+}
+
+/// The resolution is greedy: a package is only picked again when a new requirement rules out its
+/// current version. The requirements that the superseded version put on other packages are then
+/// dropped, along with the packages no longer required by anyone.
+async fn resolve_from<R>(
+    manifest: &mut Manifest,
+    previous: Option<&Manifest>,
+    project_name: &PackageName,
+    dependencies: &[Dependency],
+    registry: &R,
+) -> Result<(), Error>
+where
+    R: Registry,
+{
+    let mut requirements: HashMap<PackageName, Vec<Requirement>> = HashMap::new();
+
+    let mut available: HashMap<PackageName, Vec<String>> = HashMap::new();
+
+    let mut queue: VecDeque<(PackageName, Dependency)> = dependencies
+        .iter()
+        .map(|dep| (project_name.clone(), dep.clone()))
+        .collect();
+
+    while let Some((from, dep)) = queue.pop_front() {
+        if &dep.name == project_name {
+            continue;
+        }
+
+        let package_requirements = requirements.entry(dep.name.clone()).or_default();
+
+        package_requirements.push(Requirement {
+            from,
+            version: dep.version.clone(),
+        });
+
+        let is_picked = match manifest.packages.iter().find(|p| p.name == dep.name) {
+            Some(picked) => {
+                let still_valid =
+                    pick_version(package_requirements, std::slice::from_ref(&picked.version))
+                        .is_some_and(|version| same_version(&version, &picked.version));
+
+                if still_valid {
+                    continue;
+                }
+
+                true
+            }
+            None => false,
+        };
+
+        let candidates = if package_requirements.iter().all(|r| r.is_range()) {
+            if !available.contains_key(&dep.name) {
+                let versions = registry.versions(&dep).await?;
+                available.insert(dep.name.clone(), versions);
+            }
+            available[&dep.name].clone()
+        } else {
+            vec![]
+        };
+
+        let version = pick_version(package_requirements, &candidates).ok_or_else(|| {
+            Error::DependencyConflict {
+                package: dep.name.clone(),
+                requirements: package_requirements.clone(),
+            }
+        })?;
+
+        if is_picked {
+            supersede(&dep.name, manifest, &mut requirements, &mut queue);
+        }
+
+        let mut package = Package {
+            name: dep.name.clone(),
+            version,
+            requirements: vec![],
+            source: dep.source,
+            path: dep.path.clone(),
+            git: dep.git.clone(),
+            checksum: None,
+        };
+
+        package.checksum = previous.and_then(|previous| previous.locked_checksum(&package));
+
+        manifest.packages.retain(|p| p.name != package.name);
+        manifest.packages.push(package.clone());
+
+        let package_dependencies = registry.dependencies(&package, manifest).await?;
+
+        if let Some(picked) = manifest
+            .packages
+            .iter_mut()
+            .find(|p| p.name == package.name)
+        {
+            picked.requirements = package_dependencies
+                .iter()
+                .map(|dep| dep.name.to_string())
+                .collect();
+        }
+
+        for mut transitive in package_dependencies {
+            // Local paths of local packages are relative to those packages.
+            if let (Some(base), Some(path)) = (&package.path, &transitive.path) {
+                transitive.path = Some(base.join(path));
+            }
+            queue.push_back((package.name.clone(), transitive));
+        }
+    }
+
+    Ok(())
+}
+
+/// Forget about the requirements a package put on others, as it is about to be picked at another
+/// version. Packages that were only required through it are removed, and so are, in turn, the
+/// requirements they put on others.
+fn supersede(
+    name: &PackageName,
+    manifest: &mut Manifest,
+    requirements: &mut HashMap<PackageName, Vec<Requirement>>,
+    queue: &mut VecDeque<(PackageName, Dependency)>,
+) {
+    let mut superseded = vec![name.clone()];
+
+    while let Some(from) = superseded.pop() {
+        queue.retain(|(requirer, _)| requirer != &from);
+
+        requirements.retain(|package, package_requirements| {
+            let before = package_requirements.len();
+
+            package_requirements.retain(|requirement| requirement.from != from);
+
+            if package_requirements.is_empty() && before > 0 {
+                superseded.push(package.clone());
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    manifest
+        .packages
+        .retain(|p| &p.name == name || requirements.contains_key(&p.name));
+}
+
+#[derive(Deserialize)]
+struct Tag {
+    name: String,
+}
+
+/// A page of results from Bitbucket's API, which links to the next one in its body rather than
+/// in a header.
+#[derive(Deserialize)]
+struct BitbucketPage {
+    values: Vec<Tag>,
+    next: Option<String>,
+}
+
+/// List the tags of a package, from its repository, or from the packages cache and the vendor
+/// directory when the network is unavailable.
+async fn available_versions(
+    http: &Client,
+    dep: &Dependency,
+    vendored: &LocalPackages,
+) -> Result<Vec<String>, Error> {
+    let package = Package {
+        name: dep.name.clone(),
+        version: dep.version.clone(),
+        requirements: vec![],
+        source: dep.source,
+        path: dep.path.clone(),
+        git: dep.git.clone(),
+        checksum: None,
+    };
+
+    if dep.path.is_some() {
+        return Ok(vec![]);
+    }
+
+    if let Some(url) = &dep.git {
+        let refs = downloader::git(
+            Path::new("."),
+            &["ls-remote", "--tags", "--refs", url.as_str()],
+        )
+        .await
+        .map_err(|reason| Error::GitFetch {
+            package: package.clone(),
+            url: url.clone(),
+            reason,
+        })?;
+
+        return Ok(refs
+            .lines()
+            .filter_map(|line| line.split_once("refs/tags/"))
+            .map(|(_, tag)| tag.to_string())
+            .collect());
+    }
+
+    if let Ok(tags) = fetch_tags(http, &dep.name, dep.source).await {
+        return Ok(tags);
+    }
+
+    let mut versions = vendored.versions(&dep.name);
+
+    let prefix = format!("{}-{}-", dep.name.owner, dep.name.repo);
+
+    if let Ok(entries) = fs::read_dir(paths::packages_cache()) {
+        for entry in entries.flatten() {
+            let filename = entry.file_name().to_string_lossy().to_string();
+            if let Some(key) = filename
+                .strip_prefix(&prefix)
+                .and_then(|key| key.strip_suffix(".zip"))
+            {
+                let version = key.split('@').next().unwrap_or(key).to_string();
+                if !versions.contains(&version) {
+                    versions.push(version);
+                }
+            }
+        }
+    }
+
+    if versions.is_empty() {
+        return Err(Error::UnableToResolvePackage { package });
+    }
+
+    Ok(versions)
+}
+
+/// Fetch all the tags of a package from the API of its source platform, page after page.
+async fn fetch_tags(
+    http: &Client,
+    name: &PackageName,
+    platform: Platform,
+) -> Result<Vec<String>, reqwest::Error> {
+    let mut tags = vec![];
+
+    let mut next = Some(match platform {
+        Platform::Github => format!(
+            "https://api.github.com/repos/{}/{}/tags?per_page=100",
+            name.owner, name.repo
+        ),
+        Platform::Gitlab => format!(
+            "https://gitlab.com/api/v4/projects/{}%2F{}/repository/tags?per_page=100",
+            name.owner, name.repo
+        ),
+        Platform::Bitbucket => format!(
+            "https://api.bitbucket.org/2.0/repositories/{}/{}/refs/tags?pagelen=100",
+            name.owner, name.repo
+        ),
+    });
+
+    while let Some(url) = next {
+        let response = http
+            .get(url)
+            .header("User-Agent", "aiken-lang")
+            .send()
+            .await?
+            .error_for_status()?;
+
+        next = match platform {
+            Platform::Github | Platform::Gitlab => {
+                let next = response
+                    .headers()
+                    .get(header::LINK)
+                    .and_then(|link| link.to_str().ok())
+                    .and_then(next_page);
+
+                let page: Vec<Tag> = response.json().await?;
+
+                tags.extend(page.into_iter().map(|tag| tag.name));
+
+                next
+            }
+            Platform::Bitbucket => {
+                let page: BitbucketPage = response.json().await?;
+
+                tags.extend(page.values.into_iter().map(|tag| tag.name));
+
+                page.next
+            }
+        };
+    }
+
+    Ok(tags)
+}
+
+/// The URL of the next page of results, from a `Link` header (e.g.
+/// `<https://api.github.com/repositories/1/tags?page=2>; rel="next", <...>; rel="last"`).
+fn next_page(link: &str) -> Option<String> {
+    link.split(',').find_map(|link| {
+        let (url, params) = link.split_once(';')?;
+
+        params
+            .split(';')
+            .any(|param| param.trim() == r#"rel="next""#)
+            .then(|| {
+                url.trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future;
+    use itertools::Itertools;
+
+    fn requirement(from: &str, version: &str) -> Requirement {
+        Requirement {
+            from: PackageName {
+                owner: "acme".to_string(),
+                repo: from.to_string(),
+            },
+            version: version.to_string(),
+        }
+    }
+
+    fn dependency(repo: &str, version: &str) -> Dependency {
+        Dependency {
+            name: PackageName {
+                owner: "acme".to_string(),
+                repo: repo.to_string(),
+            },
+            version: version.to_string(),
+            source: Platform::Github,
+            path: None,
+            git: None,
+        }
+    }
+
+    /// Packages known ahead of time, by name and version, along with their dependencies.
+    struct Fixtures(HashMap<&'static str, Vec<(&'static str, Vec<Dependency>)>>);
+
+    impl Registry for Fixtures {
+        fn versions<'a>(
+            &'a self,
+            dep: &'a Dependency,
+        ) -> LocalBoxFuture<'a, Result<Vec<String>, Error>> {
+            let versions = self.0[dep.name.repo.as_str()]
+                .iter()
+                .map(|(version, _)| version.to_string())
+                .collect();
+
+            future::ready(Ok(versions)).boxed_local()
+        }
+
+        fn dependencies<'a>(
+            &'a self,
+            package: &'a Package,
+            _manifest: &'a mut Manifest,
+        ) -> LocalBoxFuture<'a, Result<Vec<Dependency>, Error>> {
+            let dependencies = self.0[package.name.repo.as_str()]
+                .iter()
+                .find(|(version, _)| *version == package.version)
+                .map(|(_, dependencies)| dependencies.clone())
+                .unwrap_or_default();
+
+            future::ready(Ok(dependencies)).boxed_local()
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn resolve_fixtures(
+        dependencies: &[Dependency],
+        fixtures: Fixtures,
+    ) -> Result<Vec<(String, String)>, Error> {
+        let mut manifest = Manifest {
+            requirements: dependencies.to_vec(),
+            packages: vec![],
+            etags: Default::default(),
+        };
+
+        let project_name = PackageName {
+            owner: "acme".to_string(),
+            repo: "project".to_string(),
+        };
+
+        futures::executor::block_on(resolve_from(
+            &mut manifest,
+            None,
+            &project_name,
+            dependencies,
+            &fixtures,
+        ))?;
+
+        Ok(manifest
+            .packages
+            .into_iter()
+            .map(|package| (package.name.repo, package.version))
+            .sorted()
+            .collect())
+    }
+
+    fn candidates() -> Vec<String> {
+        [
+            "v1.1.0", "v1.2.0", "v1.2.3", "v2.0.0", "0.4.1", "0.4.7", "0.5.0", "main",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect()
+    }
+
+    #[test]
+    fn pick_highest_within_ranges() {
+        assert_eq!(
+            pick_version(&[requirement("a", "^1.2")], &candidates()),
+            Some("v1.2.3".to_string())
+        );
+        assert_eq!(
+            pick_version(&[requirement("a", "~0.4")], &candidates()),
+            Some("0.4.7".to_string())
+        );
+        assert_eq!(
+            pick_version(
+                &[requirement("a", "^1.1"), requirement("b", "<1.2.3")],
+                &candidates()
+            ),
+            Some("v1.2.0".to_string())
+        );
+    }
+
+    #[test]
+    fn pick_exact_versions() {
+        assert_eq!(
+            pick_version(
+                &[requirement("a", "v1.2.0"), requirement("b", "^1.1")],
+                &candidates()
+            ),
+            Some("v1.2.0".to_string())
+        );
+        assert_eq!(
+            pick_version(
+                &[requirement("a", "v1.2.0"), requirement("b", "1.2.0")],
+                &candidates()
+            ),
+            Some("v1.2.0".to_string())
+        );
+        assert_eq!(
+            pick_version(&[requirement("a", "main")], &candidates()),
+            Some("main".to_string())
+        );
+    }
+
+    #[test]
+    fn conflicting_requirements() {
+        assert_eq!(
+            pick_version(
+                &[requirement("a", "^1.2"), requirement("b", "^2.0")],
+                &candidates()
+            ),
+            None
+        );
+        assert_eq!(
+            pick_version(
+                &[requirement("a", "v1.2.0"), requirement("b", "v2.0.0")],
+                &candidates()
+            ),
+            None
+        );
+        assert_eq!(
+            pick_version(
+                &[requirement("a", "main"), requirement("b", "^1.0")],
+                &candidates()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn resolve_diamond() {
+        let fixtures = Fixtures(HashMap::from([
+            ("left", vec![("v1.0.0", vec![dependency("base", "^1.0")])]),
+            ("right", vec![("v1.0.0", vec![dependency("base", "^1.2")])]),
+            (
+                "base",
+                vec![
+                    ("v1.0.0", vec![]),
+                    ("v1.2.0", vec![]),
+                    ("v1.3.1", vec![]),
+                    ("v2.0.0", vec![]),
+                ],
+            ),
+        ]));
+
+        assert_eq!(
+            resolve_fixtures(
+                &[dependency("left", "^1"), dependency("right", "^1")],
+                fixtures
+            )
+            .unwrap(),
+            vec![
+                ("base".to_string(), "v1.3.1".to_string()),
+                ("left".to_string(), "v1.0.0".to_string()),
+                ("right".to_string(), "v1.0.0".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_diamond_conflict() {
+        let fixtures = Fixtures(HashMap::from([
+            ("left", vec![("v1.0.0", vec![dependency("base", "^1.0")])]),
+            ("right", vec![("v1.0.0", vec![dependency("base", "^2.0")])]),
+            ("base", vec![("v1.0.0", vec![]), ("v2.0.0", vec![])]),
+        ]));
+
+        assert!(matches!(
+            resolve_fixtures(
+                &[dependency("left", "^1"), dependency("right", "^1")],
+                fixtures
+            ),
+            Err(Error::DependencyConflict { package, .. }) if package.repo == "base"
+        ));
+    }
+
+    #[test]
+    fn resolve_drops_requirements_of_superseded_versions() {
+        // The first pick of 'left' (v1.1.0) requires 'base' ^2.0 and 'extra'. Once 'right' rules
+        // it out, neither should get in the way of 'left' v1.0.0, nor remain in the manifest.
+        let fixtures = Fixtures(HashMap::from([
+            (
+                "left",
+                vec![
+                    ("v1.0.0", vec![dependency("base", "^1.0")]),
+                    (
+                        "v1.1.0",
+                        vec![dependency("base", "^2.0"), dependency("extra", "^1.0")],
+                    ),
+                ],
+            ),
+            (
+                "right",
+                vec![("v1.0.0", vec![dependency("left", "<1.1.0")])],
+            ),
+            ("base", vec![("v1.4.0", vec![]), ("v2.0.0", vec![])]),
+            ("extra", vec![("v1.0.0", vec![])]),
+        ]));
+
+        assert_eq!(
+            resolve_fixtures(
+                &[dependency("left", "^1"), dependency("right", "^1")],
+                fixtures
+            )
+            .unwrap(),
+            vec![
+                ("base".to_string(), "v1.4.0".to_string()),
+                ("left".to_string(), "v1.0.0".to_string()),
+                ("right".to_string(), "v1.0.0".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn next_page_from_link_header() {
+        assert_eq!(
+            next_page(
+                r#"<https://api.github.com/repositories/1/tags?per_page=100&page=2>; rel="next", <https://api.github.com/repositories/1/tags?per_page=100&page=4>; rel="last""#
+            ),
+            Some("https://api.github.com/repositories/1/tags?per_page=100&page=2".to_string())
+        );
+        assert_eq!(
+            next_page(
+                r#"<https://api.github.com/repositories/1/tags?per_page=100&page=3>; rel="prev", <https://api.github.com/repositories/1/tags?per_page=100&page=1>; rel="first""#
+            ),
+            None
+        );
+    }
+}
@@ -4,8 +4,8 @@ use crate::{
 };
 use aiken_lang::{
     ast::{
-        DataType, Definition, Function, ModuleConstant, RecordConstructor, Span, TypeAlias,
-        TypedDefinition,
+        DataType, Definition, Function, Instance, Interface, ModuleConstant, RecordConstructor,
+        Span, TypeAlias, TypedDefinition,
     },
     format,
     parser::extra::Comment,
@@ -48,6 +48,8 @@ struct ModuleTemplate<'a> {
     modules: &'a [DocLink],
     functions: Vec<Interspersed>,
     types: Vec<DocType>,
+    interfaces: Vec<DocInterface>,
+    instances: Vec<DocInstance>,
     constants: Vec<DocConstant>,
     documentation: String,
     source: &'a DocLink,
@@ -235,6 +237,30 @@ fn generate_module(
         .iter()
         .for_each(|type_info| search_indexes.push(SearchIndex::from_type(module, type_info)));
 
+    // Interfaces
+    let interfaces: Vec<DocInterface> = module
+        .ast
+        .definitions
+        .iter()
+        .flat_map(|def| DocInterface::from_definition(def, &source_linker))
+        .sorted()
+        .collect();
+    interfaces.iter().for_each(|interface| {
+        search_indexes.push(SearchIndex::from_interface(module, interface))
+    });
+
+    // Instances
+    let instances: Vec<DocInstance> = module
+        .ast
+        .definitions
+        .iter()
+        .flat_map(|def| DocInstance::from_definition(def, &source_linker))
+        .sorted()
+        .collect();
+    instances
+        .iter()
+        .for_each(|instance| search_indexes.push(SearchIndex::from_instance(module, instance)));
+
     // Constants
     let constants: Vec<DocConstant> = module
         .ast
@@ -247,7 +273,11 @@ fn generate_module(
         .iter()
         .for_each(|constant| search_indexes.push(SearchIndex::from_constant(module, constant)));
 
-    let is_empty = no_functions && types.is_empty() && constants.is_empty();
+    let is_empty = no_functions
+        && types.is_empty()
+        && interfaces.is_empty()
+        && instances.is_empty()
+        && constants.is_empty();
 
     // Module
     if !is_empty {
@@ -265,6 +295,8 @@ fn generate_module(
         project_version: &config.version.to_string(),
         functions: functions_and_headers,
         types,
+        interfaces,
+        instances,
         constants,
         source,
         timestamp: timestamp.as_secs().to_string(),
@@ -408,6 +440,8 @@ fn generate_modules_links(modules: &[&CheckedModule]) -> Vec<DocLink> {
                             | Definition::DataType(DataType { public: true, .. })
                             | Definition::TypeAlias(TypeAlias { public: true, .. })
                             | Definition::ModuleConstant(ModuleConstant { public: true, .. })
+                            | Definition::Interface(Interface { public: true, .. })
+                            | Definition::Instance(Instance { .. })
                     )
                 })
         })
@@ -464,6 +498,24 @@ impl SearchIndex {
         }
     }
 
+    fn from_interface(module: &CheckedModule, interface: &DocInterface) -> Self {
+        SearchIndex {
+            doc: module.name.to_string(),
+            title: interface.name.to_string(),
+            content: format!("{}\n{}", interface.definition, interface.raw_documentation),
+            url: format!("{}.html#{}", module.name, interface.name),
+        }
+    }
+
+    fn from_instance(module: &CheckedModule, instance: &DocInstance) -> Self {
+        SearchIndex {
+            doc: module.name.to_string(),
+            title: instance.name.to_string(),
+            content: format!("{}\n{}", instance.definition, instance.raw_documentation),
+            url: format!("{}.html#{}", module.name, instance.anchor()),
+        }
+    }
+
     fn from_constant(module: &CheckedModule, constant: &DocConstant) -> Self {
         SearchIndex {
             doc: module.name.to_string(),
@@ -526,6 +578,7 @@ impl DocFunction {
                             &func_def.arguments,
                             &func_def.return_annotation,
                             func_def.return_type.clone(),
+                            &func_def.constraints,
                         )
                         .to_pretty_string(MAX_COLUMNS),
                     source_url: source_linker
@@ -641,6 +694,112 @@ impl DocType {
     }
 }
 
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct DocInterface {
+    name: String,
+    parameter: String,
+    definition: String,
+    documentation: String,
+    raw_documentation: String,
+    methods: Vec<DocInterfaceMethod>,
+    source_url: String,
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct DocInterfaceMethod {
+    name: String,
+    documentation: String,
+}
+
+impl DocInterface {
+    fn from_definition(
+        def: &TypedDefinition,
+        source_linker: &source_links::SourceLinker,
+    ) -> Option<Self> {
+        match def {
+            Definition::Interface(info) if info.public => Some(DocInterface {
+                name: info.name.clone(),
+                parameter: info.parameter.clone(),
+                definition: format::Formatter::new()
+                    .docs_interface(&info.name, &info.parameter, &info.methods)
+                    .to_pretty_string(MAX_COLUMNS),
+                documentation: info.doc.as_deref().map(render_markdown).unwrap_or_default(),
+                raw_documentation: info.doc.as_deref().unwrap_or_default().to_string(),
+                methods: info
+                    .methods
+                    .iter()
+                    .filter_map(|method| {
+                        Some(DocInterfaceMethod {
+                            name: method.name.clone(),
+                            documentation: render_markdown(method.doc.as_deref()?),
+                        })
+                    })
+                    .collect(),
+                source_url: source_linker.url(info.location),
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct DocInstance {
+    /// The implemented interface, applied to the instance type (e.g. `Show<Int>`).
+    name: String,
+    definition: String,
+    documentation: String,
+    raw_documentation: String,
+    source_url: String,
+}
+
+impl DocInstance {
+    fn from_definition(
+        def: &TypedDefinition,
+        source_linker: &source_links::SourceLinker,
+    ) -> Option<Self> {
+        match def {
+            Definition::Instance(info) => {
+                let name = format!("{}<{}>", info.interface_name(), info.tipo.to_pretty(0));
+
+                Some(DocInstance {
+                    definition: info
+                        .methods
+                        .iter()
+                        .map(|method| {
+                            format::Formatter::new()
+                                .docs_fn_signature(
+                                    &method.name,
+                                    &method.arguments,
+                                    &method.return_annotation,
+                                    method.return_type.clone(),
+                                    &method.constraints,
+                                )
+                                .to_pretty_string(MAX_COLUMNS)
+                        })
+                        .join("\n"),
+                    name,
+                    documentation: info.doc.as_deref().map(render_markdown).unwrap_or_default(),
+                    raw_documentation: info.doc.as_deref().unwrap_or_default().to_string(),
+                    source_url: source_linker
+                        .url(info.location.map_end(|_| info.end_position)),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Instance names contain characters that aren't valid in anchors (e.g. `Show<Int>`).
+    fn anchor(&self) -> String {
+        format!(
+            "instance-{}",
+            self.name
+                .chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+        )
+    }
+}
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
 struct DocTypeConstructor {
     definition: String,
@@ -1,9 +1,13 @@
-use crate::{blueprint, deps::manifest::Package, package_name::PackageName};
+use crate::{
+    blueprint,
+    deps::{manifest::Package, resolver::Requirement},
+    package_name::PackageName,
+};
 use aiken_lang::{
     ast::{self, Span},
     error::ExtraData,
     parser::error::ParseError,
-    test_framework::{PropertyTestResult, TestResult, UnitTestResult},
+    test_framework::{BenchmarkResult, PropertyTestResult, TestResult, UnitTestResult},
     tipo,
 };
 use miette::{
@@ -114,6 +118,44 @@ pub enum Error {
     )]
     UnableToResolvePackage { package: Package },
 
+    #[error(
+        "I need to resolve a package {}/{} from {}, but couldn't find any Aiken project there.",
+        package.name.owner,
+        package.name.repo,
+        path.display(),
+    )]
+    MissingLocalPackage { package: Package, path: PathBuf },
+
+    #[error(
+        "I was unable to fetch '{}' for {}/{} from {url}",
+        package.version,
+        package.name.owner,
+        package.name.repo
+    )]
+    GitFetch {
+        package: Package,
+        url: String,
+        reason: String,
+    },
+
+    #[error(
+        "I found a different content for {}/{} at '{}' than the one recorded in the lockfile.",
+        package.name.owner,
+        package.name.repo,
+        package.version
+    )]
+    PackageChecksumMismatch {
+        package: Package,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("I couldn't find a version of {package} satisfying all requirements.")]
+    DependencyConflict {
+        package: PackageName,
+        requirements: Vec<Requirement>,
+    },
+
     #[error("I couldn't parse the provided stake address.")]
     MalformedStakeAddress {
         error: Option<pallas_addresses::Error>,
@@ -176,6 +218,11 @@ impl Error {
                 test.input_path.to_path_buf(),
                 test.program.to_pretty(),
             ),
+            TestResult::BenchmarkResult(BenchmarkResult { bench, .. }) => (
+                bench.name.to_string(),
+                bench.input_path.to_path_buf(),
+                bench.program.to_pretty(),
+            ),
         };
 
         Error::TestFailure {
@@ -221,6 +268,10 @@ impl ExtraData for Error {
             | Error::JoinError { .. }
             | Error::UnknownPackageVersion { .. }
             | Error::UnableToResolvePackage { .. }
+            | Error::MissingLocalPackage { .. }
+            | Error::GitFetch { .. }
+            | Error::PackageChecksumMismatch { .. }
+            | Error::DependencyConflict { .. }
             | Error::Json { .. }
             | Error::MalformedStakeAddress { .. }
             | Error::NoValidatorNotFound { .. }
@@ -252,6 +303,10 @@ impl GetSource for Error {
             | Error::JoinError(_)
             | Error::UnknownPackageVersion { .. }
             | Error::UnableToResolvePackage { .. }
+            | Error::MissingLocalPackage { .. }
+            | Error::GitFetch { .. }
+            | Error::PackageChecksumMismatch { .. }
+            | Error::DependencyConflict { .. }
             | Error::Json { .. }
             | Error::MalformedStakeAddress { .. }
             | Error::NoValidatorNotFound { .. }
@@ -284,6 +339,10 @@ impl GetSource for Error {
             | Error::JoinError(_)
             | Error::UnknownPackageVersion { .. }
             | Error::UnableToResolvePackage { .. }
+            | Error::MissingLocalPackage { .. }
+            | Error::GitFetch { .. }
+            | Error::PackageChecksumMismatch { .. }
+            | Error::DependencyConflict { .. }
             | Error::Json { .. }
             | Error::MalformedStakeAddress { .. }
             | Error::NoValidatorNotFound { .. }
@@ -339,6 +398,12 @@ impl Diagnostic for Error {
             Error::UnableToResolvePackage { .. } => {
                 Some(boxed(Box::new("aiken::package::download")))
             }
+            Error::MissingLocalPackage { .. } => Some(boxed(Box::new("aiken::packages::resolve"))),
+            Error::GitFetch { .. } => Some(boxed(Box::new("aiken::package::download"))),
+            Error::PackageChecksumMismatch { .. } => {
+                Some(boxed(Box::new("aiken::package::checksum")))
+            }
+            Error::DependencyConflict { .. } => Some(boxed(Box::new("aiken::packages::resolve"))),
             Error::Json { .. } => None,
             Error::MalformedStakeAddress { .. } => None,
             Error::NoValidatorNotFound { .. } => None,
@@ -393,6 +458,23 @@ impl Diagnostic for Error {
             Error::UnableToResolvePackage { .. } => Some(Box::new(
                 "The network is unavailable and the package isn't in the local cache either. Try connecting to the Internet so I can look it up?",
             )),
+            Error::MissingLocalPackage { .. } => Some(Box::new(
+                "Local dependencies are resolved relative to the project's root, and must point to a folder containing an 'aiken.toml'.",
+            )),
+            Error::GitFetch { reason, .. } => Some(Box::new(format!(
+                "Perhaps, double-check the repository URL and revision? Here's what git told me:\n\n{reason}"
+            ))),
+            Error::PackageChecksumMismatch { expected, actual, .. } => Some(Box::new(format!(
+                "The package has changed since it was locked; this can happen when a tag is moved. If that's expected, remove its checksum from 'aiken.lock' and clear the packages cache with 'aiken packages clear-cache'.\n\nexpected: {expected}\n     got: {actual}"
+            ))),
+            Error::DependencyConflict { requirements, .. } => Some(Box::new(format!(
+                "Here's what each package requires:\n\n{}\n\nVersion ranges follow semantic versioning (e.g. '^1.2' or '~0.4') and are matched against the package's tags.",
+                requirements
+                    .iter()
+                    .map(|requirement| format!("- {requirement}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ))),
             Error::Json(error) => Some(Box::new(format!("{error}"))),
             Error::MalformedStakeAddress { error } => Some(Box::new(format!(
                 "A stake address must be provided either as a base16-encoded string, or as a bech32-encoded string with the 'stake' or 'stake_test' prefix.{hint}",
@@ -454,6 +536,10 @@ impl Diagnostic for Error {
             Error::JoinError(_) => None,
             Error::UnknownPackageVersion { .. } => None,
             Error::UnableToResolvePackage { .. } => None,
+            Error::MissingLocalPackage { .. } => None,
+            Error::GitFetch { .. } => None,
+            Error::PackageChecksumMismatch { .. } => None,
+            Error::DependencyConflict { .. } => None,
             Error::Json { .. } => None,
             Error::MalformedStakeAddress { .. } => None,
             Error::NoValidatorNotFound { .. } => None,
@@ -485,6 +571,10 @@ impl Diagnostic for Error {
             Error::JoinError(_) => None,
             Error::UnknownPackageVersion { .. } => None,
             Error::UnableToResolvePackage { .. } => None,
+            Error::MissingLocalPackage { .. } => None,
+            Error::GitFetch { .. } => None,
+            Error::PackageChecksumMismatch { .. } => None,
+            Error::DependencyConflict { .. } => None,
             Error::Json { .. } => None,
             Error::MalformedStakeAddress { .. } => None,
             Error::NoValidatorNotFound { .. } => None,
@@ -513,6 +603,10 @@ impl Diagnostic for Error {
             Error::JoinError { .. } => None,
             Error::UnknownPackageVersion { .. } => None,
             Error::UnableToResolvePackage { .. } => None,
+            Error::MissingLocalPackage { .. } => None,
+            Error::GitFetch { .. } => None,
+            Error::PackageChecksumMismatch { .. } => None,
+            Error::DependencyConflict { .. } => None,
             Error::Json { .. } => None,
             Error::MalformedStakeAddress { .. } => None,
             Error::NoValidatorNotFound { .. } => None,
@@ -543,6 +637,10 @@ impl Diagnostic for Error {
             Error::JoinError { .. } => None,
             Error::UnknownPackageVersion { .. } => None,
             Error::UnableToResolvePackage { .. } => None,
+            Error::MissingLocalPackage { .. } => None,
+            Error::GitFetch { .. } => None,
+            Error::PackageChecksumMismatch { .. } => None,
+            Error::DependencyConflict { .. } => None,
             Error::Json { .. } => None,
             Error::MalformedStakeAddress { .. } => None,
             Error::NoValidatorNotFound { .. } => None,
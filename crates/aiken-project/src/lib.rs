@@ -7,10 +7,12 @@ pub mod export;
 pub mod format;
 pub mod github;
 pub mod module;
+pub mod module_cache;
 pub mod options;
 pub mod package_name;
 pub mod paths;
 pub mod pretty;
+pub mod replay;
 pub mod telemetry;
 pub mod watch;
 
@@ -21,24 +23,30 @@ mod tests;
 
 use crate::{
     blueprint::{
-        definitions::Definitions,
-        schema::{Annotated, Schema},
-        Blueprint,
+        definitions::{Definitions, Reference},
+        schema::{Annotated, Declaration, Schema},
+        Blueprint, SchemaTarget,
     },
     config::Config,
     error::{Error, Warning},
     module::{CheckedModule, CheckedModules, ParsedModule, ParsedModules},
+    module_cache::{CachedModule, ModuleCache},
+    replay::Replay,
     telemetry::Event,
 };
 use aiken_lang::{
     ast::{
-        self, DataTypeKey, Definition, FunctionAccessKey, ModuleKind, Tracing, TypedDataType,
-        TypedFunction, UntypedDefinition,
+        self, DataTypeKey, Definition, FunctionAccessKey, ModuleKind, OnTestFailure, Tracing,
+        TypedDataType, TypedDefinition, TypedFunction, TypedTest, UntypedDefinition,
     },
     builtins,
     expr::{TypedExpr, UntypedExpr},
     format::{Formatter, MAX_COLUMNS},
-    gen_uplc::CodeGenerator,
+    gen_uplc::{
+        coverage::{self, Coverage, Region},
+        profiler::{Metric, Profile},
+        CodeGenerator,
+    },
     line_numbers::LineNumbers,
     test_framework::{Test, TestResult},
     tipo::{Type, TypeInfo},
@@ -52,7 +60,7 @@ use package_name::PackageName;
 use pallas_addresses::{Address, Network, ShelleyAddress, ShelleyDelegationPart, StakePayload};
 use pallas_primitives::conway::PolicyId;
 use std::{
-    collections::{BTreeSet, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fs::{self, File},
     io::BufReader,
     path::{Path, PathBuf},
@@ -202,12 +210,13 @@ where
     pub fn build(
         &mut self,
         uplc: bool,
+        source_map: bool,
         tracing: Tracing,
         blueprint_path: PathBuf,
         env: Option<String>,
     ) -> Result<(), Vec<Error>> {
         let options = Options {
-            code_gen_mode: CodeGenMode::Build(uplc),
+            code_gen_mode: CodeGenMode::Build { uplc, source_map },
             tracing,
             env,
             blueprint_path,
@@ -274,6 +283,8 @@ where
         exact_match: bool,
         seed: u32,
         property_max_success: usize,
+        profile: bool,
+        coverage: bool,
         tracing: Tracing,
         env: Option<String>,
     ) -> Result<(), Vec<Error>> {
@@ -289,6 +300,8 @@ where
                     exact_match,
                     seed,
                     property_max_success,
+                    profile,
+                    coverage,
                 }
             },
             blueprint_path: self.blueprint_path(None),
@@ -297,6 +310,140 @@ where
         self.compile(options)
     }
 
+    pub fn benchmark(
+        &mut self,
+        match_benchmarks: Option<Vec<String>>,
+        exact_match: bool,
+        seed: u32,
+        max_size: usize,
+        tracing: Tracing,
+        env: Option<String>,
+    ) -> Result<(), Vec<Error>> {
+        let options = Options {
+            tracing,
+            env,
+            code_gen_mode: CodeGenMode::Benchmark {
+                match_benchmarks,
+                exact_match,
+                seed,
+                max_size,
+            },
+            blueprint_path: self.blueprint_path(None),
+        };
+
+        self.compile(options)
+    }
+
+    /// Write the source maps of all validators next to the blueprint, indexed by validator
+    /// title. Source maps refer to the validators' programs *before* any parameter is applied.
+    #[allow(clippy::result_large_err)]
+    pub fn dump_source_map(
+        &self,
+        blueprint: &Blueprint,
+        blueprint_path: &Path,
+    ) -> Result<(), Error> {
+        let path = blueprint_path.with_extension("sourcemap.json");
+
+        self.event_listener
+            .handle_event(Event::GeneratingSourceMap { path: path.clone() });
+
+        let source_maps = blueprint
+            .validators
+            .iter()
+            .map(|validator| (validator.title.clone(), &validator.source_map))
+            .collect::<BTreeMap<_, _>>();
+
+        let json = serde_json::to_string_pretty(&source_maps).unwrap();
+
+        fs::write(&path, json).map_err(|error| Error::FileIo { error, path })
+    }
+
+    /// Profile unit tests and write their costs as folded stacks, one file per metric, ready to be
+    /// turned into flame graphs.
+    #[allow(clippy::result_large_err)]
+    pub fn dump_profile(&self, tests: &[Test]) -> Result<(), Error> {
+        use rayon::prelude::*;
+
+        let dir = self.root.join(paths::profile());
+
+        self.event_listener
+            .handle_event(Event::ExportingProfile { path: dir.clone() });
+
+        fs::create_dir_all(&dir)?;
+
+        let plutus_version = &self.config.plutus;
+
+        let profile = tests
+            .iter()
+            .filter_map(|test| match test {
+                Test::UnitTest(unit_test) => Some(unit_test.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|unit_test| unit_test.profile(plutus_version))
+            .reduce(Profile::new, |mut profile, other| {
+                profile.merge(other);
+                profile
+            });
+
+        for (metric, file) in [(Metric::Cpu, "cpu.folded"), (Metric::Mem, "mem.folded")] {
+            let path = dir.join(file);
+            fs::write(&path, profile.to_folded(metric))
+                .map_err(|error| Error::FileIo { error, path })?;
+        }
+
+        Ok(())
+    }
+
+    /// Collect the coverage regions of the project's own modules.
+    pub fn coverage_regions(&self) -> Vec<Region> {
+        self.checked_modules
+            .values()
+            .filter(|checked_module| checked_module.package == self.config.name.to_string())
+            .flat_map(|checked_module| {
+                coverage::regions(&checked_module.name, checked_module.ast.definitions())
+            })
+            .collect()
+    }
+
+    /// Write the coverage of test runs as an lcov tracefile, one record per module.
+    #[allow(clippy::result_large_err)]
+    pub fn dump_coverage(&self, coverage: &Coverage, regions: &[Region]) -> Result<(), Error> {
+        let dir = self.root.join(paths::coverage());
+
+        let path = dir.join("lcov.info");
+
+        self.event_listener.handle_event(Event::ExportingCoverage {
+            path: path.clone(),
+            summary: coverage.summary(regions),
+        });
+
+        fs::create_dir_all(&dir)?;
+
+        let mut lcov = String::new();
+
+        for checked_module in self.checked_modules.values() {
+            let module_regions = regions
+                .iter()
+                .filter(|region| region.module == checked_module.name)
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if module_regions.is_empty() {
+                continue;
+            }
+
+            lcov.push_str(&coverage.to_lcov(
+                &checked_module.input_path,
+                &LineNumbers::new(&checked_module.code),
+                &module_regions,
+            ));
+        }
+
+        fs::write(&path, lcov).map_err(|error| Error::FileIo { error, path })
+    }
+
     pub fn dump_uplc(&self, blueprint: &Blueprint) -> Result<(), Error> {
         let dir = self.root.join("artifacts");
 
@@ -362,7 +509,10 @@ where
         self.type_check(&mut modules, options.tracing, env, true)?;
 
         match options.code_gen_mode {
-            CodeGenMode::Build(uplc_dump) => {
+            CodeGenMode::Build {
+                uplc: uplc_dump,
+                source_map,
+            } => {
                 self.event_listener
                     .handle_event(Event::GeneratingBlueprint {
                         path: options.blueprint_path.clone(),
@@ -374,6 +524,8 @@ where
 
                 let mut generator = self.new_generator(options.tracing);
 
+                generator.emit_source_map(source_map);
+
                 let blueprint = Blueprint::new(&self.config, &self.checked_modules, &mut generator)
                     .map_err(Error::Blueprint)?;
 
@@ -385,6 +537,10 @@ where
                     self.dump_uplc(&blueprint)?;
                 }
 
+                if source_map {
+                    self.dump_source_map(&blueprint, &options.blueprint_path)?;
+                }
+
                 let json = serde_json::to_string_pretty(&blueprint).unwrap();
 
                 fs::write(options.blueprint_path.as_path(), json).map_err(|error| {
@@ -401,9 +557,27 @@ where
                 exact_match,
                 seed,
                 property_max_success,
+                profile,
+                coverage,
             } => {
-                let tests =
-                    self.collect_tests(verbose, match_tests, exact_match, options.tracing)?;
+                let regions = if coverage {
+                    self.coverage_regions()
+                } else {
+                    Vec::new()
+                };
+
+                let tests = self.collect_tests(
+                    verbose,
+                    match_tests,
+                    exact_match,
+                    options.tracing,
+                    profile,
+                    &regions,
+                )?;
+
+                if profile {
+                    self.dump_profile(&tests)?;
+                }
 
                 if !tests.is_empty() {
                     self.event_listener.handle_event(Event::RunningTests);
@@ -433,9 +607,58 @@ where
                     })
                     .collect();
 
+                let coverage = coverage.then(|| {
+                    tests.iter().filter_map(|test| test.coverage()).fold(
+                        Coverage::new(),
+                        |mut coverage, test_coverage| {
+                            coverage.merge(test_coverage);
+                            coverage
+                        },
+                    )
+                });
+
                 self.event_listener
                     .handle_event(Event::FinishedTests { seed, tests });
 
+                if let Some(coverage) = coverage {
+                    self.dump_coverage(&coverage, &regions)?;
+                }
+
+                if !errors.is_empty() {
+                    Err(errors)
+                } else {
+                    Ok(())
+                }
+            }
+            CodeGenMode::Benchmark {
+                match_benchmarks,
+                exact_match,
+                seed,
+                max_size,
+            } => {
+                let benchmarks =
+                    self.collect_benchmarks(false, match_benchmarks, exact_match, options.tracing)?;
+
+                if !benchmarks.is_empty() {
+                    self.event_listener.handle_event(Event::RunningBenchmarks);
+                }
+
+                let benchmarks = self.run_benchmarks(benchmarks, seed, max_size);
+
+                let errors: Vec<Error> = benchmarks
+                    .iter()
+                    .filter_map(|e| {
+                        if e.is_success() {
+                            None
+                        } else {
+                            Some(Error::from_test_result(e, false))
+                        }
+                    })
+                    .collect();
+
+                self.event_listener
+                    .handle_event(Event::FinishedBenchmarks { seed, benchmarks });
+
                 if !errors.is_empty() {
                     Err(errors)
                 } else {
@@ -661,6 +884,163 @@ where
         Ok(blueprint)
     }
 
+    /// Encode a human-readable JSON value as Plutus data, following a schema of the blueprint.
+    #[allow(clippy::result_large_err)]
+    pub fn encode_value(
+        &self,
+        module_name: Option<&str>,
+        validator_name: Option<&str>,
+        handler_name: Option<&str>,
+        target: &SchemaTarget,
+        blueprint_path: &Path,
+        value: &serde_json::Value,
+    ) -> Result<PlutusData, Error> {
+        let (blueprint, schema) = self.lookup_schema(
+            module_name,
+            validator_name,
+            handler_name,
+            target,
+            blueprint_path,
+        )?;
+
+        Ok(blueprint::json::encode(
+            value,
+            &schema,
+            &blueprint.definitions,
+        )?)
+    }
+
+    /// Decode Plutus data into a human-readable JSON value, following a schema of the blueprint.
+    #[allow(clippy::result_large_err)]
+    pub fn decode_value(
+        &self,
+        module_name: Option<&str>,
+        validator_name: Option<&str>,
+        handler_name: Option<&str>,
+        target: &SchemaTarget,
+        blueprint_path: &Path,
+        data: &PlutusData,
+    ) -> Result<serde_json::Value, Error> {
+        let (blueprint, schema) = self.lookup_schema(
+            module_name,
+            validator_name,
+            handler_name,
+            target,
+            blueprint_path,
+        )?;
+
+        Ok(blueprint::json::decode(
+            data,
+            &schema,
+            &blueprint.definitions,
+        )?)
+    }
+
+    /// Check that some Plutus data matches a schema of the blueprint, reporting the path to the
+    /// first part of the data that doesn't.
+    #[allow(clippy::result_large_err)]
+    pub fn validate_value(
+        &self,
+        module_name: Option<&str>,
+        validator_name: Option<&str>,
+        handler_name: Option<&str>,
+        target: &SchemaTarget,
+        blueprint_path: &Path,
+        data: &PlutusData,
+    ) -> Result<(), Error> {
+        let (blueprint, schema) = self.lookup_schema(
+            module_name,
+            validator_name,
+            handler_name,
+            target,
+            blueprint_path,
+        )?;
+
+        Ok(blueprint::parameter::validate(
+            &schema,
+            &blueprint.definitions,
+            &Constant::Data(data.clone()),
+        )?)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn lookup_schema(
+        &self,
+        module_name: Option<&str>,
+        validator_name: Option<&str>,
+        handler_name: Option<&str>,
+        target: &SchemaTarget,
+        blueprint_path: &Path,
+    ) -> Result<(Blueprint, Declaration<Schema>), Error> {
+        // Read blueprint
+        let blueprint = File::open(blueprint_path)
+            .map_err(|_| blueprint::error::Error::InvalidOrMissingFile)?;
+        let blueprint: Blueprint = serde_json::from_reader(BufReader::new(blueprint))?;
+
+        if let SchemaTarget::Definition(key) = target {
+            let reference = Reference::new(key);
+
+            return match blueprint.definitions.lookup(&reference) {
+                Some(..) => Ok((blueprint, Declaration::Referenced(reference))),
+                None => Err(blueprint::error::Error::MissingSchema {
+                    target: target.to_string(),
+                }
+                .into()),
+            };
+        }
+
+        // Find the validator
+        let when_too_many =
+            |known_validators| Error::MoreThanOneValidatorFound { known_validators };
+        let when_missing = |known_validators| Error::NoValidatorNotFound { known_validators };
+
+        let title = blueprint.with_validator(
+            module_name,
+            validator_name,
+            when_too_many,
+            when_missing,
+            |validator| Ok(validator.title.clone()),
+        )?;
+
+        let prefix = |v: &str| v.split('.').take(2).collect::<Vec<&str>>().join(".");
+
+        // A validator may have several handlers, each with their own datum and redeemer.
+        let handlers = blueprint
+            .validators
+            .iter()
+            .filter(|validator| prefix(&validator.title) == prefix(&title))
+            .filter(|validator| {
+                handler_name.map_or(true, |handler| {
+                    validator.title.split('.').nth(2) == Some(handler)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let schema = match target {
+            SchemaTarget::Definition(..) => unreachable!("handled above"),
+            SchemaTarget::Datum => handlers
+                .iter()
+                .find_map(|validator| validator.datum.as_ref()),
+            SchemaTarget::Redeemer => handlers
+                .iter()
+                .find_map(|validator| validator.redeemer.as_ref()),
+            SchemaTarget::Parameter(index) => handlers
+                .iter()
+                .find_map(|validator| validator.parameters.get(*index)),
+        };
+
+        match schema {
+            Some(parameter) => {
+                let schema = parameter.schema.clone();
+                Ok((blueprint, schema))
+            }
+            None => Err(blueprint::error::Error::MissingSchema {
+                target: format!("{target} of '{}'", prefix(&title)),
+            }
+            .into()),
+        }
+    }
+
     fn with_dependencies(&mut self, parsed_packages: &mut ParsedModules) -> Result<(), Vec<Error>> {
         let manifest = deps::download(&self.event_listener, &self.root, &self.config)?;
 
@@ -683,10 +1063,9 @@ where
             parsed_modules
                 .par_iter_mut()
                 .for_each(|(_module, parsed_module)| {
-                    parsed_module
-                        .ast
-                        .definitions
-                        .retain(|def| !matches!(def, Definition::Test { .. }))
+                    parsed_module.ast.definitions.retain(|def| {
+                        !matches!(def, Definition::Test { .. } | Definition::Benchmark { .. })
+                    })
                 });
 
             parsed_packages.extend(Into::<HashMap<_, _>>::into(parsed_modules));
@@ -865,26 +1244,110 @@ where
 
         self.with_dependencies(modules)?;
 
+        let cache = ModuleCache::new(&self.root, tracing, env, validate_module_name);
+
+        let env_modules = modules.env_modules();
+
+        // Interface digests of the modules type-checked so far, by module name.
+        let mut interfaces: HashMap<String, String> = HashMap::new();
+
         for name in modules.sequence(&our_modules)? {
             if let Some(module) = modules.remove(&name) {
-                let (checked_module, warnings) = module.infer(
+                let mut dependencies = module
+                    .ast
+                    .dependencies(&env_modules)
+                    .into_iter()
+                    .map(|dependency| {
+                        let interface = interfaces.get(&dependency).cloned().unwrap_or_default();
+                        (dependency, interface)
+                    })
+                    .collect::<Vec<_>>();
+
+                dependencies.sort();
+                dependencies.dedup();
+
+                let fingerprint = ModuleCache::fingerprint(&module, &dependencies);
+
+                let report_warnings =
+                    our_modules.contains(module.name.as_str()) && module.name != ast::CONFIG_MODULE;
+
+                let cached = cache.load(
+                    &module.package,
+                    &module.name,
+                    &fingerprint,
                     &self.id_gen,
-                    &self.config.name.to_string(),
-                    tracing,
-                    env,
-                    validate_module_name,
-                    &mut self.module_sources,
-                    &mut self.module_types,
-                    &mut self.functions,
-                    &mut self.constants,
-                    &mut self.data_types,
-                )?;
+                    &self.module_types,
+                );
+
+                let checked_module = match cached {
+                    Some(cached) => {
+                        if report_warnings {
+                            self.warnings
+                                .extend(cached.warnings.into_iter().map(|warning| {
+                                    Warning::from_type_warning(
+                                        warning,
+                                        cached.module.input_path.clone(),
+                                        cached.module.code.clone(),
+                                    )
+                                }));
+                        }
 
-                if our_modules.contains(checked_module.name.as_str())
-                    && checked_module.name.as_str() != ast::CONFIG_MODULE
-                {
-                    self.warnings.extend(warnings);
-                }
+                        cached.module.register(
+                            &mut self.module_sources,
+                            &mut self.module_types,
+                            &mut self.functions,
+                            &mut self.constants,
+                            &mut self.data_types,
+                        );
+
+                        interfaces.insert(name, cached.interface);
+
+                        cached.module
+                    }
+                    None => {
+                        let first_id = self.id_gen.peek();
+
+                        let (checked_module, warnings) = module.infer(
+                            &self.id_gen,
+                            &self.config.name.to_string(),
+                            tracing,
+                            env,
+                            validate_module_name,
+                            &mut self.module_sources,
+                            &mut self.module_types,
+                            &mut self.functions,
+                            &mut self.constants,
+                            &mut self.data_types,
+                        )?;
+
+                        let interface =
+                            ModuleCache::interface(&checked_module.ast.type_info, &dependencies);
+
+                        interfaces.insert(name, interface.clone());
+
+                        cache.store(
+                            fingerprint,
+                            CachedModule {
+                                module: checked_module.clone(),
+                                interface,
+                                ids: first_id..self.id_gen.peek(),
+                                warnings: warnings
+                                    .iter()
+                                    .filter_map(|warning| match warning {
+                                        Warning::Type { warning, .. } => Some(warning.clone()),
+                                        _ => None,
+                                    })
+                                    .collect(),
+                            },
+                        );
+
+                        if report_warnings {
+                            self.warnings.extend(warnings);
+                        }
+
+                        checked_module
+                    }
+                };
 
                 self.checked_modules
                     .insert(checked_module.name.clone(), checked_module);
@@ -900,32 +1363,170 @@ where
         match_tests: Option<Vec<String>>,
         exact_match: bool,
         tracing: Tracing,
+        source_map: bool,
+        coverage: &[Region],
     ) -> Result<Vec<Test>, Error> {
-        let mut scripts = Vec::new();
+        let mut tests = self.collect_test_items(
+            verbose,
+            match_tests.clone(),
+            exact_match,
+            tracing,
+            source_map,
+            coverage,
+            |def| match def {
+                Definition::Test(func) => Some(func),
+                _ => None,
+            },
+            Test::from_function_definition,
+        )?;
 
-        let match_tests = match_tests.map(|mt| {
-            mt.into_iter()
-                .map(|match_test| {
-                    let mut match_split_dot = match_test.split('.');
+        tests.extend(self.collect_replays(
+            verbose,
+            match_tests,
+            exact_match,
+            tracing,
+            source_map,
+            coverage,
+        )?);
 
-                    let match_module = if match_test.contains('.') || match_test.contains('/') {
-                        match_split_dot.next().unwrap_or("")
-                    } else {
-                        ""
-                    };
+        Ok(tests)
+    }
+
+    /// Turn the replays found under the project's 'replays' folder into unit tests, each running
+    /// a validator against the script context of an existing transaction.
+    #[allow(clippy::result_large_err)]
+    fn collect_replays(
+        &mut self,
+        verbose: bool,
+        match_tests: Option<Vec<String>>,
+        exact_match: bool,
+        tracing: Tracing,
+        source_map: bool,
+        coverage: &[Region],
+    ) -> Result<Vec<Test>, Error> {
+        let dir = self.root.join(paths::replays());
 
-                    let match_names = match_split_dot.next().map(|names| {
-                        let names = names.replace(&['{', '}'][..], "");
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
 
-                        let names_split_comma = names.split(',');
+        let mut paths = fs::read_dir(&dir)
+            .map_err(|error| Error::FileIo {
+                error,
+                path: dir.clone(),
+            })?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect::<Vec<_>>();
 
-                        names_split_comma.map(str::to_string).collect()
-                    });
+        paths.sort();
+
+        let match_tests = match_tests.map(parse_match_tests);
+
+        let mut generator = self.new_generator(tracing);
 
-                    (match_module.to_string(), match_names)
+        generator.emit_source_map(source_map);
+
+        generator.instrument_coverage(coverage);
+
+        let mut tests = Vec::new();
+
+        for path in paths {
+            let replay = Replay::load(&path)?;
+
+            let name = replay.name();
+
+            let (module_name, validator_name) = replay.validator()?;
+
+            if let Some(match_tests) = &match_tests {
+                if !is_match_test(match_tests, module_name, &name, exact_match) {
+                    continue;
+                }
+            }
+
+            let validator = self
+                .checked_modules
+                .validators()
+                .find(|(module, validator)| {
+                    module.package == self.config.name.to_string()
+                        && module.name == module_name
+                        && validator.name == validator_name
                 })
-                .collect::<Vec<(String, Option<Vec<String>>)>>()
-        });
+                .map(|(_, validator)| validator)
+                .ok_or_else(|| {
+                    replay.invalid(format!(
+                        "I couldn't find any validator named '{validator_name}' in module '{module_name}'."
+                    ))
+                })?;
+
+            if verbose {
+                self.event_listener.handle_event(Event::GeneratingUPLCFor {
+                    name: name.clone(),
+                    path: path.clone(),
+                })
+            }
+
+            tests.push(Test::replay(
+                &mut generator,
+                validator,
+                module_name.to_string(),
+                path.clone(),
+                name,
+                if replay.fail {
+                    OnTestFailure::SucceedImmediately
+                } else {
+                    OnTestFailure::FailImmediately
+                },
+                replay.arguments(&self.config.plutus)?,
+            ));
+        }
+
+        Ok(tests)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn collect_benchmarks(
+        &mut self,
+        verbose: bool,
+        match_benchmarks: Option<Vec<String>>,
+        exact_match: bool,
+        tracing: Tracing,
+    ) -> Result<Vec<Test>, Error> {
+        self.collect_test_items(
+            verbose,
+            match_benchmarks,
+            exact_match,
+            tracing,
+            false,
+            &[],
+            |def| match def {
+                Definition::Benchmark(func) => Some(func),
+                _ => None,
+            },
+            Test::from_benchmark_definition,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[allow(clippy::result_large_err)]
+    fn collect_test_items<S, G>(
+        &mut self,
+        verbose: bool,
+        match_tests: Option<Vec<String>>,
+        exact_match: bool,
+        tracing: Tracing,
+        source_map: bool,
+        coverage: &[Region],
+        select: S,
+        generate: G,
+    ) -> Result<Vec<Test>, Error>
+    where
+        S: Fn(&TypedDefinition) -> Option<&TypedTest>,
+        G: Fn(&mut CodeGenerator<'_>, TypedTest, String, PathBuf) -> Test,
+    {
+        let mut scripts = Vec::new();
+
+        let match_tests = match_tests.map(parse_match_tests);
 
         for checked_module in self.checked_modules.values() {
             if checked_module.package != self.config.name.to_string() {
@@ -933,27 +1534,10 @@ where
             }
 
             for def in checked_module.ast.definitions() {
-                if let Definition::Test(func) = def {
+                if let Some(func) = select(def) {
                     if let Some(match_tests) = &match_tests {
-                        let is_match = match_tests.iter().any(|(module, names)| {
-                            let matched_module =
-                                module.is_empty() || checked_module.name.contains(module);
-
-                            let matched_name = match names {
-                                None => true,
-                                Some(names) => names.iter().any(|name| {
-                                    if exact_match {
-                                        name == &func.name
-                                    } else {
-                                        func.name.contains(name)
-                                    }
-                                }),
-                            };
-
-                            matched_module && matched_name
-                        });
-
-                        if is_match {
+                        if is_match_test(match_tests, &checked_module.name, &func.name, exact_match)
+                        {
                             scripts.push((
                                 checked_module.input_path.clone(),
                                 checked_module.name.clone(),
@@ -973,6 +1557,10 @@ where
 
         let mut generator = self.new_generator(tracing);
 
+        generator.emit_source_map(source_map);
+
+        generator.instrument_coverage(coverage);
+
         let mut tests = Vec::new();
 
         for (input_path, module_name, test) in scripts.into_iter() {
@@ -983,7 +1571,7 @@ where
                 })
             }
 
-            tests.push(Test::from_function_definition(
+            tests.push(generate(
                 &mut generator,
                 test.to_owned(),
                 module_name,
@@ -1013,6 +1601,9 @@ where
                 Test::PropertyTest(property_test) => {
                     property_test.run(seed, property_max_success, plutus_version)
                 }
+                Test::Benchmark(..) => {
+                    unreachable!("found a benchmark amongst tests; they're run by 'run_benchmarks'")
+                }
             })
             .collect::<Vec<TestResult<(Constant, Rc<Type>), PlutusData>>>()
             .into_iter()
@@ -1020,6 +1611,29 @@ where
             .collect()
     }
 
+    fn run_benchmarks(
+        &self,
+        benchmarks: Vec<Test>,
+        seed: u32,
+        max_size: usize,
+    ) -> Vec<TestResult<UntypedExpr, UntypedExpr>> {
+        use rayon::prelude::*;
+
+        let plutus_version = &self.config.plutus;
+
+        benchmarks
+            .into_par_iter()
+            .map(|bench| match bench {
+                Test::Benchmark(benchmark) => {
+                    TestResult::BenchmarkResult(benchmark.run(seed, max_size, plutus_version))
+                }
+                Test::UnitTest(..) | Test::PropertyTest(..) => {
+                    unreachable!("found a test amongst benchmarks; they're run by 'run_tests'")
+                }
+            })
+            .collect()
+    }
+
     fn aiken_files(&mut self, dir: &Path, kind: ModuleKind) -> Result<(), Error> {
         let mut has_default = None;
 
@@ -1109,6 +1723,57 @@ where
     }
 }
 
+/// Parse test patterns given on the command-line, of the form `module.{name1,name2}`, `module`
+/// or `name`, into a module and an optional list of names.
+fn parse_match_tests(match_tests: Vec<String>) -> Vec<(String, Option<Vec<String>>)> {
+    match_tests
+        .into_iter()
+        .map(|match_test| {
+            let mut match_split_dot = match_test.split('.');
+
+            let match_module = if match_test.contains('.') || match_test.contains('/') {
+                match_split_dot.next().unwrap_or("")
+            } else {
+                ""
+            };
+
+            let match_names = match_split_dot.next().map(|names| {
+                let names = names.replace(&['{', '}'][..], "");
+
+                let names_split_comma = names.split(',');
+
+                names_split_comma.map(str::to_string).collect()
+            });
+
+            (match_module.to_string(), match_names)
+        })
+        .collect()
+}
+
+fn is_match_test(
+    match_tests: &[(String, Option<Vec<String>>)],
+    module_name: &str,
+    test_name: &str,
+    exact_match: bool,
+) -> bool {
+    match_tests.iter().any(|(module, names)| {
+        let matched_module = module.is_empty() || module_name.contains(module);
+
+        let matched_name = match names {
+            None => true,
+            Some(names) => names.iter().any(|name| {
+                if exact_match {
+                    name == test_name
+                } else {
+                    test_name.contains(name)
+                }
+            }),
+        };
+
+        matched_module && matched_name
+    })
+}
+
 fn is_aiken_path(path: &Path, dir: impl AsRef<Path>) -> bool {
     use regex::Regex;
 
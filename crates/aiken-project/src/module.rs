@@ -1,9 +1,9 @@
 use crate::{Error, Warning};
 use aiken_lang::{
     ast::{
-        DataType, DataTypeKey, Definition, Function, FunctionAccessKey, Located, ModuleKind,
-        Tracing, TypedDataType, TypedFunction, TypedModule, TypedValidator, UntypedModule,
-        Validator,
+        DataType, DataTypeKey, Definition, Function, FunctionAccessKey, Instance, Interface,
+        Located, ModuleKind, Tracing, TypedDataType, TypedFunction, TypedModule, TypedValidator,
+        UntypedModule, Validator,
     },
     expr::TypedExpr,
     line_numbers::LineNumbers,
@@ -83,31 +83,25 @@ impl ParsedModule {
             ast.validate_module_name()?;
         }
 
-        // Register module sources for an easier access later.
-        module_sources.insert(
-            self.name.clone(),
-            (self.code.clone(), LineNumbers::new(&self.code)),
+        let checked_module = CheckedModule {
+            ast,
+            kind: self.kind,
+            extra: self.extra,
+            name: self.name,
+            code: self.code,
+            package: self.package,
+            input_path: self.path,
+        };
+
+        checked_module.register(
+            module_sources,
+            module_types,
+            functions,
+            constants,
+            data_types,
         );
 
-        // Register the types from this module so they can be
-        // imported into other modules.
-        module_types.insert(self.name.clone(), ast.type_info.clone());
-
-        // Register function definitions & data-types for easier access later.
-        ast.register_definitions(functions, constants, data_types);
-
-        Ok((
-            CheckedModule {
-                ast,
-                kind: self.kind,
-                extra: self.extra,
-                name: self.name,
-                code: self.code,
-                package: self.package,
-                input_path: self.path,
-            },
-            warnings,
-        ))
+        Ok((checked_module, warnings))
     }
 }
 
@@ -118,15 +112,19 @@ impl ParsedModules {
         Self(HashMap::new())
     }
 
-    pub fn sequence(&self, our_modules: &BTreeSet<String>) -> Result<Vec<String>, Error> {
-        let env_modules = self
-            .0
+    /// Names of the environment modules, which are all dependencies of the 'env' module.
+    pub fn env_modules(&self) -> Vec<String> {
+        self.0
             .values()
             .filter_map(|m| match m.kind {
                 ModuleKind::Env => Some(m.name.clone()),
                 ModuleKind::Lib | ModuleKind::Validator | ModuleKind::Config => None,
             })
-            .collect::<Vec<String>>();
+            .collect()
+    }
+
+    pub fn sequence(&self, our_modules: &BTreeSet<String>) -> Result<Vec<String>, Error> {
+        let env_modules = self.env_modules();
 
         let inputs = self
             .0
@@ -302,6 +300,30 @@ impl CheckedModule {
             == "@hidden"
     }
 
+    /// Make the module available to the modules depending on it, and to code generation.
+    pub fn register(
+        &self,
+        module_sources: &mut HashMap<String, (String, LineNumbers)>,
+        module_types: &mut HashMap<String, TypeInfo>,
+        functions: &mut IndexMap<FunctionAccessKey, TypedFunction>,
+        constants: &mut IndexMap<FunctionAccessKey, TypedExpr>,
+        data_types: &mut IndexMap<DataTypeKey, TypedDataType>,
+    ) {
+        // Register module sources for an easier access later.
+        module_sources.insert(
+            self.name.clone(),
+            (self.code.clone(), LineNumbers::new(&self.code)),
+        );
+
+        // Register the types from this module so they can be
+        // imported into other modules.
+        module_types.insert(self.name.clone(), self.ast.type_info.clone());
+
+        // Register function definitions & data-types for easier access later.
+        self.ast
+            .register_definitions(functions, constants, data_types);
+    }
+
     pub fn to_cbor(&self) -> Vec<u8> {
         let mut module_bytes = vec![];
 
@@ -431,6 +453,26 @@ impl CheckedModule {
                         }
                     }
                 }
+                Definition::Interface(Interface { methods, .. }) => {
+                    for method in methods {
+                        let docs: Vec<&str> =
+                            comments_before(&mut doc_comments, method.location.start, &self.code);
+
+                        if !docs.is_empty() {
+                            method.doc = Some(docs.join("\n"));
+                        }
+                    }
+                }
+                Definition::Instance(Instance { methods, .. }) => {
+                    for method in methods {
+                        let docs: Vec<&str> =
+                            comments_before(&mut doc_comments, method.location.start, &self.code);
+
+                        if !docs.is_empty() {
+                            method.doc = Some(docs.join("\n"));
+                        }
+                    }
+                }
                 _ => (),
             }
         }
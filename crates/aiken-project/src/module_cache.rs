@@ -0,0 +1,373 @@
+//! Type-checked modules, cached across compilations under the project's build folder.
+//!
+//! A module is only type-checked again when its source changes, or when the interface (i.e. the
+//! types and values it exports) of any of its dependencies changes, directly or transitively. Both
+//! are captured by a module's fingerprint; cached modules are looked up by fingerprint, and any
+//! failure to read or write the cache merely results in type-checking the module again.
+//!
+//! Where definitions are located isn't part of interfaces, so that editing the body of a function
+//! doesn't invalidate every module depending on it. Instead, references to definitions of other
+//! modules are pointed at their current location when restoring a module.
+
+use crate::{module::CheckedModule, module::ParsedModule, paths};
+use aiken_lang::{
+    ast::Tracing,
+    tipo::{error::Warning, TypeInfo},
+    version::compiler_version,
+    IdGenerator,
+};
+use ciborium::value::{Integer, Value};
+use pallas_crypto::hash::Hasher;
+use std::{
+    collections::HashMap,
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Entry {
+    fingerprint: String,
+    interface: String,
+    ids: Range<u64>,
+    module: CheckedModule,
+    warnings: Vec<Warning>,
+}
+
+/// A module restored from the cache.
+pub struct CachedModule {
+    pub module: CheckedModule,
+    /// Digest of the module's interface, and those of its dependencies.
+    pub interface: String,
+    /// Ids generated while type-checking the module.
+    pub ids: Range<u64>,
+    /// Warnings raised while type-checking the module, to be reported again.
+    pub warnings: Vec<Warning>,
+}
+
+pub struct ModuleCache {
+    root: PathBuf,
+}
+
+impl ModuleCache {
+    /// The cache of modules type-checked with the given options. Each set of options (e.g. with
+    /// or without traces, as for 'aiken check' and 'aiken build') has its own cache, so that
+    /// alternating between them doesn't invalidate modules every time.
+    pub fn new(
+        root: &Path,
+        tracing: Tracing,
+        env: Option<&str>,
+        validate_module_name: bool,
+    ) -> Self {
+        let options = format!(
+            "{} {tracing:?} {env:?} {validate_module_name}",
+            compiler_version(true)
+        );
+
+        let key = Hasher::<224>::hash(options.as_bytes()).to_string();
+
+        ModuleCache {
+            root: root.join(paths::module_cache()).join(key),
+        }
+    }
+
+    /// Fingerprint of a module, after its source and the interface digests of its dependencies.
+    pub fn fingerprint(module: &ParsedModule, dependencies: &[(String, String)]) -> String {
+        let mut hasher = Hasher::<256>::new();
+
+        for input in [
+            module.package.as_str(),
+            module.name.as_str(),
+            &format!("{:?}", module.kind),
+            &module.path.to_string_lossy(),
+            module.code.as_str(),
+        ] {
+            hash_input(&mut hasher, input.as_bytes());
+        }
+
+        for (name, interface) in dependencies {
+            hash_input(&mut hasher, name.as_bytes());
+            hash_input(&mut hasher, interface.as_bytes());
+        }
+
+        hasher.finalize().to_string()
+    }
+
+    /// Digest of a module's interface, along with those of its dependencies so that changes
+    /// propagate to modules depending on it only indirectly.
+    ///
+    /// Type variables are numbered after their first occurrence, as their ids depend on the
+    /// modules type-checked before; as do entries of maps, whose order is arbitrary. Locations are
+    /// left out.
+    pub fn interface(type_info: &TypeInfo, dependencies: &[(String, String)]) -> String {
+        let mut hasher = Hasher::<256>::new();
+
+        let type_info =
+            Value::serialized(type_info).expect("module interfaces should not fail to serialize");
+
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&canonical(type_info, &mut HashMap::new()), &mut bytes)
+            .expect("module interfaces should not fail to serialize");
+
+        hash_input(&mut hasher, &bytes);
+
+        for (name, interface) in dependencies {
+            hash_input(&mut hasher, name.as_bytes());
+            hash_input(&mut hasher, interface.as_bytes());
+        }
+
+        hasher.finalize().to_string()
+    }
+
+    /// Restore a module, provided it was cached with the given fingerprint.
+    ///
+    /// The module's type variables are renumbered past the ids generated so far, since modules
+    /// type-checked before it may have been given the same ids as it was when cached. References to
+    /// definitions of other modules are pointed at where those definitions now are.
+    pub fn load(
+        &self,
+        package: &str,
+        name: &str,
+        fingerprint: &str,
+        id_gen: &IdGenerator,
+        module_types: &HashMap<String, TypeInfo>,
+    ) -> Option<CachedModule> {
+        let bytes = fs::read(self.path(package, name)).ok()?;
+
+        let mut entry: Value = ciborium::from_reader(bytes.as_slice()).ok()?;
+
+        let fields = entry.as_map()?;
+
+        if field(fields, "fingerprint")?.as_text()? != fingerprint {
+            return None;
+        }
+
+        let ids: Range<u64> = field(fields, "ids")?.deserialized().ok()?;
+
+        let start = id_gen.peek();
+
+        refresh(&mut entry, name, &ids, start, module_types);
+
+        let entry: Entry = entry.deserialized().ok()?;
+
+        let end = start + ids.end.saturating_sub(ids.start);
+
+        id_gen.skip_to(end);
+
+        Some(CachedModule {
+            module: entry.module,
+            interface: entry.interface,
+            ids: start..end,
+            warnings: entry.warnings,
+        })
+    }
+
+    pub fn store(&self, fingerprint: String, cached_module: CachedModule) {
+        let path = self.path(&cached_module.module.package, &cached_module.module.name);
+
+        let entry = Entry {
+            fingerprint,
+            interface: cached_module.interface,
+            ids: cached_module.ids,
+            module: cached_module.module,
+            warnings: cached_module.warnings,
+        };
+
+        let mut bytes = Vec::new();
+
+        if ciborium::into_writer(&entry, &mut bytes).is_ok() {
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+
+            let _ = fs::write(path, bytes);
+        }
+    }
+
+    fn path(&self, package: &str, name: &str) -> PathBuf {
+        self.root.join(package).join(name).with_extension("cbor")
+    }
+}
+
+/// Hash a length-prefixed input, so that consecutive inputs can't be confused with one another.
+fn hash_input(hasher: &mut Hasher<256>, bytes: &[u8]) {
+    hasher.input(&(bytes.len() as u64).to_be_bytes());
+    hasher.input(bytes);
+}
+
+fn canonical(value: Value, type_vars: &mut HashMap<u64, u64>) -> Value {
+    match value {
+        Value::Map(entries) if is_span(&entries) => Value::Null,
+        Value::Map(entries) => {
+            let mut entries = entries
+                .into_iter()
+                .map(|(key, value)| {
+                    let mut bytes = Vec::new();
+                    let _ = ciborium::into_writer(&key, &mut bytes);
+                    (bytes, key, value)
+                })
+                .collect::<Vec<_>>();
+
+            entries.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+
+            let is_type_var = matches!(
+                entries.as_slice(),
+                [(_, Value::Text(tag), _)] if tag == "Unbound" || tag == "Generic"
+            );
+
+            Value::Map(
+                entries
+                    .into_iter()
+                    .map(|(_, key, value)| match value {
+                        Value::Map(fields) if is_type_var => (
+                            key,
+                            Value::Map(
+                                fields
+                                    .into_iter()
+                                    .map(|(field, id)| match (&field, &id) {
+                                        (Value::Text(name), Value::Integer(n)) if name == "id" => {
+                                            let n = u64::try_from(*n).unwrap_or_default();
+                                            let next = type_vars.len() as u64;
+                                            let id = *type_vars.entry(n).or_insert(next);
+                                            (field, Value::Integer(Integer::from(id)))
+                                        }
+                                        _ => (field, id),
+                                    })
+                                    .collect(),
+                            ),
+                        ),
+                        value => (canonical(key, type_vars), canonical(value, type_vars)),
+                    })
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| canonical(item, type_vars))
+                .collect(),
+        ),
+        Value::Tag(tag, value) => Value::Tag(tag, Box::new(canonical(*value, type_vars))),
+        value => value,
+    }
+}
+
+fn is_span(entries: &[(Value, Value)]) -> bool {
+    matches!(
+        entries,
+        [(Value::Text(start), Value::Integer(_)), (Value::Text(end), Value::Integer(_))]
+            if start == "start" && end == "end"
+    )
+}
+
+/// Renumber the type variables of a cached module, from the ids it was given to ids starting at
+/// `start`, and point references to definitions of other modules at their current location.
+fn refresh(
+    value: &mut Value,
+    module: &str,
+    ids: &Range<u64>,
+    start: u64,
+    module_types: &HashMap<String, TypeInfo>,
+) {
+    match value {
+        Value::Map(entries) => {
+            if let [(Value::Text(tag), Value::Map(fields))] = entries.as_mut_slice() {
+                if tag == "Unbound" || tag == "Generic" {
+                    for (field, id) in fields.iter_mut() {
+                        if let (Value::Text(name), Value::Integer(n)) = (&*field, &*id) {
+                            if name == "id" {
+                                let n = u64::try_from(*n).unwrap_or_default();
+                                if ids.contains(&n) {
+                                    *id = Value::Integer(Integer::from(n - ids.start + start));
+                                }
+                            }
+                        }
+                    }
+                    return;
+                }
+            }
+
+            if let (Some(other), Some(name)) = (text(entries, "module"), text(entries, "name")) {
+                if other != module {
+                    relocate(entries, &other, &name, module_types);
+                }
+            }
+
+            // NOTE: Records selected from a module (e.g. `option.Some`) don't carry the module
+            // they belong to; it is that of the enclosing selection.
+            if let Some(other) = text(entries, "module_name") {
+                for (key, constructor) in entries.iter_mut() {
+                    let fields = match (key, constructor) {
+                        (Value::Text(key), Value::Map(constructor)) if key == "constructor" => {
+                            match constructor.as_mut_slice() {
+                                [(Value::Text(tag), Value::Map(fields))] if tag == "Record" => {
+                                    fields
+                                }
+                                _ => continue,
+                            }
+                        }
+                        _ => continue,
+                    };
+
+                    if let Some(name) = text(fields, "name") {
+                        relocate(fields, &other, &name, module_types);
+                    }
+                }
+            }
+
+            for (_, value) in entries.iter_mut() {
+                refresh(value, module, ids, start, module_types);
+            }
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| refresh(item, module, ids, start, module_types)),
+        Value::Tag(_, value) => refresh(value, module, ids, start, module_types),
+        _ => {}
+    }
+}
+
+/// Update the location (and fields) of a reference to a value of another module, after the
+/// current definition of that value.
+fn relocate(
+    fields: &mut [(Value, Value)],
+    module: &str,
+    name: &str,
+    module_types: &HashMap<String, TypeInfo>,
+) {
+    let Some(constructor) = module_types
+        .get(module)
+        .and_then(|type_info| type_info.values.get(name))
+    else {
+        return;
+    };
+
+    let Ok(Value::Map(variant)) = Value::serialized(&constructor.variant) else {
+        return;
+    };
+
+    let Some((_, Value::Map(current))) = variant.into_iter().next() else {
+        return;
+    };
+
+    for (key, value) in fields.iter_mut() {
+        if !matches!(key, Value::Text(key) if key == "location" || key == "field_map") {
+            continue;
+        }
+
+        if let Some((_, current)) = current.iter().find(|(field, _)| field == key) {
+            *value = current.clone();
+        }
+    }
+}
+
+fn field<'a>(entries: &'a [(Value, Value)], key: &str) -> Option<&'a Value> {
+    entries.iter().find_map(|(k, value)| match k {
+        Value::Text(k) if k == key => Some(value),
+        _ => None,
+    })
+}
+
+fn text(entries: &[(Value, Value)], key: &str) -> Option<String> {
+    field(entries, key)?.as_text().map(str::to_string)
+}
@@ -26,7 +26,18 @@ pub enum CodeGenMode {
         exact_match: bool,
         seed: u32,
         property_max_success: usize,
+        profile: bool,
+        coverage: bool,
+    },
+    Benchmark {
+        match_benchmarks: Option<Vec<String>>,
+        exact_match: bool,
+        seed: u32,
+        max_size: usize,
+    },
+    Build {
+        uplc: bool,
+        source_map: bool,
     },
-    Build(bool),
     NoOp,
 }
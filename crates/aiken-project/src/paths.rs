@@ -5,14 +5,22 @@ use crate::{
     package_name::PackageName,
     telemetry::{Event, EventListener},
 };
+use pallas_crypto::hash::Hasher;
 use regex::Regex;
 use reqwest::Client;
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 pub fn project_config() -> PathBuf {
     PathBuf::from("aiken.toml")
 }
 
+pub fn replays() -> PathBuf {
+    PathBuf::from("replays")
+}
+
 pub fn manifest() -> PathBuf {
     PathBuf::from("aiken.lock")
 }
@@ -21,6 +29,18 @@ pub fn build() -> PathBuf {
     PathBuf::from("build")
 }
 
+pub fn profile() -> PathBuf {
+    build().join("profile")
+}
+
+pub fn module_cache() -> PathBuf {
+    build().join("cache")
+}
+
+pub fn coverage() -> PathBuf {
+    build().join("coverage")
+}
+
 pub fn packages() -> PathBuf {
     build().join("packages")
 }
@@ -29,6 +49,18 @@ pub fn packages_toml() -> PathBuf {
     packages().join("packages.toml")
 }
 
+pub fn vendor() -> PathBuf {
+    PathBuf::from("vendor")
+}
+
+pub fn vendor_toml() -> PathBuf {
+    vendor().join("packages.toml")
+}
+
+pub fn vendor_package(package_name: &PackageName) -> PathBuf {
+    vendor().join(format!("{}-{}", package_name.owner, package_name.repo))
+}
+
 pub fn build_deps_package(package_name: &PackageName) -> PathBuf {
     packages().join(format!("{}-{}", package_name.owner, package_name.repo))
 }
@@ -37,10 +69,33 @@ pub fn package_cache_zipball(cache_key: &CacheKey) -> PathBuf {
     packages_cache().join(format!("{}.zip", cache_key.get_key()))
 }
 
+/// A clone of a git repository within the given git cache, shared by all packages fetched from the
+/// same URL. The name of the repository is kept for readability, but the clone is told apart by a
+/// digest of its URL.
+pub fn package_cache_git(git_cache: &Path, url: &str) -> PathBuf {
+    let name = url
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect::<String>();
+
+    let digest = Hasher::<256>::hash(url.as_bytes()).to_string();
+
+    git_cache.join(format!("{name}-{}", &digest[..16]))
+}
+
 pub fn packages_cache() -> PathBuf {
     default_aiken_cache().join("packages")
 }
 
+pub fn packages_cache_git() -> PathBuf {
+    packages_cache().join("git")
+}
+
 pub fn default_aiken_cache() -> PathBuf {
     dirs::cache_dir()
         .expect("Failed to determine user cache directory")
@@ -197,4 +252,26 @@ mod tests {
         assert!(!is_git_sha_or_tag("main"), "main branch");
         assert!(!is_git_sha_or_tag("8ba594659468ba"), "not sha");
     }
+
+    #[test]
+    fn test_package_cache_git() {
+        let cache = |url: &str| {
+            package_cache_git(Path::new("git"), url)
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .to_string()
+        };
+
+        assert!(cache("https://github.com/acme/stdlib.git").starts_with("stdlib-"));
+        assert!(cache("git@gitlab.com:acme/stdlib").starts_with("stdlib-"));
+        assert_eq!(
+            cache("https://github.com/acme/stdlib.git"),
+            cache("https://github.com/acme/stdlib.git")
+        );
+        assert_ne!(
+            cache("https://github.com/acme/stdlib.git"),
+            cache("https://github.com/fork/stdlib.git")
+        );
+    }
 }
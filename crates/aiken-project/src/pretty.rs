@@ -180,6 +180,26 @@ pub fn multiline(max_len: usize, s: String) -> Vec<String> {
     xs
 }
 
+/// Plot a series of values as a single line of unicode blocks, scaled between the
+/// smallest and largest values of the series.
+pub fn sparkline(xs: &[i64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let lo = xs.iter().copied().min().unwrap_or_default();
+    let hi = xs.iter().copied().max().unwrap_or_default();
+
+    xs.iter()
+        .map(|x| {
+            if hi == lo {
+                BARS[0]
+            } else {
+                let ix = ((x - lo) as f64 / (hi - lo) as f64 * (BARS.len() - 1) as f64).round();
+                BARS[ix as usize]
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -199,6 +219,21 @@ mod tests {
         assert_eq!(multiline(3, "foo".to_string()), vec!["foo".to_string()]);
     }
 
+    #[test]
+    fn sparkline_empty() {
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn sparkline_constant() {
+        assert_eq!(sparkline(&[42, 42, 42]), "▁▁▁");
+    }
+
+    #[test]
+    fn sparkline_increasing() {
+        assert_eq!(sparkline(&[0, 1, 2, 3, 4, 5, 6, 7]), "▁▂▃▄▅▆▇█");
+    }
+
     #[test]
     fn multiline_many_lines() {
         assert_eq!(
@@ -0,0 +1,282 @@
+use crate::error::Error;
+use aiken_lang::{ast::Span, plutus_version::PlutusVersion};
+use miette::NamedSource;
+use pallas_primitives::{
+    conway::{Redeemer, RedeemerTag, TransactionInput, TransactionOutput},
+    Fragment,
+};
+use pallas_traverse::{Era, MultiEraTx};
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+use uplc::{
+    tx::{
+        eval::script_arguments, iter_redeemers, script_context::ResolvedInput, DataLookupTable,
+        SlotConfig,
+    },
+    PlutusData,
+};
+
+/// A transaction to replay against one of the project's validators, as a regression test.
+///
+/// Replays are described by TOML files under the project's 'replays' folder. The transaction,
+/// its resolved inputs and the validator's parameters are hex-encoded CBOR, as accepted by
+/// 'aiken tx simulate' and 'aiken blueprint apply'. For example:
+///
+/// ```toml
+/// validator = "vault.vault"
+/// redeemer = { purpose = "spend", index = 0 }
+/// transaction = "84a400..."
+/// inputs = "81825820..."
+/// outputs = "81a300..."
+/// ```
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Replay {
+    /// The validator to run, as '{module}.{validator}'.
+    pub validator: String,
+
+    /// The redeemer of the transaction to run the validator for.
+    pub redeemer: RedeemerPointer,
+
+    /// The transaction, as hex-encoded CBOR.
+    pub transaction: String,
+
+    /// The inputs (and reference inputs) of the transaction, as a hex-encoded CBOR list.
+    pub inputs: String,
+
+    /// The outputs resolving each of the inputs, in the same order, as a hex-encoded CBOR list.
+    pub outputs: String,
+
+    /// Parameters to apply to the validator, each as hex-encoded CBOR Plutus data.
+    #[serde(default)]
+    pub parameters: Vec<String>,
+
+    /// Whether the validator is expected to reject the transaction.
+    #[serde(default)]
+    pub fail: bool,
+
+    /// Slot configuration of the network the transaction is for. Defaults to mainnet's.
+    #[serde(default)]
+    pub slot_config: SlotConfigOverrides,
+
+    #[serde(skip)]
+    path: PathBuf,
+
+    #[serde(skip)]
+    src: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RedeemerPointer {
+    pub purpose: Purpose,
+    pub index: u32,
+}
+
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Purpose {
+    Spend,
+    Mint,
+    Withdraw,
+    Publish,
+    Vote,
+    Propose,
+}
+
+impl fmt::Display for Purpose {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Purpose::Spend => "spend",
+            Purpose::Mint => "mint",
+            Purpose::Withdraw => "withdraw",
+            Purpose::Publish => "publish",
+            Purpose::Vote => "vote",
+            Purpose::Propose => "propose",
+        })
+    }
+}
+
+impl From<Purpose> for RedeemerTag {
+    fn from(purpose: Purpose) -> Self {
+        match purpose {
+            Purpose::Spend => RedeemerTag::Spend,
+            Purpose::Mint => RedeemerTag::Mint,
+            Purpose::Withdraw => RedeemerTag::Reward,
+            Purpose::Publish => RedeemerTag::Cert,
+            Purpose::Vote => RedeemerTag::Vote,
+            Purpose::Propose => RedeemerTag::Propose,
+        }
+    }
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SlotConfigOverrides {
+    pub slot_length: Option<u32>,
+    pub zero_slot: Option<u64>,
+    pub zero_time: Option<u64>,
+}
+
+impl From<&SlotConfigOverrides> for SlotConfig {
+    fn from(overrides: &SlotConfigOverrides) -> Self {
+        let default = SlotConfig::default();
+        SlotConfig {
+            slot_length: overrides.slot_length.unwrap_or(default.slot_length),
+            zero_slot: overrides.zero_slot.unwrap_or(default.zero_slot),
+            zero_time: overrides.zero_time.unwrap_or(default.zero_time),
+        }
+    }
+}
+
+impl Replay {
+    #[allow(clippy::result_large_err)]
+    pub fn load(path: &Path) -> Result<Replay, Error> {
+        let src = fs::read_to_string(path).map_err(|error| Error::FileIo {
+            error,
+            path: path.to_path_buf(),
+        })?;
+
+        let replay: Replay = toml::from_str(&src).map_err(|e| Error::TomlLoading {
+            path: path.to_path_buf(),
+            src: src.clone(),
+            named: NamedSource::new(path.display().to_string(), src.clone()).into(),
+            location: e.span().map(|range| Span {
+                start: range.start,
+                end: range.end,
+            }),
+            help: e.to_string(),
+        })?;
+
+        Ok(Replay {
+            path: path.to_path_buf(),
+            src,
+            ..replay
+        })
+    }
+
+    /// Name of the replay, after its file.
+    pub fn name(&self) -> String {
+        self.path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Split the validator's designation into its module and name.
+    #[allow(clippy::result_large_err)]
+    pub fn validator(&self) -> Result<(&str, &str), Error> {
+        self.validator
+            .rsplit_once('.')
+            .ok_or_else(|| self.invalid(format!(
+                "I expected the validator to be designated as '{{module}}.{{validator}}', but found '{}'.",
+                self.validator
+            )))
+    }
+
+    /// The arguments to apply to the validator: its parameters, followed by what it receives
+    /// when evaluated for the replayed redeemer.
+    #[allow(clippy::result_large_err)]
+    pub fn arguments(&self, plutus_version: &PlutusVersion) -> Result<Vec<PlutusData>, Error> {
+        let mut arguments = self
+            .parameters
+            .iter()
+            .enumerate()
+            .map(|(ix, parameter)| {
+                let bytes = self.decode_hex(&format!("parameter #{ix}"), parameter)?;
+                uplc::plutus_data(&bytes).map_err(|e| {
+                    self.invalid(format!("Parameter #{ix} isn't valid Plutus data: {e}"))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let tx_bytes = self.decode_hex("transaction", &self.transaction)?;
+        let inputs_bytes = self.decode_hex("inputs", &self.inputs)?;
+        let outputs_bytes = self.decode_hex("outputs", &self.outputs)?;
+
+        let tx = MultiEraTx::decode_for_era(Era::Conway, &tx_bytes)
+            .map_err(|e| self.invalid(format!("I couldn't decode the transaction: {e}")))?;
+
+        let tx = tx.as_conway().ok_or_else(|| {
+            self.invalid("I can only replay transactions from the Conway era onwards.".to_string())
+        })?;
+
+        let inputs = Vec::<TransactionInput>::decode_fragment(&inputs_bytes)
+            .map_err(|e| self.invalid(format!("I couldn't decode the inputs: {e}")))?;
+
+        let outputs = Vec::<TransactionOutput>::decode_fragment(&outputs_bytes)
+            .map_err(|e| self.invalid(format!("I couldn't decode the outputs: {e}")))?;
+
+        if inputs.len() != outputs.len() {
+            return Err(self.invalid(format!(
+                "I found {} input(s) but {} output(s); each input must be resolved by exactly one output.",
+                inputs.len(),
+                outputs.len()
+            )));
+        }
+
+        let utxos = inputs
+            .into_iter()
+            .zip(outputs)
+            .map(|(input, output)| ResolvedInput { input, output })
+            .collect::<Vec<_>>();
+
+        let tag = RedeemerTag::from(self.redeemer.purpose);
+
+        let redeemer = tx
+            .transaction_witness_set
+            .redeemer
+            .as_ref()
+            .and_then(|redeemers| {
+                iter_redeemers(redeemers).find_map(|(key, data, ex_units)| {
+                    (key.tag == tag && key.index == self.redeemer.index).then(|| Redeemer {
+                        tag: key.tag,
+                        index: key.index,
+                        data: data.clone(),
+                        ex_units,
+                    })
+                })
+            })
+            .ok_or_else(|| {
+                self.invalid(format!(
+                    "The transaction has no '{}' redeemer at index {}.",
+                    self.redeemer.purpose, self.redeemer.index
+                ))
+            })?;
+
+        let lookup_table = DataLookupTable::from_transaction(tx, &utxos);
+
+        arguments.extend(
+            script_arguments(
+                tx,
+                &utxos,
+                &SlotConfig::from(&self.slot_config),
+                &redeemer,
+                &lookup_table,
+                &plutus_version.into(),
+            )
+            .map_err(|e| self.invalid(format!("I couldn't build the script context: {e}")))?,
+        );
+
+        Ok(arguments)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn decode_hex(&self, what: &str, hex_str: &str) -> Result<Vec<u8>, Error> {
+        let hex_str = hex_str.split_whitespace().collect::<String>();
+        hex::decode(hex_str)
+            .map_err(|e| self.invalid(format!("The {what} isn't a valid hex-encoded string: {e}")))
+    }
+
+    pub fn invalid(&self, help: String) -> Error {
+        Error::TomlLoading {
+            path: self.path.clone(),
+            src: self.src.clone(),
+            named: NamedSource::new(self.path.display().to_string(), self.src.clone()).into(),
+            location: None,
+            help: format!("I couldn't replay {}. {help}", self.path.display()),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use aiken_lang::{
     expr::UntypedExpr,
+    gen_uplc::coverage::CoverageSummary,
     test_framework::{PropertyTestResult, TestResult, UnitTestResult},
 };
 pub use json::{json_schema, Json};
@@ -38,6 +39,16 @@ pub enum Event {
     DumpingUPLC {
         path: PathBuf,
     },
+    GeneratingSourceMap {
+        path: PathBuf,
+    },
+    ExportingProfile {
+        path: PathBuf,
+    },
+    ExportingCoverage {
+        path: PathBuf,
+        summary: BTreeMap<String, CoverageSummary>,
+    },
     GeneratingUPLCFor {
         name: String,
         path: PathBuf,
@@ -47,6 +58,11 @@ pub enum Event {
         seed: u32,
         tests: Vec<TestResult<UntypedExpr, UntypedExpr>>,
     },
+    RunningBenchmarks,
+    FinishedBenchmarks {
+        seed: u32,
+        benchmarks: Vec<TestResult<UntypedExpr, UntypedExpr>>,
+    },
     WaitingForBuildDirLock,
     ResolvingPackages {
         name: String,
@@ -86,9 +102,12 @@ impl EventListener for EventTarget {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DownloadSource {
     Network,
     Cache,
+    Local,
+    Vendor,
 }
 
 impl Display for DownloadSource {
@@ -96,6 +115,8 @@ impl Display for DownloadSource {
         match self {
             DownloadSource::Network => write!(f, "network"),
             DownloadSource::Cache => write!(f, "cache"),
+            DownloadSource::Local => write!(f, "local paths"),
+            DownloadSource::Vendor => write!(f, "vendor directory"),
         }
     }
 }
@@ -118,6 +139,7 @@ pub(crate) fn find_max_execution_units<T>(xs: &[TestResult<T, T>]) -> (usize, us
                 TestResult::PropertyTestResult(PropertyTestResult { iterations, .. }) => {
                     (max_mem, max_cpu, std::cmp::max(max_iter, *iterations))
                 }
+                TestResult::BenchmarkResult(..) => (max_mem, max_cpu, max_iter),
                 TestResult::UnitTestResult(UnitTestResult { spent_budget, .. }) => {
                     if spent_budget.mem >= max_mem && spent_budget.cpu >= max_cpu {
                         (spent_budget.mem, spent_budget.cpu, max_iter)
@@ -3,9 +3,12 @@ use aiken_lang::{
     ast::OnTestFailure,
     expr::UntypedExpr,
     format::Formatter,
-    test_framework::{AssertionStyleOptions, PropertyTestResult, TestResult, UnitTestResult},
+    test_framework::{
+        AssertionStyleOptions, BenchmarkResult, PropertyTestResult, TestResult, UnitTestResult,
+    },
 };
 use serde_json::json;
+use uplc::machine::cost_model::ExBudget;
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Json;
@@ -39,6 +42,16 @@ impl EventListener for Json {
                 });
                 println!("{}", serde_json::to_string_pretty(&json_output).unwrap());
             }
+            Event::FinishedBenchmarks { seed, benchmarks } => {
+                let json_output = serde_json::json!({
+                    "seed": seed,
+                    "benchmarks": benchmarks.iter().filter_map(|r| match r {
+                        TestResult::BenchmarkResult(bench) => Some(fmt_bench_json(bench)),
+                        TestResult::UnitTestResult(..) | TestResult::PropertyTestResult(..) => None,
+                    }).collect::<Vec<_>>(),
+                });
+                println!("{}", serde_json::to_string_pretty(&json_output).unwrap());
+            }
             _ => super::Terminal.handle_event(event),
         }
     }
@@ -50,6 +63,7 @@ fn fmt_test_json(result: &TestResult<UntypedExpr, UntypedExpr>) -> serde_json::V
         TestResult::PropertyTestResult(PropertyTestResult { ref test, .. }) => {
             &test.on_test_failure
         }
+        TestResult::BenchmarkResult(..) => &OnTestFailure::FailImmediately,
     };
 
     let mut test = json!({
@@ -95,6 +109,9 @@ fn fmt_test_json(result: &TestResult<UntypedExpr, UntypedExpr>) -> serde_json::V
                 Err(err) => json!({"error": err.to_string()}),
             };
         }
+        TestResult::BenchmarkResult(BenchmarkResult { measures, .. }) => {
+            test["measures"] = fmt_measures_json(measures);
+        }
     }
 
     if !result.traces().is_empty() {
@@ -104,6 +121,36 @@ fn fmt_test_json(result: &TestResult<UntypedExpr, UntypedExpr>) -> serde_json::V
     test
 }
 
+fn fmt_bench_json(result: &BenchmarkResult) -> serde_json::Value {
+    let mut bench = json!({
+        "name": result.bench.name,
+        "module": result.bench.module,
+        "status": if result.error.is_none() { "pass" } else { "fail" },
+        "measures": fmt_measures_json(&result.measures),
+    });
+
+    if let Some(err) = &result.error {
+        bench["error"] = json!(err.to_string());
+    }
+
+    if !result.traces.is_empty() {
+        bench["traces"] = json!(result.traces);
+    }
+
+    bench
+}
+
+fn fmt_measures_json(measures: &[(usize, ExBudget)]) -> serde_json::Value {
+    json!(measures
+        .iter()
+        .map(|(size, budget)| json!({
+            "size": size,
+            "memory": budget.mem,
+            "cpu": budget.cpu,
+        }))
+        .collect::<Vec<_>>())
+}
+
 fn fmt_test_summary_json(tests: &[&TestResult<UntypedExpr, UntypedExpr>]) -> serde_json::Value {
     let total = tests.len();
     let passed = tests.iter().filter(|t| t.is_success()).count();
@@ -237,6 +284,38 @@ pub fn json_schema() -> serde_json::Value {
       }
     });
 
+    let benchmark = json!({
+      "type": "object",
+      "required": [
+        "name",
+        "module",
+        "status",
+        "measures"
+      ],
+      "properties": {
+        "name": { "type": "string" },
+        "module": { "type": "string" },
+        "status": { "$ref": "#/properties/definitions/Status" },
+        "measures": {
+          "type": "array",
+          "items": {
+            "type": "object",
+            "required": [ "size", "memory", "cpu" ],
+            "properties": {
+              "size": { "type": "integer" },
+              "memory": { "type": "integer" },
+              "cpu": { "type": "integer" }
+            }
+          }
+        },
+        "error": { "type": "string" },
+        "traces": {
+          "type": "array",
+          "items": { "type": "string" }
+        }
+      }
+    });
+
     json!({
       "$schema": "https://json-schema.org/draft/2020-12/schema",
       "$vocabulary": {
@@ -267,6 +346,13 @@ pub fn json_schema() -> serde_json::Value {
             }
           }
         },
+        "command[bench]": {
+          "seed": { "type": "integer" },
+          "benchmarks": {
+            "type": "array",
+            "items": benchmark
+          }
+        },
         "definitions": definitions
       }
     })
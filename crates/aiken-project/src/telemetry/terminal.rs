@@ -4,9 +4,13 @@ use aiken_lang::{
     ast::OnTestFailure,
     expr::UntypedExpr,
     format::Formatter,
-    test_framework::{AssertionStyleOptions, PropertyTestResult, TestResult, UnitTestResult},
+    gen_uplc::coverage::CoverageSummary,
+    test_framework::{
+        AssertionStyleOptions, BenchmarkResult, PropertyTestResult, TestResult, UnitTestResult,
+    },
 };
 use owo_colors::{OwoColorize, Stream::Stderr};
+use std::collections::BTreeMap;
 use uplc::machine::cost_model::ExBudget;
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -79,6 +83,43 @@ impl EventListener for Terminal {
                         .if_supports_color(Stderr, |s| s.bright_blue())
                 );
             }
+            Event::ExportingProfile { path } => {
+                eprintln!(
+                    "{} {} ({})",
+                    "    Exporting"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    "profile".if_supports_color(Stderr, |s| s.bold()),
+                    path.display()
+                        .if_supports_color(Stderr, |s| s.bright_blue())
+                );
+            }
+            Event::ExportingCoverage { path, summary } => {
+                if !summary.is_empty() {
+                    println!("{}\n", pretty::indent(&fmt_coverage(&summary, true), 4));
+                }
+
+                eprintln!(
+                    "{} {} ({})",
+                    "    Exporting"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    "coverage".if_supports_color(Stderr, |s| s.bold()),
+                    path.display()
+                        .if_supports_color(Stderr, |s| s.bright_blue())
+                );
+            }
+            Event::GeneratingSourceMap { path } => {
+                eprintln!(
+                    "{} {} ({})",
+                    "   Generating"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    "source map".if_supports_color(Stderr, |s| s.bold()),
+                    path.display()
+                        .if_supports_color(Stderr, |s| s.bright_blue())
+                );
+            }
             Event::GeneratingDocFiles { output_path } => {
                 eprintln!(
                     "{} {} to {}",
@@ -148,7 +189,8 @@ impl EventListener for Terminal {
                         println!();
                     }
 
-                    let summary = format!("{}{}", seed_info, fmt_test_summary(results, true));
+                    let summary =
+                        format!("{}{}", seed_info, fmt_test_summary(results, "tests", true));
                     println!(
                         "{}\n",
                         pretty::indent(
@@ -164,6 +206,59 @@ impl EventListener for Terminal {
                     println!();
                 }
             }
+            Event::RunningBenchmarks => {
+                eprintln!(
+                    "{} {}",
+                    " Benchmarking"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    "...".if_supports_color(Stderr, |s| s.bold())
+                );
+            }
+            Event::FinishedBenchmarks { seed, benchmarks } => {
+                for (module, results) in &group_by_module(&benchmarks) {
+                    let title = module
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.blue())
+                        .to_string();
+
+                    let benchmarks = results
+                        .iter()
+                        .filter_map(|r| match r {
+                            TestResult::BenchmarkResult(bench) => Some(fmt_bench(bench, true)),
+                            TestResult::UnitTestResult(..) | TestResult::PropertyTestResult(..) => {
+                                None
+                            }
+                        })
+                        .collect::<Vec<String>>()
+                        .join("\n");
+
+                    if !benchmarks.is_empty() {
+                        println!();
+                    }
+
+                    let summary = format!(
+                        "with {opt}={seed} → {summary}",
+                        opt = "--seed".if_supports_color(Stderr, |s| s.bold()),
+                        seed = format!("{seed}").if_supports_color(Stderr, |s| s.bold()),
+                        summary = fmt_test_summary(results, "benchmarks", true),
+                    );
+
+                    println!(
+                        "{}\n",
+                        pretty::indent(
+                            &pretty::open_box(&title, &benchmarks, &summary, |border| border
+                                .if_supports_color(Stderr, |s| s.bright_black())
+                                .to_string()),
+                            4
+                        )
+                    );
+                }
+
+                if !benchmarks.is_empty() {
+                    println!();
+                }
+            }
             Event::ResolvingPackages { name } => {
                 eprintln!(
                     "{} {}",
@@ -200,6 +295,7 @@ impl EventListener for Terminal {
                     match source {
                         DownloadSource::Network => "   Downloaded",
                         DownloadSource::Cache => "      Fetched",
+                        DownloadSource::Local | DownloadSource::Vendor => "       Copied",
                     }
                     .if_supports_color(Stderr, |s| s.bold())
                     .if_supports_color(Stderr, |s| s.purple()),
@@ -258,6 +354,7 @@ fn fmt_test(
                     .to_string()),
             );
         }
+        TestResult::BenchmarkResult(..) => (),
         TestResult::PropertyTestResult(PropertyTestResult { iterations, .. }) => {
             test = format!(
                 "{test} [after {} test{}]",
@@ -414,7 +511,161 @@ fn fmt_test(
     test
 }
 
-fn fmt_test_summary<T>(tests: &[&TestResult<T, T>], styled: bool) -> String {
+fn fmt_bench(result: &BenchmarkResult, styled: bool) -> String {
+    let BenchmarkResult {
+        bench,
+        measures,
+        traces,
+        error,
+    } = result;
+
+    // Status
+    let mut test = if error.is_none() {
+        pretty::style_if(styled, "PASS".to_string(), |s| {
+            s.if_supports_color(Stderr, |s| s.bold())
+                .if_supports_color(Stderr, |s| s.green())
+                .to_string()
+        })
+    } else {
+        pretty::style_if(styled, "FAIL".to_string(), |s| {
+            s.if_supports_color(Stderr, |s| s.bold())
+                .if_supports_color(Stderr, |s| s.red())
+                .to_string()
+        })
+    };
+
+    // Sizes & title
+    test = format!(
+        "{test} [size: {}] {title}",
+        match (measures.first(), measures.last()) {
+            (Some((lo, _)), Some((hi, _))) => format!("{lo}..{hi}"),
+            _ => "?".to_string(),
+        },
+        title = pretty::style_if(styled, bench.name.to_string(), |s| s
+            .if_supports_color(Stderr, |s| s.bright_blue())
+            .to_string())
+    );
+
+    // Curves
+    if !measures.is_empty() {
+        let curve = |label: &str, xs: Vec<i64>| {
+            format!(
+                "│ {label} {} {} → {}",
+                pretty::style_if(styled, pretty::sparkline(&xs), |s| s
+                    .if_supports_color(Stderr, |s| s.cyan())
+                    .to_string()),
+                xs.first().unwrap_or(&0),
+                xs.last().unwrap_or(&0),
+            )
+        };
+
+        test = format!(
+            "{test}\n{}\n{}",
+            curve(
+                "mem",
+                measures.iter().map(|(_, budget)| budget.mem).collect()
+            ),
+            curve(
+                "cpu",
+                measures.iter().map(|(_, budget)| budget.cpu).collect()
+            ),
+        );
+    }
+
+    // Failure
+    if let Some(err) = error {
+        test = format!(
+            "{test}\n{}\n{}",
+            format!("× benchmark failed at size {}", measures.len())
+                .if_supports_color(Stderr, |s| s.red())
+                .if_supports_color(Stderr, |s| s.bold()),
+            format!("| {err}").if_supports_color(Stderr, |s| s.red())
+        );
+    }
+
+    // Traces
+    if !traces.is_empty() {
+        test = format!(
+            "{test}\n{title}\n{traces}",
+            title = "· with traces".if_supports_color(Stderr, |s| s.bold()),
+            traces = traces
+                .iter()
+                .map(|line| { format!("| {line}",) })
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    };
+
+    test
+}
+
+fn fmt_coverage(summary: &BTreeMap<String, CoverageSummary>, styled: bool) -> String {
+    let ratio = |covered: usize, total: usize| {
+        let percent = (covered * 100).checked_div(total).unwrap_or(100);
+
+        let s = format!("{covered}/{total} ({percent}%)");
+
+        if !styled {
+            s
+        } else if percent == 100 {
+            s.if_supports_color(Stderr, |s| s.green()).to_string()
+        } else if percent >= 50 {
+            s.if_supports_color(Stderr, |s| s.yellow()).to_string()
+        } else {
+            s.if_supports_color(Stderr, |s| s.red()).to_string()
+        }
+    };
+
+    let max_module = summary.keys().map(|module| module.len()).max().unwrap_or(0);
+
+    let max_functions = summary
+        .values()
+        .map(|s| format!("{}/{} (100%)", s.functions_covered, s.functions).len())
+        .max()
+        .unwrap_or(0);
+
+    let content = summary
+        .iter()
+        .map(|(module, s)| {
+            format!(
+                "{} functions {} branches {}",
+                pretty::pad_right(module.to_string(), max_module, " "),
+                pretty::pad_right(ratio(s.functions_covered, s.functions), max_functions, " "),
+                ratio(s.branches_covered, s.branches),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let total = summary
+        .values()
+        .fold(CoverageSummary::default(), |total, s| CoverageSummary {
+            functions: total.functions + s.functions,
+            functions_covered: total.functions_covered + s.functions_covered,
+            branches: total.branches + s.branches,
+            branches_covered: total.branches_covered + s.branches_covered,
+        });
+
+    let footer = format!(
+        "total → functions {}, branches {}",
+        ratio(total.functions_covered, total.functions),
+        ratio(total.branches_covered, total.branches),
+    );
+
+    let title = pretty::style_if(styled, "coverage".to_string(), |s| {
+        s.if_supports_color(Stderr, |s| s.bold())
+            .if_supports_color(Stderr, |s| s.blue())
+            .to_string()
+    });
+
+    pretty::open_box(&title, &content, &footer, |border| {
+        border
+            .if_supports_color(Stderr, |s| s.bright_black())
+            .to_string()
+    })
+}
+
+fn fmt_test_summary<T>(tests: &[&TestResult<T, T>], kind: &str, styled: bool) -> String {
     let (n_passed, n_failed) = tests.iter().fold((0, 0), |(n_passed, n_failed), result| {
         if result.is_success() {
             (n_passed + 1, n_failed)
@@ -424,7 +675,7 @@ fn fmt_test_summary<T>(tests: &[&TestResult<T, T>], styled: bool) -> String {
     });
     format!(
         "{} | {} | {}",
-        pretty::style_if(styled, format!("{} tests", tests.len()), |s| s
+        pretty::style_if(styled, format!("{} {kind}", tests.len()), |s| s
             .if_supports_color(Stderr, |s| s.bold())
             .to_string()),
         pretty::style_if(styled, format!("{n_passed} passed"), |s| s
@@ -9,7 +9,7 @@ mod test {
         builtins,
         expr::UntypedExpr,
         format::Formatter,
-        gen_uplc::CodeGenerator,
+        gen_uplc::{coverage::Coverage, profiler::Metric, CodeGenerator},
         line_numbers::LineNumbers,
         parser::{self, extra::ModuleExtra},
         plutus_version::PlutusVersion,
@@ -54,7 +54,7 @@ mod test {
         let test = ast
             .definitions()
             .filter_map(|def| match def {
-                Definition::Test(test) => Some(test.clone()),
+                Definition::Test(..) | Definition::Benchmark(..) => Some(def.clone()),
                 _ => None,
             })
             .last()
@@ -95,15 +95,25 @@ mod test {
             Tracing::All(TraceLevel::Verbose),
         );
 
-        (
-            Test::from_function_definition(
+        generator.emit_source_map(true);
+
+        let test = match test {
+            Definition::Test(test) => Test::from_function_definition(
+                &mut generator,
+                test,
+                module_name.to_string(),
+                PathBuf::new(),
+            ),
+            Definition::Benchmark(bench) => Test::from_benchmark_definition(
                 &mut generator,
-                test.to_owned(),
+                bench,
                 module_name.to_string(),
                 PathBuf::new(),
             ),
-            data_types,
-        )
+            _ => unreachable!("filtered out above"),
+        };
+
+        (test, data_types)
     }
 
     fn property(src: &str) -> (PropertyTest, impl Fn(PlutusData) -> String) {
@@ -214,6 +224,44 @@ mod test {
             (Test::UnitTest(..), _) => {
                 panic!("Expected to yield a PropertyTest but found a UnitTest")
             }
+            (Test::Benchmark(..), _) => {
+                panic!("Expected to yield a PropertyTest but found a Benchmark")
+            }
+        }
+    }
+
+    fn benchmark(src: &str) -> Benchmark {
+        let prelude = indoc! { r#"
+            fn repeat(n: Int, x: a) -> List<a> {
+              if n <= 0 {
+                []
+              } else {
+                [x, ..repeat(n - 1, x)]
+              }
+            }
+
+            fn length(xs: List<a>) -> Int {
+              when xs is {
+                [] -> 0
+                [_, ..rest] -> 1 + length(rest)
+              }
+            }
+
+            fn list(size: Int) -> Fuzzer<List<Int>> {
+              fn(prng) { Some((prng, repeat(size, 42))) }
+            }
+        "#};
+
+        let src = format!("{prelude}\n{src}");
+
+        match test_from_source(&src) {
+            (Test::Benchmark(bench), _) => bench,
+            (Test::UnitTest(..), _) => {
+                panic!("Expected to yield a Benchmark but found a UnitTest")
+            }
+            (Test::PropertyTest(..), _) => {
+                panic!("Expected to yield a Benchmark but found a PropertyTest")
+            }
         }
     }
 
@@ -227,6 +275,7 @@ mod test {
             &mut remaining,
             Prng::from_seed(42),
             &mut labels,
+            &mut Coverage::new(),
             plutus_version,
         ) {
             Ok(Some(counterexample)) => counterexample,
@@ -276,6 +325,9 @@ mod test {
             &PlutusVersion::default(),
         ) {
             TestResult::UnitTestResult(..) => unreachable!("property returned unit-test result ?!"),
+            TestResult::BenchmarkResult(..) => {
+                unreachable!("property returned benchmark result ?!")
+            }
             TestResult::PropertyTestResult(result) => {
                 assert!(
                     result
@@ -289,6 +341,85 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_bench_basic() {
+        let bench = benchmark(indoc! { r#"
+            bench foo(xs via list) {
+                length(xs)
+            }
+        "#});
+
+        let result = bench.run(42, 10, &PlutusVersion::default());
+
+        assert!(result.error.is_none(), "{:#?}", result.error);
+        assert_eq!(
+            result
+                .measures
+                .iter()
+                .map(|(size, _)| *size)
+                .collect::<Vec<_>>(),
+            (0..=10).collect::<Vec<_>>()
+        );
+        assert!(
+            result
+                .measures
+                .windows(2)
+                .all(|w| w[0].1.cpu < w[1].1.cpu && w[0].1.mem < w[1].1.mem),
+            "measures: {:#?}",
+            result.measures
+        );
+    }
+
+    #[test]
+    fn test_profile_unit() {
+        let (test, _) = test_from_source(indoc! { r#"
+            fn length(xs: List<a>) -> Int {
+              when xs is {
+                [] -> 0
+                [_, ..rest] -> 1 + length(rest)
+              }
+            }
+
+            test foo() {
+              length([1, 2, 3]) == 3
+            }
+        "#});
+
+        let unit_test = match test {
+            Test::UnitTest(unit_test) => unit_test,
+            _ => panic!("Expected to yield a UnitTest"),
+        };
+
+        let profile = unit_test.profile(&PlutusVersion::default());
+
+        let folded = profile.to_folded(Metric::Cpu);
+
+        assert!(folded.contains("\n.foo;.length "), "{folded}");
+        assert!(
+            folded.contains("\n.foo;.length;builtin.add_integer "),
+            "{folded}"
+        );
+        match unit_test.run::<UntypedExpr>(&PlutusVersion::default()) {
+            TestResult::UnitTestResult(result) => assert_eq!(profile.total(), result.spent_budget),
+            _ => unreachable!("unit tests yield unit test results"),
+        }
+    }
+
+    #[test]
+    fn test_bench_failure() {
+        let bench = benchmark(indoc! { r#"
+            bench foo(xs via list) {
+                expect length(xs) < 3
+                Void
+            }
+        "#});
+
+        let result = bench.run(42, 10, &PlutusVersion::default());
+
+        assert!(result.error.is_some());
+        assert_eq!(result.measures.len(), 3);
+    }
+
     #[test]
     fn test_prop_always_odd() {
         let (prop, reify) = property(indoc! { r#"
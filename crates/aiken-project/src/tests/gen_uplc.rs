@@ -56,6 +56,13 @@ fn assert_uplc(source_code: &str, expected: Term<Name>, should_fail: bool, verbo
         TestType::Func(Function { body: func, .. }) => {
             let program = generator.generate_raw(func, &[], &script.1);
 
+            generator.emit_source_map(true);
+            assert_eq!(
+                generator.generate_raw(func, &[], &script.1),
+                program,
+                "source maps must not alter the generated program"
+            );
+
             let pretty_program = program.to_pretty();
 
             let debruijn_program: Program<DeBruijn> = program.try_into().unwrap();
@@ -96,6 +103,13 @@ fn assert_uplc(source_code: &str, expected: Term<Name>, should_fail: bool, verbo
         TestType::Validator(func) => {
             let program = generator.generate(func, &script.1);
 
+            generator.emit_source_map(true);
+            assert_eq!(
+                generator.generate(func, &script.1),
+                program,
+                "source maps must not alter the generated program"
+            );
+
             let pretty_program = program.to_pretty();
 
             let debruijn_program: Program<DeBruijn> = program.try_into().unwrap();
@@ -0,0 +1,247 @@
+use super::TestProject;
+use aiken_lang::{
+    ast::{Definition, ModuleKind, TraceLevel, Tracing},
+    plutus_version::PlutusVersion,
+    test_framework::Test,
+};
+use std::path::PathBuf;
+use uplc::{
+    ast::{Data, NamedDeBruijn, Program},
+    machine::cost_model::ExBudget,
+};
+
+const PRINTING: &str = r#"
+pub interface Show<a> {
+  show: fn(a) -> ByteArray,
+}
+
+instance Show<Int> {
+  fn show(n: Int) -> ByteArray {
+    if n < 0 {
+      "-"
+    } else {
+      "+"
+    }
+  }
+}
+
+instance Show<Bool> {
+  fn show(b: Bool) -> ByteArray {
+    if b {
+      "T"
+    } else {
+      "F"
+    }
+  }
+}
+
+pub fn show_all(xs: List<a>) -> List<ByteArray> where Show<a> {
+  when xs is {
+    [] -> []
+    [x, ..rest] -> [show(x), ..show_all(rest)]
+  }
+}
+"#;
+
+const COLOR: &str = r#"
+use foo/printing.{Show}
+
+pub type Color {
+  Red
+  Green
+}
+
+instance Show<Color> {
+  fn show(color: Color) -> ByteArray {
+    when color is {
+      Red -> "R"
+      Green -> "G"
+    }
+  }
+}
+"#;
+
+/// Check the given modules in order, then evaluate the tests (and validators, applied to a
+/// script context with dummy fields) of the last one; returning whether each of them succeeded,
+/// by name.
+fn evaluate(modules: &[(&str, ModuleKind, &str)]) -> Vec<(String, bool)> {
+    let mut project = TestProject::new();
+
+    let mut checked = None;
+
+    for (name, kind, source_code) in modules {
+        checked = Some(project.check(project.parse_module(name, *kind, source_code)));
+    }
+
+    let module = checked.expect("no module to evaluate");
+
+    let mut generator = project.new_generator(Tracing::All(TraceLevel::Verbose));
+
+    let mut results = vec![];
+
+    for definition in module.ast.definitions() {
+        match definition {
+            Definition::Test(test) => {
+                let success = match Test::from_function_definition(
+                    &mut generator,
+                    test.clone(),
+                    module.name.clone(),
+                    PathBuf::new(),
+                ) {
+                    Test::UnitTest(test) => test.run::<()>(&PlutusVersion::default()).is_success(),
+                    _ => unreachable!("only unit tests are expected"),
+                };
+
+                results.push((test.name.clone(), success));
+            }
+
+            Definition::Validator(validator) => {
+                let program = generator.generate(validator, &module.name);
+
+                let program = Program::<NamedDeBruijn>::try_from(program)
+                    .unwrap()
+                    .apply_data(Data::constr(
+                        0,
+                        vec![
+                            Data::integer(0.into()),
+                            Data::integer(0.into()),
+                            Data::integer(0.into()),
+                        ],
+                    ));
+
+                let success = !program.eval(ExBudget::max()).failed(false);
+
+                results.push((validator.name.clone(), success));
+            }
+
+            _ => {}
+        }
+    }
+
+    results
+}
+
+fn assert_all_succeed(results: Vec<(String, bool)>) {
+    assert!(!results.is_empty());
+
+    for (name, success) in results {
+        assert!(success, "{name} failed");
+    }
+}
+
+#[test]
+fn instances_across_modules() {
+    let source_code = r#"
+        use foo/color.{Green, Red}
+        use foo/printing.{show, show_all}
+
+        test show_from_interface_module() {
+          show(1) == "+" && show(-1) == "-" && show(True) == "T"
+        }
+
+        test show_from_type_module() {
+          show(Red) == "R" && show(Green) == "G"
+        }
+
+        test show_all_across_modules() {
+          and {
+            show_all([1, -2, 3]) == ["+", "-", "+"],
+            show_all([Green, Red]) == ["G", "R"],
+            show_all([False]) == ["F"],
+          }
+        }
+    "#;
+
+    assert_all_succeed(evaluate(&[
+        ("foo/printing", ModuleKind::Lib, PRINTING),
+        ("foo/color", ModuleKind::Lib, COLOR),
+        ("test_module", ModuleKind::Validator, source_code),
+    ]));
+}
+
+#[test]
+fn constrained_functions_pass_dictionaries_along() {
+    let source_code = r#"
+        use aiken/builtin
+        use foo/printing.{Show, show, show_all}
+
+        fn show_twice(x: a) -> List<ByteArray> where Show<a> {
+          show_all([x, x])
+        }
+
+        fn show_pair(x: a, y: b) -> ByteArray where Show<a>, Show<b> {
+          builtin.append_bytearray(show(x), show(y))
+        }
+
+        test nested_constrained_calls() {
+          show_twice(-1) == ["-", "-"] && show_twice(True) == ["T", "T"]
+        }
+
+        test several_constraints() {
+          show_pair(1, False) == "+F" && show_pair(False, -1) == "F-"
+        }
+
+        test wrong_expectation() fail {
+          show_twice(1) == ["-", "-"]
+        }
+    "#;
+
+    assert_all_succeed(evaluate(&[
+        ("foo/printing", ModuleKind::Lib, PRINTING),
+        ("test_module", ModuleKind::Validator, source_code),
+    ]));
+}
+
+#[test]
+fn constrained_functions_as_values() {
+    let source_code = r#"
+        use foo/printing.{show, show_all}
+
+        fn apply(f: fn(a) -> b, x: a) -> b {
+          f(x)
+        }
+
+        test partial_application() {
+          let f = show_all
+          f([1, -1]) == ["+", "-"]
+        }
+
+        test as_argument() {
+          apply(show_all, [True, False]) == ["T", "F"] && apply(show, -5) == "-"
+        }
+    "#;
+
+    assert_all_succeed(evaluate(&[
+        ("foo/printing", ModuleKind::Lib, PRINTING),
+        ("test_module", ModuleKind::Validator, source_code),
+    ]));
+}
+
+#[test]
+fn constrained_functions_in_validators() {
+    let source_code = r#"
+        use foo/color.{Green}
+        use foo/printing.{show_all}
+
+        validator shows {
+          else(_) {
+            show_all([Green]) == ["G"] && show_all([1, -1]) == ["+", "-"]
+          }
+        }
+
+        validator fails {
+          else(_) {
+            show_all([Green]) == ["R"]
+          }
+        }
+    "#;
+
+    assert_eq!(
+        evaluate(&[
+            ("foo/printing", ModuleKind::Lib, PRINTING),
+            ("foo/color", ModuleKind::Lib, COLOR),
+            ("test_module", ModuleKind::Validator, source_code),
+        ]),
+        vec![("shows".to_string(), true), ("fails".to_string(), false)]
+    );
+}
@@ -21,6 +21,9 @@ use indexmap::IndexMap;
 use std::{collections::HashMap, path::PathBuf};
 
 mod gen_uplc;
+mod interfaces;
+mod module_cache;
+mod source_map;
 
 // TODO: Possible refactor this out of the module and have it used by `Project`. The idea would
 // be to make this struct below the actual project, and wrap it in another metadata struct
@@ -76,8 +79,11 @@ impl TestProject {
     }
 
     pub fn parse(&self, source_code: &str) -> ParsedModule {
-        let kind = ModuleKind::Validator;
-        let name = "test_module".to_owned();
+        self.parse_module("test_module", ModuleKind::Validator, source_code)
+    }
+
+    pub fn parse_module(&self, name: &str, kind: ModuleKind, source_code: &str) -> ParsedModule {
+        let name = name.to_owned();
         let (mut ast, extra) = parser::module(source_code, kind).expect("Failed to parse module");
         ast.name.clone_from(&name);
 
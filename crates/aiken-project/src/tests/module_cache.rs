@@ -0,0 +1,315 @@
+use super::TestProject;
+use crate::module_cache::{CachedModule, ModuleCache};
+use aiken_lang::{
+    ast::{Located, ModuleKind, Span, TraceLevel, Tracing},
+    tipo::{error::Warning, TypeInfo},
+};
+use ciborium::value::Value;
+use std::{collections::BTreeSet, path::PathBuf};
+
+const SOURCE: &str = r#"
+pub type Box<a> {
+  Box { inner: a }
+}
+
+pub fn map(xs: List<a>, f: fn(a) -> b) -> List<b> {
+  when xs is {
+    [] -> []
+    [x, ..rest] -> [f(x), ..map(rest, f)]
+  }
+}
+
+pub fn unbox(box: Box<a>) -> a {
+  box.inner
+}
+
+pub fn next(n: Int) -> Int {
+  n + 1
+}
+"#;
+
+fn interface(source_code: &str, first_id: u64) -> String {
+    let mut project = TestProject::new();
+
+    project.id_gen.skip_to(first_id);
+
+    let module = project.check(project.parse(source_code));
+
+    ModuleCache::interface(&module.ast.type_info, &[])
+}
+
+#[test]
+fn interface_ignores_type_variable_ids() {
+    assert_eq!(interface(SOURCE, 0), interface(SOURCE, 1000));
+}
+
+#[test]
+fn interface_ignores_implementations() {
+    assert_eq!(
+        interface(SOURCE, 0),
+        interface(&SOURCE.replace("n + 1", "1 + n"), 0)
+    );
+}
+
+#[test]
+fn interface_ignores_locations() {
+    assert_eq!(
+        interface(SOURCE, 0),
+        interface(
+            &SOURCE.replace("box.inner", "let inner = box.inner\n  inner"),
+            0
+        )
+    );
+}
+
+#[test]
+fn interface_changes_with_signatures() {
+    assert_ne!(
+        interface(SOURCE, 0),
+        interface(&SOURCE.replace("box: Box<a>", "box: Box<a>, _n: Int"), 0)
+    );
+}
+
+fn cache_root(test: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("aiken-module-cache-{test}-{}", std::process::id()))
+}
+
+/// Ids of the type variables found in a module's interface.
+fn type_var_ids(type_info: &TypeInfo) -> BTreeSet<u64> {
+    fn collect(value: &Value, ids: &mut BTreeSet<u64>) {
+        match value {
+            Value::Map(entries) => {
+                for (key, value) in entries {
+                    match (key, value) {
+                        (Value::Text(tag), Value::Map(fields))
+                            if tag == "Unbound" || tag == "Generic" =>
+                        {
+                            ids.extend(fields.iter().filter_map(|(_, id)| match id {
+                                Value::Integer(id) => u64::try_from(*id).ok(),
+                                _ => None,
+                            }))
+                        }
+                        _ => collect(value, ids),
+                    }
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|item| collect(item, ids)),
+            Value::Tag(_, value) => collect(value, ids),
+            _ => {}
+        }
+    }
+
+    let mut ids = BTreeSet::new();
+    collect(&Value::serialized(type_info).unwrap(), &mut ids);
+    ids
+}
+
+#[test]
+fn store_and_load() {
+    let root = cache_root("store_and_load");
+
+    let cache = ModuleCache::new(&root, Tracing::All(TraceLevel::Silent), None, true);
+
+    let mut project = TestProject::new();
+
+    let parsed = project.parse(SOURCE);
+
+    let fingerprint = ModuleCache::fingerprint(&parsed, &[]);
+
+    let first_id = project.id_gen.peek();
+
+    let module = project.check(parsed);
+
+    let ids = first_id..project.id_gen.peek();
+
+    let load = |cache: &ModuleCache, fingerprint: &str| {
+        cache.load(
+            &module.package,
+            &module.name,
+            fingerprint,
+            &project.id_gen,
+            &project.module_types,
+        )
+    };
+
+    assert!(load(&cache, &fingerprint).is_none());
+
+    let warnings = vec![Warning::UnusedVariable {
+        location: Span::create(42, 1),
+        name: "x".to_string(),
+    }];
+
+    cache.store(
+        fingerprint.clone(),
+        CachedModule {
+            module: module.clone(),
+            interface: ModuleCache::interface(&module.ast.type_info, &[]),
+            ids: ids.clone(),
+            warnings: warnings.clone(),
+        },
+    );
+
+    let cached = load(&cache, &fingerprint).expect("module should be cached");
+
+    assert_eq!(cached.module.code, module.code);
+    assert_eq!(cached.ids.end - cached.ids.start, ids.end - ids.start);
+    assert_eq!(cached.warnings, warnings);
+
+    assert!(load(&cache, "not a fingerprint").is_none());
+
+    let other = ModuleCache::new(&root, Tracing::All(TraceLevel::Verbose), None, true);
+
+    assert!(load(&other, &fingerprint).is_none());
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn cached_modules_are_given_fresh_ids() {
+    let root = cache_root("fresh_ids");
+
+    let cache = ModuleCache::new(&root, Tracing::All(TraceLevel::Silent), None, true);
+
+    // A first compilation type-checks, and caches, a module on its own.
+    let mut project = TestProject::new();
+
+    let parsed = project.parse_module("box", ModuleKind::Lib, SOURCE);
+
+    let fingerprint = ModuleCache::fingerprint(&parsed, &[]);
+
+    let first_id = project.id_gen.peek();
+
+    let module = project.check(parsed);
+
+    cache.store(
+        fingerprint.clone(),
+        CachedModule {
+            interface: ModuleCache::interface(&module.ast.type_info, &[]),
+            module,
+            ids: first_id..project.id_gen.peek(),
+            warnings: vec![],
+        },
+    );
+
+    // A second compilation type-checks another module first, which is given the very same ids,
+    // before restoring the cached one.
+    let mut project = TestProject::new();
+
+    let start = project.id_gen.peek();
+
+    let other = project.check(project.parse_module("other", ModuleKind::Lib, SOURCE));
+
+    let end = project.id_gen.peek();
+
+    let cached = cache
+        .load(
+            &other.package,
+            "box",
+            &fingerprint,
+            &project.id_gen,
+            &project.module_types,
+        )
+        .expect("module should be cached");
+
+    let other_ids = type_var_ids(&other.ast.type_info);
+
+    let cached_ids = type_var_ids(&cached.module.ast.type_info);
+
+    assert!(other_ids.iter().any(|id| (start..end).contains(id)));
+
+    assert!(cached_ids.iter().any(|id| cached.ids.contains(id)));
+
+    assert!(cached_ids.is_disjoint(&other_ids));
+
+    assert_eq!(cached.ids.start, end);
+
+    assert_eq!(project.id_gen.peek(), cached.ids.end);
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn cached_modules_refer_to_current_locations() {
+    const MAIN: &str = r#"
+use box
+
+pub fn two() -> box.Box<Int> {
+  box.Box { inner: box.next(1) }
+}
+"#;
+
+    let root = cache_root("current_locations");
+
+    let cache = ModuleCache::new(&root, Tracing::All(TraceLevel::Silent), None, true);
+
+    let check = |dependency: &str| {
+        let mut project = TestProject::new();
+
+        let dependency = project.check(project.parse_module("box", ModuleKind::Lib, dependency));
+
+        let dependencies = vec![(
+            "box".to_string(),
+            ModuleCache::interface(&dependency.ast.type_info, &[]),
+        )];
+
+        let parsed = project.parse_module("main", ModuleKind::Lib, MAIN);
+
+        let fingerprint = ModuleCache::fingerprint(&parsed, &dependencies);
+
+        (project, dependency, parsed, fingerprint)
+    };
+
+    let definition = |module: &crate::module::CheckedModule, text: &str| match module
+        .ast
+        .find_node(MAIN.find(text).unwrap())
+    {
+        Some(Located::Expression(expression)) => expression.definition_location().unwrap().span,
+        _ => panic!("no expression at '{text}'"),
+    };
+
+    // The main module is cached against a first version of its dependency.
+    let (mut project, _, parsed, fingerprint) = check(SOURCE);
+
+    let first_id = project.id_gen.peek();
+
+    let main = project.check(parsed);
+
+    cache.store(
+        fingerprint.clone(),
+        CachedModule {
+            interface: ModuleCache::interface(&main.ast.type_info, &[]),
+            ids: first_id..project.id_gen.peek(),
+            module: main,
+            warnings: vec![],
+        },
+    );
+
+    // Then, definitions of the dependency move, without changing its interface.
+    let (project, dependency, _, moved_fingerprint) = check(&format!("\n// A box.\n{SOURCE}"));
+
+    assert_eq!(moved_fingerprint, fingerprint);
+
+    let cached = cache
+        .load(
+            &dependency.package,
+            "main",
+            &fingerprint,
+            &project.id_gen,
+            &project.module_types,
+        )
+        .expect("module should be cached");
+
+    let values = &dependency.ast.type_info.values;
+
+    assert_eq!(
+        definition(&cached.module, "next"),
+        values["next"].variant.location()
+    );
+
+    assert_eq!(
+        definition(&cached.module, "Box {"),
+        values["Box"].variant.location()
+    );
+
+    let _ = std::fs::remove_dir_all(root);
+}
@@ -0,0 +1,80 @@
+use super::TestProject;
+use aiken_lang::{
+    ast::{Definition, TraceLevel, Tracing},
+    gen_uplc::source_map::TermPath,
+};
+use uplc::ast::Term;
+
+const SOURCE_CODE: &str = r#"
+fn double(n: Int) -> Int {
+  n * 2
+}
+
+fn quadruple(n: Int) -> Int {
+  double(double(n))
+}
+
+validator foo {
+  mint(redeemer: Int, _policy_id: ByteArray, _transaction: Data) {
+    quadruple(redeemer) == quadruple(redeemer + 1) - 4
+  }
+}
+"#;
+
+#[test]
+fn source_map_of_calls() {
+    let mut project = TestProject::new();
+
+    let module = project.check(project.parse(SOURCE_CODE));
+
+    let validator = module
+        .ast
+        .definitions()
+        .find_map(|def| match def {
+            Definition::Validator(validator) => Some(validator),
+            _ => None,
+        })
+        .expect("no validator in module");
+
+    let mut generator = project.new_generator(Tracing::All(TraceLevel::Silent));
+    let without_source_map = generator.generate(validator, &module.name);
+
+    generator.emit_source_map(true);
+    let program = generator.generate(validator, &module.name);
+    let source_map = generator.source_map().clone();
+
+    assert_eq!(program, without_source_map);
+
+    let find = |snippet: &str| {
+        source_map
+            .iter()
+            .find(|(_, location)| &SOURCE_CODE[location.span.start..location.span.end] == snippet)
+            .unwrap_or_else(|| panic!("no entry for {snippet} in {source_map:#?}"))
+    };
+
+    let (call, location) = find("quadruple(redeemer)");
+    assert_ne!(call, &TermPath::root());
+    assert_eq!(location.name.as_deref(), Some("foo"));
+    assert_eq!((location.line, location.column), (12, 5));
+    match call.resolve(&program.term) {
+        Some(Term::Apply { function, .. }) => {
+            assert!(
+                matches!(function.as_ref(), Term::Var(name) if name.text == "test_module_quadruple")
+            )
+        }
+        term => panic!("unexpected term at {call}: {term:?}"),
+    }
+
+    let (definition, location) = find("fn quadruple(n: Int) -> Int");
+    assert_eq!(location.name.as_deref(), Some("quadruple"));
+    assert!(matches!(
+        definition.resolve(&program.term),
+        Some(Term::Lambda { .. })
+    ));
+
+    // `double` is inlined, but its calls remain attributed to `quadruple`.
+    let (call, location) = find("double(n)");
+    assert_eq!(location.name.as_deref(), Some("quadruple"));
+    assert!(definition.contains(call));
+    assert!(call.resolve(&program.term).is_some());
+}
@@ -1,5 +1,5 @@
 use aiken_project::{
-    blueprint::{error::Error as BlueprintError, Blueprint},
+    blueprint::{error::Error as BlueprintError, rust, typescript, Blueprint},
     config::Config,
     error::Error as ProjectError,
 };
@@ -30,6 +30,14 @@ pub struct Args {
 #[derive(Copy, Clone, ValueEnum)]
 pub enum Format {
     CardanoCli,
+    /// TypeScript types, encoders and decoders for every definition of the blueprint. This
+    /// covers all validators at once.
+    Typescript,
+    /// Rust types implementing conversions to and from Plutus data, along with constructors for
+    /// every validator. This covers all validators at once. The generated code depends on the
+    /// `uplc` (of the same version as Aiken), `pallas-addresses` (0.31) and `num-bigint` (0.4)
+    /// crates, as listed in its header.
+    Rust,
 }
 
 pub fn exec(
@@ -56,6 +64,18 @@ pub fn exec(
     let blueprint: Blueprint =
         serde_json::from_reader(BufReader::new(blueprint)).into_diagnostic()?;
 
+    match to {
+        Format::Typescript => {
+            print!("{}", typescript::generate(&blueprint));
+            return Ok(());
+        }
+        Format::Rust => {
+            print!("{}", rust::generate(&blueprint));
+            return Ok(());
+        }
+        Format::CardanoCli => (),
+    }
+
     let opt_config = Config::load(&project_path).ok();
 
     let cardano_cli_type = opt_config
@@ -74,6 +94,9 @@ pub fn exec(
         when_too_many,
         when_missing,
         |validator| match to {
+            Format::Typescript | Format::Rust => {
+                unreachable!("handled for all validators at once")
+            }
             Format::CardanoCli => {
                 let cbor_bytes = validator.program.inner().to_cbor().unwrap();
 
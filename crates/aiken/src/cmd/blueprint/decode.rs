@@ -0,0 +1,106 @@
+use super::encode::{read_input, schema_target};
+use aiken_project::{blueprint, error::Error, watch::with_project};
+use std::path::PathBuf;
+
+/// Decode Plutus data (CBOR, hex-encoded) into a JSON value, following a schema from the
+/// blueprint.
+///
+/// This is the converse of 'blueprint encode': constructors are shown by their name and fields
+/// by their label.
+#[derive(clap::Args)]
+#[clap(verbatim_doc_comment)]
+#[clap(group(clap::ArgGroup::new("schema").required(true)))]
+pub struct Args {
+    /// The data, as CBOR (hex-encoded). Read from stdin when omitted.
+    #[clap(value_name = "CBOR")]
+    data: Option<String>,
+
+    /// Optional path to the blueprint file to be used as input.
+    ///
+    /// [default: plutus.json]
+    #[clap(
+        short,
+        long = "in",
+        value_parser,
+        value_name = "FILEPATH",
+        verbatim_doc_comment
+    )]
+    input: Option<PathBuf>,
+
+    /// Name of the validator's module within the project. Optional if there's only one validator.
+    #[clap(short, long)]
+    module: Option<String>,
+
+    /// Name of the validator within the module. Optional if there's only one validator.
+    #[clap(short, long)]
+    validator: Option<String>,
+
+    /// Name of the validator's handler (e.g. 'spend'). Defaults to the first one with a
+    /// matching schema.
+    #[clap(long)]
+    handler: Option<String>,
+
+    /// Use the schema of a definition from the blueprint, by key (e.g. 'aiken/interval/Interval$Int').
+    #[clap(long, group = "schema")]
+    definition: Option<String>,
+
+    /// Use the schema of the validator's datum.
+    #[clap(long, group = "schema")]
+    datum: bool,
+
+    /// Use the schema of the validator's redeemer.
+    #[clap(long, group = "schema")]
+    redeemer: bool,
+
+    /// Use the schema of the validator's parameter at the given position, starting from 0.
+    #[clap(long, group = "schema", value_name = "INDEX")]
+    parameter: Option<usize>,
+}
+
+pub fn exec(
+    Args {
+        data,
+        input,
+        module,
+        validator,
+        handler,
+        definition,
+        datum,
+        redeemer,
+        parameter,
+    }: Args,
+) -> miette::Result<()> {
+    let target = schema_target(definition, datum, redeemer, parameter);
+
+    with_project(None, false, false, |p| {
+        let data = read_input(data.clone())?;
+
+        let bytes = hex::decode(data.trim()).map_err::<Error, _>(|e| {
+            blueprint::error::Error::MalformedParameter {
+                hint: format!("Invalid hex-encoded string: {e}"),
+            }
+            .into()
+        })?;
+
+        let data = uplc::plutus_data(&bytes).map_err::<Error, _>(|e| {
+            blueprint::error::Error::MalformedParameter {
+                hint: format!("Invalid Plutus data; malformed CBOR encoding: {e}"),
+            }
+            .into()
+        })?;
+
+        let value = p.decode_value(
+            module.as_deref(),
+            validator.as_deref(),
+            handler.as_deref(),
+            &target,
+            &p.blueprint_path(input.as_deref()),
+            &data,
+        )?;
+
+        println!("{}", serde_json::to_string_pretty(&value).unwrap());
+
+        Ok(())
+    })
+    .map_err(|_| std::process::exit(1))
+}
@@ -0,0 +1,135 @@
+use aiken_project::{
+    blueprint::{self, SchemaTarget},
+    error::Error,
+    watch::with_project,
+};
+use std::{
+    io::{self, Read},
+    path::PathBuf,
+};
+use uplc::ast::Data as UplcData;
+
+/// Encode a JSON value as Plutus data (CBOR, hex-encoded), following a schema from the
+/// blueprint.
+///
+/// Constructors are designated by their name and fields by their label. For example, given
+/// `type Datum { owner: ByteArray, deadline: Option<Int> }`, the value
+/// `{ "owner": "abcd", "deadline": { "Some": [42] } }` is encoded as
+/// `d8799f42abcdd8799f182affff`.
+#[derive(clap::Args)]
+#[clap(verbatim_doc_comment)]
+#[clap(group(clap::ArgGroup::new("schema").required(true)))]
+pub struct Args {
+    /// The value, as JSON. Read from stdin when omitted.
+    #[clap(value_name = "JSON")]
+    value: Option<String>,
+
+    /// Optional path to the blueprint file to be used as input.
+    ///
+    /// [default: plutus.json]
+    #[clap(
+        short,
+        long = "in",
+        value_parser,
+        value_name = "FILEPATH",
+        verbatim_doc_comment
+    )]
+    input: Option<PathBuf>,
+
+    /// Name of the validator's module within the project. Optional if there's only one validator.
+    #[clap(short, long)]
+    module: Option<String>,
+
+    /// Name of the validator within the module. Optional if there's only one validator.
+    #[clap(short, long)]
+    validator: Option<String>,
+
+    /// Name of the validator's handler (e.g. 'spend'). Defaults to the first one with a
+    /// matching schema.
+    #[clap(long)]
+    handler: Option<String>,
+
+    /// Use the schema of a definition from the blueprint, by key (e.g. 'aiken/interval/Interval$Int').
+    #[clap(long, group = "schema")]
+    definition: Option<String>,
+
+    /// Use the schema of the validator's datum.
+    #[clap(long, group = "schema")]
+    datum: bool,
+
+    /// Use the schema of the validator's redeemer.
+    #[clap(long, group = "schema")]
+    redeemer: bool,
+
+    /// Use the schema of the validator's parameter at the given position, starting from 0.
+    #[clap(long, group = "schema", value_name = "INDEX")]
+    parameter: Option<usize>,
+}
+
+pub fn exec(
+    Args {
+        value,
+        input,
+        module,
+        validator,
+        handler,
+        definition,
+        datum,
+        redeemer,
+        parameter,
+    }: Args,
+) -> miette::Result<()> {
+    let target = schema_target(definition, datum, redeemer, parameter);
+
+    with_project(None, false, false, |p| {
+        let value = read_input(value.clone())?;
+
+        let value = serde_json::from_str(value.trim()).map_err::<Error, _>(|e| {
+            blueprint::error::Error::MalformedParameter {
+                hint: format!("Invalid JSON: {e}"),
+            }
+            .into()
+        })?;
+
+        let data = p.encode_value(
+            module.as_deref(),
+            validator.as_deref(),
+            handler.as_deref(),
+            &target,
+            &p.blueprint_path(input.as_deref()),
+            &value,
+        )?;
+
+        println!("{}", UplcData::to_hex(data));
+
+        Ok(())
+    })
+    .map_err(|_| std::process::exit(1))
+}
+
+pub(super) fn schema_target(
+    definition: Option<String>,
+    datum: bool,
+    redeemer: bool,
+    parameter: Option<usize>,
+) -> SchemaTarget {
+    match (definition, parameter) {
+        (Some(key), _) => SchemaTarget::Definition(key),
+        (_, Some(index)) => SchemaTarget::Parameter(index),
+        _ if datum => SchemaTarget::Datum,
+        _ if redeemer => SchemaTarget::Redeemer,
+        _ => unreachable!("clap ensures one schema is given"),
+    }
+}
+
+#[allow(clippy::result_large_err)]
+pub(super) fn read_input(input: Option<String>) -> Result<String, Error> {
+    match input {
+        Some(input) => Ok(input),
+        None => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+    }
+}
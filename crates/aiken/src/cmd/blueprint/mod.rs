@@ -1,8 +1,11 @@
 pub mod address;
 pub mod apply;
 pub mod convert;
+pub mod decode;
+pub mod encode;
 pub mod hash;
 pub mod policy;
+pub mod validate;
 
 use clap::Subcommand;
 
@@ -14,6 +17,9 @@ pub enum Cmd {
     Hash(hash::Args),
     Apply(apply::Args),
     Convert(convert::Args),
+    Encode(encode::Args),
+    Decode(decode::Args),
+    Validate(validate::Args),
 }
 
 pub fn exec(cmd: Cmd) -> miette::Result<()> {
@@ -23,5 +29,8 @@ pub fn exec(cmd: Cmd) -> miette::Result<()> {
         Cmd::Hash(args) => hash::exec(args),
         Cmd::Apply(args) => apply::exec(args),
         Cmd::Convert(args) => convert::exec(args),
+        Cmd::Encode(args) => encode::exec(args),
+        Cmd::Decode(args) => decode::exec(args),
+        Cmd::Validate(args) => validate::exec(args),
     }
 }
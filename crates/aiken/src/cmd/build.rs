@@ -21,6 +21,11 @@ pub struct Args {
     #[clap(short, long)]
     uplc: bool,
 
+    /// Also generate a source map next to the blueprint, relating validators' UPLC to the
+    /// Aiken definitions and function calls they come from
+    #[clap(long)]
+    source_map: bool,
+
     /// Environment to build against.
     #[clap(long)]
     env: Option<String>,
@@ -77,6 +82,7 @@ pub fn exec(
         deny,
         watch,
         uplc,
+        source_map,
         trace_filter,
         trace_level,
         output,
@@ -87,6 +93,7 @@ pub fn exec(
         watch_project(directory.as_deref(), watch::default_filter, 500, |p| {
             p.build(
                 uplc,
+                source_map,
                 match trace_filter {
                     Some(trace_filter) => trace_filter(trace_level),
                     None => Tracing::All(trace_level),
@@ -99,6 +106,7 @@ pub fn exec(
         with_project(directory.as_deref(), deny, false, |p| {
             p.build(
                 uplc,
+                source_map,
                 match trace_filter {
                     Some(trace_filter) => trace_filter(trace_level),
                     None => Tracing::All(trace_level),
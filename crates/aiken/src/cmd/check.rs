@@ -20,6 +20,12 @@ use std::{
     about = color_print::cstr!(r#"
 Type-check an Aiken project and run any tests found.
 
+Transactions described by TOML files under `replays/` are also replayed against the project's
+validators, each as a unit test. A replay names a validator ('module.validator'), one of the
+transaction's redeemers (e.g. `{ purpose = "spend", index = 0 }`), and gives the transaction,
+its inputs and their resolved outputs as hex-encoded CBOR, like `aiken tx simulate`. Set
+`fail = true` when the validator is expected to reject the transaction.
+
 Test results are printed as stylized outputs when `stdout` is a TTY-capable terminal. If it
 isn't, (e.g. because you are redirecting the output to a file), test results are printed as
 a JSON structured object. Use `--show-json-schema` to see the whole schema.
@@ -71,6 +77,19 @@ pub struct Args {
     #[clap(short, long)]
     exact_match: bool,
 
+    /// When enabled, profile unit tests and write where their execution budget is spent as
+    /// folded stacks (one file per metric, under build/profile), ready to be turned into
+    /// flame graphs. Cannot be combined with --coverage, whose instrumentation would show up in
+    /// the profiles.
+    #[clap(long, conflicts_with = "coverage")]
+    profile: bool,
+
+    /// When enabled, record which functions and branches are evaluated by tests, print a
+    /// summary per module and write an lcov report under build/coverage. Execution units of
+    /// tests then include the cost of the instrumentation.
+    #[clap(long)]
+    coverage: bool,
+
     /// Environment to build against.
     #[clap(long)]
     env: Option<String>,
@@ -118,6 +137,8 @@ pub fn exec(
         trace_level,
         seed,
         max_success,
+        profile,
+        coverage,
         env,
     }: Args,
 ) -> miette::Result<()> {
@@ -139,6 +160,8 @@ pub fn exec(
                 exact_match,
                 seed,
                 max_success,
+                profile,
+                coverage,
                 match trace_filter {
                     Some(trace_filter) => trace_filter(trace_level),
                     None => Tracing::All(trace_level),
@@ -159,6 +182,8 @@ pub fn exec(
                     exact_match,
                     seed,
                     max_success,
+                    profile,
+                    coverage,
                     match trace_filter {
                         Some(trace_filter) => trace_filter(trace_level),
                         None => Tracing::All(trace_level),
@@ -1,6 +1,7 @@
 use aiken_project::config;
 use clap::Parser;
 
+pub mod benchmark;
 pub mod blueprint;
 pub mod build;
 pub mod check;
@@ -32,6 +33,7 @@ pub enum Cmd {
 
     #[clap(visible_alias("c"))]
     Check(check::Args),
+    Bench(benchmark::Args),
     Docs(docs::Args),
     Add(packages::add::Args),
 
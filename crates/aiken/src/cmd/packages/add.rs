@@ -18,7 +18,8 @@ pub struct Args {
     /// Note that by default, this assumes the package is located
     /// on Github.
     pub package: String,
-    /// The package version, as a git commit hash, a tag or a branch name.
+    /// The package version, as a git commit hash, a tag, a branch name or
+    /// a semver range over tags (e.g. '^1.2' or '~0.4').
     #[clap(long)]
     pub version: String,
 
@@ -33,6 +34,8 @@ pub fn exec(args: Args) -> miette::Result<()> {
         name: PackageName::from_str(&args.package)?,
         version: args.version,
         source: Platform::Github,
+        path: None,
+        git: None,
     };
 
     let config = match Config::load(&root) {
@@ -1,6 +1,7 @@
 pub mod add;
 pub mod clear_cache;
 pub mod upgrade;
+pub mod vendor;
 
 use clap::Subcommand;
 
@@ -15,6 +16,10 @@ pub enum Cmd {
 
     /// Clear the system-wide dependencies cache
     ClearCache,
+
+    /// Copy all dependencies into a local 'vendor' directory, used instead of
+    /// the network or the system-wide cache when resolving packages
+    Vendor,
 }
 
 pub fn exec(cmd: Cmd) -> miette::Result<()> {
@@ -22,5 +27,6 @@ pub fn exec(cmd: Cmd) -> miette::Result<()> {
         Cmd::Add(args) => add::exec(args),
         Cmd::ClearCache => clear_cache::exec(),
         Cmd::Upgrade(args) => upgrade::exec(args),
+        Cmd::Vendor => vendor::exec(),
     }
 }
@@ -10,7 +10,8 @@ pub struct Args {
     /// Note that by default, this assumes the package is located
     /// on Github.
     package: String,
-    /// The package version, as a git commit hash, a tag or a branch name.
+    /// The package version, as a git commit hash, a tag, a branch name or
+    /// a semver range over tags (e.g. '^1.2' or '~0.4').
     #[clap(long)]
     version: String,
 }
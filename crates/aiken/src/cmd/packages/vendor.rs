@@ -0,0 +1,45 @@
+use aiken_project::{config::Config, deps, paths, pretty, telemetry::Terminal};
+use owo_colors::{OwoColorize, Stream::Stderr};
+use std::{path::PathBuf, process};
+
+pub fn exec() -> miette::Result<()> {
+    let root = PathBuf::from(".");
+
+    let config = match Config::load(&root) {
+        Ok(config) => config,
+        Err(e) => {
+            e.report();
+            process::exit(1);
+        }
+    };
+
+    let packages = match deps::vendor(&Terminal, &root, &config) {
+        Ok(packages) => packages,
+        Err(e) => {
+            e.report();
+            process::exit(1);
+        }
+    };
+
+    for package in packages {
+        eprintln!(
+            "{} {} ({})",
+            pretty::pad_left("Vendoring".to_string(), 13, " ")
+                .if_supports_color(Stderr, |s| s.purple())
+                .if_supports_color(Stderr, |s| s.bold()),
+            package.if_supports_color(Stderr, |s| s.bold()),
+            paths::vendor_package(&package)
+                .display()
+                .if_supports_color(Stderr, |s| s.bright_blue()),
+        );
+    }
+
+    eprintln!(
+        "{}",
+        pretty::pad_left("Done".to_string(), 13, " ")
+            .if_supports_color(Stderr, |s| s.purple())
+            .if_supports_color(Stderr, |s| s.bold())
+    );
+
+    Ok(())
+}
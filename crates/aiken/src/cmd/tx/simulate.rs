@@ -1,3 +1,4 @@
+use clap::ValueEnum;
 use miette::IntoDiagnostic;
 use owo_colors::{OwoColorize, Stream::Stderr};
 use pallas_primitives::{
@@ -9,15 +10,18 @@ use std::{fmt, fs, path::PathBuf, process};
 use uplc::{
     machine::cost_model::ExBudget,
     tx::{
-        self, redeemer_tag_to_string,
+        self, eval_ledger_rules, eval_phase_one,
+        json::TransactionJson,
+        redeemer_tag_to_string,
         script_context::{ResolvedInput, SlotConfig},
+        DataLookupTable, ProtocolParameters, RedeemerReport,
     },
 };
 
 #[derive(clap::Args)]
 /// Simulate a transaction by evaluating it's script
 pub struct Args {
-    /// A file containing cbor hex for a transaction
+    /// A file containing cbor hex for a transaction, or a JSON document with --json
     #[clap(value_name = "FILEPATH")]
     input: PathBuf,
 
@@ -25,13 +29,21 @@ pub struct Args {
     #[clap(short, long)]
     cbor: bool,
 
+    /// Read the transaction and its resolved inputs from a single JSON document, with the
+    /// transaction as cbor hex (or a text envelope) and the UTxO set in the format of
+    /// 'cardano-cli query utxo --output-json':
+    ///
+    /// { "transaction": "84a4...", "utxos": { "{tx id}#{index}": { "address": ..., "value": ... } } }
+    #[clap(long, conflicts_with_all = ["cbor", "raw_inputs", "raw_outputs"])]
+    json: bool,
+
     /// A file containing cbor hex for the raw inputs
-    #[clap(value_name = "FILEPATH")]
-    raw_inputs: PathBuf,
+    #[clap(value_name = "FILEPATH", required_unless_present = "json")]
+    raw_inputs: Option<PathBuf>,
 
     /// A file containing cbor hex for the raw outputs
-    #[clap(value_name = "FILEPATH")]
-    raw_outputs: PathBuf,
+    #[clap(value_name = "FILEPATH", required_unless_present = "json")]
+    raw_outputs: Option<PathBuf>,
 
     /// Time between each slot
     #[clap(short, long, default_value_t = 1000, value_name = "MILLISECOND")]
@@ -44,17 +56,51 @@ pub struct Args {
     /// Slot number at the start of the shelley hardfork
     #[clap(long, default_value_t = 4492800, value_name = "SLOT")]
     zero_slot: u64,
+
+    /// Report on every redeemer (purpose, script hash, execution units consumed vs declared,
+    /// traces and failure reason) instead of only printing the execution units. Exits with a
+    /// non-zero status when any script fails or exceeds its declared execution units.
+    #[clap(long, value_name = "FORMAT")]
+    report: Option<ReportFormat>,
+
+    /// Also check the ledger rules unrelated to scripts (value preservation, minimum ada, fee,
+    /// total execution units, collateral, validity interval and required signers) before
+    /// evaluating scripts.
+    #[clap(long)]
+    ledger_rules: bool,
+
+    /// Slot at which the transaction is submitted, to check against its validity interval.
+    /// Only relevant with --ledger-rules.
+    #[clap(long, value_name = "SLOT", requires = "ledger_rules")]
+    current_slot: Option<u64>,
+
+    /// A file containing protocol parameters, as output by 'cardano-cli query
+    /// protocol-parameters'. Their cost models and maximum execution units are used to evaluate
+    /// scripts, and the other parameters to check ledger rules. Defaults to mainnet's.
+    #[clap(long, value_name = "FILEPATH")]
+    protocol_params: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+pub enum ReportFormat {
+    Json,
+    Table,
 }
 
 pub fn exec(
     Args {
         input,
         cbor,
+        json,
         raw_inputs,
         raw_outputs,
         slot_length,
         zero_time,
         zero_slot,
+        report,
+        ledger_rules,
+        current_slot,
+        protocol_params,
     }: Args,
 ) -> miette::Result<()> {
     eprintln!(
@@ -64,22 +110,51 @@ pub fn exec(
             .if_supports_color(Stderr, |s| s.bold())
     );
 
-    let (tx_bytes, inputs_bytes, outputs_bytes) = if cbor {
-        (
-            fs::read(input).into_diagnostic()?,
-            fs::read(raw_inputs).into_diagnostic()?,
-            fs::read(raw_outputs).into_diagnostic()?,
-        )
+    let (tx_bytes, resolved_inputs) = if json {
+        let document = fs::read_to_string(input).into_diagnostic()?;
+
+        let parsed = TransactionJson::parse(&document)
+            .and_then(|document| Ok((document.transaction()?, document.resolved_inputs()?)));
+
+        match parsed {
+            Ok(parsed) => parsed,
+            Err(err) => fail(err),
+        }
     } else {
-        let cbor_hex = fs::read_to_string(input).into_diagnostic()?;
-        let inputs_hex = fs::read_to_string(raw_inputs).into_diagnostic()?;
-        let outputs_hex = fs::read_to_string(raw_outputs).into_diagnostic()?;
-
-        (
-            hex::decode(cbor_hex.trim()).into_diagnostic()?,
-            hex::decode(inputs_hex.trim()).into_diagnostic()?,
-            hex::decode(outputs_hex.trim()).into_diagnostic()?,
-        )
+        let raw_inputs = raw_inputs.expect("required without --json");
+        let raw_outputs = raw_outputs.expect("required without --json");
+
+        let (tx_bytes, inputs_bytes, outputs_bytes) = if cbor {
+            (
+                fs::read(input).into_diagnostic()?,
+                fs::read(raw_inputs).into_diagnostic()?,
+                fs::read(raw_outputs).into_diagnostic()?,
+            )
+        } else {
+            let cbor_hex = fs::read_to_string(input).into_diagnostic()?;
+            let inputs_hex = fs::read_to_string(raw_inputs).into_diagnostic()?;
+            let outputs_hex = fs::read_to_string(raw_outputs).into_diagnostic()?;
+
+            (
+                hex::decode(cbor_hex.trim()).into_diagnostic()?,
+                hex::decode(inputs_hex.trim()).into_diagnostic()?,
+                hex::decode(outputs_hex.trim()).into_diagnostic()?,
+            )
+        };
+
+        let inputs = Vec::<TransactionInput>::decode_fragment(&inputs_bytes).unwrap();
+        let outputs = Vec::<TransactionOutput>::decode_fragment(&outputs_bytes).unwrap();
+
+        let resolved_inputs: Vec<ResolvedInput> = inputs
+            .iter()
+            .zip(outputs.iter())
+            .map(|(input, output)| ResolvedInput {
+                input: input.clone(),
+                output: output.clone(),
+            })
+            .collect();
+
+        (tx_bytes, resolved_inputs)
     };
 
     let tx = MultiEraTx::decode_for_era(Era::Conway, &tx_bytes).into_diagnostic()?;
@@ -92,17 +167,11 @@ pub fn exec(
         tx.hash()
     );
 
-    let inputs = Vec::<TransactionInput>::decode_fragment(&inputs_bytes).unwrap();
-    let outputs = Vec::<TransactionOutput>::decode_fragment(&outputs_bytes).unwrap();
-
-    let resolved_inputs: Vec<ResolvedInput> = inputs
-        .iter()
-        .zip(outputs.iter())
-        .map(|(input, output)| ResolvedInput {
-            input: input.clone(),
-            output: output.clone(),
-        })
-        .collect();
+    let protocol_parameters = match protocol_params {
+        Some(path) => ProtocolParameters::from_json(&fs::read_to_string(path).into_diagnostic()?)
+            .unwrap_or_else(|err| fail(err)),
+        None => ProtocolParameters::default(),
+    };
 
     if let Some(tx_conway) = tx.as_conway() {
         let slot_config = SlotConfig {
@@ -111,6 +180,51 @@ pub fn exec(
             slot_length,
         };
 
+        if ledger_rules {
+            if let Err(err) = eval_ledger_rules(
+                tx_conway,
+                &resolved_inputs,
+                &protocol_parameters,
+                &slot_config,
+                current_slot,
+            ) {
+                fail(err);
+            }
+        }
+
+        if let Some(format) = report {
+            let lookup_table = DataLookupTable::from_transaction(tx_conway, &resolved_inputs);
+
+            if let Err(err) = eval_phase_one(tx_conway, &resolved_inputs, &lookup_table) {
+                fail(err);
+            }
+
+            let reports = tx::simulate(
+                tx_conway,
+                &resolved_inputs,
+                protocol_parameters.cost_models.as_ref(),
+                Some(&protocol_parameters.max_tx_ex_units),
+                &slot_config,
+            );
+
+            match format {
+                ReportFormat::Json => println!(
+                    "{}",
+                    serde_json::to_string_pretty(
+                        &reports.iter().map(report_json).collect::<Vec<_>>()
+                    )
+                    .into_diagnostic()?
+                ),
+                ReportFormat::Table => print!("{}", report_table(&reports)),
+            }
+
+            if !reports.iter().all(RedeemerReport::is_success) {
+                process::exit(1);
+            }
+
+            return Ok(());
+        }
+
         let with_redeemer = |redeemer: &Redeemer| {
             eprintln!(
                 "{} {}[{}]",
@@ -125,8 +239,8 @@ pub fn exec(
         let result = tx::eval_phase_two(
             tx_conway,
             &resolved_inputs,
-            None,
-            None,
+            protocol_parameters.cost_models.as_ref(),
+            Some(&protocol_parameters.max_tx_ex_units),
             &slot_config,
             true,
             with_redeemer,
@@ -151,19 +265,157 @@ pub fn exec(
                         .into_diagnostic()?
                 );
             }
-            Err(err) => {
-                eprintln!(
-                    "{} {}",
-                    "        Error"
-                        .if_supports_color(Stderr, |s| s.red())
-                        .if_supports_color(Stderr, |s| s.bold()),
-                    err.red()
-                );
-
-                process::exit(1);
-            }
+            Err(err) => fail(err),
         }
     }
 
     Ok(())
 }
+
+fn fail(err: impl fmt::Display) -> ! {
+    eprintln!(
+        "{} {}",
+        "        Error"
+            .if_supports_color(Stderr, |s| s.red())
+            .if_supports_color(Stderr, |s| s.bold()),
+        err.red()
+    );
+
+    process::exit(1)
+}
+
+fn purpose(report: &RedeemerReport) -> String {
+    format!(
+        "{}[{}]",
+        redeemer_tag_to_string(&report.redeemer.tag).to_lowercase(),
+        report.redeemer.index
+    )
+}
+
+fn failure(report: &RedeemerReport) -> Option<String> {
+    match &report.evaluation {
+        Ok(evaluation) => match &evaluation.result {
+            Ok(()) if report.is_success() => None,
+            Ok(()) => Some("exceeded declared execution units".to_string()),
+            Err(err) => Some(err.to_string()),
+        },
+        Err(err) => Some(err.to_string()),
+    }
+}
+
+fn report_json(report: &RedeemerReport) -> serde_json::Value {
+    let declared = ExBudget {
+        mem: report.redeemer.ex_units.mem as i64,
+        cpu: report.redeemer.ex_units.steps as i64,
+    };
+
+    let evaluation = report.evaluation.as_ref().ok();
+
+    serde_json::json!({
+        "purpose": redeemer_tag_to_string(&report.redeemer.tag).to_lowercase(),
+        "index": report.redeemer.index,
+        "script_hash": evaluation.map(|e| e.script_hash.to_string()),
+        "language": evaluation.map(|e| format!("{:?}", e.language)),
+        "execution_units": {
+            "declared": declared,
+            "consumed": evaluation.map(|e| e.cost),
+        },
+        "success": report.is_success(),
+        "traces": evaluation.map(|e| e.logs.clone()).unwrap_or_default(),
+        "error": failure(report),
+    })
+}
+
+fn report_table(reports: &[RedeemerReport]) -> String {
+    let header = [
+        "Redeemer".to_string(),
+        "Script".to_string(),
+        "Memory (used/declared)".to_string(),
+        "CPU (used/declared)".to_string(),
+        "Status".to_string(),
+    ];
+
+    let rows = reports
+        .iter()
+        .map(|report| {
+            let evaluation = report.evaluation.as_ref().ok();
+            [
+                purpose(report),
+                evaluation
+                    .map(|e| e.script_hash.to_string())
+                    .unwrap_or_else(|| "?".to_string()),
+                format!(
+                    "{}/{}",
+                    evaluation
+                        .map(|e| e.cost.mem.to_string())
+                        .unwrap_or_default(),
+                    report.redeemer.ex_units.mem
+                ),
+                format!(
+                    "{}/{}",
+                    evaluation
+                        .map(|e| e.cost.cpu.to_string())
+                        .unwrap_or_default(),
+                    report.redeemer.ex_units.steps
+                ),
+                if report.is_success() {
+                    "ok".to_string()
+                } else {
+                    "FAILED".to_string()
+                },
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let widths = (0..header.len())
+        .map(|col| {
+            rows.iter()
+                .map(|row| row[col].len())
+                .chain([header[col].len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    let line = |row: &[String; 5]| {
+        row.iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut table = format!("{}\n", line(&header));
+
+    for row in rows.iter() {
+        table.push_str(&format!("{}\n", line(row)));
+    }
+
+    for report in reports {
+        let traces = report
+            .evaluation
+            .as_ref()
+            .map(|e| e.logs.clone())
+            .unwrap_or_default();
+
+        let failure = failure(report);
+
+        if traces.is_empty() && failure.is_none() {
+            continue;
+        }
+
+        table.push_str(&format!("\n{}\n", purpose(report)));
+
+        for trace in traces {
+            table.push_str(&format!("{:>13} {trace}\n", "Trace"));
+        }
+
+        if let Some(failure) = failure {
+            table.push_str(&format!("{:>13} {failure}\n", "Error"));
+        }
+    }
+
+    table
+}
@@ -0,0 +1,258 @@
+use miette::IntoDiagnostic;
+use owo_colors::{OwoColorize, Stream::Stderr};
+use pallas_primitives::conway::Language;
+use std::{
+    io::{self, BufRead, Write},
+    path::PathBuf,
+};
+use uplc::{
+    ast::{FakeNamedDeBruijn, Name, NamedDeBruijn, Program, Term},
+    builtins::DefaultFunction,
+    machine::{
+        cost_model::ExBudget,
+        debugger::{Breakpoint, Debugger, Focus, Stop, TermKind},
+        Machine,
+    },
+    parser,
+};
+
+#[derive(clap::Args)]
+/// Step through the evaluation of an Untyped Plutus Core program
+pub struct Args {
+    script: PathBuf,
+
+    #[clap(short, long)]
+    flat: bool,
+
+    #[clap(short, long)]
+    cbor: bool,
+
+    /// Arguments to pass to the UPLC program
+    args: Vec<String>,
+}
+
+const HELP: &str = r#"Commands:
+  s, step [N]              perform one (or N) evaluation step(s)
+  c, continue              run until the next breakpoint or the end of the evaluation
+  b, break builtin NAME    pause before calling the builtin NAME (e.g. addInteger)
+  b, break term KIND       pause before computing a term of the given KIND
+                           (var, delay, lam, apply, con, force, error, builtin, constr, case)
+  d, delete N              remove the N-th breakpoint
+  i, breakpoints           list breakpoints
+  t, term                  show the term being computed or the value being returned
+  e, env                   show the current environment, most recent binding first
+  k, stack                 show the current stack, innermost frame first
+  budget                   show the budget spent so far
+  logs                     show traces emitted so far
+  h, help                  show this help
+  q, quit                  exit the debugger
+
+An empty line repeats the last command."#;
+
+pub fn exec(
+    Args {
+        script,
+        flat,
+        args,
+        cbor,
+    }: Args,
+) -> miette::Result<()> {
+    let mut program: Program<Name> = if cbor {
+        let cbor_hex = std::fs::read_to_string(&script).into_diagnostic()?;
+
+        let raw_cbor = hex::decode(cbor_hex.trim()).into_diagnostic()?;
+
+        let program = Program::<FakeNamedDeBruijn>::from_cbor(&raw_cbor, &mut Vec::new())
+            .into_diagnostic()?;
+
+        let program: Program<NamedDeBruijn> = program.into();
+
+        Program::<Name>::try_from(program).into_diagnostic()?
+    } else if flat {
+        let bytes = std::fs::read(&script).into_diagnostic()?;
+
+        let program = Program::<FakeNamedDeBruijn>::from_flat(&bytes).into_diagnostic()?;
+
+        let program: Program<NamedDeBruijn> = program.into();
+
+        Program::<Name>::try_from(program).into_diagnostic()?
+    } else {
+        let code = std::fs::read_to_string(&script).into_diagnostic()?;
+
+        parser::program(&code).into_diagnostic()?
+    };
+
+    for arg in args {
+        let term = parser::term(&arg).into_diagnostic()?;
+
+        program = program.apply_term(&term)
+    }
+
+    let program = Program::<NamedDeBruijn>::try_from(program).into_diagnostic()?;
+
+    // A slippage of 1 ensures that the budget is spent after every step, so that it can be
+    // inspected accurately at any point.
+    let machine = Machine::new(
+        Language::PlutusV2,
+        Default::default(),
+        ExBudget::default(),
+        1,
+    );
+
+    let mut debugger = Debugger::new(machine, program.term);
+
+    eprintln!("{HELP}\n");
+
+    show_focus(&debugger);
+
+    let stdin = io::stdin();
+    let mut last_command = String::new();
+
+    loop {
+        eprint!("{} ", "(uplc)".if_supports_color(Stderr, |s| s.bold()));
+        io::stderr().flush().into_diagnostic()?;
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).into_diagnostic()? == 0 {
+            return Ok(());
+        }
+
+        let line = if line.trim().is_empty() {
+            last_command.clone()
+        } else {
+            line.trim().to_string()
+        };
+
+        let words = line.split_whitespace().collect::<Vec<_>>();
+
+        match words.as_slice() {
+            [] => {}
+            ["s" | "step"] => report(&debugger.step(), &debugger),
+            ["s" | "step", n] => match n.parse::<usize>() {
+                Ok(n) => {
+                    let mut stop = Stop::Step;
+                    for _ in 0..n {
+                        stop = debugger.step();
+                        if stop != Stop::Step {
+                            break;
+                        }
+                    }
+                    report(&stop, &debugger)
+                }
+                Err(_) => eprintln!("invalid number of steps: {n}"),
+            },
+            ["c" | "continue"] => report(&debugger.resume(), &debugger),
+            ["b" | "break", "builtin", name] => match name.parse::<DefaultFunction>() {
+                Ok(fun) => debugger.add_breakpoint(Breakpoint::Builtin(fun)),
+                Err(err) => eprintln!("{err}"),
+            },
+            ["b" | "break", "term", kind] => match kind.parse::<TermKind>() {
+                Ok(kind) => debugger.add_breakpoint(Breakpoint::Term(kind)),
+                Err(err) => eprintln!("{err}"),
+            },
+            ["d" | "delete", n] => match n
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| debugger.breakpoints().get(n).cloned())
+            {
+                Some(breakpoint) => {
+                    debugger.remove_breakpoint(&breakpoint);
+                }
+                None => eprintln!("no breakpoint at index {n}"),
+            },
+            ["i" | "breakpoints"] => {
+                for (ix, breakpoint) in debugger.breakpoints().iter().enumerate() {
+                    eprintln!("{ix}: {breakpoint}");
+                }
+            }
+            ["t" | "term"] => show_focus(&debugger),
+            ["e" | "env"] => {
+                for (ix, value) in debugger.env().iter().enumerate() {
+                    eprintln!("{}: {}", ix + 1, pretty(value));
+                }
+            }
+            ["k" | "stack"] => {
+                for frame in debugger.stack() {
+                    eprintln!("{frame}");
+                }
+            }
+            ["budget"] => {
+                let cost = debugger.cost();
+                eprintln!(
+                    "steps: {}\ncpu: {}\nmemory: {}",
+                    debugger.steps(),
+                    cost.cpu,
+                    cost.mem
+                );
+            }
+            ["logs"] => {
+                for log in debugger.logs() {
+                    eprintln!("{log}");
+                }
+            }
+            ["h" | "help"] => eprintln!("{HELP}"),
+            ["q" | "quit"] => return Ok(()),
+            _ => eprintln!("unknown command: {line} (type 'help' for a list of commands)"),
+        }
+
+        last_command = line;
+    }
+}
+
+fn report(stop: &Stop, debugger: &Debugger) {
+    match stop {
+        Stop::Step => show_focus(debugger),
+        Stop::Breakpoint(breakpoint) => {
+            eprintln!(
+                "{} {breakpoint}",
+                "breakpoint".if_supports_color(Stderr, |s| s.yellow())
+            );
+            show_focus(debugger);
+        }
+        Stop::Done(term) => {
+            eprintln!(
+                "{} {}",
+                "done".if_supports_color(Stderr, |s| s.green()),
+                pretty(term)
+            );
+        }
+        Stop::Failed(err) => {
+            eprintln!("{} {err}", "failed".if_supports_color(Stderr, |s| s.red()));
+        }
+    }
+}
+
+fn show_focus(debugger: &Debugger) {
+    match debugger.current() {
+        Some(Focus::Compute(term)) => eprintln!(
+            "{} {}",
+            "compute".if_supports_color(Stderr, |s| s.cyan()),
+            pretty(&term)
+        ),
+        Some(Focus::Return(term)) => eprintln!(
+            "{} {}",
+            "return".if_supports_color(Stderr, |s| s.cyan()),
+            pretty(&term)
+        ),
+        None => match debugger.outcome() {
+            Some(Ok(term)) => eprintln!(
+                "{} {}",
+                "done".if_supports_color(Stderr, |s| s.green()),
+                pretty(term)
+            ),
+            Some(Err(err)) => {
+                eprintln!("{} {err}", "failed".if_supports_color(Stderr, |s| s.red()))
+            }
+            None => {}
+        },
+    }
+}
+
+fn pretty(term: &Term<NamedDeBruijn>) -> String {
+    // Closed terms read better with their original names; open ones (e.g. the body of a lambda
+    // being computed) can only be shown with their de Bruijn indices.
+    match Term::<Name>::try_from(term.clone()) {
+        Ok(term) => term.to_pretty(),
+        Err(_) => term.to_pretty(),
+    }
+}
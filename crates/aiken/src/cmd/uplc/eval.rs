@@ -1,10 +1,14 @@
+use aiken_lang::plutus_version::PlutusVersion;
+use clap::builder::{MapValueParser, PossibleValuesParser, TypedValueParser};
 use miette::IntoDiagnostic;
+use pallas_primitives::conway::Language;
 use serde_json::json;
 use std::{path::PathBuf, process};
 use uplc::{
     ast::{FakeNamedDeBruijn, Name, NamedDeBruijn, Program, Term},
     machine::cost_model::ExBudget,
     parser,
+    tx::ProtocolParameters,
 };
 
 #[derive(clap::Args)]
@@ -20,6 +24,27 @@ pub struct Args {
 
     /// Arguments to pass to the UPLC program
     args: Vec<String>,
+
+    /// A file containing protocol parameters, as output by 'cardano-cli query
+    /// protocol-parameters'. The program is then evaluated with the cost model of its Plutus
+    /// version (see --plutus-version) and a budget of the maximum execution units of a
+    /// transaction.
+    #[clap(long, value_name = "FILEPATH")]
+    protocol_params: Option<PathBuf>,
+
+    /// Plutus version to evaluate the program as. Defaults to v3 with --protocol-params.
+    #[clap(long, value_name = "VERSION", value_parser = plutus_version_parser())]
+    plutus_version: Option<PlutusVersion>,
+}
+
+pub fn plutus_version_parser() -> MapValueParser<PossibleValuesParser, fn(String) -> PlutusVersion>
+{
+    PossibleValuesParser::new(["v1", "v2", "v3"]).map(|s| match s.as_str() {
+        "v1" => PlutusVersion::V1,
+        "v2" => PlutusVersion::V2,
+        "v3" => PlutusVersion::V3,
+        _ => unreachable!(),
+    })
 }
 
 pub fn exec(
@@ -28,6 +53,8 @@ pub fn exec(
         flat,
         args,
         cbor,
+        protocol_params,
+        plutus_version,
     }: Args,
 ) -> miette::Result<()> {
     let mut program: Program<Name> = if cbor {
@@ -61,11 +88,44 @@ pub fn exec(
         program = program.apply_term(&term)
     }
 
-    let budget = ExBudget::default();
-
     let program = Program::<NamedDeBruijn>::try_from(program).into_diagnostic()?;
 
-    let mut eval_result = program.eval(budget);
+    let mut eval_result = match protocol_params {
+        None => match plutus_version {
+            None => program.eval(ExBudget::default()),
+            Some(version) => program.eval_version(ExBudget::default(), &version.into()),
+        },
+        Some(path) => {
+            let json = std::fs::read_to_string(path).into_diagnostic()?;
+
+            let protocol_parameters = ProtocolParameters::from_json(&json).unwrap_or_else(|err| {
+                eprintln!("\nError\n-----\n\n{err}\n");
+                process::exit(1)
+            });
+
+            let language = plutus_version
+                .map(Language::from)
+                .unwrap_or(Language::PlutusV3);
+
+            let costs = protocol_parameters
+                .cost_models
+                .and_then(|cost_models| match language {
+                    Language::PlutusV1 => cost_models.plutus_v1,
+                    Language::PlutusV2 => cost_models.plutus_v2,
+                    Language::PlutusV3 => cost_models.plutus_v3,
+                })
+                .unwrap_or_else(|| {
+                    eprintln!("\nError\n-----\n\nno cost model for {language:?} in the protocol parameters\n");
+                    process::exit(1)
+                });
+
+            program.eval_as(
+                &language,
+                &costs,
+                Some(&protocol_parameters.max_tx_ex_units),
+            )
+        }
+    };
 
     let cost = eval_result.cost();
     let logs = eval_result.logs();
@@ -1,3 +1,4 @@
+mod debug;
 mod decode;
 mod encode;
 mod eval;
@@ -18,6 +19,7 @@ pub(super) enum Format {
 pub enum Cmd {
     Fmt(fmt::Args),
     Eval(eval::Args),
+    Debug(debug::Args),
     #[clap(alias = "flat")]
     Encode(encode::Args),
     #[clap(alias = "unflat")]
@@ -30,6 +32,7 @@ pub fn exec(cmd: Cmd) -> miette::Result<()> {
     match cmd {
         Cmd::Fmt(args) => fmt::exec(args),
         Cmd::Eval(args) => eval::exec(args),
+        Cmd::Debug(args) => debug::exec(args),
         Cmd::Encode(args) => encode::exec(args),
         Cmd::Decode(args) => decode::exec(args),
         Cmd::Shrink(args) => shrink::exec(args),
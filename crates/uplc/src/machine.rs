@@ -3,6 +3,7 @@ use std::rc::Rc;
 use crate::ast::{Constant, NamedDeBruijn, Term, Type};
 
 pub mod cost_model;
+pub mod debugger;
 mod discharge;
 mod error;
 pub mod eval_result;
@@ -19,14 +20,17 @@ use self::{
     value::{Env, Value},
 };
 
-enum MachineState {
+/// A snapshot of the CEK machine in-between two transitions. The machine is either computing a
+/// term in some environment, returning a value to the innermost frame of its context, or done.
+pub enum MachineState {
     Return(Context, Value),
     Compute(Context, Env, Term<NamedDeBruijn>),
     Done(Term<NamedDeBruijn>),
 }
 
+/// The continuation (a.k.a stack) of the CEK machine, as a chain of frames.
 #[derive(Clone)]
-enum Context {
+pub enum Context {
     FrameAwaitArg(Value, Box<Context>),
     FrameAwaitFunTerm(Env, Term<NamedDeBruijn>, Box<Context>),
     FrameAwaitFunValue(Value, Box<Context>),
@@ -51,6 +55,58 @@ pub struct Machine {
     version: Language,
 }
 
+impl Context {
+    /// Number of frames in the continuation.
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut context = self;
+
+        loop {
+            context = match context {
+                Context::NoFrame => return depth,
+                Context::FrameAwaitArg(_, ctx)
+                | Context::FrameAwaitFunTerm(_, _, ctx)
+                | Context::FrameAwaitFunValue(_, ctx)
+                | Context::FrameForce(ctx)
+                | Context::FrameConstr(_, _, _, _, ctx)
+                | Context::FrameCases(_, _, ctx) => ctx,
+            };
+            depth += 1;
+        }
+    }
+}
+
+impl MachineState {
+    /// Number of frames the next step pushes onto the context, or pops from it when negative.
+    /// This lets observers keep track of the depth of the context without walking it.
+    pub fn frames_delta(&self) -> isize {
+        match self {
+            MachineState::Compute(_, _, term) => match term {
+                Term::Apply { .. } | Term::Force(_) | Term::Case { .. } => 1,
+                Term::Constr { fields, .. } => isize::from(!fields.is_empty()),
+                Term::Var(_)
+                | Term::Delay(_)
+                | Term::Lambda { .. }
+                | Term::Constant(_)
+                | Term::Builtin(_)
+                | Term::Error => 0,
+            },
+            MachineState::Return(context, value) => match context {
+                Context::NoFrame | Context::FrameAwaitFunTerm(..) => 0,
+                Context::FrameAwaitArg(..)
+                | Context::FrameAwaitFunValue(..)
+                | Context::FrameForce(_) => -1,
+                Context::FrameConstr(_, _, fields, _, _) => -isize::from(fields.is_empty()),
+                Context::FrameCases(..) => match value {
+                    Value::Constr { fields, .. } => fields.len() as isize - 1,
+                    _ => -1,
+                },
+            },
+            MachineState::Done(_) => 0,
+        }
+    }
+}
+
 impl Machine {
     pub fn new(
         version: Language,
@@ -69,25 +125,41 @@ impl Machine {
     }
 
     pub fn run(&mut self, term: Term<NamedDeBruijn>) -> Result<Term<NamedDeBruijn>, Error> {
-        use MachineState::*;
-
-        let startup_budget = self.costs.machine_costs.get(StepKind::StartUp);
-
-        self.spend_budget(startup_budget)?;
-
-        let mut state = Compute(Context::NoFrame, Rc::new(vec![]), term);
+        let mut state = self.start(term)?;
 
         loop {
             state = match state {
-                Compute(context, env, t) => self.compute(context, env, t)?,
-                Return(context, value) => self.return_compute(context, value)?,
-                Done(t) => {
+                MachineState::Done(t) => {
                     return Ok(t);
                 }
+                _ => self.step(state)?,
             };
         }
     }
 
+    /// Spend the startup budget and yield the initial state of the machine for the given term.
+    pub fn start(&mut self, term: Term<NamedDeBruijn>) -> Result<MachineState, Error> {
+        let startup_budget = self.costs.machine_costs.get(StepKind::StartUp);
+
+        self.spend_budget(startup_budget)?;
+
+        Ok(MachineState::Compute(
+            Context::NoFrame,
+            Rc::new(vec![]),
+            term,
+        ))
+    }
+
+    /// Perform a single transition of the machine. Stepping a machine that is already done
+    /// yields the same final state.
+    pub fn step(&mut self, state: MachineState) -> Result<MachineState, Error> {
+        match state {
+            MachineState::Compute(context, env, t) => self.compute(context, env, t),
+            MachineState::Return(context, value) => self.return_compute(context, value),
+            MachineState::Done(t) => Ok(MachineState::Done(t)),
+        }
+    }
+
     fn compute(
         &mut self,
         context: Context,
@@ -408,11 +480,16 @@ impl From<&Constant> for Type {
 mod tests {
     use num_bigint::BigInt;
 
-    use super::{cost_model::ExBudget, runtime::Compressable};
+    use super::{
+        cost_model::{CostModel, ExBudget},
+        runtime::Compressable,
+        Machine, MachineState,
+    };
     use crate::{
         ast::{Constant, NamedDeBruijn, Program, Term},
         builtins::DefaultFunction,
     };
+    use pallas_primitives::conway::Language;
 
     #[test]
     fn add_big_ints() {
@@ -486,6 +563,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn frames_delta() {
+        let program: Program<NamedDeBruijn> = crate::parser::program(
+            r#"(program 1.1.0
+              (case
+                (constr 1 (con integer 1) [(lam x (force (delay x))) (con integer 2)])
+                (lam a (lam b [(builtin addInteger) a b]))
+                (lam a (lam b (force [(force (builtin ifThenElse)) (con bool True) (delay [(builtin subtractInteger) a b]) (delay (error))])))
+              )
+            )"#,
+        )
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+        let mut machine = Machine::new(
+            Language::PlutusV3,
+            CostModel::default(),
+            ExBudget::max(),
+            200,
+        );
+
+        let mut state = machine.start(program.term).unwrap();
+
+        let mut depth = 0;
+
+        loop {
+            match state {
+                MachineState::Done(term) => {
+                    assert_eq!(term, Term::Constant(Constant::Integer((-1).into()).into()));
+                    break;
+                }
+                MachineState::Compute(ref context, ..) | MachineState::Return(ref context, ..) => {
+                    assert_eq!(context.depth() as isize, depth);
+                }
+            }
+
+            depth += state.frames_delta();
+
+            state = machine.step(state).unwrap();
+        }
+    }
+
     #[test]
     fn case_constr_case_0() {
         let make_program =
@@ -85,6 +85,16 @@ impl CostModel {
     }
 }
 
+impl From<&Language> for CostModel {
+    fn from(version: &Language) -> Self {
+        match version {
+            Language::PlutusV1 => CostModel::v1(),
+            Language::PlutusV2 => CostModel::v2(),
+            Language::PlutusV3 => CostModel::v3(),
+        }
+    }
+}
+
 /// There's no entry for Error since we'll be exiting anyway; also, what would
 /// happen if calling 'Error' caused the budget to be exceeded?
 #[derive(Debug, PartialEq)]
@@ -0,0 +1,429 @@
+use std::{fmt, str::FromStr};
+
+use crate::{
+    ast::{NamedDeBruijn, Term},
+    builtins::DefaultFunction,
+};
+
+use super::{
+    cost_model::ExBudget,
+    discharge,
+    value::{Env, Value},
+    Context, Error, Machine, MachineState,
+};
+
+/// A step-through debugger for the CEK machine. It drives a [`Machine`] one transition at a time,
+/// pausing on breakpoints and giving access to the current term, environment and stack in-between
+/// steps.
+///
+/// Note that the machine only spends its budget for steps once it has accumulated enough of them
+/// (see 'slippage'); use a slippage of 1 for budgets to be accurate after every single step.
+pub struct Debugger {
+    machine: Machine,
+    initial_budget: ExBudget,
+    state: Option<MachineState>,
+    outcome: Option<Result<Term<NamedDeBruijn>, Error>>,
+    breakpoints: Vec<Breakpoint>,
+    steps: usize,
+}
+
+/// The reason why the debugger handed control back.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stop {
+    /// A single step was performed.
+    Step,
+    /// The machine is about to perform a step matching the given breakpoint.
+    Breakpoint(Breakpoint),
+    /// The evaluation terminated with the given term.
+    Done(Term<NamedDeBruijn>),
+    /// The evaluation failed with the given error.
+    Failed(Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Break before computing any term of the given kind.
+    Term(TermKind),
+    /// Break before calling the given builtin, once all of its arguments have been evaluated.
+    Builtin(DefaultFunction),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TermKind {
+    Var,
+    Delay,
+    Lambda,
+    Apply,
+    Constant,
+    Force,
+    Error,
+    Builtin,
+    Constr,
+    Case,
+}
+
+impl Debugger {
+    pub fn new(mut machine: Machine, term: Term<NamedDeBruijn>) -> Self {
+        let initial_budget = machine.ex_budget;
+
+        let (state, outcome) = match machine.start(term) {
+            Ok(state) => (Some(state), None),
+            Err(err) => (None, Some(Err(err))),
+        };
+
+        Debugger {
+            machine,
+            initial_budget,
+            state,
+            outcome,
+            breakpoints: vec![],
+            steps: 0,
+        }
+    }
+
+    /// Perform a single transition of the machine.
+    pub fn step(&mut self) -> Stop {
+        if let Some(stop) = self.finished() {
+            return stop;
+        }
+
+        let state = self.state.take().expect("no state on unfinished machine");
+
+        self.steps += 1;
+
+        match self.machine.step(state) {
+            Ok(MachineState::Done(term)) => {
+                self.outcome = Some(Ok(term.clone()));
+                Stop::Done(term)
+            }
+            Ok(state) => {
+                self.state = Some(state);
+                Stop::Step
+            }
+            Err(err) => {
+                self.outcome = Some(Err(err.clone()));
+                Stop::Failed(err)
+            }
+        }
+    }
+
+    /// Keep stepping until the evaluation terminates, or until the machine is about to perform a
+    /// step matching one of the breakpoints. The current step never triggers a breakpoint, so that
+    /// resuming from a breakpoint always makes progress.
+    pub fn resume(&mut self) -> Stop {
+        loop {
+            match self.step() {
+                Stop::Step => {
+                    if let Some(breakpoint) = self.hit_breakpoint() {
+                        return Stop::Breakpoint(breakpoint);
+                    }
+                }
+                stop => return stop,
+            }
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    pub fn remove_breakpoint(&mut self, breakpoint: &Breakpoint) -> bool {
+        let before = self.breakpoints.len();
+        self.breakpoints.retain(|b| b != breakpoint);
+        before != self.breakpoints.len()
+    }
+
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    /// Number of transitions performed so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Budget spent so far.
+    pub fn cost(&self) -> ExBudget {
+        self.initial_budget - self.machine.ex_budget
+    }
+
+    pub fn logs(&self) -> &[String] {
+        &self.machine.logs
+    }
+
+    /// The final result of the evaluation, if it has terminated.
+    pub fn outcome(&self) -> Option<&Result<Term<NamedDeBruijn>, Error>> {
+        self.outcome.as_ref()
+    }
+
+    /// The term being computed, or the value being returned, depending on the current state.
+    pub fn current(&self) -> Option<Focus> {
+        match self.state.as_ref()? {
+            MachineState::Compute(_, _, term) => Some(Focus::Compute(term.clone())),
+            MachineState::Return(_, value) => Some(Focus::Return(as_term(value))),
+            MachineState::Done(term) => Some(Focus::Return(term.clone())),
+        }
+    }
+
+    /// Values bound in the current environment, starting from the most recent binding (i.e. de
+    /// Bruijn index 1). The environment is only available while computing a term.
+    pub fn env(&self) -> Vec<Term<NamedDeBruijn>> {
+        match self.state.as_ref() {
+            Some(MachineState::Compute(_, env, _)) => env_as_terms(env),
+            _ => vec![],
+        }
+    }
+
+    /// Frames of the current continuation, from the innermost to the outermost.
+    pub fn stack(&self) -> Vec<String> {
+        let mut context = match self.state.as_ref() {
+            Some(MachineState::Compute(context, _, _)) | Some(MachineState::Return(context, _)) => {
+                context
+            }
+            _ => return vec![],
+        };
+
+        let mut frames = vec![];
+
+        loop {
+            let (frame, next) = match context {
+                Context::NoFrame => return frames,
+                Context::FrameAwaitArg(fun, ctx) => {
+                    (format!("apply {} to ◆", as_term(fun).to_pretty()), ctx)
+                }
+                Context::FrameAwaitFunTerm(_, arg, ctx) => {
+                    (format!("apply ◆ to {}", arg.to_pretty()), ctx)
+                }
+                Context::FrameAwaitFunValue(arg, ctx) => {
+                    (format!("apply ◆ to {}", as_term(arg).to_pretty()), ctx)
+                }
+                Context::FrameForce(ctx) => ("force ◆".to_string(), ctx),
+                Context::FrameConstr(_, tag, fields, resolved, ctx) => (
+                    format!(
+                        "constr {tag} ({} evaluated, ◆, {} remaining)",
+                        resolved.len(),
+                        fields.len()
+                    ),
+                    ctx,
+                ),
+                Context::FrameCases(_, branches, ctx) => {
+                    (format!("case ◆ ({} branches)", branches.len()), ctx)
+                }
+            };
+
+            frames.push(frame);
+            context = next;
+        }
+    }
+
+    fn finished(&self) -> Option<Stop> {
+        match self.outcome.as_ref()? {
+            Ok(term) => Some(Stop::Done(term.clone())),
+            Err(err) => Some(Stop::Failed(err.clone())),
+        }
+    }
+
+    fn hit_breakpoint(&self) -> Option<Breakpoint> {
+        let state = self.state.as_ref()?;
+
+        self.breakpoints
+            .iter()
+            .find(|breakpoint| match (breakpoint, state) {
+                (Breakpoint::Term(kind), MachineState::Compute(_, _, term)) => {
+                    TermKind::from(term) == *kind
+                }
+                (Breakpoint::Builtin(fun), _) => pending_builtin(state) == Some(*fun),
+                _ => false,
+            })
+            .cloned()
+    }
+}
+
+/// What the machine is currently focused on.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Focus {
+    Compute(Term<NamedDeBruijn>),
+    Return(Term<NamedDeBruijn>),
+}
+
+/// The builtin that the next step is going to call, if any. A builtin gets called as soon as it
+/// receives its last argument or its last force.
+pub fn pending_builtin(state: &MachineState) -> Option<DefaultFunction> {
+    let saturated_by_arg = |fun: &DefaultFunction, runtime: &super::runtime::BuiltinRuntime| {
+        runtime.is_arrow() && !runtime.needs_force() && runtime.args.len() + 1 == fun.arity()
+    };
+
+    match state {
+        MachineState::Return(Context::FrameAwaitArg(Value::Builtin { fun, runtime }, _), _)
+        | MachineState::Return(
+            Context::FrameAwaitFunValue(_, _),
+            Value::Builtin { fun, runtime },
+        ) if saturated_by_arg(fun, runtime) => Some(*fun),
+        MachineState::Return(Context::FrameForce(_), Value::Builtin { fun, runtime })
+            if runtime.forces + 1 == fun.force_count() && runtime.args.len() == fun.arity() =>
+        {
+            Some(*fun)
+        }
+        _ => None,
+    }
+}
+
+fn as_term(value: &Value) -> Term<NamedDeBruijn> {
+    discharge::value_as_term(value.clone())
+}
+
+fn env_as_terms(env: &Env) -> Vec<Term<NamedDeBruijn>> {
+    env.iter().rev().map(as_term).collect()
+}
+
+impl From<&Term<NamedDeBruijn>> for TermKind {
+    fn from(term: &Term<NamedDeBruijn>) -> Self {
+        match term {
+            Term::Var(_) => TermKind::Var,
+            Term::Delay(_) => TermKind::Delay,
+            Term::Lambda { .. } => TermKind::Lambda,
+            Term::Apply { .. } => TermKind::Apply,
+            Term::Constant(_) => TermKind::Constant,
+            Term::Force(_) => TermKind::Force,
+            Term::Error => TermKind::Error,
+            Term::Builtin(_) => TermKind::Builtin,
+            Term::Constr { .. } => TermKind::Constr,
+            Term::Case { .. } => TermKind::Case,
+        }
+    }
+}
+
+impl FromStr for TermKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "var" => Ok(TermKind::Var),
+            "delay" => Ok(TermKind::Delay),
+            "lam" | "lambda" => Ok(TermKind::Lambda),
+            "apply" => Ok(TermKind::Apply),
+            "con" | "constant" => Ok(TermKind::Constant),
+            "force" => Ok(TermKind::Force),
+            "error" => Ok(TermKind::Error),
+            "builtin" => Ok(TermKind::Builtin),
+            "constr" => Ok(TermKind::Constr),
+            "case" => Ok(TermKind::Case),
+            rest => Err(format!("unknown term kind: {rest}")),
+        }
+    }
+}
+
+impl fmt::Display for TermKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            TermKind::Var => "var",
+            TermKind::Delay => "delay",
+            TermKind::Lambda => "lam",
+            TermKind::Apply => "apply",
+            TermKind::Constant => "con",
+            TermKind::Force => "force",
+            TermKind::Error => "error",
+            TermKind::Builtin => "builtin",
+            TermKind::Constr => "constr",
+            TermKind::Case => "case",
+        };
+
+        write!(f, "{kind}")
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Breakpoint::Term(kind) => write!(f, "term {kind}"),
+            Breakpoint::Builtin(fun) => write!(f, "builtin {fun}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Breakpoint, Debugger, Focus, Stop, TermKind};
+    use crate::{
+        ast::{NamedDeBruijn, Program, Term},
+        builtins::DefaultFunction,
+        machine::{cost_model::ExBudget, Error, Machine},
+        parser,
+    };
+    use pallas_primitives::conway::Language;
+
+    fn debugger(src: &str) -> Debugger {
+        let program = parser::program(src).unwrap();
+        let program = Program::<NamedDeBruijn>::try_from(program).unwrap();
+        let machine = Machine::new(Language::PlutusV2, Default::default(), ExBudget::max(), 1);
+        Debugger::new(machine, program.term)
+    }
+
+    #[test]
+    fn step_until_done_matches_run() {
+        let src =
+            "(program 1.0.0 [(lam x [(builtin addInteger) x (con integer 2)]) (con integer 1)])";
+
+        let mut debugger = debugger(src);
+
+        let result = loop {
+            match debugger.step() {
+                Stop::Step => continue,
+                stop => break stop,
+            }
+        };
+
+        let program = Program::<NamedDeBruijn>::try_from(parser::program(src).unwrap()).unwrap();
+        let eval_result = program.eval(ExBudget::max());
+
+        assert_eq!(result, Stop::Done(eval_result.result().unwrap()));
+        assert_eq!(debugger.cost(), eval_result.cost());
+        assert_eq!(debugger.step(), result);
+    }
+
+    #[test]
+    fn break_on_builtin() {
+        let mut debugger = debugger(
+            "(program 1.0.0 [(lam x [(builtin addInteger) x (con integer 2)]) (con integer 1)])",
+        );
+
+        debugger.add_breakpoint(Breakpoint::Builtin(DefaultFunction::AddInteger));
+
+        assert_eq!(
+            debugger.resume(),
+            Stop::Breakpoint(Breakpoint::Builtin(DefaultFunction::AddInteger))
+        );
+        assert_eq!(
+            debugger.current(),
+            Some(Focus::Return(Term::integer(2.into())))
+        );
+        assert_eq!(
+            debugger.stack(),
+            vec!["apply [ (builtin addInteger) (con integer 1) ] to ◆".to_string()]
+        );
+
+        assert_eq!(debugger.resume(), Stop::Done(Term::integer(3.into())));
+    }
+
+    #[test]
+    fn break_on_term() {
+        let mut debugger = debugger(
+            "(program 1.0.0 [(lam x [(lam y (error)) (con integer 14)]) (con integer 42)])",
+        );
+
+        debugger.add_breakpoint(Breakpoint::Term(TermKind::Error));
+
+        assert_eq!(
+            debugger.resume(),
+            Stop::Breakpoint(Breakpoint::Term(TermKind::Error))
+        );
+        assert_eq!(
+            debugger.env(),
+            vec![Term::integer(14.into()), Term::integer(42.into())]
+        );
+
+        assert_eq!(debugger.resume(), Stop::Failed(Error::EvaluationFailure));
+    }
+}
@@ -1,15 +1,28 @@
-use crate::ast::{Name, Program};
+use crate::{
+    ast::{Name, Program},
+    optimize::annotations::Annotations,
+};
 
+pub mod annotations;
 pub mod interner;
 pub mod shrinker;
 
 pub fn aiken_optimize_and_intern(program: Program<Name>) -> Program<Name> {
-    let mut prog = program.run_once_pass();
+    aiken_optimize_and_intern_annotated(program, &mut Annotations::new())
+}
+
+/// Same as [`aiken_optimize_and_intern`], while keeping the given annotations in sync with the
+/// optimized program.
+pub fn aiken_optimize_and_intern_annotated(
+    program: Program<Name>,
+    annotations: &mut Annotations,
+) -> Program<Name> {
+    let mut prog = program.run_once_pass_annotated(annotations);
 
     let mut prev_count = 0;
 
     loop {
-        let (current_program, context) = prog.multi_pass();
+        let (current_program, context) = prog.multi_pass_annotated(annotations);
 
         if context.node_count == prev_count {
             prog = current_program;
@@ -21,13 +34,13 @@ pub fn aiken_optimize_and_intern(program: Program<Name>) -> Program<Name> {
     }
 
     prog = prog
-        .builtin_curry_reducer()
-        .multi_pass()
+        .builtin_curry_reducer_annotated(annotations)
+        .multi_pass_annotated(annotations)
         .0
-        .builtin_curry_reducer();
+        .builtin_curry_reducer_annotated(annotations);
 
     loop {
-        let (current_program, context) = prog.multi_pass();
+        let (current_program, context) = prog.multi_pass_annotated(annotations);
 
         if context.node_count == prev_count {
             prog = current_program;
@@ -38,5 +51,5 @@ pub fn aiken_optimize_and_intern(program: Program<Name>) -> Program<Name> {
         }
     }
 
-    prog.clean_up()
+    prog.clean_up_annotated(annotations)
 }
@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+
+/// Opaque identifiers attached to sub-terms of a program, addressed by their path from the root
+/// of the program. Each step of a path selects a child of the current term: the body (0) of a
+/// `delay`, `force` or `lam`; the function (0) or the argument (1) of an `apply`.
+///
+/// Annotations are carried through optimizations: passes report every structural change they make
+/// as a [`Rewrite`], and annotated sub-terms follow along. Sub-terms discarded by an optimization
+/// lose their annotations.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Annotations(BTreeMap<Vec<usize>, usize>);
+
+impl Annotations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn insert(&mut self, path: Vec<usize>, annotation: usize) {
+        self.0.insert(path, annotation);
+    }
+
+    pub fn get(&self, path: &[usize]) -> Option<usize> {
+        self.0.get(path).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&[usize], usize)> {
+        self.0
+            .iter()
+            .map(|(path, annotation)| (path.as_slice(), *annotation))
+    }
+
+    /// Relocate the annotations below the term found at `path`, after it has been replaced by a
+    /// new term (see [`Rewrite::Moves`]). The term itself keeps its annotation, since
+    /// optimizations preserve its meaning.
+    pub fn relocate(&mut self, path: &[usize], moves: &[(Vec<usize>, Vec<usize>)]) {
+        if self.0.is_empty() {
+            return;
+        }
+
+        let mut relocated = vec![];
+
+        for (key, annotation) in self.remove_below(path) {
+            let relative = &key[path.len()..];
+
+            if relative.is_empty() {
+                relocated.insert(0, (key, annotation));
+            } else if let Some((from, to)) =
+                moves.iter().find(|(from, _)| relative.starts_with(from))
+            {
+                relocated.push(([path, to, &relative[from.len()..]].concat(), annotation));
+            }
+        }
+
+        for (key, annotation) in relocated {
+            self.0.entry(key).or_insert(annotation);
+        }
+    }
+
+    /// Copy the annotations of the term found at `from` (and below) to `to`, after the former
+    /// has been substituted for the latter. Annotations already present at the destination win.
+    pub fn copy(&mut self, from: &[usize], to: &[usize]) {
+        let copied = self
+            .range(from)
+            .map(|(key, annotation)| ([to, &key[from.len()..]].concat(), *annotation))
+            .collect::<Vec<_>>();
+
+        for (key, annotation) in copied {
+            self.0.entry(key).or_insert(annotation);
+        }
+    }
+
+    fn range<'a>(&'a self, path: &'a [usize]) -> impl Iterator<Item = (&'a Vec<usize>, &'a usize)> {
+        self.0
+            .range(path.to_vec()..)
+            .take_while(move |(key, _)| key.starts_with(path))
+    }
+
+    fn remove_below(&mut self, path: &[usize]) -> Vec<(Vec<usize>, usize)> {
+        let keys = self
+            .range(path)
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+
+        keys.into_iter()
+            .filter_map(|key| self.0.remove_entry(&key))
+            .collect()
+    }
+}
+
+/// A structural change made by an optimization to the term it visits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rewrite {
+    /// The term at `at` (relative to the visited term) is replaced by a new one. Sub-terms found
+    /// under the origin of one of the `moves` now live under its destination, both being relative
+    /// to the replaced term. Anything else below the replaced term is gone.
+    Moves {
+        at: Vec<usize>,
+        moves: Vec<(Vec<usize>, Vec<usize>)>,
+    },
+    /// The argument of an application, or rather its sub-term found at `from`, has been
+    /// substituted for each of the sub-terms found at `to` (relative to the visited term).
+    Substitute {
+        apply: usize,
+        from: Vec<usize>,
+        to: Vec<Vec<usize>>,
+    },
+}
+
+impl Rewrite {
+    /// The term is replaced by one of its descendants.
+    pub fn lift(from: Vec<usize>) -> Self {
+        Self::moves(vec![(from, vec![])])
+    }
+
+    /// The term is wrapped into a new one, under the given path.
+    pub fn wrap(to: Vec<usize>) -> Self {
+        Self::moves(vec![(vec![], to)])
+    }
+
+    /// The term is replaced by a new one, which retains some of its descendants.
+    pub fn moves(moves: Vec<(Vec<usize>, Vec<usize>)>) -> Self {
+        Rewrite::Moves { at: vec![], moves }
+    }
+
+    /// The replacement happens on a descendant of the visited term, rather than on the term
+    /// itself.
+    pub fn at(self, path: Vec<usize>) -> Self {
+        match self {
+            Rewrite::Moves { moves, .. } => Rewrite::Moves { at: path, moves },
+            substitute @ Rewrite::Substitute { .. } => substitute,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Annotations;
+
+    fn annotations(entries: &[(&[usize], usize)]) -> Annotations {
+        let mut annotations = Annotations::new();
+        for (path, annotation) in entries {
+            annotations.insert(path.to_vec(), *annotation);
+        }
+        annotations
+    }
+
+    #[test]
+    fn relocate_lift() {
+        let mut subject = annotations(&[(&[0], 1), (&[0, 1], 2), (&[0, 0, 1], 3), (&[0, 1, 0], 4)]);
+
+        subject.relocate(&[0], &[(vec![0], vec![])]);
+
+        assert_eq!(subject, annotations(&[(&[0], 1), (&[0, 1], 3)]));
+    }
+
+    #[test]
+    fn relocate_lift_keeps_outer_annotation() {
+        let mut subject = annotations(&[(&[], 1), (&[0], 2), (&[0, 0], 3)]);
+
+        subject.relocate(&[], &[(vec![0], vec![])]);
+
+        assert_eq!(subject, annotations(&[(&[], 1), (&[0], 3)]));
+    }
+
+    #[test]
+    fn relocate_wrap() {
+        let mut subject = annotations(&[(&[], 1), (&[1], 2), (&[1, 0], 3)]);
+
+        subject.relocate(&[], &[(vec![], vec![0, 0])]);
+
+        assert_eq!(
+            subject,
+            annotations(&[(&[], 1), (&[0, 0, 1], 2), (&[0, 0, 1, 0], 3)])
+        );
+    }
+
+    #[test]
+    fn relocate_moves() {
+        let mut subject = annotations(&[(&[1, 0], 1), (&[1, 0, 1], 2), (&[1, 0, 0], 3), (&[0], 4)]);
+
+        subject.relocate(&[1, 0], &[(vec![1], vec![1, 1])]);
+
+        assert_eq!(
+            subject,
+            annotations(&[(&[1, 0], 1), (&[1, 0, 1, 1], 2), (&[0], 4)])
+        );
+    }
+
+    #[test]
+    fn copy() {
+        let mut subject = annotations(&[(&[1], 1), (&[1, 0], 2), (&[0, 1], 3), (&[0, 0, 0], 4)]);
+
+        subject.copy(&[1], &[0, 0]);
+
+        assert_eq!(
+            subject,
+            annotations(&[
+                (&[1], 1),
+                (&[1, 0], 2),
+                (&[0, 1], 3),
+                (&[0, 0], 1),
+                (&[0, 0, 0], 4)
+            ])
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use super::interner::CodeGenInterner;
+use super::{
+    annotations::{Annotations, Rewrite},
+    interner::CodeGenInterner,
+};
 use crate::{
     ast::{Constant, Data, Name, NamedDeBruijn, Program, Term, Type},
     builder::{CONSTR_FIELDS_EXPOSER, CONSTR_INDEX_EXPOSER},
@@ -9,7 +12,7 @@ use blst::{blst_p1, blst_p2};
 use indexmap::IndexMap;
 use itertools::Itertools;
 use pallas_primitives::conway::{BigInt, PlutusData};
-use std::{cmp::Ordering, iter, ops::Neg, rc::Rc};
+use std::{cmp::Ordering, collections::HashMap, iter, ops::Neg, rc::Rc};
 
 #[derive(Eq, Hash, PartialEq, Clone, Debug, PartialOrd)]
 pub enum ScopePath {
@@ -861,6 +864,40 @@ pub struct Context {
     pub blst_p1_list: Vec<blst_p1>,
     pub blst_p2_list: Vec<blst_p2>,
     pub node_count: usize,
+    /// Structural changes made to the term being visited, applied to the annotations once the
+    /// visit is over.
+    pub rewrites: Vec<Rewrite>,
+    path: Vec<usize>,
+    apply_paths: HashMap<usize, Vec<usize>>,
+    annotations: Annotations,
+}
+
+impl Context {
+    fn enter_apply(&mut self, apply_id: usize) {
+        if !self.annotations.is_empty() {
+            self.apply_paths.insert(apply_id, self.path.clone());
+        }
+    }
+
+    fn apply_rewrites(&mut self) {
+        for rewrite in self.rewrites.drain(..) {
+            match rewrite {
+                Rewrite::Moves { at, moves } => {
+                    self.annotations
+                        .relocate(&[self.path.as_slice(), &at].concat(), &moves);
+                }
+                Rewrite::Substitute { apply, from, to } => {
+                    if let Some(apply_path) = self.apply_paths.get(&apply) {
+                        let from = [apply_path.as_slice(), &[1], &from].concat();
+                        for to in to {
+                            self.annotations
+                                .copy(&from, &[self.path.as_slice(), &to].concat());
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -883,6 +920,7 @@ impl Term<Name> {
             Term::Apply { function, argument } => {
                 let arg = Rc::make_mut(argument);
 
+                context.path.push(1);
                 arg.traverse_uplc_with_helper(
                     &scope.push(ScopePath::ARG),
                     vec![],
@@ -891,12 +929,16 @@ impl Term<Name> {
                     context,
                     inline_lambda,
                 );
+                context.path.pop();
                 let apply_id = id_gen.next_id();
 
+                context.enter_apply(apply_id);
+
                 arg_stack.push(Args::Apply(apply_id, arg.clone()));
 
                 let func = Rc::make_mut(function);
 
+                context.path.push(0);
                 func.traverse_uplc_with_helper(
                     &scope.push(ScopePath::FUNC),
                     arg_stack,
@@ -905,8 +947,11 @@ impl Term<Name> {
                     context,
                     inline_lambda,
                 );
+                context.path.pop();
 
                 with(Some(apply_id), self, vec![], scope, context);
+
+                context.apply_rewrites();
             }
             Term::Force(f) => {
                 let f = Rc::make_mut(f);
@@ -914,9 +959,13 @@ impl Term<Name> {
 
                 arg_stack.push(Args::Force(force_id));
 
+                context.path.push(0);
                 f.traverse_uplc_with_helper(scope, arg_stack, id_gen, with, context, inline_lambda);
+                context.path.pop();
 
                 with(Some(force_id), self, vec![], scope, context);
+
+                context.apply_rewrites();
             }
             Term::Delay(d) => {
                 let d = Rc::make_mut(d);
@@ -928,9 +977,13 @@ impl Term<Name> {
                     })
                     .unwrap_or_default();
 
+                context.path.push(0);
                 d.traverse_uplc_with_helper(scope, arg_stack, id_gen, with, context, inline_lambda);
+                context.path.pop();
 
                 with(None, self, delay_arg, scope, context);
+
+                context.apply_rewrites();
             }
             Term::Lambda {
                 parameter_name,
@@ -958,12 +1011,15 @@ impl Term<Name> {
 
                     with(None, self, args, scope, context);
 
+                    context.apply_rewrites();
+
                     match self {
                         Term::Lambda {
                             parameter_name,
                             body,
                         } if parameter_name.text == p.text && parameter_name.unique == p.unique => {
                             let body = Rc::make_mut(body);
+                            context.path.push(0);
                             body.traverse_uplc_with_helper(
                                 scope,
                                 arg_stack,
@@ -972,6 +1028,7 @@ impl Term<Name> {
                                 context,
                                 inline_lambda,
                             );
+                            context.path.pop();
                         }
 
                         Term::Constr { .. } => todo!(),
@@ -988,6 +1045,7 @@ impl Term<Name> {
                 } else {
                     let body = Rc::make_mut(body);
 
+                    context.path.push(0);
                     body.traverse_uplc_with_helper(
                         scope,
                         arg_stack,
@@ -996,8 +1054,11 @@ impl Term<Name> {
                         context,
                         inline_lambda,
                     );
+                    context.path.pop();
 
                     with(None, self, args, scope, context);
+
+                    context.apply_rewrites();
                 }
             }
 
@@ -1014,34 +1075,49 @@ impl Term<Name> {
                 }
                 // Pass in args up to function arity.
                 with(None, self, args, scope, context);
+                context.apply_rewrites();
             }
             term => {
                 with(None, term, vec![], scope, context);
+                context.apply_rewrites();
             }
         }
         context.node_count += 1;
     }
 
-    fn substitute_var(&mut self, original: Rc<Name>, replace_with: &Term<Name>) {
+    fn substitute_var(
+        &mut self,
+        original: Rc<Name>,
+        replace_with: &Term<Name>,
+        path: &mut Vec<usize>,
+        occurrences: &mut Vec<Vec<usize>>,
+    ) {
+        let mut substitute_child = |child: &mut Rc<Term<Name>>, index, original| {
+            path.push(index);
+            Rc::make_mut(child).substitute_var(original, replace_with, path, occurrences);
+            path.pop();
+        };
+
         match self {
             Term::Var(name) if name.text == original.text && name.unique == original.unique => {
                 *self = replace_with.clone();
+                occurrences.push(path.clone());
             }
-            Term::Delay(body) => Rc::make_mut(body).substitute_var(original, replace_with),
+            Term::Delay(body) => substitute_child(body, 0, original),
             Term::Lambda {
                 parameter_name,
                 body,
             } if parameter_name.text != original.text
                 || parameter_name.unique != original.unique =>
             {
-                Rc::make_mut(body).substitute_var(original, replace_with);
+                substitute_child(body, 0, original);
             }
             Term::Apply { function, argument } => {
-                Rc::make_mut(function).substitute_var(original.clone(), replace_with);
-                Rc::make_mut(argument).substitute_var(original, replace_with);
+                substitute_child(function, 0, original.clone());
+                substitute_child(argument, 1, original);
             }
             Term::Force(f) => {
-                Rc::make_mut(f).substitute_var(original, replace_with);
+                substitute_child(f, 0, original);
             }
             Term::Case { .. } => todo!(),
             Term::Constr { .. } => todo!(),
@@ -1049,10 +1125,17 @@ impl Term<Name> {
         }
     }
 
-    fn replace_identity_usage(&mut self, original: Rc<Name>) {
+    fn replace_identity_usage(
+        &mut self,
+        original: Rc<Name>,
+        path: Vec<usize>,
+        rewrites: &mut Vec<Rewrite>,
+    ) {
+        let child = |index| [path.as_slice(), &[index]].concat();
+
         match self {
             Term::Delay(body) => {
-                Rc::make_mut(body).replace_identity_usage(original.clone());
+                Rc::make_mut(body).replace_identity_usage(original.clone(), child(0), rewrites);
             }
             Term::Lambda {
                 parameter_name,
@@ -1060,15 +1143,15 @@ impl Term<Name> {
             } => {
                 if parameter_name.text != original.text || parameter_name.unique != original.unique
                 {
-                    Rc::make_mut(body).replace_identity_usage(original.clone());
+                    Rc::make_mut(body).replace_identity_usage(original.clone(), child(0), rewrites);
                 }
             }
             Term::Apply { function, argument } => {
                 let func = Rc::make_mut(function);
                 let arg = Rc::make_mut(argument);
 
-                func.replace_identity_usage(original.clone());
-                arg.replace_identity_usage(original.clone());
+                func.replace_identity_usage(original.clone(), child(0), rewrites);
+                arg.replace_identity_usage(original.clone(), child(1), rewrites);
 
                 let Term::Var(name) = &func else {
                     return;
@@ -1076,10 +1159,11 @@ impl Term<Name> {
 
                 if name.text == original.text && name.unique == original.unique {
                     *self = std::mem::replace(arg, Term::Error.force());
+                    rewrites.push(Rewrite::lift(vec![1]).at(path));
                 }
             }
             Term::Force(f) => {
-                Rc::make_mut(f).replace_identity_usage(original.clone());
+                Rc::make_mut(f).replace_identity_usage(original.clone(), child(0), rewrites);
             }
             Term::Case { .. } => todo!(),
             Term::Constr { .. } => todo!(),
@@ -1175,9 +1259,21 @@ impl Term<Name> {
                         let body = Rc::make_mut(body);
                         context.inlined_apply_ids.push(arg_id);
 
-                        body.substitute_var(parameter_name.clone(), arg_term.pierce_no_inlines());
+                        let mut occurrences = vec![];
+                        body.substitute_var(
+                            parameter_name.clone(),
+                            arg_term.pierce_no_inlines(),
+                            &mut vec![0],
+                            &mut occurrences,
+                        );
+                        context.rewrites.push(Rewrite::Substitute {
+                            apply: arg_id,
+                            from: vec![0; arg_term.no_inline_depth()],
+                            to: occurrences,
+                        });
                         // creates new body that replaces all var occurrences with the arg
                         *self = std::mem::replace(body, Term::Error.force());
+                        context.rewrites.push(Rewrite::lift(vec![0]));
                     }
                 }
             }
@@ -1296,7 +1392,11 @@ impl Term<Name> {
                         && identity_var.unique == identity_name.unique
                     {
                         // Replace all applied usages of identity with the arg
-                        body.replace_identity_usage(parameter_name.clone());
+                        body.replace_identity_usage(
+                            parameter_name.clone(),
+                            vec![0],
+                            &mut context.rewrites,
+                        );
                         // Have to check if the body still has any occurrences of the parameter
                         // After attempting replacement
                         if !body
@@ -1306,6 +1406,7 @@ impl Term<Name> {
                             changed = true;
                             context.inlined_apply_ids.push(arg_id);
                             *self = std::mem::replace(body, Term::Error.force());
+                            context.rewrites.push(Rewrite::lift(vec![0]));
                         }
                     }
                 }
@@ -1334,6 +1435,8 @@ impl Term<Name> {
             } => {
                 // pops stack here no matter what
                 if let Some(Args::Apply(arg_id, arg_term)) = arg_stack.pop() {
+                    let no_inline_depth = arg_term.no_inline_depth();
+
                     let arg_term = match &arg_term {
                         Term::Lambda {
                             parameter_name,
@@ -1358,10 +1461,22 @@ impl Term<Name> {
 
                     if var_lookup.occurrences == 1 && substitute_condition {
                         changed = true;
-                        body.substitute_var(parameter_name.clone(), arg_term.pierce_no_inlines());
+                        let mut occurrences = vec![];
+                        body.substitute_var(
+                            parameter_name.clone(),
+                            arg_term.pierce_no_inlines(),
+                            &mut vec![0],
+                            &mut occurrences,
+                        );
+                        context.rewrites.push(Rewrite::Substitute {
+                            apply: arg_id,
+                            from: vec![0; no_inline_depth],
+                            to: occurrences,
+                        });
 
                         context.inlined_apply_ids.push(arg_id);
                         *self = std::mem::replace(body, Term::Error.force());
+                        context.rewrites.push(Rewrite::lift(vec![0]));
 
                     // This will strip out unused terms that can't throw an error by themselves
                     } else if !var_lookup.found
@@ -1377,6 +1492,7 @@ impl Term<Name> {
                         changed = true;
                         context.inlined_apply_ids.push(arg_id);
                         *self = std::mem::replace(body, Term::Error.force());
+                        context.rewrites.push(Rewrite::lift(vec![0]));
                     }
                 }
             }
@@ -1399,11 +1515,13 @@ impl Term<Name> {
             if let Some(Args::Force(id)) = arg_stack.pop() {
                 changed = true;
                 context.inlined_apply_ids.push(id);
-                *self = std::mem::replace(Rc::make_mut(d), Term::Error.force())
+                *self = std::mem::replace(Rc::make_mut(d), Term::Error.force());
+                context.rewrites.push(Rewrite::lift(vec![0]));
             } else if let Term::Force(var) = d.as_ref() {
                 if let Term::Var(_) = var.as_ref() {
                     changed = true;
                     *self = var.as_ref().clone();
+                    context.rewrites.push(Rewrite::lift(vec![0, 0]));
                 }
             }
         }
@@ -1415,7 +1533,7 @@ impl Term<Name> {
         _id: Option<usize>,
         _arg_stack: Vec<Args>,
         _scope: &Scope,
-        _context: &mut Context,
+        context: &mut Context,
     ) {
         match self {
             Term::Lambda {
@@ -1423,6 +1541,7 @@ impl Term<Name> {
                 body,
             } if parameter_name.text == NO_INLINE => {
                 *self = std::mem::replace(Rc::make_mut(body), Term::Error.force());
+                context.rewrites.push(Rewrite::lift(vec![0]));
             }
             _ => (),
         }
@@ -1434,7 +1553,7 @@ impl Term<Name> {
         _id: Option<usize>,
         _arg_stack: Vec<Args>,
         _scope: &Scope,
-        _context: &mut Context,
+        context: &mut Context,
     ) {
         if let Term::Apply { function, argument } = self {
             if let Term::Var(name) = function.as_ref() {
@@ -1442,11 +1561,17 @@ impl Term<Name> {
                 if name.text == CONSTR_FIELDS_EXPOSER {
                     *self = Term::snd_pair().apply(
                         Term::unconstr_data().apply(std::mem::replace(arg, Term::Error.force())),
-                    )
+                    );
+                    context
+                        .rewrites
+                        .push(Rewrite::moves(vec![(vec![1], vec![1, 1])]));
                 } else if name.text == CONSTR_INDEX_EXPOSER {
                     *self = Term::fst_pair().apply(
                         Term::unconstr_data().apply(std::mem::replace(arg, Term::Error.force())),
-                    )
+                    );
+                    context
+                        .rewrites
+                        .push(Rewrite::moves(vec![(vec![1], vec![1, 1])]));
                 }
             }
         }
@@ -1485,6 +1610,11 @@ impl Term<Name> {
                                         Rc::make_mut(argument),
                                         Term::Error.force(),
                                     );
+                                    context.rewrites.push(Rewrite::Substitute {
+                                        apply: arg_id,
+                                        from: vec![1],
+                                        to: vec![vec![]],
+                                    });
                                 }
                                 _ => {}
                             }
@@ -1693,6 +1823,7 @@ impl Term<Name> {
                 if context.inlined_apply_ids.contains(&id) {
                     let func = Rc::make_mut(function);
                     *self = std::mem::replace(func, Term::Error.force());
+                    context.rewrites.push(Rewrite::lift(vec![0]));
                 }
             }
             _ => (),
@@ -1725,6 +1856,26 @@ impl Term<Name> {
         }
     }
 
+    fn no_inline_depth(&self) -> usize {
+        let mut depth = 0;
+        let mut term = self;
+
+        while let Term::Lambda {
+            parameter_name,
+            body,
+        } = term
+        {
+            if parameter_name.as_ref().text == NO_INLINE {
+                depth += 1;
+                term = body;
+            } else {
+                break;
+            }
+        }
+
+        depth
+    }
+
     pub fn pierce_no_inlines(&self) -> &Self {
         let mut term = self;
 
@@ -1748,6 +1899,7 @@ impl Program<Name> {
     fn traverse_uplc_with(
         self,
         inline_lambda: bool,
+        annotations: &mut Annotations,
         with: &mut impl FnMut(Option<usize>, &mut Term<Name>, Vec<Args>, &Scope, &mut Context),
     ) -> (Self, Context) {
         let mut term = self.term;
@@ -1762,6 +1914,10 @@ impl Program<Name> {
             blst_p1_list: vec![],
             blst_p2_list: vec![],
             node_count: 0,
+            rewrites: vec![],
+            path: vec![],
+            apply_paths: HashMap::new(),
+            annotations: std::mem::take(annotations),
         };
 
         term.traverse_uplc_with_helper(
@@ -1772,6 +1928,8 @@ impl Program<Name> {
             &mut context,
             inline_lambda,
         );
+
+        *annotations = std::mem::take(&mut context.annotations);
         (
             Program {
                 version: self.version,
@@ -1782,18 +1940,29 @@ impl Program<Name> {
     }
     // This one runs the optimizations that are only done a single time
     pub fn run_once_pass(self) -> Self {
+        self.run_once_pass_annotated(&mut Annotations::new())
+    }
+
+    pub fn run_once_pass_annotated(self, annotations: &mut Annotations) -> Self {
         let program = self
-            .traverse_uplc_with(false, &mut |id, term, _arg_stack, scope, context| {
-                term.inline_constr_ops(id, vec![], scope, context);
-            })
+            .traverse_uplc_with(
+                false,
+                annotations,
+                &mut |id, term, _arg_stack, scope, context| {
+                    term.inline_constr_ops(id, vec![], scope, context);
+                },
+            )
             .0;
 
-        let (program, context) =
-            program.traverse_uplc_with(false, &mut |id, term, arg_stack, scope, context| {
+        let (program, context) = program.traverse_uplc_with(
+            false,
+            annotations,
+            &mut |id, term, arg_stack, scope, context| {
                 term.bls381_compressor(id, vec![], scope, context);
                 term.builtin_force_reducer(id, arg_stack, scope, context);
                 term.remove_inlined_ids(id, vec![], scope, context);
-            });
+            },
+        );
 
         let mut term = program.term;
 
@@ -1803,6 +1972,8 @@ impl Program<Name> {
             term = term
                 .lambda(format!("blst_p1_index_{}", index))
                 .apply(Term::bls12_381_g1_uncompress().apply(Term::byte_string(compressed)));
+
+            annotations.relocate(&[], &[(vec![], vec![0, 0])]);
         }
 
         for (index, blst_p2) in context.blst_p2_list.into_iter().enumerate() {
@@ -1811,6 +1982,8 @@ impl Program<Name> {
             term = term
                 .lambda(format!("blst_p2_index_{}", index))
                 .apply(Term::bls12_381_g2_uncompress().apply(Term::byte_string(compressed)));
+
+            annotations.relocate(&[], &[(vec![], vec![0, 0])]);
         }
 
         for default_func_index in context.builtins_map.keys().sorted().cloned() {
@@ -1823,6 +1996,8 @@ impl Program<Name> {
                 } else {
                     Term::Builtin(default_func).force().force()
                 });
+
+            annotations.relocate(&[], &[(vec![], vec![0, 0])]);
         }
 
         let mut program = Program {
@@ -1840,43 +2015,51 @@ impl Program<Name> {
     }
 
     pub fn multi_pass(self) -> (Self, Context) {
-        self.traverse_uplc_with(true, &mut |id, term, arg_stack, scope, context| {
-            let mut changed;
+        self.multi_pass_annotated(&mut Annotations::new())
+    }
 
-            changed = term.lambda_reducer(id, arg_stack.clone(), scope, context);
-            if changed {
-                term.remove_inlined_ids(id, vec![], scope, context);
-                return;
-            }
-            changed = term.identity_reducer(id, arg_stack.clone(), scope, context);
-            if changed {
-                term.remove_inlined_ids(id, vec![], scope, context);
-                return;
-            }
-            changed = term.inline_reducer(id, arg_stack.clone(), scope, context);
-            if changed {
-                term.remove_inlined_ids(id, vec![], scope, context);
-                return;
-            }
-            changed = term.force_delay_reducer(id, arg_stack.clone(), scope, context);
-            if changed {
-                term.remove_inlined_ids(id, vec![], scope, context);
-                return;
-            }
-            changed = term.cast_data_reducer(id, arg_stack.clone(), scope, context);
-            if changed {
-                term.remove_inlined_ids(id, vec![], scope, context);
-                return;
-            }
-            changed = term.builtin_eval_reducer(id, arg_stack.clone(), scope, context);
-            if changed {
+    pub fn multi_pass_annotated(self, annotations: &mut Annotations) -> (Self, Context) {
+        self.traverse_uplc_with(
+            true,
+            annotations,
+            &mut |id, term, arg_stack, scope, context| {
+                let mut changed;
+
+                changed = term.lambda_reducer(id, arg_stack.clone(), scope, context);
+                if changed {
+                    term.remove_inlined_ids(id, vec![], scope, context);
+                    return;
+                }
+                changed = term.identity_reducer(id, arg_stack.clone(), scope, context);
+                if changed {
+                    term.remove_inlined_ids(id, vec![], scope, context);
+                    return;
+                }
+                changed = term.inline_reducer(id, arg_stack.clone(), scope, context);
+                if changed {
+                    term.remove_inlined_ids(id, vec![], scope, context);
+                    return;
+                }
+                changed = term.force_delay_reducer(id, arg_stack.clone(), scope, context);
+                if changed {
+                    term.remove_inlined_ids(id, vec![], scope, context);
+                    return;
+                }
+                changed = term.cast_data_reducer(id, arg_stack.clone(), scope, context);
+                if changed {
+                    term.remove_inlined_ids(id, vec![], scope, context);
+                    return;
+                }
+                changed = term.builtin_eval_reducer(id, arg_stack.clone(), scope, context);
+                if changed {
+                    term.remove_inlined_ids(id, vec![], scope, context);
+                    return;
+                }
+                term.convert_arithmetic_ops(id, arg_stack, scope, context);
+                term.flip_constants(id, vec![], scope, context);
                 term.remove_inlined_ids(id, vec![], scope, context);
-                return;
-            }
-            term.convert_arithmetic_ops(id, arg_stack, scope, context);
-            term.flip_constants(id, vec![], scope, context);
-            term.remove_inlined_ids(id, vec![], scope, context);
-        })
+            },
+        )
     }
 
     pub fn run_one_opt(
@@ -1884,23 +2067,39 @@ impl Program<Name> {
         inline_lambda: bool,
         with: &mut impl FnMut(Option<usize>, &mut Term<Name>, Vec<Args>, &Scope, &mut Context),
     ) -> Self {
-        self.traverse_uplc_with(inline_lambda, &mut |id, term, arg_stack, scope, context| {
-            with(id, term, arg_stack, scope, context);
-            term.flip_constants(id, vec![], scope, context);
-            term.remove_inlined_ids(id, vec![], scope, context);
-        })
+        self.traverse_uplc_with(
+            inline_lambda,
+            &mut Annotations::new(),
+            &mut |id, term, arg_stack, scope, context| {
+                with(id, term, arg_stack, scope, context);
+                term.flip_constants(id, vec![], scope, context);
+                term.remove_inlined_ids(id, vec![], scope, context);
+            },
+        )
         .0
     }
 
     pub fn clean_up(self) -> Self {
-        self.traverse_uplc_with(true, &mut |id, term, _arg_stack, scope, context| {
-            term.remove_no_inlines(id, vec![], scope, context);
-        })
+        self.clean_up_annotated(&mut Annotations::new())
+    }
+
+    pub fn clean_up_annotated(self, annotations: &mut Annotations) -> Self {
+        self.traverse_uplc_with(
+            true,
+            annotations,
+            &mut |id, term, _arg_stack, scope, context| {
+                term.remove_no_inlines(id, vec![], scope, context);
+            },
+        )
         .0
     }
 
-    // This one doesn't use the context since it's complicated and traverses the ast twice
     pub fn builtin_curry_reducer(self) -> Self {
+        self.builtin_curry_reducer_annotated(&mut Annotations::new())
+    }
+
+    // This one doesn't use the context since it's complicated and traverses the ast twice
+    pub fn builtin_curry_reducer_annotated(self, annotations: &mut Annotations) -> Self {
         let mut curried_terms = vec![];
         let mut id_mapped_curry_terms: IndexMap<CurriedName, (Scope, Term<Name>, usize)> =
             IndexMap::new();
@@ -1914,6 +2113,7 @@ impl Program<Name> {
 
         let (step_a, _) = self.traverse_uplc_with(
             false,
+            annotations,
             &mut |_id, term, arg_stack, scope, _context| match term {
                 Term::Builtin(func) => {
                     if func.can_curry_builtin() && arg_stack.len() == func.arity() {
@@ -2032,7 +2232,8 @@ impl Program<Name> {
 
         let (mut step_b, _) = step_a.traverse_uplc_with(
             false,
-            &mut |id, term, arg_stack, scope, _context| match term {
+            annotations,
+            &mut |id, term, arg_stack, scope, context| match term {
                 Term::Builtin(func) => {
                     if func.can_curry_builtin() && arg_stack.len() == func.arity() {
                         let mut arg_stack = arg_stack
@@ -2091,6 +2292,7 @@ impl Program<Name> {
 
                     if curry_applied_ids.contains(&id) {
                         *term = function.as_ref().clone();
+                        context.rewrites.push(Rewrite::lift(vec![0]));
                     }
 
                     if let Some(insert_list) = scope_mapped_to_term.remove(scope) {
@@ -2102,6 +2304,7 @@ impl Program<Name> {
                                 .found
                             {
                                 *term = term.clone().lambda(name).apply(val);
+                                context.rewrites.push(Rewrite::wrap(vec![0, 0]));
                             }
                         }
                     }
@@ -2118,6 +2321,7 @@ impl Program<Name> {
                                 .found
                             {
                                 *term = term.clone().lambda(name).apply(val);
+                                context.rewrites.push(Rewrite::wrap(vec![0, 0]));
                             }
                         }
                     }
@@ -2193,7 +2397,7 @@ mod tests {
         ast::{Constant, Data, Name, NamedDeBruijn, Program, Term},
         builder::{CONSTR_FIELDS_EXPOSER, CONSTR_INDEX_EXPOSER},
         builtins::DefaultFunction,
-        optimize::interner::CodeGenInterner,
+        optimize::{annotations::Annotations, interner::CodeGenInterner},
     };
     use pallas_primitives::conway::{BigInt, PlutusData};
     use pretty_assertions::assert_eq;
@@ -3223,4 +3427,34 @@ mod tests {
 
         compare_optimization(expected, program, |p| p.builtin_curry_reducer());
     }
+
+    #[test]
+    fn annotations_follow_inlining() {
+        // [(lam foo [bar foo]) (con integer 1)]
+        let program: Program<Name> = Program {
+            version: (1, 0, 0),
+            term: Term::var("bar")
+                .apply(Term::var("foo"))
+                .lambda("foo")
+                .apply(Term::integer(1.into())),
+        };
+
+        let mut annotations = Annotations::new();
+        annotations.insert(vec![0, 0], 1);
+        annotations.insert(vec![0, 0, 1], 2);
+        annotations.insert(vec![1], 3);
+
+        let (program, _) = program.multi_pass_annotated(&mut annotations);
+
+        assert_eq!(
+            program.term,
+            Term::var("bar").apply(Term::integer(1.into()))
+        );
+
+        let mut expected = Annotations::new();
+        expected.insert(vec![], 1);
+        expected.insert(vec![1], 2);
+
+        assert_eq!(annotations, expected);
+    }
 }
@@ -12,12 +12,15 @@ use pallas_primitives::{
     Fragment,
 };
 use pallas_traverse::{Era, MultiEraTx};
-pub use phase_one::{eval_phase_one, redeemer_tag_to_string};
+pub use phase_one::{eval_ledger_rules, eval_phase_one, redeemer_tag_to_string};
+pub use protocol_parameters::ProtocolParameters;
 pub use script_context::{DataLookupTable, ResolvedInput, SlotConfig};
 
 pub mod error;
 pub mod eval;
+pub mod json;
 mod phase_one;
+pub mod protocol_parameters;
 pub mod script_context;
 #[cfg(test)]
 mod tests;
@@ -86,6 +89,80 @@ pub fn eval_phase_two(
     }
 }
 
+/// Report on a redeemer of a transaction, as obtained from [`simulate`].
+#[derive(Debug)]
+pub struct RedeemerReport {
+    /// The redeemer as found in the transaction, with its declared execution units.
+    pub redeemer: Redeemer,
+    pub evaluation: Result<eval::Evaluation, Error>,
+}
+
+impl RedeemerReport {
+    /// Whether the script succeeded within the execution units declared by the redeemer.
+    pub fn is_success(&self) -> bool {
+        match &self.evaluation {
+            Ok(evaluation) => {
+                evaluation.result.is_ok()
+                    && evaluation.cost.mem as u64 <= self.redeemer.ex_units.mem
+                    && evaluation.cost.cpu as u64 <= self.redeemer.ex_units.steps
+            }
+            Err(..) => false,
+        }
+    }
+}
+
+/// Evaluate every redeemer of a transaction and report on each, including the traces of
+/// successful scripts. Unlike [`eval_phase_two`], this doesn't stop at the first failure. Scripts
+/// share the initial budget as they do on-chain: each is given what the previous ones left, so
+/// that going over the budget of the whole transaction is reported as a failure.
+pub fn simulate(
+    tx: &MintedTx,
+    utxos: &[ResolvedInput],
+    cost_mdls: Option<&CostModels>,
+    initial_budget: Option<&ExBudget>,
+    slot_config: &SlotConfig,
+) -> Vec<RedeemerReport> {
+    let lookup_table = DataLookupTable::from_transaction(tx, utxos);
+
+    let mut reports = vec![];
+
+    let mut remaining_budget = *initial_budget.unwrap_or(&ExBudget::default());
+
+    if let Some(rs) = tx.transaction_witness_set.redeemer.as_ref() {
+        for (key, data, ex_units) in iter_redeemers(rs) {
+            let redeemer = Redeemer {
+                tag: key.tag,
+                index: key.index,
+                data: data.clone(),
+                ex_units,
+            };
+
+            let evaluation = eval::run_redeemer(
+                tx,
+                utxos,
+                slot_config,
+                &redeemer,
+                &lookup_table,
+                cost_mdls,
+                &remaining_budget,
+            );
+
+            // Scripts that run out of budget may overshoot it slightly before they're halted.
+            if let Ok(evaluation) = &evaluation {
+                remaining_budget.cpu = (remaining_budget.cpu - evaluation.cost.cpu).max(0);
+                remaining_budget.mem = (remaining_budget.mem - evaluation.cost.mem).max(0);
+            }
+
+            reports.push(RedeemerReport {
+                redeemer,
+                evaluation,
+            });
+        }
+    }
+
+    reports
+}
+
 /// This function is the same as [`eval_phase_two`]
 /// but the inputs are raw bytes.
 /// initial_budget expects (cpu, mem).
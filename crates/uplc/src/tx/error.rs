@@ -14,6 +14,8 @@ pub enum Error {
     FlatDecode(#[from] pallas_codec::flat::de::Error),
     #[error("{0}")]
     FragmentDecode(#[from] pallas_primitives::Error),
+    #[error("{0}")]
+    Json(String),
     #[error("{}{}", .0, .2.iter()
         .map(|trace| {
             format!(
@@ -101,4 +103,99 @@ pub enum Error {
     ApplyParamsError,
     #[error("validity start or end too far in the past")]
     SlotTooFarInThePast { oldest_allowed: u64 },
+    #[error(
+        "value not conserved\n{:>13} {}\n{:>13} {}",
+        "Consumed",
+        .consumed,
+        "Produced",
+        .produced
+    )]
+    ValueNotConserved { consumed: String, produced: String },
+    #[error(
+        "output holds less than the minimum ada\n{:>13} {}\n{:>13} {}\n{:>13} {}",
+        "Output",
+        .output,
+        "Lovelace",
+        .lovelace,
+        "Minimum",
+        .minimum
+    )]
+    OutputTooSmall {
+        output: String,
+        lovelace: u64,
+        minimum: u64,
+    },
+    #[error("fee too small\n{:>13} {}\n{:>13} {}", "Fee", .fee, "Minimum", .minimum)]
+    FeeTooSmall { fee: u64, minimum: u64 },
+    #[error("transaction too large\n{:>13} {}\n{:>13} {}", "Size", .size, "Maximum", .maximum)]
+    MaxTxSizeExceeded { size: u64, maximum: u64 },
+    #[error(
+        "execution units of redeemers exceed the maximum per transaction\n{:>13} {}\n{:>13} {}\n{:>13} {}\n{:>13} {}",
+        "Memory",
+        .mem,
+        "Max memory",
+        .max_mem,
+        "CPU",
+        .steps,
+        "Max CPU",
+        .max_steps
+    )]
+    ExUnitsTooBig {
+        mem: u64,
+        steps: u64,
+        max_mem: u64,
+        max_steps: u64,
+    },
+    #[error("scripts are executed but no collateral input is provided")]
+    NoCollateralInputs,
+    #[error("too many collateral inputs\n{:>13} {}\n{:>13} {}", "Count", .count, "Maximum", .maximum)]
+    TooManyCollateralInputs { count: u64, maximum: u64 },
+    #[error("collateral input locked by a script\n{:>13} {}#{}", "Input", .0.transaction_id, .0.index)]
+    ScriptLockedCollateral(TransactionInput),
+    #[error("collateral (minus collateral return) holds assets other than ada")]
+    NonAdaCollateral,
+    #[error(
+        "insufficient collateral\n{:>13} {}\n{:>13} {}",
+        "Provided",
+        .provided,
+        "Required",
+        .required
+    )]
+    InsufficientCollateral { provided: u64, required: u64 },
+    #[error(
+        "declared total collateral doesn't match the collateral provided\n{:>13} {}\n{:>13} {}",
+        "Declared",
+        .declared,
+        "Provided",
+        .provided
+    )]
+    IncorrectTotalCollateral { declared: u64, provided: u64 },
+    #[error(
+        "validity interval is empty\n{:>13} {}\n{:>13} {}",
+        "Start",
+        .lower_bound,
+        "End",
+        .upper_bound
+    )]
+    EmptyValidityInterval { lower_bound: u64, upper_bound: u64 },
+    #[error(
+        "current slot outside of the validity interval\n{:>13} {}\n{:>13} {}\n{:>13} {}",
+        "Slot",
+        .slot,
+        "Start",
+        .lower_bound.map(|slot| slot.to_string()).unwrap_or_else(|| "-∞".to_string()),
+        "End",
+        .upper_bound.map(|slot| slot.to_string()).unwrap_or_else(|| "+∞".to_string())
+    )]
+    OutsideValidityInterval {
+        slot: u64,
+        lower_bound: Option<u64>,
+        upper_bound: Option<u64>,
+    },
+    #[error(
+        "missing signature(s) from required signer(s)\n{:>13} {}",
+        "Missing",
+        .missing.join(&format!("\n{:>14}", ""))
+    )]
+    MissingRequiredSigners { missing: Vec<String> },
 }
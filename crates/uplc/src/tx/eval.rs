@@ -1,11 +1,11 @@
 use super::{
-    script_context::{find_script, ResolvedInput, ScriptContext, SlotConfig, TxInfo},
+    script_context::{find_datum, find_script, ResolvedInput, ScriptContext, SlotConfig, TxInfo},
     to_plutus_data::ToPlutusData,
     Error,
 };
 use crate::{
     ast::{FakeNamedDeBruijn, NamedDeBruijn, Program},
-    machine::cost_model::ExBudget,
+    machine::{self, cost_model::ExBudget},
     tx::{
         phase_one::redeemer_tag_to_string,
         script_context::{DataLookupTable, ScriptVersion, TxInfoV1, TxInfoV2, TxInfoV3},
@@ -13,7 +13,20 @@ use crate::{
     PlutusData,
 };
 use pallas_codec::utils::Bytes;
-use pallas_primitives::conway::{CostModel, CostModels, ExUnits, Language, MintedTx, Redeemer};
+use pallas_primitives::conway::{
+    CostModel, CostModels, ExUnits, Language, MintedTx, Redeemer, ScriptHash,
+};
+use pallas_traverse::ComputeHash;
+
+/// Outcome of running the script of a redeemer, whether the script succeeded or not.
+#[derive(Debug)]
+pub struct Evaluation {
+    pub script_hash: ScriptHash,
+    pub language: Language,
+    pub cost: ExBudget,
+    pub logs: Vec<String>,
+    pub result: Result<(), machine::Error>,
+}
 
 pub fn eval_redeemer(
     tx: &MintedTx,
@@ -24,15 +37,56 @@ pub fn eval_redeemer(
     cost_mdls_opt: Option<&CostModels>,
     initial_budget: &ExBudget,
 ) -> Result<Redeemer, Error> {
-    fn do_eval_redeemer(
+    run_redeemer(
+        tx,
+        utxos,
+        slot_config,
+        redeemer,
+        lookup_table,
+        cost_mdls_opt,
+        initial_budget,
+    )
+    .and_then(|evaluation| match evaluation.result {
+        Ok(()) => Ok(Redeemer {
+            tag: redeemer.tag,
+            index: redeemer.index,
+            data: redeemer.data.clone(),
+            ex_units: ExUnits {
+                mem: evaluation.cost.mem as u64,
+                steps: evaluation.cost.cpu as u64,
+            },
+        }),
+        Err(err) => Err(Error::RedeemerError {
+            tag: redeemer_tag_to_string(&redeemer.tag),
+            index: redeemer.index,
+            err: Box::new(Error::Machine(err, evaluation.cost, evaluation.logs)),
+        }),
+    })
+}
+
+/// Run the script of a redeemer. Unlike 'eval_redeemer', a failing script isn't an error: the
+/// evaluation is returned either way, along with its cost and traces. Errors are only raised when
+/// the script can't be run at all (e.g. because it is missing).
+pub fn run_redeemer(
+    tx: &MintedTx,
+    utxos: &[ResolvedInput],
+    slot_config: &SlotConfig,
+    redeemer: &Redeemer,
+    lookup_table: &DataLookupTable,
+    cost_mdls_opt: Option<&CostModels>,
+    initial_budget: &ExBudget,
+) -> Result<Evaluation, Error> {
+    #[allow(clippy::too_many_arguments)]
+    fn do_run_redeemer(
         cost_mdl_opt: Option<&CostModel>,
         initial_budget: &ExBudget,
         lang: &Language,
         datum: Option<PlutusData>,
         redeemer: &Redeemer,
         tx_info: TxInfo,
+        script_hash: ScriptHash,
         program: Program<NamedDeBruijn>,
-    ) -> Result<Redeemer, Error> {
+    ) -> Result<Evaluation, Error> {
         let script_context = tx_info
             .into_script_context(redeemer, datum.as_ref())
             .expect("couldn't create script context from transaction?");
@@ -58,22 +112,13 @@ pub fn eval_redeemer(
         let cost = eval_result.cost();
         let logs = eval_result.logs();
 
-        match eval_result.result() {
-            Ok(_) => (),
-            Err(err) => return Err(Error::Machine(err, cost, logs)),
-        }
-
-        let new_redeemer = Redeemer {
-            tag: redeemer.tag,
-            index: redeemer.index,
-            data: redeemer.data.clone(),
-            ex_units: ExUnits {
-                mem: cost.mem as u64,
-                steps: cost.cpu as u64,
-            },
-        };
-
-        Ok(new_redeemer)
+        Ok(Evaluation {
+            script_hash,
+            language: lang.clone(),
+            cost,
+            logs,
+            result: eval_result.result().map(|_| ()),
+        })
     }
 
     let program = |script: Bytes| {
@@ -85,7 +130,7 @@ pub fn eval_redeemer(
     match find_script(redeemer, tx, utxos, lookup_table)? {
         (ScriptVersion::Native(_), _) => Err(Error::NativeScriptPhaseTwo),
 
-        (ScriptVersion::V1(script), datum) => do_eval_redeemer(
+        (ScriptVersion::V1(script), datum) => do_run_redeemer(
             cost_mdls_opt
                 .map(|cost_mdls| {
                     cost_mdls
@@ -99,10 +144,11 @@ pub fn eval_redeemer(
             datum,
             redeemer,
             TxInfoV1::from_transaction(tx, utxos, slot_config)?,
+            script.compute_hash(),
             program(script.0)?,
         ),
 
-        (ScriptVersion::V2(script), datum) => do_eval_redeemer(
+        (ScriptVersion::V2(script), datum) => do_run_redeemer(
             cost_mdls_opt
                 .map(|cost_mdls| {
                     cost_mdls
@@ -116,10 +162,11 @@ pub fn eval_redeemer(
             datum,
             redeemer,
             TxInfoV2::from_transaction(tx, utxos, slot_config)?,
+            script.compute_hash(),
             program(script.0)?,
         ),
 
-        (ScriptVersion::V3(script), datum) => do_eval_redeemer(
+        (ScriptVersion::V3(script), datum) => do_run_redeemer(
             cost_mdls_opt
                 .map(|cost_mdls| {
                     cost_mdls
@@ -133,6 +180,7 @@ pub fn eval_redeemer(
             datum,
             redeemer,
             TxInfoV3::from_transaction(tx, utxos, slot_config)?,
+            script.compute_hash(),
             program(script.0)?,
         ),
     }
@@ -142,3 +190,37 @@ pub fn eval_redeemer(
         err: Box::new(err),
     })
 }
+
+/// Build the arguments a script receives when evaluated for the given redeemer: the datum (if
+/// any), the redeemer and the script context for Plutus V1 & V2; the script context alone for
+/// Plutus V3. Unlike 'eval_redeemer', this doesn't look for the script in the transaction, so
+/// that any script can be run against it (e.g. another version of a validator).
+pub fn script_arguments(
+    tx: &MintedTx,
+    utxos: &[ResolvedInput],
+    slot_config: &SlotConfig,
+    redeemer: &Redeemer,
+    lookup_table: &DataLookupTable,
+    lang: &Language,
+) -> Result<Vec<PlutusData>, Error> {
+    let datum = find_datum(redeemer, tx, utxos, lookup_table)?;
+
+    let tx_info = match lang {
+        Language::PlutusV1 => TxInfoV1::from_transaction(tx, utxos, slot_config)?,
+        Language::PlutusV2 => TxInfoV2::from_transaction(tx, utxos, slot_config)?,
+        Language::PlutusV3 => TxInfoV3::from_transaction(tx, utxos, slot_config)?,
+    };
+
+    let script_context = tx_info
+        .into_script_context(redeemer, datum.as_ref())
+        .ok_or(Error::ExtraneousRedeemer)?;
+
+    Ok(match script_context {
+        ScriptContext::V1V2 { .. } => datum
+            .into_iter()
+            .chain([redeemer.data.clone(), script_context.to_plutus_data()])
+            .collect(),
+
+        ScriptContext::V3 { .. } => vec![script_context.to_plutus_data()],
+    })
+}
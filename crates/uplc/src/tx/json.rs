@@ -0,0 +1,299 @@
+use super::{error::Error, script_context::ResolvedInput};
+use crate::ast::Data;
+use num_bigint::BigInt;
+use pallas_addresses::Address;
+use pallas_codec::{
+    minicbor,
+    utils::{Bytes, CborWrap, NonEmptyKeyValuePairs, PositiveCoin},
+};
+use pallas_primitives::conway::{
+    DatumOption, NativeScript, PlutusData, PlutusScript, PostAlonzoTransactionOutput, PseudoScript,
+    ScriptRef, TransactionInput, TransactionOutput, Value,
+};
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use std::collections::BTreeMap;
+
+/// A transaction along with the UTxOs it spends, references or uses as collateral, in the
+/// formats emitted by common off-chain tools. For example:
+///
+/// ```json
+/// {
+///   "transaction": { "type": "Tx ConwayEra", "description": "", "cborHex": "84a400..." },
+///   "utxos": {
+///     "4b9c...#0": {
+///       "address": "addr_test1...",
+///       "value": { "lovelace": 5000000, "<policy id>": { "<asset name>": 1 } },
+///       "inlineDatum": { "constructor": 0, "fields": [{ "int": 42 }] }
+///     }
+///   }
+/// }
+/// ```
+///
+/// The transaction is either hex-encoded CBOR or a text envelope (as written by cardano-cli),
+/// and the UTxO set follows the output of `cardano-cli query utxo --output-json`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransactionJson {
+    pub transaction: TransactionCbor,
+    #[serde(default)]
+    pub utxos: BTreeMap<String, UtxoJson>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum TransactionCbor {
+    Hex(String),
+    TextEnvelope {
+        #[serde(rename = "cborHex")]
+        cbor_hex: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UtxoJson {
+    pub address: String,
+    pub value: BTreeMap<String, serde_json::Value>,
+    #[serde(default, rename = "datumhash")]
+    pub datum_hash: Option<String>,
+    #[serde(default)]
+    pub inline_datum: Option<PlutusDataJson>,
+    #[serde(default)]
+    pub inline_datum_raw: Option<String>,
+    #[serde(default)]
+    pub reference_script: Option<ReferenceScriptJson>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReferenceScriptJson {
+    pub script: ScriptJson,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScriptJson {
+    #[serde(rename = "type")]
+    pub script_type: String,
+    #[serde(rename = "cborHex")]
+    pub cbor_hex: String,
+}
+
+impl TransactionJson {
+    pub fn parse(json: &str) -> Result<TransactionJson, Error> {
+        serde_json::from_str(json).map_err(|e| Error::Json(e.to_string()))
+    }
+
+    /// The transaction, as CBOR.
+    pub fn transaction(&self) -> Result<Vec<u8>, Error> {
+        let cbor_hex = match &self.transaction {
+            TransactionCbor::Hex(cbor_hex) => cbor_hex,
+            TransactionCbor::TextEnvelope { cbor_hex } => cbor_hex,
+        };
+
+        decode_hex("transaction", cbor_hex)
+    }
+
+    /// The UTxO set, as inputs along with the outputs they resolve to.
+    pub fn resolved_inputs(&self) -> Result<Vec<ResolvedInput>, Error> {
+        self.utxos
+            .iter()
+            .map(|(out_ref, utxo)| {
+                Ok(ResolvedInput {
+                    input: output_reference(out_ref)?,
+                    output: utxo
+                        .output()
+                        .map_err(|e| Error::Json(format!("invalid UTxO {out_ref}: {e}")))?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl UtxoJson {
+    fn output(&self) -> Result<TransactionOutput, String> {
+        let address = self
+            .address
+            .parse::<Address>()
+            .map_err(|e| format!("invalid address: {e}"))?;
+
+        let datum_option = match (&self.datum_hash, &self.inline_datum_raw, &self.inline_datum) {
+            (Some(hash), _, _) => Some(DatumOption::Hash(
+                hash.parse().map_err(|_| "invalid datum hash".to_string())?,
+            )),
+            (None, Some(raw), _) => Some(DatumOption::Data(CborWrap(
+                crate::plutus_data(&hex::decode(raw).map_err(|e| format!("invalid datum: {e}"))?)
+                    .map_err(|e| format!("invalid datum: {e}"))?,
+            ))),
+            (None, None, Some(datum)) => Some(DatumOption::Data(CborWrap(
+                datum
+                    .plutus_data()
+                    .map_err(|e| format!("invalid datum: {e}"))?,
+            ))),
+            (None, None, None) => None,
+        };
+
+        let script_ref = self
+            .reference_script
+            .as_ref()
+            .map(|reference_script| reference_script.script.script_ref())
+            .transpose()?
+            .map(CborWrap);
+
+        Ok(TransactionOutput::PostAlonzo(PostAlonzoTransactionOutput {
+            address: address.to_vec().into(),
+            value: self.value()?,
+            datum_option,
+            script_ref,
+        }))
+    }
+
+    fn value(&self) -> Result<Value, String> {
+        let mut lovelace = 0;
+        let mut assets = Vec::new();
+
+        for (key, quantity) in self.value.iter() {
+            if key == "lovelace" {
+                lovelace = quantity
+                    .as_u64()
+                    .ok_or_else(|| format!("invalid quantity of lovelace: {quantity}"))?;
+                continue;
+            }
+
+            let policy_id = key
+                .parse()
+                .map_err(|_| format!("invalid policy id: {key}"))?;
+
+            let tokens = quantity
+                .as_object()
+                .ok_or_else(|| format!("expected assets under policy {key}"))?
+                .iter()
+                .map(|(asset_name, quantity)| {
+                    let asset_name = hex::decode(asset_name)
+                        .map_err(|_| format!("invalid asset name: {asset_name}"))?;
+
+                    let quantity = quantity
+                        .as_u64()
+                        .and_then(|quantity| PositiveCoin::try_from(quantity).ok())
+                        .ok_or_else(|| format!("invalid quantity of asset: {quantity}"))?;
+
+                    Ok((Bytes::from(asset_name), quantity))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+
+            if let Ok(tokens) = NonEmptyKeyValuePairs::try_from(tokens) {
+                assets.push((policy_id, tokens));
+            }
+        }
+
+        Ok(match NonEmptyKeyValuePairs::try_from(assets) {
+            Ok(assets) => Value::Multiasset(lovelace, assets),
+            Err(_) => Value::Coin(lovelace),
+        })
+    }
+}
+
+impl ScriptJson {
+    fn script_ref(&self) -> Result<ScriptRef, String> {
+        let cbor = hex::decode(&self.cbor_hex).map_err(|e| format!("invalid script: {e}"))?;
+
+        // Plutus scripts are wrapped in an extra layer of CBOR bytes, as in text envelopes.
+        let plutus =
+            || minicbor::decode::<Bytes>(&cbor).map_err(|e| format!("invalid script: {e}"));
+
+        match self.script_type.as_str() {
+            "PlutusScriptV1" => {
+                plutus().map(|bytes| PseudoScript::PlutusV1Script(PlutusScript(bytes)))
+            }
+            "PlutusScriptV2" => {
+                plutus().map(|bytes| PseudoScript::PlutusV2Script(PlutusScript(bytes)))
+            }
+            "PlutusScriptV3" => {
+                plutus().map(|bytes| PseudoScript::PlutusV3Script(PlutusScript(bytes)))
+            }
+            "SimpleScript" => minicbor::decode::<NativeScript>(&cbor)
+                .map(PseudoScript::NativeScript)
+                .map_err(|e| format!("invalid script: {e}")),
+            other => Err(format!("unknown script type: {other}")),
+        }
+    }
+}
+
+/// Plutus data, in the detailed JSON schema of cardano-cli. Integers are kept as written, since
+/// they needn't fit in 64 bits.
+#[derive(Debug, Deserialize)]
+pub struct PlutusDataJson {
+    #[serde(default)]
+    pub int: Option<Box<RawValue>>,
+    #[serde(default)]
+    pub bytes: Option<String>,
+    #[serde(default)]
+    pub list: Option<Vec<PlutusDataJson>>,
+    #[serde(default)]
+    pub map: Option<Vec<MapEntryJson>>,
+    #[serde(default)]
+    pub constructor: Option<u64>,
+    #[serde(default)]
+    pub fields: Option<Vec<PlutusDataJson>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MapEntryJson {
+    pub k: PlutusDataJson,
+    pub v: PlutusDataJson,
+}
+
+impl PlutusDataJson {
+    pub fn plutus_data(&self) -> Result<PlutusData, String> {
+        let items = |items: &[PlutusDataJson]| {
+            items
+                .iter()
+                .map(PlutusDataJson::plutus_data)
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        if let Some(int) = &self.int {
+            let int = int
+                .get()
+                .parse::<BigInt>()
+                .map_err(|_| format!("invalid integer: {int}"))?;
+            Ok(Data::integer(int))
+        } else if let Some(bytes) = &self.bytes {
+            let bytes = hex::decode(bytes).map_err(|_| format!("invalid bytes: {bytes}"))?;
+            Ok(Data::bytestring(bytes))
+        } else if let Some(list) = &self.list {
+            Ok(Data::list(items(list)?))
+        } else if let Some(map) = &self.map {
+            Ok(Data::map(
+                map.iter()
+                    .map(|entry| Ok((entry.k.plutus_data()?, entry.v.plutus_data()?)))
+                    .collect::<Result<_, String>>()?,
+            ))
+        } else if let Some(constructor) = self.constructor {
+            let fields = self.fields.as_deref().ok_or_else(|| {
+                format!("expected a list of 'fields' for constructor {constructor}")
+            })?;
+            Ok(Data::constr(constructor, items(fields)?))
+        } else {
+            Err("unrecognised Plutus data".to_string())
+        }
+    }
+}
+
+fn output_reference(out_ref: &str) -> Result<TransactionInput, Error> {
+    let invalid = || {
+        Error::Json(format!(
+            "invalid output reference {out_ref}, expected '{{transaction id}}#{{index}}'"
+        ))
+    };
+
+    let (transaction_id, index) = out_ref.split_once('#').ok_or_else(invalid)?;
+
+    Ok(TransactionInput {
+        transaction_id: transaction_id.parse().map_err(|_| invalid())?,
+        index: index.parse().map_err(|_| invalid())?,
+    })
+}
+
+fn decode_hex(what: &str, cbor_hex: &str) -> Result<Vec<u8>, Error> {
+    hex::decode(cbor_hex.trim()).map_err(|e| Error::Json(format!("invalid {what}: {e}")))
+}
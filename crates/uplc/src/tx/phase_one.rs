@@ -1,16 +1,29 @@
 use super::{
     error::Error,
-    script_context::{sort_voters, DataLookupTable, ResolvedInput, ScriptPurpose, ScriptVersion},
+    iter_redeemers,
+    protocol_parameters::ProtocolParameters,
+    script_context::{
+        get_validity_range_info, output_address, sort_voters, DataLookupTable, ResolvedInput,
+        ScriptPurpose, ScriptVersion, SlotConfig,
+    },
 };
 use crate::tx::script_context::sort_reward_accounts;
 use itertools::Itertools;
 use pallas_addresses::{Address, ScriptHash, ShelleyPaymentPart, StakePayload};
-use pallas_codec::utils::Nullable;
-use pallas_primitives::conway::{
-    Certificate, GovAction, MintedTx, PolicyId, RedeemerTag, Redeemers, RedeemersKey,
-    RewardAccount, StakeCredential, TransactionOutput, Voter,
+use pallas_codec::{minicbor, utils::Nullable};
+use pallas_crypto::hash::Hasher;
+use pallas_primitives::{
+    alonzo,
+    conway::{
+        Certificate, GovAction, Mint, MintedTx, PolicyId, PseudoScript, RationalNumber,
+        RedeemerTag, Redeemers, RedeemersKey, RewardAccount, StakeCredential, TransactionInput,
+        TransactionOutput, Value, Voter,
+    },
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
 };
-use std::collections::HashMap;
 
 type ScriptsNeeded = Vec<(ScriptPurpose, ScriptHash)>;
 
@@ -29,6 +42,36 @@ pub fn eval_phase_one(
     Ok(())
 }
 
+/// Phase-1 ledger checks unrelated to scripts, as far as they can be performed offline: size
+/// and fee, execution units declared by redeemers, value preservation, minimum ada of outputs, collateral, validity interval and
+/// required signers. The validity interval is checked against the `current_slot` when provided.
+///
+/// Note that a pool registration is assumed to be a first registration (and thus, to take a
+/// deposit), since knowing otherwise requires the state of the ledger.
+pub fn eval_ledger_rules(
+    tx: &MintedTx,
+    utxos: &[ResolvedInput],
+    protocol_parameters: &ProtocolParameters,
+    slot_config: &SlotConfig,
+    current_slot: Option<u64>,
+) -> Result<(), Error> {
+    validate_fee(tx, utxos, protocol_parameters)?;
+
+    validate_ex_units(tx, protocol_parameters)?;
+
+    validate_value_preservation(tx, utxos, protocol_parameters)?;
+
+    validate_min_ada(tx, protocol_parameters)?;
+
+    validate_collateral(tx, utxos, protocol_parameters)?;
+
+    validate_validity_interval(tx, slot_config, current_slot)?;
+
+    validate_required_signers(tx)?;
+
+    Ok(())
+}
+
 pub fn validate_missing_scripts(
     needed: &ScriptsNeeded,
     txscripts: HashMap<ScriptHash, ScriptVersion>,
@@ -412,3 +455,503 @@ pub fn redeemer_tag_to_string(redeemer_tag: &RedeemerTag) -> String {
     }
     .to_string()
 }
+
+pub fn validate_fee(
+    tx: &MintedTx,
+    utxos: &[ResolvedInput],
+    protocol_parameters: &ProtocolParameters,
+) -> Result<(), Error> {
+    let size = minicbor::to_vec(tx).expect("infallible encoding").len() as u64;
+
+    if size > protocol_parameters.max_tx_size {
+        return Err(Error::MaxTxSizeExceeded {
+            size,
+            maximum: protocol_parameters.max_tx_size,
+        });
+    }
+
+    let txb = &tx.transaction_body;
+
+    let mut ref_scripts_size = 0;
+    for input in txb
+        .inputs
+        .iter()
+        .chain(txb.reference_inputs.iter().flat_map(|inputs| inputs.iter()))
+    {
+        ref_scripts_size += script_ref_size(&resolve(utxos, input)?.output);
+    }
+
+    let (mem, steps) = tx
+        .transaction_witness_set
+        .redeemer
+        .as_ref()
+        .map(|redeemers| {
+            iter_redeemers(redeemers).fold((0, 0), |(mem, steps), (_, _, ex_units)| {
+                (mem + ex_units.mem as u128, steps + ex_units.steps as u128)
+            })
+        })
+        .unwrap_or_default();
+
+    let minimum = protocol_parameters.min_fee_a * size
+        + protocol_parameters.min_fee_b
+        + ref_scripts_fee(
+            protocol_parameters.min_fee_ref_script_cost_per_byte,
+            ref_scripts_size,
+        )
+        + execution_fee(
+            &protocol_parameters.price_mem,
+            mem,
+            &protocol_parameters.price_steps,
+            steps,
+        );
+
+    if txb.fee < minimum {
+        return Err(Error::FeeTooSmall {
+            fee: txb.fee,
+            minimum,
+        });
+    }
+
+    Ok(())
+}
+
+pub fn validate_ex_units(
+    tx: &MintedTx,
+    protocol_parameters: &ProtocolParameters,
+) -> Result<(), Error> {
+    let (mem, steps) = tx
+        .transaction_witness_set
+        .redeemer
+        .as_ref()
+        .map(|redeemers| {
+            iter_redeemers(redeemers).fold((0u64, 0u64), |(mem, steps), (_, _, ex_units)| {
+                (
+                    mem.saturating_add(ex_units.mem),
+                    steps.saturating_add(ex_units.steps),
+                )
+            })
+        })
+        .unwrap_or_default();
+
+    let max_mem = protocol_parameters.max_tx_ex_units.mem as u64;
+    let max_steps = protocol_parameters.max_tx_ex_units.cpu as u64;
+
+    if mem > max_mem || steps > max_steps {
+        return Err(Error::ExUnitsTooBig {
+            mem,
+            steps,
+            max_mem,
+            max_steps,
+        });
+    }
+
+    Ok(())
+}
+
+pub fn validate_value_preservation(
+    tx: &MintedTx,
+    utxos: &[ResolvedInput],
+    protocol_parameters: &ProtocolParameters,
+) -> Result<(), Error> {
+    let txb = &tx.transaction_body;
+
+    let mut consumed = Balance::default();
+    let mut produced = Balance::default();
+
+    for input in txb.inputs.iter() {
+        consumed.add_output(&resolve(utxos, input)?.output);
+    }
+
+    if let Some(mint) = &txb.mint {
+        consumed.add_mint(mint);
+    }
+
+    for (_, amount) in txb
+        .withdrawals
+        .iter()
+        .flat_map(|withdrawals| withdrawals.iter())
+    {
+        consumed.add_lovelace(*amount);
+    }
+
+    for certificate in txb.certificates.iter().flat_map(|certs| certs.iter()) {
+        match certificate {
+            Certificate::StakeRegistration(..) => {
+                produced.add_lovelace(protocol_parameters.key_deposit)
+            }
+            Certificate::StakeDeregistration(..) => {
+                consumed.add_lovelace(protocol_parameters.key_deposit)
+            }
+            Certificate::PoolRegistration { .. } => {
+                produced.add_lovelace(protocol_parameters.pool_deposit)
+            }
+            Certificate::Reg(_, deposit)
+            | Certificate::StakeRegDeleg(_, _, deposit)
+            | Certificate::VoteRegDeleg(_, _, deposit)
+            | Certificate::StakeVoteRegDeleg(_, _, _, deposit)
+            | Certificate::RegDRepCert(_, deposit, _) => produced.add_lovelace(*deposit),
+            Certificate::UnReg(_, refund) | Certificate::UnRegDRepCert(_, refund) => {
+                consumed.add_lovelace(*refund)
+            }
+            Certificate::StakeDelegation(..)
+            | Certificate::PoolRetirement(..)
+            | Certificate::VoteDeleg(..)
+            | Certificate::StakeVoteDeleg(..)
+            | Certificate::AuthCommitteeHot(..)
+            | Certificate::ResignCommitteeCold(..)
+            | Certificate::UpdateDRepCert(..) => (),
+        }
+    }
+
+    for output in txb.outputs.iter() {
+        produced.add_output(&output.clone().into());
+    }
+
+    produced.add_lovelace(txb.fee);
+
+    for procedure in txb.proposal_procedures.iter().flat_map(|ps| ps.iter()) {
+        produced.add_lovelace(procedure.deposit);
+    }
+
+    if let Some(donation) = txb.donation {
+        produced.add_lovelace(donation.into());
+    }
+
+    if consumed.minus(&produced) != Balance::default() {
+        return Err(Error::ValueNotConserved {
+            consumed: consumed.to_string(),
+            produced: produced.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+pub fn validate_min_ada(
+    tx: &MintedTx,
+    protocol_parameters: &ProtocolParameters,
+) -> Result<(), Error> {
+    let txb = &tx.transaction_body;
+
+    let outputs = txb
+        .outputs
+        .iter()
+        .enumerate()
+        .map(|(ix, output)| (format!("#{ix}"), output))
+        .chain(
+            txb.collateral_return
+                .iter()
+                .map(|output| ("collateral return".to_string(), output)),
+        );
+
+    for (output, minted_output) in outputs {
+        // Fixed overhead of an entry in the UTxO, on top of the serialised output.
+        let size = 160
+            + minicbor::to_vec(minted_output)
+                .expect("infallible encoding")
+                .len() as u64;
+
+        let minimum = size * protocol_parameters.coins_per_utxo_byte;
+
+        let lovelace = match TransactionOutput::from(minted_output.clone()) {
+            TransactionOutput::Legacy(output) => match output.amount {
+                alonzo::Value::Coin(coin) | alonzo::Value::Multiasset(coin, _) => coin,
+            },
+            TransactionOutput::PostAlonzo(output) => match output.value {
+                Value::Coin(coin) | Value::Multiasset(coin, _) => coin,
+            },
+        };
+
+        if lovelace < minimum {
+            return Err(Error::OutputTooSmall {
+                output,
+                lovelace,
+                minimum,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_collateral(
+    tx: &MintedTx,
+    utxos: &[ResolvedInput],
+    protocol_parameters: &ProtocolParameters,
+) -> Result<(), Error> {
+    let has_redeemers = tx
+        .transaction_witness_set
+        .redeemer
+        .as_ref()
+        .is_some_and(|redeemers| iter_redeemers(redeemers).next().is_some());
+
+    if !has_redeemers {
+        return Ok(());
+    }
+
+    let txb = &tx.transaction_body;
+
+    let collateral = txb.collateral.as_deref().cloned().unwrap_or_default();
+
+    if collateral.is_empty() {
+        return Err(Error::NoCollateralInputs);
+    }
+
+    if collateral.len() as u64 > protocol_parameters.max_collateral_inputs {
+        return Err(Error::TooManyCollateralInputs {
+            count: collateral.len() as u64,
+            maximum: protocol_parameters.max_collateral_inputs,
+        });
+    }
+
+    let mut provided = Balance::default();
+
+    for input in collateral.iter() {
+        let utxo = resolve(utxos, input)?;
+
+        if let Address::Shelley(address) = output_address(&utxo.output) {
+            if let ShelleyPaymentPart::Script(_) = address.payment() {
+                return Err(Error::ScriptLockedCollateral(input.clone()));
+            }
+        }
+
+        provided.add_output(&utxo.output);
+    }
+
+    let mut returned = Balance::default();
+
+    if let Some(collateral_return) = &txb.collateral_return {
+        returned.add_output(&collateral_return.clone().into());
+    }
+
+    let balance = provided.minus(&returned);
+
+    if !balance.assets.is_empty() {
+        return Err(Error::NonAdaCollateral);
+    }
+
+    let provided = balance.lovelace.max(0) as u64;
+
+    if provided as u128 * 100 < txb.fee as u128 * protocol_parameters.collateral_percentage as u128
+    {
+        return Err(Error::InsufficientCollateral {
+            provided,
+            required: (txb.fee * protocol_parameters.collateral_percentage + 99) / 100,
+        });
+    }
+
+    if let Some(declared) = txb.total_collateral {
+        if declared != provided {
+            return Err(Error::IncorrectTotalCollateral { declared, provided });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_validity_interval(
+    tx: &MintedTx,
+    slot_config: &SlotConfig,
+    current_slot: Option<u64>,
+) -> Result<(), Error> {
+    let txb = &tx.transaction_body;
+
+    let lower_bound = txb.validity_interval_start;
+    let upper_bound = txb.ttl;
+
+    if let (Some(lower_bound), Some(upper_bound)) = (lower_bound, upper_bound) {
+        if lower_bound >= upper_bound {
+            return Err(Error::EmptyValidityInterval {
+                lower_bound,
+                upper_bound,
+            });
+        }
+    }
+
+    // Scripts see the interval as POSIX time, which must be computable from the slot config.
+    get_validity_range_info(txb, slot_config)?;
+
+    if let Some(slot) = current_slot {
+        if lower_bound.is_some_and(|lower_bound| slot < lower_bound)
+            || upper_bound.is_some_and(|upper_bound| slot >= upper_bound)
+        {
+            return Err(Error::OutsideValidityInterval {
+                slot,
+                lower_bound,
+                upper_bound,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_required_signers(tx: &MintedTx) -> Result<(), Error> {
+    let signers = tx
+        .transaction_witness_set
+        .vkeywitness
+        .iter()
+        .flat_map(|witnesses| witnesses.iter())
+        .map(|witness| Hasher::<224>::hash(&witness.vkey))
+        .collect::<Vec<_>>();
+
+    let missing = tx
+        .transaction_body
+        .required_signers
+        .iter()
+        .flat_map(|required_signers| required_signers.iter())
+        .filter(|required_signer| !signers.contains(required_signer))
+        .map(|required_signer| required_signer.to_string())
+        .collect::<Vec<_>>();
+
+    if !missing.is_empty() {
+        return Err(Error::MissingRequiredSigners { missing });
+    }
+
+    Ok(())
+}
+
+fn resolve<'a>(
+    utxos: &'a [ResolvedInput],
+    input: &TransactionInput,
+) -> Result<&'a ResolvedInput, Error> {
+    utxos
+        .iter()
+        .find(|utxo| utxo.input == *input)
+        .ok_or_else(|| Error::ResolvedInputNotFound(input.clone()))
+}
+
+fn script_ref_size(output: &TransactionOutput) -> u64 {
+    match output {
+        TransactionOutput::Legacy(_) => 0,
+        TransactionOutput::PostAlonzo(output) => match output.script_ref.as_deref() {
+            None => 0,
+            Some(PseudoScript::NativeScript(script)) => {
+                minicbor::to_vec(script).expect("infallible encoding").len() as u64
+            }
+            Some(PseudoScript::PlutusV1Script(script)) => script.0.len() as u64,
+            Some(PseudoScript::PlutusV2Script(script)) => script.0.len() as u64,
+            Some(PseudoScript::PlutusV3Script(script)) => script.0.len() as u64,
+        },
+    }
+}
+
+/// Fee for reference scripts, which grows by a factor of 1.2 for every 25KiB.
+fn ref_scripts_fee(cost_per_byte: u64, size: u64) -> u64 {
+    const TIER_SIZE: u128 = 25 * 1024;
+
+    // Prices and fee accumulated so far share the same denominator, to stay exact.
+    let mut denominator: u128 = 1;
+    let mut price: u128 = cost_per_byte as u128;
+    let mut fee: u128 = 0;
+    let mut remaining = size as u128;
+
+    while remaining >= TIER_SIZE {
+        fee += TIER_SIZE * price;
+        remaining -= TIER_SIZE;
+        fee *= 5;
+        denominator *= 5;
+        price *= 6;
+    }
+
+    ((fee + remaining * price) / denominator) as u64
+}
+
+fn execution_fee(
+    price_mem: &RationalNumber,
+    mem: u128,
+    price_steps: &RationalNumber,
+    steps: u128,
+) -> u64 {
+    let numerator = price_mem.numerator as u128 * mem * price_steps.denominator as u128
+        + price_steps.numerator as u128 * steps * price_mem.denominator as u128;
+
+    let denominator = price_mem.denominator as u128 * price_steps.denominator as u128;
+
+    ((numerator + denominator - 1) / denominator) as u64
+}
+
+/// A multi-asset value where quantities may be negative, to balance consumed and produced values.
+#[derive(Debug, Default, PartialEq)]
+struct Balance {
+    lovelace: i128,
+    assets: BTreeMap<(PolicyId, Vec<u8>), i128>,
+}
+
+impl Balance {
+    fn add_lovelace(&mut self, lovelace: u64) {
+        self.lovelace += lovelace as i128;
+    }
+
+    fn add_asset(&mut self, policy_id: PolicyId, asset_name: Vec<u8>, quantity: i128) {
+        let total = self.assets.entry((policy_id, asset_name)).or_default();
+        *total += quantity;
+        if *total == 0 {
+            self.assets.retain(|_, quantity| *quantity != 0);
+        }
+    }
+
+    fn add_output(&mut self, output: &TransactionOutput) {
+        match output {
+            TransactionOutput::Legacy(output) => match &output.amount {
+                alonzo::Value::Coin(coin) => self.add_lovelace(*coin),
+                alonzo::Value::Multiasset(coin, assets) => {
+                    self.add_lovelace(*coin);
+                    for (policy_id, tokens) in assets.iter() {
+                        for (asset_name, quantity) in tokens.iter() {
+                            self.add_asset(*policy_id, asset_name.to_vec(), *quantity as i128);
+                        }
+                    }
+                }
+            },
+            TransactionOutput::PostAlonzo(output) => match &output.value {
+                Value::Coin(coin) => self.add_lovelace(*coin),
+                Value::Multiasset(coin, assets) => {
+                    self.add_lovelace(*coin);
+                    for (policy_id, tokens) in assets.iter() {
+                        for (asset_name, quantity) in tokens.iter() {
+                            self.add_asset(
+                                *policy_id,
+                                asset_name.to_vec(),
+                                u64::from(quantity) as i128,
+                            );
+                        }
+                    }
+                }
+            },
+        }
+    }
+
+    fn add_mint(&mut self, mint: &Mint) {
+        for (policy_id, tokens) in mint.iter() {
+            for (asset_name, quantity) in tokens.iter() {
+                self.add_asset(*policy_id, asset_name.to_vec(), i64::from(quantity) as i128);
+            }
+        }
+    }
+
+    fn minus(&self, other: &Balance) -> Balance {
+        let mut balance = Balance {
+            lovelace: self.lovelace - other.lovelace,
+            assets: self.assets.clone(),
+        };
+
+        for ((policy_id, asset_name), quantity) in other.assets.iter() {
+            balance.add_asset(*policy_id, asset_name.clone(), -quantity);
+        }
+
+        balance
+    }
+}
+
+impl fmt::Display for Balance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} lovelace", self.lovelace)?;
+
+        for ((policy_id, asset_name), quantity) in self.assets.iter() {
+            write!(f, " + {quantity} {policy_id}.{}", hex::encode(asset_name))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+use super::error::Error;
+use crate::machine::cost_model::ExBudget;
+use pallas_primitives::conway::{CostModels, RationalNumber};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Protocol parameters involved in the (offline) phase-one ledger rules and in the evaluation
+/// of scripts. Defaults to mainnet's values at the beginning of the Conway era.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtocolParameters {
+    /// Fee per byte of transaction, in lovelace.
+    pub min_fee_a: u64,
+    /// Constant part of the fee, in lovelace.
+    pub min_fee_b: u64,
+    /// Fee per byte of reference scripts, in lovelace, for the first tier of 25KiB.
+    pub min_fee_ref_script_cost_per_byte: u64,
+    /// Maximum size of a transaction, in bytes.
+    pub max_tx_size: u64,
+    /// Lovelace required per byte of output, to compute the minimum ada of outputs.
+    pub coins_per_utxo_byte: u64,
+    /// Collateral required, as a percentage of the fee.
+    pub collateral_percentage: u64,
+    /// Maximum number of collateral inputs.
+    pub max_collateral_inputs: u64,
+    /// Deposit of (legacy) stake registration certificates, in lovelace.
+    pub key_deposit: u64,
+    /// Deposit of pool registration certificates, in lovelace.
+    pub pool_deposit: u64,
+    /// Price of a unit of memory, in lovelace.
+    pub price_mem: RationalNumber,
+    /// Price of a unit of CPU, in lovelace.
+    pub price_steps: RationalNumber,
+    /// Maximum execution units of all scripts of a transaction.
+    pub max_tx_ex_units: ExBudget,
+    /// Cost models of each Plutus version, or the built-in ones when absent.
+    pub cost_models: Option<CostModels>,
+}
+
+impl Default for ProtocolParameters {
+    fn default() -> Self {
+        Self {
+            min_fee_a: 44,
+            min_fee_b: 155381,
+            min_fee_ref_script_cost_per_byte: 15,
+            max_tx_size: 16384,
+            coins_per_utxo_byte: 4310,
+            collateral_percentage: 150,
+            max_collateral_inputs: 3,
+            key_deposit: 2000000,
+            pool_deposit: 500000000,
+            price_mem: RationalNumber {
+                numerator: 577,
+                denominator: 10000,
+            },
+            price_steps: RationalNumber {
+                numerator: 721,
+                denominator: 10000000,
+            },
+            max_tx_ex_units: ExBudget::default(),
+            cost_models: None,
+        }
+    }
+}
+
+/// Protocol parameters as output by `cardano-cli query protocol-parameters`. Parameters
+/// irrelevant here are ignored, and missing ones keep their default value.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProtocolParametersJson {
+    tx_fee_per_byte: Option<u64>,
+    tx_fee_fixed: Option<u64>,
+    min_fee_ref_script_cost_per_byte: Option<serde_json::Number>,
+    max_tx_size: Option<u64>,
+    utxo_cost_per_byte: Option<u64>,
+    collateral_percentage: Option<u64>,
+    max_collateral_inputs: Option<u64>,
+    stake_address_deposit: Option<u64>,
+    stake_pool_deposit: Option<u64>,
+    execution_unit_prices: Option<ExecutionUnitPricesJson>,
+    max_tx_execution_units: Option<ExecutionUnitsJson>,
+    #[serde(default)]
+    cost_models: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExecutionUnitPricesJson {
+    price_memory: serde_json::Number,
+    price_steps: serde_json::Number,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExecutionUnitsJson {
+    memory: i64,
+    steps: i64,
+}
+
+impl ProtocolParameters {
+    pub fn from_json(json: &str) -> Result<ProtocolParameters, Error> {
+        let json: ProtocolParametersJson =
+            serde_json::from_str(json).map_err(|e| Error::Json(e.to_string()))?;
+
+        let default = ProtocolParameters::default();
+
+        let (price_mem, price_steps) = match json.execution_unit_prices {
+            None => (default.price_mem, default.price_steps),
+            Some(prices) => (
+                rational_number(&prices.price_memory)?,
+                rational_number(&prices.price_steps)?,
+            ),
+        };
+
+        let min_fee_ref_script_cost_per_byte = match json.min_fee_ref_script_cost_per_byte {
+            None => default.min_fee_ref_script_cost_per_byte,
+            Some(cost) => {
+                let cost = rational_number(&cost)?;
+                if cost.denominator != 1 {
+                    return Err(Error::Json(format!(
+                        "unsupported fractional minFeeRefScriptCostPerByte: {}/{}",
+                        cost.numerator, cost.denominator
+                    )));
+                }
+                cost.numerator
+            }
+        };
+
+        let cost_model = |version: &str| {
+            json.cost_models
+                .get(version)
+                .map(|costs| {
+                    serde_json::from_value::<Vec<i64>>(costs.clone()).map_err(|_| {
+                        Error::Json(format!(
+                            "expected the cost model of {version} as a list of integers"
+                        ))
+                    })
+                })
+                .transpose()
+        };
+
+        let cost_models = if json.cost_models.is_empty() {
+            None
+        } else {
+            Some(CostModels {
+                plutus_v1: cost_model("PlutusV1")?,
+                plutus_v2: cost_model("PlutusV2")?,
+                plutus_v3: cost_model("PlutusV3")?,
+            })
+        };
+
+        Ok(ProtocolParameters {
+            min_fee_a: json.tx_fee_per_byte.unwrap_or(default.min_fee_a),
+            min_fee_b: json.tx_fee_fixed.unwrap_or(default.min_fee_b),
+            min_fee_ref_script_cost_per_byte,
+            max_tx_size: json.max_tx_size.unwrap_or(default.max_tx_size),
+            coins_per_utxo_byte: json
+                .utxo_cost_per_byte
+                .unwrap_or(default.coins_per_utxo_byte),
+            collateral_percentage: json
+                .collateral_percentage
+                .unwrap_or(default.collateral_percentage),
+            max_collateral_inputs: json
+                .max_collateral_inputs
+                .unwrap_or(default.max_collateral_inputs),
+            key_deposit: json.stake_address_deposit.unwrap_or(default.key_deposit),
+            pool_deposit: json.stake_pool_deposit.unwrap_or(default.pool_deposit),
+            price_mem,
+            price_steps,
+            max_tx_ex_units: json
+                .max_tx_execution_units
+                .map(|ex_units| ExBudget {
+                    mem: ex_units.memory,
+                    cpu: ex_units.steps,
+                })
+                .unwrap_or(default.max_tx_ex_units),
+            cost_models,
+        })
+    }
+}
+
+/// Exact rational number from a decimal number, possibly in scientific notation (e.g. 7.21e-5).
+fn rational_number(number: &serde_json::Number) -> Result<RationalNumber, Error> {
+    let invalid = || Error::Json(format!("invalid price or cost: {number}"));
+
+    let number = number.to_string();
+
+    let (mantissa, exponent) = match number.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i32>().map_err(|_| invalid())?),
+        None => (number.as_str(), 0),
+    };
+
+    let (integral, fractional) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+    let mut numerator = format!("{integral}{fractional}")
+        .parse::<u64>()
+        .map_err(|_| invalid())?;
+
+    let mut denominator: u64 = 1;
+
+    let exponent = exponent - fractional.len() as i32;
+
+    for _ in 0..exponent.unsigned_abs() {
+        if exponent < 0 {
+            denominator = denominator.checked_mul(10).ok_or_else(invalid)?;
+        } else {
+            numerator = numerator.checked_mul(10).ok_or_else(invalid)?;
+        }
+    }
+
+    Ok(RationalNumber {
+        numerator,
+        denominator,
+    })
+}
@@ -955,6 +955,42 @@ pub fn find_script(
     }
 }
 
+/// Resolve the datum of the output spent by the given redeemer, if any. Unlike 'find_script', this
+/// doesn't require the script locking the output to be known.
+pub fn find_datum(
+    redeemer: &Redeemer,
+    tx: &MintedTx,
+    utxos: &[ResolvedInput],
+    lookup_table: &DataLookupTable,
+) -> Result<Option<PlutusData>, Error> {
+    if redeemer.tag != RedeemerTag::Spend {
+        return Ok(None);
+    }
+
+    let inputs = get_tx_in_info_v2(&tx.transaction_body.inputs, utxos).or_else(|err| {
+        if matches!(err, Error::ByronAddressNotAllowed) {
+            get_tx_in_info_v1(&tx.transaction_body.inputs, utxos)
+        } else {
+            Err(err)
+        }
+    })?;
+
+    let input = inputs
+        .get(redeemer.index as usize)
+        .ok_or(Error::MissingScriptForRedeemer)?;
+
+    match output_datum(&input.resolved) {
+        Some(DatumOption::Hash(hash)) => match lookup_table.datum.get(&hash) {
+            Some(d) => Ok(Some(d.clone())),
+            None => Err(Error::MissingRequiredDatum {
+                hash: hash.to_string(),
+            }),
+        },
+        Some(DatumOption::Data(data)) => Ok(Some(data.0.clone())),
+        None => Ok(None),
+    }
+}
+
 pub fn from_alonzo_value(value: &alonzo::Value) -> Value {
     match value {
         alonzo::Value::Coin(coin) => Value::Coin(*coin),
@@ -1,8 +1,17 @@
-use super::{eval_phase_two, ResolvedInput, SlotConfig};
-use crate::machine::cost_model::ExBudget;
-use pallas_codec::utils::MaybeIndefArray;
+use super::{
+    error::Error, eval::script_arguments, eval_ledger_rules, eval_phase_two, iter_redeemers,
+    json::TransactionJson, script_context::from_alonzo_output, simulate, DataLookupTable,
+    ProtocolParameters, ResolvedInput, SlotConfig,
+};
+use crate::{ast::Data, machine::cost_model::ExBudget};
+use indoc::indoc;
+use pallas_codec::utils::{CborWrap, MaybeIndefArray, NonEmptySet};
+use pallas_crypto::hash::Hasher;
 use pallas_primitives::{
-    conway::{CostModels, TransactionInput, TransactionOutput},
+    conway::{
+        CostModels, DatumOption, Language, PlutusScript, PseudoScript, Redeemer, TransactionInput,
+        TransactionOutput, Tx, Value,
+    },
     Fragment,
 };
 use pallas_traverse::{Era, MultiEraTx};
@@ -1771,3 +1780,647 @@ fn eval_extraneous_redeemer() {
         _ => unreachable!(),
     };
 }
+
+#[test]
+fn script_arguments_without_script() {
+    let tx_bytes = hex::decode("84a800818258206c732139de33e916342707de2aebef2252c781640326ff37b86ec99d97f1ba8d01018282581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f67235821a000f41f0a1581ccba7bc9e83499376b6ad49304157778dba7c14bd748e4fd31792a930a1400a82581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f672351b00000001af006ac1021a00050c0309a1581ccba7bc9e83499376b6ad49304157778dba7c14bd748e4fd31792a930a1400a0b5820eb3b868ec2b33dffaf5d5481703ed00870333812b96e0f75ae89fd150b4744300d818258206c732139de33e916342707de2aebef2252c781640326ff37b86ec99d97f1ba8d011082581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f672351b00000001af0d26af111a00079205a3008182582031ae74f8058527afb305d7495b10a99422d9337fc199e1f28044f2c477a0f94658405a2dd70be89483bd6291a018c6ca91328dad37e092fdeab2eea14685004878da5f1e5962f35d771498bf54e79be3dcf922ea93b46a1356960dcf0bfd80a91b0b038159094259093f010000323322323232323232323232323232323232323233223232323232323232332232322232325335332232323233355300f12001350265025235001223335530121200135029502823500122333500123302f4800000488cc0c00080048cc0bc00520000013355300e120012350012233550250023335001233553012120012350012233550290023550140010012233355500f0150020012335530121200123500122335502900235501300100133355500a01000200130105002300f5001135001220023333573466e1cd55ce9baa00448000807c8c98c8078cd5ce01000f80e1999ab9a3370e6aae754009200023322123300100300232323232323232323232323333573466e1cd55cea8052400046666666666444444444424666666666600201601401201000e00c00a00800600466a034464646666ae68cdc39aab9d5002480008cc8848cc00400c008c094d5d0a801180f9aba135744a004464c6405c66ae700c00bc0b04d55cf280089baa00135742a01466a0340366ae854024ccd54075d7280e1aba150083335501d75ca0386ae85401ccd4068094d5d0a80319a80d19aa8140133ad35742a00a6464646666ae68cdc39aab9d5002480008cc8848cc00400c008c8c8c8cccd5cd19b8735573aa004900011991091980080180119a815bad35742a00460586ae84d5d1280111931901919ab9c034033030135573ca00226ea8004d5d0a8011919191999ab9a3370e6aae754009200023322123300100300233502b75a6ae854008c0b0d5d09aba2500223263203233573806806606026aae7940044dd50009aba135744a004464c6405c66ae700c00bc0b04d55cf280089baa00135742a00866a034eb8d5d0a80199a80d19aa8143ae200135742a00460446ae84d5d1280111931901519ab9c02c02b028135744a00226ae8940044d5d1280089aba25001135744a00226ae8940044d5d1280089aba25001135573ca00226ea8004d5d0a8011919191999ab9a3370ea0029003119091111802002980e9aba135573ca00646666ae68cdc3a8012400846424444600400a603e6ae84d55cf280211999ab9a3370ea0069001119091111800802980d9aba135573ca00a46666ae68cdc3a8022400046424444600600a6eb8d5d09aab9e500623263202533573804e04c04604404204026aae7540044dd50009aba135744a004464c6403c66ae7008007c07040784c98c8074cd5ce249035054350001e135573ca00226ea8004444888ccd54c01048005403ccd54c01c480048d400488cd54078008d54024004ccd54c0104800488d4008894cd4ccd54c03048004c8cd409488ccd400c88008008004d40048800448cc004894cd400840a8400409c8d400488cc028008014018400c4cd404c01000d4040004cd54c01c480048d400488c8cd5407c00cc004014c8004d5409c894cd40044d5402800c884d4008894cd4cc03000802044888cc0080280104c01800c008c8004d5408088448894cd40044008884cc014008ccd54c01c480040140100044484888c00c0104484888c004010c8004d540748844894cd400454034884cd4038c010008cd54c01848004010004c8004d5407088448894cd40044d400c88004884ccd401488008c010008ccd54c01c4800401401000488ccd5cd19b8f00200101c01b23500122222222220081232230023758002640026aa034446666aae7c004940248cd4020c010d5d080118019aba200201423232323333573466e1cd55cea801a40004666444246660020080060046464646666ae68cdc39aab9d5002480008cc8848cc00400c008c054d5d0a80119a80700a1aba135744a004464c6403066ae700680640584d55cf280089baa00135742a006666aa00eeb94018d5d0a80119a8053ae357426ae8940088c98c8050cd5ce00b00a80909aba25001135573ca00226ea80044cd54005d73ad112232230023756002640026aa03044646666aae7c008940208cd401ccd5404cc018d55cea80118029aab9e500230043574400602626ae840044488008488488cc00401000c488c8c8cccd5cd19b875001480008c8488c00800cc014d5d09aab9e500323333573466e1d40092002212200123263201033573802402201c01a26aae7540044dd5000919191999ab9a3370e6aae7540092000233221233001003002300535742a0046eb4d5d09aba2500223263200d33573801e01c01626aae7940044dd50009191999ab9a3370e6aae75400520002375c6ae84d55cf280111931900599ab9c00d00c0091375400224464646666ae68cdc3a800a40084244400246666ae68cdc3a8012400446424446006008600c6ae84d55cf280211999ab9a3370ea00690001091100111931900719ab9c01000f00c00b00a135573aa00226ea80048c8cccd5cd19b8750014800880448cccd5cd19b8750024800080448c98c8028cd5ce00600580400389aab9d3754002464646464646666ae68cdc3a800a401842444444400646666ae68cdc3a8012401442444444400846666ae68cdc3a801a40104664424444444660020120106eb8d5d0a8029bad357426ae8940148cccd5cd19b875004480188cc8848888888cc008024020dd71aba15007375c6ae84d5d1280391999ab9a3370ea00a900211991091111111980300480418061aba15009375c6ae84d5d1280491999ab9a3370ea00c900111909111111180380418069aba135573ca01646666ae68cdc3a803a400046424444444600a010601c6ae84d55cf280611931900919ab9c01401301000f00e00d00c00b00a135573aa00826aae79400c4d55cf280109aab9e5001137540024646464646666ae68cdc3a800a4004466644424466600200a0080066eb4d5d0a8021bad35742a0066eb4d5d09aba2500323333573466e1d4009200023212230020033008357426aae7940188c98c802ccd5ce00680600480409aab9d5003135744a00226aae7940044dd5000919191999ab9a3370ea002900111909118008019bae357426aae79400c8cccd5cd19b875002480008c8488c00800cdd71aba135573ca008464c6401066ae700280240180144d55cea80089baa0011122232323333573466e1cd55cea80124000466aa010600c6ae854008c014d5d09aba2500223263200833573801401200c26aae7940044dd5000a4c22442466002006004240029210350543100112330012253350021001100700612335002223335003220020020013500122001122123300100300222333573466e1c00800401000c488008488004448c8c00400488cc00cc00800800410581840100d87980821a000a01a61a0b3b82b2f5f6").unwrap();
+
+    let raw_inputs = hex::decode("84825820b16778c9cf065d9efeefe37ec269b4fc5107ecdbd0dd6bf3274b224165c2edd9008258206c732139de33e916342707de2aebef2252c781640326ff37b86ec99d97f1ba8d01825820975c17a4fed0051be622328efa548e206657d2b65a19224bf6ff8132571e6a500282582018f86700660fc88d0370a8f95ea58f75507e6b27a18a17925ad3b1777eb0d77600").unwrap();
+    let raw_outputs = hex::decode("8482581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f67235821a000f8548a1581c15be994a64bdb79dde7fe080d8e7ff81b33a9e4860e9ee0d857a8e85a144576177610182581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f672351b00000001af14b8b482581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f672351a0098968082581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f672351a00acd8c6").unwrap();
+
+    let inputs = MaybeIndefArray::<TransactionInput>::decode_fragment(&raw_inputs).unwrap();
+    let outputs = MaybeIndefArray::<TransactionOutput>::decode_fragment(&raw_outputs).unwrap();
+
+    let utxos: Vec<ResolvedInput> = inputs
+        .iter()
+        .zip(outputs.iter())
+        .map(|(input, output)| ResolvedInput {
+            input: input.clone(),
+            output: output.clone(),
+        })
+        .collect();
+
+    let slot_config = SlotConfig {
+        zero_time: 1660003200000, // Preview network
+        zero_slot: 0,
+        slot_length: 1000,
+    };
+
+    let multi_era_tx = MultiEraTx::decode_for_era(Era::Conway, &tx_bytes).unwrap();
+
+    let tx = multi_era_tx.as_conway().unwrap();
+
+    let (key, data, ex_units) =
+        iter_redeemers(tx.transaction_witness_set.redeemer.as_ref().unwrap())
+            .next()
+            .unwrap();
+
+    let redeemer = Redeemer {
+        tag: key.tag,
+        index: key.index,
+        data: data.clone(),
+        ex_units,
+    };
+
+    let lookup_table = DataLookupTable::from_transaction(tx, &utxos);
+
+    // A minting policy receives no datum: only the redeemer and the script context in Plutus V1 &
+    // V2, and only the script context (which embeds the redeemer) in Plutus V3.
+    let v2 = script_arguments(
+        tx,
+        &utxos,
+        &slot_config,
+        &redeemer,
+        &lookup_table,
+        &Language::PlutusV2,
+    )
+    .unwrap();
+
+    assert_eq!(v2.len(), 2);
+    assert_eq!(v2[0], redeemer.data);
+
+    let v3 = script_arguments(
+        tx,
+        &utxos,
+        &slot_config,
+        &redeemer,
+        &lookup_table,
+        &Language::PlutusV3,
+    )
+    .unwrap();
+
+    assert_eq!(v3.len(), 1);
+}
+
+#[test]
+fn simulate_reports_every_redeemer() {
+    let tx_bytes = hex::decode("84a800818258206c732139de33e916342707de2aebef2252c781640326ff37b86ec99d97f1ba8d01018282581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f67235821a000f41f0a1581ccba7bc9e83499376b6ad49304157778dba7c14bd748e4fd31792a930a1400a82581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f672351b00000001af006ac1021a00050c0309a1581ccba7bc9e83499376b6ad49304157778dba7c14bd748e4fd31792a930a1400a0b5820eb3b868ec2b33dffaf5d5481703ed00870333812b96e0f75ae89fd150b4744300d818258206c732139de33e916342707de2aebef2252c781640326ff37b86ec99d97f1ba8d011082581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f672351b00000001af0d26af111a00079205a3008182582031ae74f8058527afb305d7495b10a99422d9337fc199e1f28044f2c477a0f94658405a2dd70be89483bd6291a018c6ca91328dad37e092fdeab2eea14685004878da5f1e5962f35d771498bf54e79be3dcf922ea93b46a1356960dcf0bfd80a91b0b038159094259093f010000323322323232323232323232323232323232323233223232323232323232332232322232325335332232323233355300f12001350265025235001223335530121200135029502823500122333500123302f4800000488cc0c00080048cc0bc00520000013355300e120012350012233550250023335001233553012120012350012233550290023550140010012233355500f0150020012335530121200123500122335502900235501300100133355500a01000200130105002300f5001135001220023333573466e1cd55ce9baa00448000807c8c98c8078cd5ce01000f80e1999ab9a3370e6aae754009200023322123300100300232323232323232323232323333573466e1cd55cea8052400046666666666444444444424666666666600201601401201000e00c00a00800600466a034464646666ae68cdc39aab9d5002480008cc8848cc00400c008c094d5d0a801180f9aba135744a004464c6405c66ae700c00bc0b04d55cf280089baa00135742a01466a0340366ae854024ccd54075d7280e1aba150083335501d75ca0386ae85401ccd4068094d5d0a80319a80d19aa8140133ad35742a00a6464646666ae68cdc39aab9d5002480008cc8848cc00400c008c8c8c8cccd5cd19b8735573aa004900011991091980080180119a815bad35742a00460586ae84d5d1280111931901919ab9c034033030135573ca00226ea8004d5d0a8011919191999ab9a3370e6aae754009200023322123300100300233502b75a6ae854008c0b0d5d09aba2500223263203233573806806606026aae7940044dd50009aba135744a004464c6405c66ae700c00bc0b04d55cf280089baa00135742a00866a034eb8d5d0a80199a80d19aa8143ae200135742a00460446ae84d5d1280111931901519ab9c02c02b028135744a00226ae8940044d5d1280089aba25001135744a00226ae8940044d5d1280089aba25001135573ca00226ea8004d5d0a8011919191999ab9a3370ea0029003119091111802002980e9aba135573ca00646666ae68cdc3a8012400846424444600400a603e6ae84d55cf280211999ab9a3370ea0069001119091111800802980d9aba135573ca00a46666ae68cdc3a8022400046424444600600a6eb8d5d09aab9e500623263202533573804e04c04604404204026aae7540044dd50009aba135744a004464c6403c66ae7008007c07040784c98c8074cd5ce249035054350001e135573ca00226ea8004444888ccd54c01048005403ccd54c01c480048d400488cd54078008d54024004ccd54c0104800488d4008894cd4ccd54c03048004c8cd409488ccd400c88008008004d40048800448cc004894cd400840a8400409c8d400488cc028008014018400c4cd404c01000d4040004cd54c01c480048d400488c8cd5407c00cc004014c8004d5409c894cd40044d5402800c884d4008894cd4cc03000802044888cc0080280104c01800c008c8004d5408088448894cd40044008884cc014008ccd54c01c480040140100044484888c00c0104484888c004010c8004d540748844894cd400454034884cd4038c010008cd54c01848004010004c8004d5407088448894cd40044d400c88004884ccd401488008c010008ccd54c01c4800401401000488ccd5cd19b8f00200101c01b23500122222222220081232230023758002640026aa034446666aae7c004940248cd4020c010d5d080118019aba200201423232323333573466e1cd55cea801a40004666444246660020080060046464646666ae68cdc39aab9d5002480008cc8848cc00400c008c054d5d0a80119a80700a1aba135744a004464c6403066ae700680640584d55cf280089baa00135742a006666aa00eeb94018d5d0a80119a8053ae357426ae8940088c98c8050cd5ce00b00a80909aba25001135573ca00226ea80044cd54005d73ad112232230023756002640026aa03044646666aae7c008940208cd401ccd5404cc018d55cea80118029aab9e500230043574400602626ae840044488008488488cc00401000c488c8c8cccd5cd19b875001480008c8488c00800cc014d5d09aab9e500323333573466e1d40092002212200123263201033573802402201c01a26aae7540044dd5000919191999ab9a3370e6aae7540092000233221233001003002300535742a0046eb4d5d09aba2500223263200d33573801e01c01626aae7940044dd50009191999ab9a3370e6aae75400520002375c6ae84d55cf280111931900599ab9c00d00c0091375400224464646666ae68cdc3a800a40084244400246666ae68cdc3a8012400446424446006008600c6ae84d55cf280211999ab9a3370ea00690001091100111931900719ab9c01000f00c00b00a135573aa00226ea80048c8cccd5cd19b8750014800880448cccd5cd19b8750024800080448c98c8028cd5ce00600580400389aab9d3754002464646464646666ae68cdc3a800a401842444444400646666ae68cdc3a8012401442444444400846666ae68cdc3a801a40104664424444444660020120106eb8d5d0a8029bad357426ae8940148cccd5cd19b875004480188cc8848888888cc008024020dd71aba15007375c6ae84d5d1280391999ab9a3370ea00a900211991091111111980300480418061aba15009375c6ae84d5d1280491999ab9a3370ea00c900111909111111180380418069aba135573ca01646666ae68cdc3a803a400046424444444600a010601c6ae84d55cf280611931900919ab9c01401301000f00e00d00c00b00a135573aa00826aae79400c4d55cf280109aab9e5001137540024646464646666ae68cdc3a800a4004466644424466600200a0080066eb4d5d0a8021bad35742a0066eb4d5d09aba2500323333573466e1d4009200023212230020033008357426aae7940188c98c802ccd5ce00680600480409aab9d5003135744a00226aae7940044dd5000919191999ab9a3370ea002900111909118008019bae357426aae79400c8cccd5cd19b875002480008c8488c00800cdd71aba135573ca008464c6401066ae700280240180144d55cea80089baa0011122232323333573466e1cd55cea80124000466aa010600c6ae854008c014d5d09aba2500223263200833573801401200c26aae7940044dd5000a4c22442466002006004240029210350543100112330012253350021001100700612335002223335003220020020013500122001122123300100300222333573466e1c00800401000c488008488004448c8c00400488cc00cc00800800410581840100d87980821a000a01a61a0b3b82b2f5f6").unwrap();
+
+    let raw_inputs = hex::decode("84825820b16778c9cf065d9efeefe37ec269b4fc5107ecdbd0dd6bf3274b224165c2edd9008258206c732139de33e916342707de2aebef2252c781640326ff37b86ec99d97f1ba8d01825820975c17a4fed0051be622328efa548e206657d2b65a19224bf6ff8132571e6a500282582018f86700660fc88d0370a8f95ea58f75507e6b27a18a17925ad3b1777eb0d77600").unwrap();
+    let raw_outputs = hex::decode("8482581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f67235821a000f8548a1581c15be994a64bdb79dde7fe080d8e7ff81b33a9e4860e9ee0d857a8e85a144576177610182581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f672351b00000001af14b8b482581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f672351a0098968082581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f672351a00acd8c6").unwrap();
+
+    let inputs = MaybeIndefArray::<TransactionInput>::decode_fragment(&raw_inputs).unwrap();
+    let outputs = MaybeIndefArray::<TransactionOutput>::decode_fragment(&raw_outputs).unwrap();
+
+    let utxos: Vec<ResolvedInput> = inputs
+        .iter()
+        .zip(outputs.iter())
+        .map(|(input, output)| ResolvedInput {
+            input: input.clone(),
+            output: output.clone(),
+        })
+        .collect();
+
+    let slot_config = SlotConfig {
+        zero_time: 1660003200000, // Preview network
+        zero_slot: 0,
+        slot_length: 1000,
+    };
+
+    let costs: Vec<i64> = vec![
+        205665, 812, 1, 1, 1000, 571, 0, 1, 1000, 24177, 4, 1, 1000, 32, 117366, 10475, 4, 23000,
+        100, 23000, 100, 23000, 100, 23000, 100, 23000, 100, 23000, 100, 100, 100, 23000, 100,
+        19537, 32, 175354, 32, 46417, 4, 221973, 511, 0, 1, 89141, 32, 497525, 14068, 4, 2, 196500,
+        453240, 220, 0, 1, 1, 1000, 28662, 4, 2, 245000, 216773, 62, 1, 1060367, 12586, 1, 208512,
+        421, 1, 187000, 1000, 52998, 1, 80436, 32, 43249, 32, 1000, 32, 80556, 1, 57667, 4, 1000,
+        10, 197145, 156, 1, 197145, 156, 1, 204924, 473, 1, 208896, 511, 1, 52467, 32, 64832, 32,
+        65493, 32, 22558, 32, 16563, 32, 76511, 32, 196500, 453240, 220, 0, 1, 1, 69522, 11687, 0,
+        1, 60091, 32, 196500, 453240, 220, 0, 1, 1, 196500, 453240, 220, 0, 1, 1, 806990, 30482, 4,
+        1927926, 82523, 4, 265318, 0, 4, 0, 85931, 32, 205665, 812, 1, 1, 41182, 32, 212342, 32,
+        31220, 32, 32696, 32, 43357, 32, 32247, 32, 38314, 32, 9462713, 1021, 10,
+    ];
+
+    let cost_mdl = CostModels {
+        plutus_v1: Some(costs),
+        plutus_v2: None,
+        plutus_v3: None,
+    };
+
+    let multi_era_tx = MultiEraTx::decode_for_era(Era::Conway, &tx_bytes).unwrap();
+
+    let tx = multi_era_tx.as_conway().unwrap();
+
+    let reports = simulate(
+        tx,
+        &utxos,
+        Some(&cost_mdl),
+        Some(&ExBudget {
+            cpu: 10000000000,
+            mem: 14000000,
+        }),
+        &slot_config,
+    );
+
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].is_success());
+
+    let evaluation = reports[0].evaluation.as_ref().unwrap();
+    assert_eq!(
+        evaluation.script_hash.to_string(),
+        "cba7bc9e83499376b6ad49304157778dba7c14bd748e4fd31792a930"
+    );
+    assert_eq!(
+        evaluation.cost,
+        ExBudget {
+            mem: 655782,
+            cpu: 188449458,
+        }
+    );
+
+    // Running out of budget is reported, along with what was consumed until then.
+    let reports = simulate(
+        tx,
+        &utxos,
+        Some(&cost_mdl),
+        Some(&ExBudget {
+            cpu: 1000,
+            mem: 1000,
+        }),
+        &slot_config,
+    );
+
+    assert_eq!(reports.len(), 1);
+    assert!(!reports[0].is_success());
+    assert!(reports[0].evaluation.as_ref().unwrap().result.is_err());
+}
+
+#[test]
+fn ledger_rules() {
+    let tx_bytes = hex::decode("84a800818258206c732139de33e916342707de2aebef2252c781640326ff37b86ec99d97f1ba8d01018282581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f67235821a000f41f0a1581ccba7bc9e83499376b6ad49304157778dba7c14bd748e4fd31792a930a1400a82581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f672351b00000001af006ac1021a00050c0309a1581ccba7bc9e83499376b6ad49304157778dba7c14bd748e4fd31792a930a1400a0b5820eb3b868ec2b33dffaf5d5481703ed00870333812b96e0f75ae89fd150b4744300d818258206c732139de33e916342707de2aebef2252c781640326ff37b86ec99d97f1ba8d011082581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f672351b00000001af0d26af111a00079205a3008182582031ae74f8058527afb305d7495b10a99422d9337fc199e1f28044f2c477a0f94658405a2dd70be89483bd6291a018c6ca91328dad37e092fdeab2eea14685004878da5f1e5962f35d771498bf54e79be3dcf922ea93b46a1356960dcf0bfd80a91b0b038159094259093f010000323322323232323232323232323232323232323233223232323232323232332232322232325335332232323233355300f12001350265025235001223335530121200135029502823500122333500123302f4800000488cc0c00080048cc0bc00520000013355300e120012350012233550250023335001233553012120012350012233550290023550140010012233355500f0150020012335530121200123500122335502900235501300100133355500a01000200130105002300f5001135001220023333573466e1cd55ce9baa00448000807c8c98c8078cd5ce01000f80e1999ab9a3370e6aae754009200023322123300100300232323232323232323232323333573466e1cd55cea8052400046666666666444444444424666666666600201601401201000e00c00a00800600466a034464646666ae68cdc39aab9d5002480008cc8848cc00400c008c094d5d0a801180f9aba135744a004464c6405c66ae700c00bc0b04d55cf280089baa00135742a01466a0340366ae854024ccd54075d7280e1aba150083335501d75ca0386ae85401ccd4068094d5d0a80319a80d19aa8140133ad35742a00a6464646666ae68cdc39aab9d5002480008cc8848cc00400c008c8c8c8cccd5cd19b8735573aa004900011991091980080180119a815bad35742a00460586ae84d5d1280111931901919ab9c034033030135573ca00226ea8004d5d0a8011919191999ab9a3370e6aae754009200023322123300100300233502b75a6ae854008c0b0d5d09aba2500223263203233573806806606026aae7940044dd50009aba135744a004464c6405c66ae700c00bc0b04d55cf280089baa00135742a00866a034eb8d5d0a80199a80d19aa8143ae200135742a00460446ae84d5d1280111931901519ab9c02c02b028135744a00226ae8940044d5d1280089aba25001135744a00226ae8940044d5d1280089aba25001135573ca00226ea8004d5d0a8011919191999ab9a3370ea0029003119091111802002980e9aba135573ca00646666ae68cdc3a8012400846424444600400a603e6ae84d55cf280211999ab9a3370ea0069001119091111800802980d9aba135573ca00a46666ae68cdc3a8022400046424444600600a6eb8d5d09aab9e500623263202533573804e04c04604404204026aae7540044dd50009aba135744a004464c6403c66ae7008007c07040784c98c8074cd5ce249035054350001e135573ca00226ea8004444888ccd54c01048005403ccd54c01c480048d400488cd54078008d54024004ccd54c0104800488d4008894cd4ccd54c03048004c8cd409488ccd400c88008008004d40048800448cc004894cd400840a8400409c8d400488cc028008014018400c4cd404c01000d4040004cd54c01c480048d400488c8cd5407c00cc004014c8004d5409c894cd40044d5402800c884d4008894cd4cc03000802044888cc0080280104c01800c008c8004d5408088448894cd40044008884cc014008ccd54c01c480040140100044484888c00c0104484888c004010c8004d540748844894cd400454034884cd4038c010008cd54c01848004010004c8004d5407088448894cd40044d400c88004884ccd401488008c010008ccd54c01c4800401401000488ccd5cd19b8f00200101c01b23500122222222220081232230023758002640026aa034446666aae7c004940248cd4020c010d5d080118019aba200201423232323333573466e1cd55cea801a40004666444246660020080060046464646666ae68cdc39aab9d5002480008cc8848cc00400c008c054d5d0a80119a80700a1aba135744a004464c6403066ae700680640584d55cf280089baa00135742a006666aa00eeb94018d5d0a80119a8053ae357426ae8940088c98c8050cd5ce00b00a80909aba25001135573ca00226ea80044cd54005d73ad112232230023756002640026aa03044646666aae7c008940208cd401ccd5404cc018d55cea80118029aab9e500230043574400602626ae840044488008488488cc00401000c488c8c8cccd5cd19b875001480008c8488c00800cc014d5d09aab9e500323333573466e1d40092002212200123263201033573802402201c01a26aae7540044dd5000919191999ab9a3370e6aae7540092000233221233001003002300535742a0046eb4d5d09aba2500223263200d33573801e01c01626aae7940044dd50009191999ab9a3370e6aae75400520002375c6ae84d55cf280111931900599ab9c00d00c0091375400224464646666ae68cdc3a800a40084244400246666ae68cdc3a8012400446424446006008600c6ae84d55cf280211999ab9a3370ea00690001091100111931900719ab9c01000f00c00b00a135573aa00226ea80048c8cccd5cd19b8750014800880448cccd5cd19b8750024800080448c98c8028cd5ce00600580400389aab9d3754002464646464646666ae68cdc3a800a401842444444400646666ae68cdc3a8012401442444444400846666ae68cdc3a801a40104664424444444660020120106eb8d5d0a8029bad357426ae8940148cccd5cd19b875004480188cc8848888888cc008024020dd71aba15007375c6ae84d5d1280391999ab9a3370ea00a900211991091111111980300480418061aba15009375c6ae84d5d1280491999ab9a3370ea00c900111909111111180380418069aba135573ca01646666ae68cdc3a803a400046424444444600a010601c6ae84d55cf280611931900919ab9c01401301000f00e00d00c00b00a135573aa00826aae79400c4d55cf280109aab9e5001137540024646464646666ae68cdc3a800a4004466644424466600200a0080066eb4d5d0a8021bad35742a0066eb4d5d09aba2500323333573466e1d4009200023212230020033008357426aae7940188c98c802ccd5ce00680600480409aab9d5003135744a00226aae7940044dd5000919191999ab9a3370ea002900111909118008019bae357426aae79400c8cccd5cd19b875002480008c8488c00800cdd71aba135573ca008464c6401066ae700280240180144d55cea80089baa0011122232323333573466e1cd55cea80124000466aa010600c6ae854008c014d5d09aba2500223263200833573801401200c26aae7940044dd5000a4c22442466002006004240029210350543100112330012253350021001100700612335002223335003220020020013500122001122123300100300222333573466e1c00800401000c488008488004448c8c00400488cc00cc00800800410581840100d87980821a000a01a61a0b3b82b2f5f6").unwrap();
+
+    let raw_inputs = hex::decode("84825820b16778c9cf065d9efeefe37ec269b4fc5107ecdbd0dd6bf3274b224165c2edd9008258206c732139de33e916342707de2aebef2252c781640326ff37b86ec99d97f1ba8d01825820975c17a4fed0051be622328efa548e206657d2b65a19224bf6ff8132571e6a500282582018f86700660fc88d0370a8f95ea58f75507e6b27a18a17925ad3b1777eb0d77600").unwrap();
+    let raw_outputs = hex::decode("8482581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f67235821a000f8548a1581c15be994a64bdb79dde7fe080d8e7ff81b33a9e4860e9ee0d857a8e85a144576177610182581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f672351b00000001af14b8b482581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f672351a0098968082581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f672351a00acd8c6").unwrap();
+
+    let inputs = MaybeIndefArray::<TransactionInput>::decode_fragment(&raw_inputs).unwrap();
+    let outputs = MaybeIndefArray::<TransactionOutput>::decode_fragment(&raw_outputs).unwrap();
+
+    let utxos: Vec<ResolvedInput> = inputs
+        .iter()
+        .zip(outputs.iter())
+        .map(|(input, output)| ResolvedInput {
+            input: input.clone(),
+            output: output.clone(),
+        })
+        .collect();
+
+    let slot_config = SlotConfig {
+        zero_time: 1660003200000, // Preview network
+        zero_slot: 0,
+        slot_length: 1000,
+    };
+
+    let multi_era_tx = MultiEraTx::decode_for_era(Era::Conway, &tx_bytes).unwrap();
+
+    let tx = multi_era_tx.as_conway().unwrap();
+
+    assert!(eval_ledger_rules(
+        tx,
+        &utxos,
+        &ProtocolParameters::default(),
+        &slot_config,
+        None
+    )
+    .is_ok());
+
+    assert!(matches!(
+        eval_ledger_rules(
+            tx,
+            &utxos,
+            &ProtocolParameters {
+                min_fee_b: 10000000,
+                ..ProtocolParameters::default()
+            },
+            &slot_config,
+            None
+        ),
+        Err(Error::FeeTooSmall { .. })
+    ));
+
+    assert!(matches!(
+        eval_ledger_rules(
+            tx,
+            &utxos,
+            &ProtocolParameters {
+                coins_per_utxo_byte: 1000000,
+                ..ProtocolParameters::default()
+            },
+            &slot_config,
+            None
+        ),
+        Err(Error::OutputTooSmall { .. })
+    ));
+
+    assert!(matches!(
+        eval_ledger_rules(
+            tx,
+            &utxos,
+            &ProtocolParameters {
+                collateral_percentage: 1000000,
+                ..ProtocolParameters::default()
+            },
+            &slot_config,
+            None
+        ),
+        Err(Error::InsufficientCollateral { .. })
+    ));
+
+    let unbalanced: Vec<ResolvedInput> = utxos
+        .iter()
+        .cloned()
+        .map(|mut utxo| {
+            if let TransactionOutput::Legacy(output) = &utxo.output {
+                utxo.output = from_alonzo_output(output);
+            }
+            if let TransactionOutput::PostAlonzo(ref mut output) = utxo.output {
+                output.value = match &output.value {
+                    Value::Coin(coin) => Value::Coin(coin + 1),
+                    Value::Multiasset(coin, assets) => Value::Multiasset(coin + 1, assets.clone()),
+                };
+            }
+            utxo
+        })
+        .collect();
+
+    assert!(matches!(
+        eval_ledger_rules(
+            tx,
+            &unbalanced,
+            &ProtocolParameters::default(),
+            &slot_config,
+            None
+        ),
+        Err(Error::ValueNotConserved { .. })
+    ));
+
+    assert!(matches!(
+        eval_ledger_rules(
+            tx,
+            &utxos,
+            &ProtocolParameters {
+                max_tx_ex_units: ExBudget {
+                    mem: 655781,
+                    cpu: 10000000000,
+                },
+                ..ProtocolParameters::default()
+            },
+            &slot_config,
+            None
+        ),
+        Err(Error::ExUnitsTooBig {
+            mem: 655782,
+            max_mem: 655781,
+            ..
+        })
+    ));
+
+    // Reference scripts, whether spent or only referenced, are paid for by size, with a price
+    // growing by a factor of 1.2 for every 25KiB.
+    let minimum_fee = |utxos: &[ResolvedInput]| match eval_ledger_rules(
+        tx,
+        utxos,
+        &ProtocolParameters {
+            min_fee_b: 10000000,
+            ..ProtocolParameters::default()
+        },
+        &slot_config,
+        None,
+    ) {
+        Err(Error::FeeTooSmall { minimum, .. }) => minimum,
+        result => panic!("unexpected result: {result:?}"),
+    };
+
+    let with_reference_script: Vec<ResolvedInput> = utxos
+        .iter()
+        .cloned()
+        .map(|mut utxo| {
+            if utxo.input == tx.transaction_body.inputs[0] {
+                if let TransactionOutput::Legacy(output) = &utxo.output {
+                    utxo.output = from_alonzo_output(output);
+                }
+                if let TransactionOutput::PostAlonzo(ref mut output) = utxo.output {
+                    output.script_ref = Some(CborWrap(PseudoScript::PlutusV2Script(
+                        PlutusScript::<2>(vec![0; 30 * 1024].into()),
+                    )));
+                }
+            }
+            utxo
+        })
+        .collect();
+
+    assert_eq!(
+        minimum_fee(&with_reference_script) - minimum_fee(&utxos),
+        25 * 1024 * 15 + 5 * 1024 * 18
+    );
+
+    // Validity interval and required signers, on a copy of the transaction that declares them;
+    // its fee only covers its original size, hence the size isn't charged for.
+    let amend = |amend: &dyn Fn(&mut Tx)| {
+        let mut tx = Tx::decode_fragment(&tx_bytes).unwrap();
+        amend(&mut tx);
+        tx.encode_fragment().unwrap()
+    };
+
+    let check = |tx_bytes: &[u8], current_slot: Option<u64>| {
+        let multi_era_tx = MultiEraTx::decode_for_era(Era::Conway, tx_bytes).unwrap();
+
+        eval_ledger_rules(
+            multi_era_tx.as_conway().unwrap(),
+            &utxos,
+            &ProtocolParameters {
+                min_fee_a: 0,
+                ..ProtocolParameters::default()
+            },
+            &slot_config,
+            current_slot,
+        )
+    };
+
+    let bounded = amend(&|tx| {
+        tx.transaction_body.validity_interval_start = Some(100);
+        tx.transaction_body.ttl = Some(200);
+    });
+
+    assert!(check(&bounded, None).is_ok());
+    assert!(check(&bounded, Some(100)).is_ok());
+    assert!(check(&bounded, Some(199)).is_ok());
+    assert!(matches!(
+        check(&bounded, Some(99)),
+        Err(Error::OutsideValidityInterval {
+            slot: 99,
+            lower_bound: Some(100),
+            upper_bound: Some(200),
+        })
+    ));
+    assert!(matches!(
+        check(&bounded, Some(200)),
+        Err(Error::OutsideValidityInterval { slot: 200, .. })
+    ));
+
+    let empty = amend(&|tx| {
+        tx.transaction_body.validity_interval_start = Some(200);
+        tx.transaction_body.ttl = Some(200);
+    });
+
+    assert!(matches!(
+        check(&empty, None),
+        Err(Error::EmptyValidityInterval {
+            lower_bound: 200,
+            upper_bound: 200,
+        })
+    ));
+
+    let signer = |tx: &Tx| {
+        Hasher::<224>::hash(&tx.transaction_witness_set.vkeywitness.as_ref().unwrap()[0].vkey)
+    };
+
+    let signed = amend(&|tx| {
+        tx.transaction_body.required_signers = NonEmptySet::from_vec(vec![signer(tx)]);
+    });
+
+    assert!(check(&signed, None).is_ok());
+
+    let unsigned = amend(&|tx| {
+        tx.transaction_body.required_signers =
+            NonEmptySet::from_vec(vec![signer(tx), Hasher::<224>::hash(b"someone else")]);
+    });
+
+    assert!(matches!(
+        check(&unsigned, None),
+        Err(Error::MissingRequiredSigners { missing }) if missing.len() == 1
+    ));
+}
+
+#[test]
+fn eval_from_json() {
+    let tx_hex = "84a800818258206c732139de33e916342707de2aebef2252c781640326ff37b86ec99d97f1ba8d01018282581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f67235821a000f41f0a1581ccba7bc9e83499376b6ad49304157778dba7c14bd748e4fd31792a930a1400a82581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f672351b00000001af006ac1021a00050c0309a1581ccba7bc9e83499376b6ad49304157778dba7c14bd748e4fd31792a930a1400a0b5820eb3b868ec2b33dffaf5d5481703ed00870333812b96e0f75ae89fd150b4744300d818258206c732139de33e916342707de2aebef2252c781640326ff37b86ec99d97f1ba8d011082581d60b6c8794e9a7a26599440a4d0fd79cd07644d15917ff13694f1f672351b00000001af0d26af111a00079205a3008182582031ae74f8058527afb305d7495b10a99422d9337fc199e1f28044f2c477a0f94658405a2dd70be89483bd6291a018c6ca91328dad37e092fdeab2eea14685004878da5f1e5962f35d771498bf54e79be3dcf922ea93b46a1356960dcf0bfd80a91b0b038159094259093f010000323322323232323232323232323232323232323233223232323232323232332232322232325335332232323233355300f12001350265025235001223335530121200135029502823500122333500123302f4800000488cc0c00080048cc0bc00520000013355300e120012350012233550250023335001233553012120012350012233550290023550140010012233355500f0150020012335530121200123500122335502900235501300100133355500a01000200130105002300f5001135001220023333573466e1cd55ce9baa00448000807c8c98c8078cd5ce01000f80e1999ab9a3370e6aae754009200023322123300100300232323232323232323232323333573466e1cd55cea8052400046666666666444444444424666666666600201601401201000e00c00a00800600466a034464646666ae68cdc39aab9d5002480008cc8848cc00400c008c094d5d0a801180f9aba135744a004464c6405c66ae700c00bc0b04d55cf280089baa00135742a01466a0340366ae854024ccd54075d7280e1aba150083335501d75ca0386ae85401ccd4068094d5d0a80319a80d19aa8140133ad35742a00a6464646666ae68cdc39aab9d5002480008cc8848cc00400c008c8c8c8cccd5cd19b8735573aa004900011991091980080180119a815bad35742a00460586ae84d5d1280111931901919ab9c034033030135573ca00226ea8004d5d0a8011919191999ab9a3370e6aae754009200023322123300100300233502b75a6ae854008c0b0d5d09aba2500223263203233573806806606026aae7940044dd50009aba135744a004464c6405c66ae700c00bc0b04d55cf280089baa00135742a00866a034eb8d5d0a80199a80d19aa8143ae200135742a00460446ae84d5d1280111931901519ab9c02c02b028135744a00226ae8940044d5d1280089aba25001135744a00226ae8940044d5d1280089aba25001135573ca00226ea8004d5d0a8011919191999ab9a3370ea0029003119091111802002980e9aba135573ca00646666ae68cdc3a8012400846424444600400a603e6ae84d55cf280211999ab9a3370ea0069001119091111800802980d9aba135573ca00a46666ae68cdc3a8022400046424444600600a6eb8d5d09aab9e500623263202533573804e04c04604404204026aae7540044dd50009aba135744a004464c6403c66ae7008007c07040784c98c8074cd5ce249035054350001e135573ca00226ea8004444888ccd54c01048005403ccd54c01c480048d400488cd54078008d54024004ccd54c0104800488d4008894cd4ccd54c03048004c8cd409488ccd400c88008008004d40048800448cc004894cd400840a8400409c8d400488cc028008014018400c4cd404c01000d4040004cd54c01c480048d400488c8cd5407c00cc004014c8004d5409c894cd40044d5402800c884d4008894cd4cc03000802044888cc0080280104c01800c008c8004d5408088448894cd40044008884cc014008ccd54c01c480040140100044484888c00c0104484888c004010c8004d540748844894cd400454034884cd4038c010008cd54c01848004010004c8004d5407088448894cd40044d400c88004884ccd401488008c010008ccd54c01c4800401401000488ccd5cd19b8f00200101c01b23500122222222220081232230023758002640026aa034446666aae7c004940248cd4020c010d5d080118019aba200201423232323333573466e1cd55cea801a40004666444246660020080060046464646666ae68cdc39aab9d5002480008cc8848cc00400c008c054d5d0a80119a80700a1aba135744a004464c6403066ae700680640584d55cf280089baa00135742a006666aa00eeb94018d5d0a80119a8053ae357426ae8940088c98c8050cd5ce00b00a80909aba25001135573ca00226ea80044cd54005d73ad112232230023756002640026aa03044646666aae7c008940208cd401ccd5404cc018d55cea80118029aab9e500230043574400602626ae840044488008488488cc00401000c488c8c8cccd5cd19b875001480008c8488c00800cc014d5d09aab9e500323333573466e1d40092002212200123263201033573802402201c01a26aae7540044dd5000919191999ab9a3370e6aae7540092000233221233001003002300535742a0046eb4d5d09aba2500223263200d33573801e01c01626aae7940044dd50009191999ab9a3370e6aae75400520002375c6ae84d55cf280111931900599ab9c00d00c0091375400224464646666ae68cdc3a800a40084244400246666ae68cdc3a8012400446424446006008600c6ae84d55cf280211999ab9a3370ea00690001091100111931900719ab9c01000f00c00b00a135573aa00226ea80048c8cccd5cd19b8750014800880448cccd5cd19b8750024800080448c98c8028cd5ce00600580400389aab9d3754002464646464646666ae68cdc3a800a401842444444400646666ae68cdc3a8012401442444444400846666ae68cdc3a801a40104664424444444660020120106eb8d5d0a8029bad357426ae8940148cccd5cd19b875004480188cc8848888888cc008024020dd71aba15007375c6ae84d5d1280391999ab9a3370ea00a900211991091111111980300480418061aba15009375c6ae84d5d1280491999ab9a3370ea00c900111909111111180380418069aba135573ca01646666ae68cdc3a803a400046424444444600a010601c6ae84d55cf280611931900919ab9c01401301000f00e00d00c00b00a135573aa00826aae79400c4d55cf280109aab9e5001137540024646464646666ae68cdc3a800a4004466644424466600200a0080066eb4d5d0a8021bad35742a0066eb4d5d09aba2500323333573466e1d4009200023212230020033008357426aae7940188c98c802ccd5ce00680600480409aab9d5003135744a00226aae7940044dd5000919191999ab9a3370ea002900111909118008019bae357426aae79400c8cccd5cd19b875002480008c8488c00800cdd71aba135573ca008464c6401066ae700280240180144d55cea80089baa0011122232323333573466e1cd55cea80124000466aa010600c6ae854008c014d5d09aba2500223263200833573801401200c26aae7940044dd5000a4c22442466002006004240029210350543100112330012253350021001100700612335002223335003220020020013500122001122123300100300222333573466e1c00800401000c488008488004448c8c00400488cc00cc00800800410581840100d87980821a000a01a61a0b3b82b2f5f6";
+
+    let json = format!(
+        r#"{{ "transaction": {{ "type": "Tx ConwayEra", "description": "", "cborHex": "{tx_hex}" }}, "utxos": {} }}"#,
+        indoc! {r#"
+        {
+            "18f86700660fc88d0370a8f95ea58f75507e6b27a18a17925ad3b1777eb0d776#0": {
+                "address": "addr_test1vzmvs72wnfazvkv5gzjdpltee5rkgng4j9llzd5578m8ydgkp6edr",
+                "value": {
+                    "lovelace": 11327686
+                }
+            },
+            "6c732139de33e916342707de2aebef2252c781640326ff37b86ec99d97f1ba8d#1": {
+                "address": "addr_test1vzmvs72wnfazvkv5gzjdpltee5rkgng4j9llzd5578m8ydgkp6edr",
+                "value": {
+                    "lovelace": 7232338100
+                }
+            },
+            "975c17a4fed0051be622328efa548e206657d2b65a19224bf6ff8132571e6a50#2": {
+                "address": "addr_test1vzmvs72wnfazvkv5gzjdpltee5rkgng4j9llzd5578m8ydgkp6edr",
+                "value": {
+                    "lovelace": 10000000
+                }
+            },
+            "b16778c9cf065d9efeefe37ec269b4fc5107ecdbd0dd6bf3274b224165c2edd9#0": {
+                "address": "addr_test1vzmvs72wnfazvkv5gzjdpltee5rkgng4j9llzd5578m8ydgkp6edr",
+                "value": {
+                    "15be994a64bdb79dde7fe080d8e7ff81b33a9e4860e9ee0d857a8e85": {
+                        "57617761": 1
+                    },
+                    "lovelace": 1017160
+                }
+            }
+        }
+        "#}
+    );
+
+    let document = TransactionJson::parse(&json).unwrap();
+
+    let tx_bytes = document.transaction().unwrap();
+
+    let utxos = document.resolved_inputs().unwrap();
+
+    let slot_config = SlotConfig {
+        zero_time: 1660003200000, // Preview network
+        zero_slot: 0,
+        slot_length: 1000,
+    };
+
+    let costs: Vec<i64> = vec![
+        205665, 812, 1, 1, 1000, 571, 0, 1, 1000, 24177, 4, 1, 1000, 32, 117366, 10475, 4, 23000,
+        100, 23000, 100, 23000, 100, 23000, 100, 23000, 100, 23000, 100, 100, 100, 23000, 100,
+        19537, 32, 175354, 32, 46417, 4, 221973, 511, 0, 1, 89141, 32, 497525, 14068, 4, 2, 196500,
+        453240, 220, 0, 1, 1, 1000, 28662, 4, 2, 245000, 216773, 62, 1, 1060367, 12586, 1, 208512,
+        421, 1, 187000, 1000, 52998, 1, 80436, 32, 43249, 32, 1000, 32, 80556, 1, 57667, 4, 1000,
+        10, 197145, 156, 1, 197145, 156, 1, 204924, 473, 1, 208896, 511, 1, 52467, 32, 64832, 32,
+        65493, 32, 22558, 32, 16563, 32, 76511, 32, 196500, 453240, 220, 0, 1, 1, 69522, 11687, 0,
+        1, 60091, 32, 196500, 453240, 220, 0, 1, 1, 196500, 453240, 220, 0, 1, 1, 806990, 30482, 4,
+        1927926, 82523, 4, 265318, 0, 4, 0, 85931, 32, 205665, 812, 1, 1, 41182, 32, 212342, 32,
+        31220, 32, 32696, 32, 43357, 32, 32247, 32, 38314, 32, 9462713, 1021, 10,
+    ];
+
+    let cost_mdl = CostModels {
+        plutus_v1: Some(costs),
+        plutus_v2: None,
+        plutus_v3: None,
+    };
+
+    let multi_era_tx = MultiEraTx::decode_for_era(Era::Conway, &tx_bytes).unwrap();
+
+    let tx = multi_era_tx.as_conway().unwrap();
+
+    let redeemers = eval_phase_two(
+        tx,
+        &utxos,
+        Some(&cost_mdl),
+        Some(&ExBudget {
+            cpu: 10000000000,
+            mem: 14000000,
+        }),
+        &slot_config,
+        true,
+        |_| (),
+    )
+    .unwrap();
+
+    assert_eq!(redeemers.len(), 1);
+    assert_eq!(redeemers[0].ex_units.mem, 655782);
+    assert_eq!(redeemers[0].ex_units.steps, 188449458);
+}
+
+#[test]
+fn resolved_inputs_from_json() {
+    let document = TransactionJson::parse(indoc! {r#"
+        {
+            "transaction": "84a0a0f5f6",
+            "utxos": {
+                "6c732139de33e916342707de2aebef2252c781640326ff37b86ec99d97f1ba8d#1": {
+                    "address": "addr_test1wpnlxv2xv9a9ucvnvzqakwepzl9ltx7jzgm53av2e9ncv4sysemm8",
+                    "value": {
+                        "lovelace": 2000000,
+                        "15be994a64bdb79dde7fe080d8e7ff81b33a9e4860e9ee0d857a8e85": { "57617761": 1 }
+                    },
+                    "datum": null,
+                    "inlineDatum": { "constructor": 0, "fields": [{ "int": 42 }, { "list": [{ "bytes": "cafe" }] }] },
+                    "referenceScript": {
+                        "script": { "cborHex": "4e4d01000033222220051200120011", "description": "", "type": "PlutusScriptV2" },
+                        "scriptLanguage": "PlutusScriptLanguage PlutusScriptV2"
+                    }
+                }
+            }
+        }
+    "#})
+    .unwrap();
+
+    let utxos = document.resolved_inputs().unwrap();
+
+    assert_eq!(utxos.len(), 1);
+    assert_eq!(utxos[0].input.index, 1);
+
+    let TransactionOutput::PostAlonzo(output) = &utxos[0].output else {
+        unreachable!("resolved inputs are always post-alonzo outputs");
+    };
+
+    assert!(matches!(output.value, Value::Multiasset(2000000, _)));
+
+    assert_eq!(
+        output.datum_option,
+        Some(DatumOption::Data(CborWrap(Data::constr(
+            0,
+            vec![
+                Data::integer(42.into()),
+                Data::list(vec![Data::bytestring(vec![0xca, 0xfe])])
+            ]
+        ))))
+    );
+
+    assert!(matches!(
+        output.script_ref.as_deref(),
+        Some(PseudoScript::PlutusV2Script(script)) if script.0.len() == 14
+    ));
+}
+
+#[test]
+fn big_integers_from_json() {
+    let document = |int: &str| {
+        TransactionJson::parse(&format!(
+            r#"{{
+                "transaction": "84a0a0f5f6",
+                "utxos": {{
+                    "6c732139de33e916342707de2aebef2252c781640326ff37b86ec99d97f1ba8d#1": {{
+                        "address": "addr_test1wpnlxv2xv9a9ucvnvzqakwepzl9ltx7jzgm53av2e9ncv4sysemm8",
+                        "value": {{ "lovelace": 2000000 }},
+                        "inlineDatum": {{ "map": [{{ "k": {{ "int": {int} }}, "v": {{ "int": -{int} }} }}] }}
+                    }}
+                }}
+            }}"#
+        ))
+        .unwrap()
+    };
+
+    let int = "123456789012345678901234567890";
+
+    let utxos = document(int).resolved_inputs().unwrap();
+
+    let TransactionOutput::PostAlonzo(output) = &utxos[0].output else {
+        unreachable!("resolved inputs are always post-alonzo outputs");
+    };
+
+    let int = int.parse::<num_bigint::BigInt>().unwrap();
+
+    assert_eq!(
+        output.datum_option,
+        Some(DatumOption::Data(CborWrap(Data::map(vec![(
+            Data::integer(int.clone()),
+            Data::integer(-int)
+        )]))))
+    );
+
+    assert!(matches!(
+        document("1.5").resolved_inputs(),
+        Err(Error::Json(e)) if e.contains("invalid integer: 1.5")
+    ));
+}
+
+#[test]
+fn protocol_parameters_from_json() {
+    let protocol_parameters = ProtocolParameters::from_json(indoc! {r#"
+        {
+            "collateralPercentage": 150,
+            "costModels": {
+                "PlutusV2": [100788, 420, 1, 1, 1000]
+            },
+            "executionUnitPrices": {
+                "priceMemory": 0.0577,
+                "priceSteps": 7.21e-05
+            },
+            "maxTxExecutionUnits": {
+                "memory": 16000000,
+                "steps": 10000000000
+            },
+            "maxBlockBodySize": 90112,
+            "txFeeFixed": 155381,
+            "txFeePerByte": 44,
+            "utxoCostPerByte": 4310
+        }
+    "#})
+    .unwrap();
+
+    assert_eq!(
+        protocol_parameters,
+        ProtocolParameters {
+            max_tx_ex_units: ExBudget {
+                mem: 16000000,
+                cpu: 10000000000,
+            },
+            cost_models: Some(CostModels {
+                plutus_v1: None,
+                plutus_v2: Some(vec![100788, 420, 1, 1, 1000]),
+                plutus_v3: None,
+            }),
+            ..ProtocolParameters::default()
+        }
+    );
+
+    assert!(matches!(
+        ProtocolParameters::from_json(
+            r#"{ "costModels": { "PlutusV3": { "addInteger-cpu-arguments-intercept": 100788 } } }"#
+        ),
+        Err(Error::Json(..))
+    ));
+}